@@ -16,6 +16,8 @@ use symphonia::core::{
 use tracing::{debug, instrument, warn};
 use walkdir::WalkDir;
 
+use crate::audio_features::{AudioFeatures, analyze_audio_features};
+
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
 pub struct AudioWaveformPeaks {
     pub bucket_size: usize,
@@ -31,6 +33,9 @@ pub struct AudioAnalysis {
     pub duration_seconds: f64,
     pub peaks: AudioWaveformPeaks,
     pub cache_path: Option<String>,
+    /// Missing from caches written before tempo, key and loudness analysis.
+    #[serde(default)]
+    pub features: Option<AudioFeatures>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
@@ -61,6 +66,7 @@ pub fn analyze_audio_file(path: &Path, bucket_size: usize) -> Result<AudioAnalys
         total_frames as f64 / f64::from(decoded.sample_rate)
     };
     let peaks = generate_waveform_peaks(&decoded.samples, bucket_size);
+    let features = analyze_audio_features(&decoded);
 
     Ok(AudioAnalysis {
         source_path: path.display().to_string(),
@@ -70,6 +76,7 @@ pub fn analyze_audio_file(path: &Path, bucket_size: usize) -> Result<AudioAnalys
         duration_seconds,
         peaks: AudioWaveformPeaks { bucket_size, peaks },
         cache_path: None,
+        features: Some(features),
     })
 }
 
@@ -92,7 +99,9 @@ pub fn analyze_audio_file_with_cache(
         let cached_bytes = fs::read(&cache_path)
             .with_context(|| format!("failed to read waveform cache {}", cache_path.display()))?;
         match serde_json::from_slice::<AudioAnalysis>(&cached_bytes) {
-            Ok(mut cached) if cached.peaks.bucket_size == bucket_size => {
+            Ok(mut cached)
+                if cached.peaks.bucket_size == bucket_size && cached.features.is_some() =>
+            {
                 cached.cache_path = Some(cache_path.display().to_string());
                 debug!(path = %cache_path.display(), "waveform cache hit");
                 return Ok(cached);
@@ -100,7 +109,7 @@ pub fn analyze_audio_file_with_cache(
             Ok(_) => {
                 warn!(
                     path = %cache_path.display(),
-                    "waveform cache bucket_size mismatch, regenerating"
+                    "waveform cache bucket_size mismatch or missing features, regenerating"
                 );
             }
             Err(error) => {
@@ -269,6 +278,94 @@ pub fn scan_audio_assets(directory: &Path) -> Result<Vec<AudioAssetEntry>> {
     Ok(assets)
 }
 
+/// Onset found by [`detect_transients`].
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq)]
+pub struct Transient {
+    pub frame: usize,
+    /// Onset strength relative to the strongest transient in the audio.
+    pub strength: f32,
+}
+
+const TRANSIENT_HOP_FRAMES: usize = 256;
+const TRANSIENT_CONTEXT_HOPS: usize = 8;
+const TRANSIENT_MIN_GAP_SECONDS: f64 = 0.05;
+
+/// Finds note onsets from rises in the short-time energy envelope.
+/// `sensitivity` runs from `0.0` (only the strongest hits) to `1.0`.
+#[must_use]
+pub fn detect_transients(audio: &DecodedAudio, sensitivity: f32) -> Vec<Transient> {
+    let envelope: Vec<f32> = audio
+        .samples
+        .chunks(TRANSIENT_HOP_FRAMES)
+        .map(|hop| {
+            (hop.iter().map(|sample| sample * sample).sum::<f32>() / hop.len() as f32).sqrt()
+        })
+        .collect();
+    let mut onset = vec![0.0_f32; envelope.len()];
+    for index in 1..envelope.len() {
+        onset[index] = (envelope[index] - envelope[index - 1]).max(0.0);
+    }
+    if let Some(first) = envelope.first() {
+        onset[0] = *first;
+    }
+    let strongest = onset.iter().copied().fold(0.0_f32, f32::max);
+    if strongest <= 1e-4 {
+        return Vec::new();
+    }
+    for value in &mut onset {
+        *value /= strongest;
+    }
+
+    let sensitivity = if sensitivity.is_finite() {
+        sensitivity.clamp(0.0, 1.0)
+    } else {
+        0.5
+    };
+    let floor = 0.02 + (1.0 - sensitivity) * 0.4;
+    let min_gap_frames = (TRANSIENT_MIN_GAP_SECONDS * f64::from(audio.sample_rate.max(1))) as usize;
+    let mut transients: Vec<Transient> = Vec::new();
+    for index in 0..onset.len() {
+        let value = onset[index];
+        let from = index.saturating_sub(TRANSIENT_CONTEXT_HOPS);
+        let to = (index + TRANSIENT_CONTEXT_HOPS + 1).min(onset.len());
+        let local_mean = onset[from..to].iter().sum::<f32>() / (to - from) as f32;
+        let is_peak = (index == 0 || value > onset[index - 1])
+            && onset.get(index + 1).is_none_or(|next| value >= *next);
+        if !is_peak || value < floor || value < local_mean * 1.5 {
+            continue;
+        }
+
+        let frame = onset_frame(&audio.samples, index);
+        match transients.last_mut() {
+            Some(previous) if frame < previous.frame + min_gap_frames => {
+                if value > previous.strength {
+                    *previous = Transient {
+                        frame,
+                        strength: value,
+                    };
+                }
+            }
+            _ => transients.push(Transient {
+                frame,
+                strength: value,
+            }),
+        }
+    }
+    transients
+}
+
+/// First frame around envelope hop `hop` that reaches a third of the local peak.
+fn onset_frame(samples: &[f32], hop: usize) -> usize {
+    let from = hop.saturating_sub(1) * TRANSIENT_HOP_FRAMES;
+    let to = ((hop + 1) * TRANSIENT_HOP_FRAMES).min(samples.len());
+    let window = &samples[from..to];
+    let peak = window.iter().copied().map(f32::abs).fold(0.0_f32, f32::max);
+    window
+        .iter()
+        .position(|sample| sample.abs() >= peak / 3.0)
+        .map_or(hop * TRANSIENT_HOP_FRAMES, |offset| from + offset)
+}
+
 fn push_mono_samples(decoded: symphonia::core::audio::AudioBufferRef<'_>, samples: &mut Vec<f32>) {
     let spec = *decoded.spec();
     let channel_count = spec.channels.count().max(1);
@@ -0,0 +1,277 @@
+use std::f64::consts::PI;
+
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    assets::DecodedAudio,
+    theory::{KeyEstimate, detect_key_from_pitch_classes},
+};
+
+/// Level reported for digital silence.
+const SILENCE_FLOOR_DB: f64 = -120.0;
+/// Samples quieter than this count as silence when trimming.
+const SILENCE_THRESHOLD_DB: f64 = -60.0;
+
+const LOUDNESS_BLOCK_SECONDS: f64 = 0.4;
+const LOUDNESS_STEP_SECONDS: f64 = 0.1;
+const LOUDNESS_ABSOLUTE_GATE: f64 = -70.0;
+const LOUDNESS_RELATIVE_GATE: f64 = -10.0;
+
+const TEMPO_HOP_FRAMES: usize = 256;
+/// Hops the tempo envelope is averaged over, long enough to smooth out the
+/// beating between tones a few semitones apart.
+const TEMPO_SMOOTHING_HOPS: usize = 4;
+const MIN_TEMPO_BPM: f64 = 70.0;
+const MAX_TEMPO_BPM: f64 = 180.0;
+/// Centre of the log-normal tempo prior that settles octave ambiguity.
+const PREFERRED_TEMPO_BPM: f64 = 120.0;
+
+const CHROMA_FRAME_SECONDS: f64 = 0.17;
+const CHROMA_LOW_NOTE: u8 = 48;
+const CHROMA_HIGH_NOTE: u8 = 95;
+/// Key detection only listens to this much audio, which keeps long files cheap.
+const MAX_CHROMA_SECONDS: f64 = 30.0;
+
+/// Tempo, key and level measurements of a decoded file. Everything is measured
+/// on the mono mixdown.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct AudioFeatures {
+    /// `None` when the audio has no steady pulse.
+    pub bpm: Option<f64>,
+    pub key: Option<KeyEstimate>,
+    /// ITU-R BS.1770 integrated loudness. `None` when every block is gated out
+    /// as silence.
+    pub integrated_lufs: Option<f64>,
+    pub rms_dbfs: f64,
+    pub peak_dbfs: f64,
+    /// Range between the first and last sample above the silence threshold;
+    /// both are `0.0` for a silent file.
+    pub trim_start_seconds: f64,
+    pub trim_end_seconds: f64,
+}
+
+#[must_use]
+pub fn analyze_audio_features(audio: &DecodedAudio) -> AudioFeatures {
+    let samples = &audio.samples;
+    let rate = f64::from(audio.sample_rate.max(1));
+    let peak = samples
+        .iter()
+        .map(|sample| f64::from(sample.abs()))
+        .fold(0.0, f64::max);
+    let mean_square = if samples.is_empty() {
+        0.0
+    } else {
+        samples
+            .iter()
+            .map(|sample| f64::from(*sample).powi(2))
+            .sum::<f64>()
+            / samples.len() as f64
+    };
+
+    let threshold = 10_f64.powf(SILENCE_THRESHOLD_DB / 20.0);
+    let audible = |sample: &f32| f64::from(sample.abs()) >= threshold;
+    let (trim_start, trim_end) = match (
+        samples.iter().position(audible),
+        samples.iter().rposition(audible),
+    ) {
+        (Some(first), Some(last)) => (first, last + 1),
+        _ => (0, 0),
+    };
+
+    AudioFeatures {
+        bpm: estimate_tempo(samples, audio.sample_rate),
+        key: estimate_key(&samples[trim_start..trim_end], audio.sample_rate),
+        integrated_lufs: integrated_loudness(samples, audio.sample_rate),
+        rms_dbfs: amplitude_to_db(mean_square.sqrt()),
+        peak_dbfs: amplitude_to_db(peak),
+        trim_start_seconds: trim_start as f64 / rate,
+        trim_end_seconds: trim_end as f64 / rate,
+    }
+}
+
+/// Picks the beat period that best explains the autocorrelation of the rises in
+/// a smoothed energy envelope, weighted towards moderate tempos.
+#[must_use]
+pub fn estimate_tempo(samples: &[f32], sample_rate: u32) -> Option<f64> {
+    let hops_per_second = f64::from(sample_rate.max(1)) / TEMPO_HOP_FRAMES as f64;
+    let energies: Vec<f32> = samples
+        .chunks(TEMPO_HOP_FRAMES)
+        .map(|hop| hop.iter().map(|sample| sample * sample).sum::<f32>() / hop.len() as f32)
+        .collect();
+    let envelope: Vec<f32> = (0..energies.len())
+        .map(|index| {
+            let window = &energies[index.saturating_sub(TEMPO_SMOOTHING_HOPS - 1)..=index];
+            (window.iter().sum::<f32>() / window.len() as f32).sqrt()
+        })
+        .collect();
+    let onset: Vec<f32> = std::iter::once(0.0)
+        .chain(envelope.windows(2).map(|pair| (pair[1] - pair[0]).max(0.0)))
+        .collect();
+    let min_lag = (hops_per_second * 60.0 / MAX_TEMPO_BPM).floor().max(1.0) as usize;
+    let max_lag = (hops_per_second * 60.0 / MIN_TEMPO_BPM).ceil() as usize;
+    if onset.len() < max_lag * 2 {
+        return None;
+    }
+    let mean = onset.iter().sum::<f32>() / onset.len() as f32;
+    let centred: Vec<f64> = onset.iter().map(|value| f64::from(value - mean)).collect();
+    let autocorrelation = |lag: usize| -> f64 {
+        centred[lag..]
+            .iter()
+            .zip(&centred)
+            .map(|(later, earlier)| later * earlier)
+            .sum::<f64>()
+            / (centred.len() - lag) as f64
+    };
+
+    let scores: Vec<f64> = (min_lag - 1..=max_lag + 1).map(autocorrelation).collect();
+    let (best, score) = (1..scores.len() - 1)
+        .map(|index| {
+            let bpm = hops_per_second * 60.0 / (min_lag - 1 + index) as f64;
+            let prior = (-0.5 * (bpm / PREFERRED_TEMPO_BPM).log2().powi(2)).exp();
+            (index, scores[index] * prior)
+        })
+        .fold((0, f64::NEG_INFINITY), |best, current| {
+            if current.1 > best.1 { current } else { best }
+        });
+    if score <= 0.0 {
+        return None;
+    }
+
+    let (before, at, after) = (scores[best - 1], scores[best], scores[best + 1]);
+    let curvature = before - 2.0 * at + after;
+    let offset = if curvature < 0.0 {
+        (0.5 * (before - after) / curvature).clamp(-0.5, 0.5)
+    } else {
+        0.0
+    };
+    let lag = (min_lag - 1 + best) as f64 + offset;
+    Some((hops_per_second * 60.0 / lag * 100.0).round() / 100.0)
+}
+
+/// Correlates a chromagram, from Goertzel filters on each note between C3 and
+/// B6, with the major and minor key profiles.
+#[must_use]
+pub fn estimate_key(samples: &[f32], sample_rate: u32) -> Option<KeyEstimate> {
+    let rate = f64::from(sample_rate.max(1));
+    let frame = (rate * CHROMA_FRAME_SECONDS) as usize;
+    let listened = samples.len().min((rate * MAX_CHROMA_SECONDS) as usize);
+    if frame == 0 || listened < frame {
+        return None;
+    }
+
+    let coefficients: Vec<(usize, f64)> = (CHROMA_LOW_NOTE..=CHROMA_HIGH_NOTE)
+        .map(|note| {
+            let hz = 440.0 * 2_f64.powf((f64::from(note) - 69.0) / 12.0);
+            (usize::from(note % 12), 2.0 * (2.0 * PI * hz / rate).cos())
+        })
+        .collect();
+    let mut chroma = [0.0_f64; 12];
+    for window in samples[..listened].chunks_exact(frame) {
+        for (class, coefficient) in &coefficients {
+            let (mut previous, mut before) = (0.0_f64, 0.0_f64);
+            for sample in window {
+                let current = f64::from(*sample) + coefficient * previous - before;
+                before = previous;
+                previous = current;
+            }
+            let power = previous * previous + before * before - coefficient * previous * before;
+            chroma[*class] += power.max(0.0).sqrt();
+        }
+    }
+    detect_key_from_pitch_classes(&chroma)
+}
+
+/// Gated integrated loudness of a mono signal after K-weighting, per
+/// ITU-R BS.1770.
+#[must_use]
+pub fn integrated_loudness(samples: &[f32], sample_rate: u32) -> Option<f64> {
+    let rate = f64::from(sample_rate.max(1));
+    let weighted = k_weight(samples, rate);
+    let block = ((rate * LOUDNESS_BLOCK_SECONDS) as usize).clamp(1, weighted.len().max(1));
+    let step = ((rate * LOUDNESS_STEP_SECONDS) as usize).max(1);
+    if weighted.is_empty() {
+        return None;
+    }
+
+    let powers: Vec<f64> = (0..=weighted.len() - block)
+        .step_by(step)
+        .map(|start| {
+            weighted[start..start + block]
+                .iter()
+                .map(|sample| sample * sample)
+                .sum::<f64>()
+                / block as f64
+        })
+        .collect();
+    let loudness = |power: f64| -0.691 + 10.0 * power.log10();
+    let gated_mean = |gate: f64| {
+        let kept: Vec<f64> = powers
+            .iter()
+            .copied()
+            .filter(|power| *power > 0.0 && loudness(*power) > gate)
+            .collect();
+        (!kept.is_empty()).then(|| kept.iter().sum::<f64>() / kept.len() as f64)
+    };
+
+    let absolute = gated_mean(LOUDNESS_ABSOLUTE_GATE)?;
+    let relative = gated_mean(loudness(absolute) + LOUDNESS_RELATIVE_GATE)?;
+    Some(loudness(relative))
+}
+
+/// The BS.1770 pre-filter (high shelf) and RLB high-pass, designed for any
+/// sample rate.
+fn k_weight(samples: &[f32], rate: f64) -> Vec<f64> {
+    let shelf = {
+        let (gain_db, q, hz) = (
+            3.999_843_853_973_347,
+            0.707_175_236_955_419_6,
+            1_681.974_450_955_533,
+        );
+        let k = (PI * hz / rate).tan();
+        let vh = 10_f64.powf(gain_db / 20.0);
+        let vb = vh.powf(0.499_666_774_154_541_6);
+        let a0 = 1.0 + k / q + k * k;
+        [
+            (vh + vb * k / q + k * k) / a0,
+            2.0 * (k * k - vh) / a0,
+            (vh - vb * k / q + k * k) / a0,
+            2.0 * (k * k - 1.0) / a0,
+            (1.0 - k / q + k * k) / a0,
+        ]
+    };
+    let high_pass = {
+        let (q, hz) = (0.500_327_037_323_877_3, 38.135_470_876_024_44);
+        let k = (PI * hz / rate).tan();
+        let a0 = 1.0 + k / q + k * k;
+        [
+            1.0,
+            -2.0,
+            1.0,
+            2.0 * (k * k - 1.0) / a0,
+            (1.0 - k / q + k * k) / a0,
+        ]
+    };
+
+    let input: Vec<f64> = samples.iter().map(|sample| f64::from(*sample)).collect();
+    biquad(&biquad(&input, shelf), high_pass)
+}
+
+/// Direct form I biquad with coefficients `[b0, b1, b2, a1, a2]`.
+fn biquad(input: &[f64], [b0, b1, b2, a1, a2]: [f64; 5]) -> Vec<f64> {
+    let (mut x1, mut x2, mut y1, mut y2) = (0.0, 0.0, 0.0, 0.0);
+    input
+        .iter()
+        .map(|x0| {
+            let y0 = b0 * x0 + b1 * x1 + b2 * x2 - a1 * y1 - a2 * y2;
+            (x2, x1, y2, y1) = (x1, *x0, y1, y0);
+            y0
+        })
+        .collect()
+}
+
+fn amplitude_to_db(amplitude: f64) -> f64 {
+    if amplitude <= 0.0 {
+        return SILENCE_FLOOR_DB;
+    }
+    (20.0 * amplitude.log10()).max(SILENCE_FLOOR_DB)
+}
@@ -1,5 +1,6 @@
 use std::{
     collections::{BTreeMap, HashMap, HashSet},
+    ops::RangeInclusive,
     path::{Path, PathBuf},
 };
 
@@ -11,46 +12,138 @@ use uuid::Uuid;
 use crate::{
     assets::{
         AudioAnalysis, AudioAssetEntry, analyze_audio_file, analyze_audio_file_with_cache,
-        scan_audio_assets,
+        decode_audio_file_mono, detect_transients, scan_audio_assets,
     },
     export,
+    generators::{self, EuclideanRhythm, ProbabilityFill},
+    groove::{self, QuantizeOptions},
+    midi_import::{self, MidiImportOptions},
     model::{
-        AudioClip, AutomationClip, AutomationPoint, ChipMacroLane, Clip, ClipPayload,
-        DEFAULT_SAMPLE_RATE, EffectSpec, MidiNote, PatternClip, Project, Track, TrackKind,
-        TrackSend, TrackerRow,
+        AudioClip, AudioStretchMode, AutomationClip, AutomationPoint, ChipMacroLane, Clip,
+        ClipPayload, ClipPoolEntry, ControllerLane, ControllerTarget, DEFAULT_CLIP_VELOCITY_SCALE,
+        DEFAULT_SAMPLE_RATE, EffectSpec, GrooveTemplate, LinkedClip, MidiClip, MidiNote, OrderList,
+        OrderRow, PatternClip, Project, SamplerInstrument, SamplerZone, Step, StepRow,
+        StepSequencerClip, SynthPatch, Track, TrackKind, TrackSend, TrackerRow, Tuning, WarpMarker,
     },
+    module_import,
+    note_tools::{self, ArpeggiatorOptions, HumanizeOptions, StrumDirection, VelocityShape},
     persistence,
-    time::{seconds_to_ticks, tracker_rows_to_ticks},
+    sequencer::STEP_DRUM_CHANNEL,
+    theory::{self, ChordKind, Key, KeyEstimate, ScaleMode},
+    time::{ticks_to_seconds, tracker_rows_to_ticks},
+    tracker_import,
+    transcribe::{self, TranscriptionOptions},
+    tuning,
+    vgm::{self, VgmChip},
 };
 
+const MAX_CLIP_TRANSPOSE: i8 = 48;
+const MAX_CLIP_VELOCITY_SCALE: f32 = 4.0;
+const MAX_GROOVE_STEPS: usize = 64;
+const MAX_ARPEGGIO_OCTAVES: u8 = 4;
+const MAX_ARPEGGIO_GATE_PERCENT: f32 = 200.0;
+const MAX_EUCLIDEAN_STEPS: u32 = 64;
+/// Largest pattern the tracker formats we import can hold.
+const MAX_GENERATED_PATTERN_ROWS: u64 = 256;
+/// Most order rows a single ripple edit may open in an order list.
+const MAX_INSERTED_ORDER_ROWS: u64 = 256;
+const MAX_AUDIO_PITCH_SEMITONES: f32 = 24.0;
+const MIN_SOURCE_BPM: f64 = 20.0;
+const MAX_SOURCE_BPM: f64 = 999.0;
+const GROOVE_TRANSIENT_SENSITIVITY: f32 = 0.5;
+
 #[derive(Debug, Error)]
 pub enum EngineError {
     #[error("track not found: {0}")]
     TrackNotFound(Uuid),
     #[error("track {track_id} is not an audio track (found: {kind:?})")]
     InvalidAudioTrack { track_id: Uuid, kind: TrackKind },
+    #[error("track {track_id} cannot hold note clips (found: {kind:?})")]
+    InvalidNoteTrack { track_id: Uuid, kind: TrackKind },
     #[error("clip not found: {0}")]
     ClipNotFound(Uuid),
+    #[error("clip pool content not found: {0}")]
+    PoolContentNotFound(Uuid),
     #[error("clip does not support midi note editing: {0}")]
     UnsupportedClipPayload(Uuid),
+    #[error("unknown source chip: {0:?}")]
+    UnknownSourceChip(String),
     #[error("clip is not an audio clip: {0}")]
     UnsupportedAudioClip(Uuid),
+    #[error("clip is not a midi clip: {0}")]
+    UnsupportedMidiClip(Uuid),
     #[error("clip is not an automation clip: {0}")]
     UnsupportedAutomationClip(Uuid),
     #[error("clip is not a pattern clip: {0}")]
     UnsupportedPatternClip(Uuid),
+    #[error("clip is not a step sequencer clip: {0}")]
+    UnsupportedStepClip(Uuid),
     #[error("track {track_id} has invalid bus target: {target_bus}")]
     InvalidBusTarget { track_id: Uuid, target_bus: Uuid },
     #[error("track {track_id} has invalid send target: {target_bus}")]
     InvalidTrackSend { track_id: Uuid, target_bus: Uuid },
     #[error("track send not found: {0}")]
     SendNotFound(Uuid),
+    #[error("synth patch not found: {0}")]
+    SynthPatchNotFound(String),
+    #[error("sampler not found: {0}")]
+    SamplerNotFound(String),
+    #[error("sampler requires at least one audio asset")]
+    EmptySamplerAssets,
     #[error("routing graph contains a cycle")]
     RoutingCycleDetected,
     #[error("invalid quantize grid ticks: {0}")]
     InvalidQuantizeGrid(u64),
+    #[error("invalid quantize amount: {0}")]
+    InvalidQuantizeAmount(f32),
+    #[error("invalid groove step count: {0}")]
+    InvalidGrooveSteps(usize),
+    #[error("groove template not found: {0}")]
+    GrooveNotFound(String),
+    #[error("clip has no onsets to take a groove from: {0}")]
+    EmptyGroove(Uuid),
+    #[error("invalid humanize length percent: {0}")]
+    InvalidHumanizeAmount(f32),
+    #[error("invalid velocity curve: {0}")]
+    InvalidVelocityCurve(f32),
+    #[error("invalid note length ticks: {0}")]
+    InvalidNoteLength(u64),
+    #[error("invalid arpeggio rate ticks: {0}")]
+    InvalidArpeggioRate(u64),
+    #[error("invalid arpeggio octave count: {0}")]
+    InvalidArpeggioOctaves(u8),
+    #[error("invalid arpeggio gate percent: {0}")]
+    InvalidArpeggioGate(f32),
+    #[error("invalid key root pitch class: {0}")]
+    InvalidKeyRoot(u8),
+    #[error("invalid scale: {0}")]
+    InvalidScale(String),
+    #[error("no key set for clip or project: {0}")]
+    KeyMissing(Uuid),
+    #[error("invalid euclidean rhythm: hits={hits} steps={steps}")]
+    InvalidEuclideanRhythm { hits: u32, steps: u32 },
+    #[error("pattern clip spans {rows} rows, more than the {max} a generator can fill")]
+    TooManyPatternRows { rows: u64, max: u64 },
+    #[error("invalid fill chance percent: {0}")]
+    InvalidFillChance(f32),
+    #[error("probability fill needs at least one chance and one pitch")]
+    EmptyProbabilityFill,
+    #[error("pattern needs at least two notes to continue: {0}")]
+    MarkovSourceTooShort(Uuid),
     #[error("invalid tracker lines_per_beat: {0}")]
     InvalidTrackerLinesPerBeat(u16),
+    #[error("invalid step sequencer steps_per_beat: {0}")]
+    InvalidStepsPerBeat(u16),
+    #[error("invalid step position: row={row} step={step}")]
+    InvalidStepIndex { row: usize, step: usize },
+    #[error("project has no order list")]
+    OrderListMissing,
+    #[error("invalid order list row length ticks: {0}")]
+    InvalidOrderRowLength(u64),
+    #[error("invalid order list loop row: {0}")]
+    InvalidOrderLoopRow(usize),
+    #[error("clips overlap in order list row {row} on track {track_id}")]
+    OrderCellOverlap { track_id: Uuid, row: u64 },
     #[error("invalid note index: {0}")]
     InvalidNoteIndex(usize),
     #[error("invalid reorder from {from} to {to}")]
@@ -62,8 +155,46 @@ pub enum EngineError {
     },
     #[error("invalid audio stretch ratio: {0}")]
     InvalidAudioStretchRatio(f32),
+    #[error("invalid audio pitch shift: {0}")]
+    InvalidAudioPitchShift(f32),
+    #[error("invalid warp markers: {0}")]
+    InvalidWarpMarkers(String),
+    #[error("invalid source bpm: {0}")]
+    InvalidSourceBpm(f64),
+    #[error("invalid clip velocity scale: {0}")]
+    InvalidClipVelocityScale(f32),
+    #[error("clip loop length must be at least a sixteenth note: {0} ticks")]
+    InvalidClipLoopLength(u64),
+    #[error("split tick {tick} is outside clip {clip_id}")]
+    InvalidSplitTick { clip_id: Uuid, tick: u64 },
+    #[error("invalid slice count: {0}")]
+    InvalidSliceCount(u32),
+    #[error("invalid slice grid ticks: {0}")]
+    InvalidSliceGrid(u64),
+    #[error("no transients found in audio clip: {0}")]
+    NoTransients(Uuid),
+    #[error("reversed audio clip cannot be sliced to a sampler: {0}")]
+    ReversedAudioSlices(Uuid),
+    #[error("no pitched notes found in audio clip: {0}")]
+    NoPitchedNotes(Uuid),
+    #[error("clips cannot be glued: {0}")]
+    InvalidGlue(String),
+    #[error("invalid time range: start={start_tick} end={end_tick}")]
+    InvalidTimeRange { start_tick: u64, end_tick: u64 },
+    #[error(
+        "ripple edit of {length_ticks} ticks at {tick} does not line up with order rows of {row_length_ticks} ticks"
+    )]
+    MisalignedOrderListEdit {
+        tick: u64,
+        length_ticks: u64,
+        row_length_ticks: u64,
+    },
+    #[error("ripple edit would open {rows} order rows, more than {max}")]
+    TooManyOrderRows { rows: u64, max: u64 },
     #[error("invalid audio analysis bucket size: {0}")]
     InvalidAudioBucketSize(usize),
+    #[error("invalid tuning: {0}")]
+    InvalidTuning(String),
     #[error("io error: {0}")]
     Io(String),
 }
@@ -118,6 +249,19 @@ pub struct AudioClipPatch {
     pub fade_out_seconds: Option<f64>,
     pub reverse: Option<bool>,
     pub stretch_ratio: Option<f32>,
+    pub stretch_mode: Option<AudioStretchMode>,
+    pub pitch_semitones: Option<f32>,
+    pub pitch_cents: Option<f32>,
+}
+
+/// Non-destructive playback settings of a clip placement. `loop_length_ticks`
+/// of `Some(None)` or `Some(Some(0))` stops looping.
+#[derive(Debug, Clone, Serialize, Deserialize, Default)]
+pub struct ClipPlaybackPatch {
+    pub content_offset_ticks: Option<u64>,
+    pub loop_length_ticks: Option<Option<u64>>,
+    pub transpose_semitones: Option<i8>,
+    pub velocity_scale: Option<f32>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize, Default)]
@@ -134,21 +278,17 @@ pub enum ExportKind {
     Wav,
     Mp3,
     StemWav,
+    Vgm,
 }
 
-#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq, Default)]
 #[serde(rename_all = "snake_case")]
 pub enum RenderMode {
+    #[default]
     Offline,
     Realtime,
 }
 
-impl Default for RenderMode {
-    fn default() -> Self {
-        Self::Offline
-    }
-}
-
 #[derive(Debug, Clone)]
 pub struct Engine {
     project: Project,
@@ -282,6 +422,153 @@ impl Engine {
         Ok(effect)
     }
 
+    #[instrument(skip(self, patch), fields(project_id = %self.project.id, track_id = %track_id, patch = %patch.name))]
+    pub fn upsert_synth_patch(
+        &mut self,
+        track_id: Uuid,
+        mut patch: SynthPatch,
+    ) -> Result<Track, EngineError> {
+        sanitize_synth_patch(&mut patch);
+        let updated_track = {
+            let track = self.find_track_mut(track_id)?;
+            if let Some(existing) = track
+                .synth_patches
+                .iter_mut()
+                .find(|existing| existing.name.eq_ignore_ascii_case(&patch.name))
+            {
+                *existing = patch;
+            } else {
+                track.synth_patches.push(patch);
+            }
+            track.clone()
+        };
+
+        self.project.touch();
+        info!(
+            patch_count = updated_track.synth_patches.len(),
+            "synth patch upserted"
+        );
+        Ok(updated_track)
+    }
+
+    #[instrument(skip(self), fields(project_id = %self.project.id, track_id = %track_id, patch = %name))]
+    pub fn remove_synth_patch(&mut self, track_id: Uuid, name: &str) -> Result<Track, EngineError> {
+        let updated_track = {
+            let track = self.find_track_mut(track_id)?;
+            let before = track.synth_patches.len();
+            track
+                .synth_patches
+                .retain(|patch| !patch.name.eq_ignore_ascii_case(name.trim()));
+            if track.synth_patches.len() == before {
+                return Err(EngineError::SynthPatchNotFound(name.to_string()));
+            }
+            track.clone()
+        };
+
+        self.project.touch();
+        info!("synth patch removed");
+        Ok(updated_track)
+    }
+
+    #[instrument(skip(self, sampler), fields(project_id = %self.project.id, track_id = %track_id, sampler = %sampler.name, zones = sampler.zones.len()))]
+    pub fn upsert_sampler(
+        &mut self,
+        track_id: Uuid,
+        mut sampler: SamplerInstrument,
+    ) -> Result<Track, EngineError> {
+        sanitize_sampler(&mut sampler);
+        let updated_track = {
+            let track = self.find_track_mut(track_id)?;
+            if let Some(existing) = track
+                .samplers
+                .iter_mut()
+                .find(|existing| existing.name.eq_ignore_ascii_case(&sampler.name))
+            {
+                *existing = sampler;
+            } else {
+                track.samplers.push(sampler);
+            }
+            track.clone()
+        };
+
+        self.project.touch();
+        info!(
+            sampler_count = updated_track.samplers.len(),
+            "sampler upserted"
+        );
+        Ok(updated_track)
+    }
+
+    #[instrument(skip(self), fields(project_id = %self.project.id, track_id = %track_id, sampler = %name))]
+    pub fn remove_sampler(&mut self, track_id: Uuid, name: &str) -> Result<Track, EngineError> {
+        let updated_track = {
+            let track = self.find_track_mut(track_id)?;
+            let before = track.samplers.len();
+            track
+                .samplers
+                .retain(|sampler| !sampler.name.eq_ignore_ascii_case(name.trim()));
+            if track.samplers.len() == before {
+                return Err(EngineError::SamplerNotFound(name.to_string()));
+            }
+            track.clone()
+        };
+
+        self.project.touch();
+        info!("sampler removed");
+        Ok(updated_track)
+    }
+
+    /// Builds a sampler from scanned assets: a single asset is spread chromatically
+    /// around `first_note`, several assets become a one-shot kit on consecutive keys.
+    #[instrument(skip(self, assets), fields(project_id = %self.project.id, track_id = %track_id, sampler = %name, assets = assets.len(), first_note))]
+    pub fn create_sampler_from_assets(
+        &mut self,
+        track_id: Uuid,
+        name: String,
+        assets: &[AudioAssetEntry],
+        first_note: u8,
+    ) -> Result<Track, EngineError> {
+        if assets.is_empty() {
+            return Err(EngineError::EmptySamplerAssets);
+        }
+
+        let first_note = first_note.min(127);
+        let is_kit = assets.len() > 1;
+        let mut zones = Vec::with_capacity(assets.len());
+        for (offset, asset) in assets.iter().enumerate() {
+            let Some(key) = usize::from(first_note)
+                .checked_add(offset)
+                .filter(|key| *key <= 127)
+            else {
+                warn!(path = %asset.path, "sampler key range exhausted, skipping asset");
+                continue;
+            };
+            let key = key as u8;
+            let decoded = decode_audio_file_mono(Path::new(&asset.path))?;
+            let duration_seconds =
+                decoded.samples.len() as f64 / f64::from(decoded.sample_rate.max(1));
+
+            zones.push(SamplerZone {
+                source_path: asset.path.clone(),
+                root_note: key,
+                low_note: if is_kit { key } else { 0 },
+                high_note: if is_kit { key } else { 127 },
+                one_shot: is_kit,
+                loop_end_seconds: duration_seconds,
+                ..SamplerZone::default()
+            });
+        }
+
+        self.upsert_sampler(
+            track_id,
+            SamplerInstrument {
+                name,
+                zones,
+                ..SamplerInstrument::default()
+            },
+        )
+    }
+
     #[must_use]
     pub fn automation_parameter_ids(&self) -> Vec<String> {
         let mut ids = Vec::new();
@@ -437,17 +724,15 @@ impl Engine {
             .find(|track| track.id == track_id)
             .ok_or(EngineError::TrackNotFound(track_id))?;
 
-        let clip = Clip {
-            id: Uuid::new_v4(),
+        let clip = Clip::new(
             name,
             start_tick,
-            length_ticks: length_ticks.max(1),
-            disabled: false,
-            payload: ClipPayload::Automation(AutomationClip {
+            length_ticks.max(1),
+            ClipPayload::Automation(AutomationClip {
                 target_parameter_id,
                 points,
             }),
-        };
+        );
 
         track.clips.push(clip.clone());
         self.project.touch();
@@ -464,8 +749,7 @@ impl Engine {
         mut points: Vec<AutomationPoint>,
     ) -> Result<Clip, EngineError> {
         sanitize_automation_points(&mut points);
-        let updated_clip = {
-            let clip = self.find_clip_mut(track_id, clip_id)?;
+        let updated_clip = self.edit_clip(track_id, clip_id, |clip| {
             let automation =
                 clip_automation_mut(clip).ok_or(EngineError::UnsupportedAutomationClip(clip_id))?;
 
@@ -474,8 +758,8 @@ impl Engine {
                     sanitize_automation_target_id(target_parameter_id, track_id);
             }
             automation.points = points;
-            clip.clone()
-        };
+            Ok(())
+        })?;
 
         self.project.touch();
         info!("automation clip updated");
@@ -485,8 +769,16 @@ impl Engine {
     #[instrument(skip(self, request), fields(project_id = %self.project.id, track_id = %request.track_id, clip_name = %request.name))]
     pub fn add_clip(&mut self, request: AddClipRequest) -> Result<Clip, EngineError> {
         let mut payload = request.payload;
-        if let ClipPayload::Pattern(pattern) = &mut payload {
-            normalize_pattern_clip(pattern, self.project.ppq)?;
+        match &mut payload {
+            ClipPayload::Pattern(pattern) => normalize_pattern_clip(pattern, self.project.ppq)?,
+            ClipPayload::Steps(steps) => sanitize_step_clip(steps)?,
+            ClipPayload::Linked(link) => {
+                if self.project.pool_entry(link.content_id).is_none() {
+                    return Err(EngineError::PoolContentNotFound(link.content_id));
+                }
+            }
+            ClipPayload::Midi(midi) => sanitize_controller_lanes(&mut midi.controllers),
+            ClipPayload::Audio(_) | ClipPayload::Automation(_) => {}
         }
 
         let track = self
@@ -496,14 +788,12 @@ impl Engine {
             .find(|track| track.id == request.track_id)
             .ok_or(EngineError::TrackNotFound(request.track_id))?;
 
-        let clip = Clip {
-            id: Uuid::new_v4(),
-            name: request.name,
-            start_tick: request.start_tick,
-            length_ticks: request.length_ticks.max(1),
-            disabled: false,
+        let clip = Clip::new(
+            request.name,
+            request.start_tick,
+            request.length_ticks.max(1),
             payload,
-        };
+        );
 
         track.clips.push(clip.clone());
         self.project.touch();
@@ -511,6 +801,317 @@ impl Engine {
         Ok(clip)
     }
 
+    /// Moves a clip's content into the project pool and turns the clip into a linked
+    /// placement. Clips that are already linked are returned unchanged.
+    #[instrument(skip(self), fields(project_id = %self.project.id, track_id = %track_id, clip_id = %clip_id))]
+    pub fn pool_clip(&mut self, track_id: Uuid, clip_id: Uuid) -> Result<Clip, EngineError> {
+        let clip = self.find_clip_mut(track_id, clip_id)?;
+        if matches!(clip.payload, ClipPayload::Linked(_)) {
+            return Ok(clip.clone());
+        }
+
+        let content_id = Uuid::new_v4();
+        let payload = std::mem::replace(
+            &mut clip.payload,
+            ClipPayload::Linked(LinkedClip { content_id }),
+        );
+        let entry = ClipPoolEntry {
+            id: content_id,
+            name: clip.name.clone(),
+            payload,
+        };
+        let pooled = clip.clone();
+        self.project.clip_pool.push(entry);
+        self.project.touch();
+        info!(content_id = %content_id, "clip content moved to pool");
+        Ok(pooled)
+    }
+
+    /// Places another instance of a clip's content at `start_tick`, pooling the
+    /// source clip first so both placements share one payload.
+    #[instrument(skip(self), fields(project_id = %self.project.id, track_id = %track_id, clip_id = %clip_id, start_tick))]
+    pub fn duplicate_clip_linked(
+        &mut self,
+        track_id: Uuid,
+        clip_id: Uuid,
+        start_tick: u64,
+    ) -> Result<Clip, EngineError> {
+        let source = self.pool_clip(track_id, clip_id)?;
+        let clip = Clip {
+            id: Uuid::new_v4(),
+            start_tick,
+            ..source
+        };
+
+        self.find_track_mut(track_id)?.clips.push(clip.clone());
+        self.project.touch();
+        info!(new_clip_id = %clip.id, "linked clip instance placed");
+        Ok(clip)
+    }
+
+    #[instrument(skip(self), fields(project_id = %self.project.id, track_id = %track_id, content_id = %content_id, start_tick))]
+    pub fn place_pool_content(
+        &mut self,
+        track_id: Uuid,
+        content_id: Uuid,
+        start_tick: u64,
+        length_ticks: u64,
+    ) -> Result<Clip, EngineError> {
+        let name = self
+            .project
+            .pool_entry(content_id)
+            .ok_or(EngineError::PoolContentNotFound(content_id))?
+            .name
+            .clone();
+        let clip = Clip::new(
+            name,
+            start_tick,
+            length_ticks.max(1),
+            ClipPayload::Linked(LinkedClip { content_id }),
+        );
+
+        self.find_track_mut(track_id)?.clips.push(clip.clone());
+        self.project.touch();
+        info!(clip_id = %clip.id, "pool content placed");
+        Ok(clip)
+    }
+
+    /// Forks a linked placement into an independent copy of its pooled content.
+    #[instrument(skip(self), fields(project_id = %self.project.id, track_id = %track_id, clip_id = %clip_id))]
+    pub fn make_clip_unique(&mut self, track_id: Uuid, clip_id: Uuid) -> Result<Clip, EngineError> {
+        let ClipPayload::Linked(link) = &self.find_clip_mut(track_id, clip_id)?.payload else {
+            debug!("clip already owns its content");
+            return Ok(self.find_clip_mut(track_id, clip_id)?.clone());
+        };
+        let content_id = link.content_id;
+        let payload = self
+            .project
+            .pool_entry(content_id)
+            .ok_or(EngineError::PoolContentNotFound(content_id))?
+            .payload
+            .clone();
+
+        let clip = self.find_clip_mut(track_id, clip_id)?;
+        clip.payload = payload;
+        let unique = clip.clone();
+        self.project.touch();
+        info!(content_id = %content_id, "linked clip made unique");
+        Ok(unique)
+    }
+
+    /// Drops pool entries that no placement links to. Returns how many were removed.
+    #[instrument(skip(self), fields(project_id = %self.project.id))]
+    pub fn prune_clip_pool(&mut self) -> usize {
+        let referenced: HashSet<Uuid> = self
+            .project
+            .tracks
+            .iter()
+            .flat_map(|track| track.clips.iter())
+            .filter_map(|clip| match &clip.payload {
+                ClipPayload::Linked(link) => Some(link.content_id),
+                _ => None,
+            })
+            .collect();
+        let before = self.project.clip_pool.len();
+        self.project
+            .clip_pool
+            .retain(|entry| referenced.contains(&entry.id));
+        let removed = before - self.project.clip_pool.len();
+        if removed > 0 {
+            self.project.touch();
+        }
+        info!(removed, "clip pool pruned");
+        removed
+    }
+
+    /// Builds an order list from clips that start on `row_length_ticks` boundaries of
+    /// the given tracks, pooling them so every row references shared pattern content.
+    /// Clips with identical content share one pool entry. The timeline itself is left
+    /// untouched; off-grid clips are skipped, and two clips in one cell are an error.
+    #[instrument(skip(self, track_ids), fields(project_id = %self.project.id, channels = track_ids.len(), row_length_ticks))]
+    pub fn order_list_from_timeline(
+        &mut self,
+        track_ids: Vec<Uuid>,
+        row_length_ticks: u64,
+    ) -> Result<OrderList, EngineError> {
+        if row_length_ticks == 0 {
+            return Err(EngineError::InvalidOrderRowLength(row_length_ticks));
+        }
+
+        let mut placements = Vec::new();
+        let mut cells = HashSet::new();
+        let mut end_tick = 0_u64;
+        for track_id in &track_ids {
+            let track = self.find_track(*track_id)?;
+            for clip in track
+                .clips
+                .iter()
+                .filter(|clip| !clip.disabled && is_order_payload(&clip.payload))
+            {
+                if clip.start_tick % row_length_ticks != 0 {
+                    warn!(clip_id = %clip.id, start_tick = clip.start_tick, "clip is off the order grid, skipping");
+                    continue;
+                }
+                let row = clip.start_tick / row_length_ticks;
+                if !cells.insert((*track_id, row)) {
+                    return Err(EngineError::OrderCellOverlap {
+                        track_id: *track_id,
+                        row,
+                    });
+                }
+                placements.push((*track_id, clip.id, row));
+                end_tick = end_tick.max(clip.start_tick.saturating_add(row_length_ticks));
+            }
+        }
+
+        let row_count = usize::try_from(end_tick / row_length_ticks).unwrap_or_default();
+        let mut rows = vec![
+            OrderRow {
+                patterns: vec![None; track_ids.len()],
+            };
+            row_count
+        ];
+        let mut placement_ids = Vec::with_capacity(placements.len());
+        for (track_id, clip_id, row) in placements {
+            let clip = self.find_clip(track_id, clip_id)?;
+            let shared = self
+                .project
+                .clip_pool
+                .iter()
+                .find(|entry| entry.payload == clip.payload)
+                .map(|entry| entry.id);
+            let content_id = if let Some(content_id) = shared {
+                self.find_clip_mut(track_id, clip_id)?.payload =
+                    ClipPayload::Linked(LinkedClip { content_id });
+                content_id
+            } else {
+                let ClipPayload::Linked(link) = self.pool_clip(track_id, clip_id)?.payload else {
+                    continue;
+                };
+                link.content_id
+            };
+            let column = track_ids
+                .iter()
+                .position(|candidate| *candidate == track_id)
+                .unwrap_or_default();
+            rows[row as usize].patterns[column] = Some(content_id);
+            placement_ids.push(clip_id);
+        }
+
+        let loop_row = self
+            .project
+            .order_list
+            .as_ref()
+            .and_then(|order_list| order_list.loop_row)
+            .filter(|row| *row < row_count);
+        let order_list = OrderList {
+            row_length_ticks,
+            channels: track_ids,
+            rows,
+            loop_row,
+            placement_ids,
+        };
+        self.project.order_list = Some(order_list.clone());
+        self.project.touch();
+        info!(rows = row_count, "order list built from timeline");
+        Ok(order_list)
+    }
+
+    /// Stores an order list and lays its rows out on the channel tracks, replacing the
+    /// clips a previous order list placed. Other clips on those tracks are kept.
+    #[instrument(skip(self, order_list), fields(project_id = %self.project.id, rows = order_list.rows.len(), loop_row = ?order_list.loop_row))]
+    pub fn set_order_list(&mut self, mut order_list: OrderList) -> Result<OrderList, EngineError> {
+        sanitize_order_list(&mut order_list)?;
+        for track_id in &order_list.channels {
+            self.find_track(*track_id)?;
+        }
+        for content_id in order_list
+            .rows
+            .iter()
+            .flat_map(|row| row.patterns.iter().flatten())
+        {
+            if self.project.pool_entry(*content_id).is_none() {
+                return Err(EngineError::PoolContentNotFound(*content_id));
+            }
+        }
+
+        let (previous_placements, previous_loop) =
+            self.project
+                .order_list
+                .as_ref()
+                .map_or((HashSet::new(), false), |previous| {
+                    (
+                        previous
+                            .placement_ids
+                            .iter()
+                            .copied()
+                            .collect::<HashSet<_>>(),
+                        previous.loop_ticks().is_some(),
+                    )
+                });
+        for track in &mut self.project.tracks {
+            track
+                .clips
+                .retain(|clip| !previous_placements.contains(&clip.id));
+        }
+
+        order_list.placement_ids.clear();
+        for (column, track_id) in order_list.channels.iter().enumerate() {
+            let mut placements = Vec::new();
+            for (row_index, row) in order_list.rows.iter().enumerate() {
+                let Some(content_id) = row.patterns[column] else {
+                    continue;
+                };
+                let name = self
+                    .project
+                    .pool_entry(content_id)
+                    .map(|entry| entry.name.clone())
+                    .unwrap_or_default();
+                placements.push(Clip::new(
+                    name,
+                    order_list.row_start_tick(row_index),
+                    order_list.row_length_ticks,
+                    ClipPayload::Linked(LinkedClip { content_id }),
+                ));
+            }
+
+            order_list
+                .placement_ids
+                .extend(placements.iter().map(|clip| clip.id));
+            let track = self.find_track_mut(*track_id)?;
+            track.clips.extend(placements);
+            track.clips.sort_by_key(|clip| clip.start_tick);
+        }
+
+        let transport = &mut self.project.transport;
+        if let Some((loop_start_tick, loop_end_tick)) = order_list.loop_ticks() {
+            transport.loop_start_tick = loop_start_tick;
+            transport.loop_end_tick = loop_end_tick;
+            transport.loop_enabled = true;
+        } else if previous_loop {
+            transport.loop_enabled = false;
+        }
+
+        self.project.order_list = Some(order_list.clone());
+        self.project.touch();
+        info!("order list applied to timeline");
+        Ok(order_list)
+    }
+
+    #[instrument(skip(self), fields(project_id = %self.project.id, loop_row = ?loop_row))]
+    pub fn set_order_loop_row(
+        &mut self,
+        loop_row: Option<usize>,
+    ) -> Result<OrderList, EngineError> {
+        let mut order_list = self
+            .project
+            .order_list
+            .clone()
+            .ok_or(EngineError::OrderListMissing)?;
+        order_list.loop_row = loop_row;
+        self.set_order_list(order_list)
+    }
+
     #[instrument(skip(self), fields(directory = %directory.display()))]
     pub fn scan_audio_assets(&self, directory: &Path) -> Result<Vec<AudioAssetEntry>, EngineError> {
         scan_audio_assets(directory).map_err(Into::into)
@@ -529,6 +1130,7 @@ impl Engine {
         analyze_audio_file_with_cache(path, cache_dir, bucket_size).map_err(Into::into)
     }
 
+    #[allow(clippy::too_many_arguments)]
     #[instrument(skip(self), fields(project_id = %self.project.id, track_id = %track_id, source_path = %source_path.display(), start_tick, bucket_size, cache_dir = ?cache_dir.map(|value| value.display().to_string())))]
     pub fn import_audio_clip(
         &mut self,
@@ -564,26 +1166,21 @@ impl Engine {
             fade_out_seconds: 0.0,
             reverse: false,
             stretch_ratio: 1.0,
+            stretch_mode: AudioStretchMode::Resample,
+            pitch_semitones: 0.0,
+            pitch_cents: 0.0,
+            warp_markers: Vec::new(),
+            source_bpm: None,
             waveform_bucket_size: analysis.peaks.bucket_size,
             waveform_peaks: analysis.peaks.peaks.clone(),
             waveform_cache_path: analysis.cache_path.clone(),
         };
-        sanitize_audio_clip(&mut audio)?;
-        let length_ticks = seconds_to_ticks(
-            audio.effective_duration_seconds(),
-            self.project.bpm,
-            self.project.ppq,
-        )
-        .max(1);
+        sanitize_audio_clip(&mut audio, self.project.bpm, self.project.ppq)?;
+        let length_ticks = audio
+            .timeline_length_ticks(self.project.bpm, self.project.ppq)
+            .max(1);
 
-        let clip = Clip {
-            id: Uuid::new_v4(),
-            name,
-            start_tick,
-            length_ticks,
-            disabled: false,
-            payload: ClipPayload::Audio(audio),
-        };
+        let clip = Clip::new(name, start_tick, length_ticks, ClipPayload::Audio(audio));
 
         let track = self.find_track_mut(track_id)?;
         if !matches!(track.kind, TrackKind::Audio) {
@@ -611,11 +1208,17 @@ impl Engine {
         {
             return Err(EngineError::InvalidAudioStretchRatio(stretch_ratio));
         }
+        if let Some(pitch) = [patch.pitch_semitones, patch.pitch_cents]
+            .into_iter()
+            .flatten()
+            .find(|pitch| !pitch.is_finite())
+        {
+            return Err(EngineError::InvalidAudioPitchShift(pitch));
+        }
 
         let bpm = self.project.bpm;
         let ppq = self.project.ppq;
-        let updated_clip = {
-            let clip = self.find_clip_mut(track_id, clip_id)?;
+        let updated_clip = self.edit_clip(track_id, clip_id, |clip| {
             let audio = match &mut clip.payload {
                 ClipPayload::Audio(audio) => audio,
                 _ => return Err(EngineError::UnsupportedAudioClip(clip_id)),
@@ -645,18 +1248,125 @@ impl Engine {
             if let Some(stretch_ratio) = patch.stretch_ratio {
                 audio.stretch_ratio = stretch_ratio.max(0.01);
             }
+            if let Some(stretch_mode) = patch.stretch_mode {
+                audio.stretch_mode = stretch_mode;
+            }
+            if let Some(pitch_semitones) = patch.pitch_semitones {
+                audio.pitch_semitones = pitch_semitones;
+            }
+            if let Some(pitch_cents) = patch.pitch_cents {
+                audio.pitch_cents = pitch_cents;
+            }
 
-            sanitize_audio_clip(audio)?;
-            clip.length_ticks =
-                seconds_to_ticks(audio.effective_duration_seconds(), bpm, ppq).max(1);
-            clip.clone()
-        };
+            sanitize_audio_clip(audio, bpm, ppq)?;
+            clip.length_ticks = audio.timeline_length_ticks(bpm, ppq).max(1);
+            Ok(())
+        })?;
 
         self.project.touch();
         info!("audio clip patched");
         Ok(updated_clip)
     }
 
+    /// Replaces an audio clip's warp markers and refits the clip to them. An
+    /// empty list returns the clip to plain `stretch_ratio` playback.
+    #[instrument(skip(self, markers), fields(project_id = %self.project.id, track_id = %track_id, clip_id = %clip_id, markers = markers.len()))]
+    pub fn set_audio_warp_markers(
+        &mut self,
+        track_id: Uuid,
+        clip_id: Uuid,
+        markers: Vec<WarpMarker>,
+    ) -> Result<Clip, EngineError> {
+        let updated_clip = self.edit_audio_warp(track_id, clip_id, |audio, _| {
+            audio.warp_markers = markers;
+            audio.source_bpm = None;
+        })?;
+
+        self.project.touch();
+        info!("audio warp markers replaced");
+        Ok(updated_clip)
+    }
+
+    /// Conforms a loop recorded at `source_bpm` to the project tempo by pinning
+    /// every source beat to a project beat.
+    #[instrument(skip(self), fields(project_id = %self.project.id, track_id = %track_id, clip_id = %clip_id, source_bpm))]
+    pub fn auto_warp_audio_clip(
+        &mut self,
+        track_id: Uuid,
+        clip_id: Uuid,
+        source_bpm: f64,
+    ) -> Result<Clip, EngineError> {
+        if !source_bpm.is_finite() || !(MIN_SOURCE_BPM..=MAX_SOURCE_BPM).contains(&source_bpm) {
+            return Err(EngineError::InvalidSourceBpm(source_bpm));
+        }
+        let updated_clip = self.edit_audio_warp(track_id, clip_id, |audio, ppq| {
+            let beat_seconds = 60.0 / source_bpm;
+            let duration = audio.source_duration_seconds.max(0.0);
+            let beats = (duration / beat_seconds).ceil().max(1.0) as u64;
+            audio.warp_markers = (0..=beats)
+                .map(|beat| WarpMarker {
+                    source_seconds: beat as f64 * beat_seconds,
+                    tick: beat * u64::from(ppq),
+                })
+                .collect();
+            audio.source_bpm = Some(source_bpm);
+        })?;
+
+        self.project.touch();
+        info!("audio clip auto-warped");
+        Ok(updated_clip)
+    }
+
+    /// Sets offset, loop, transpose and velocity scale on the placement itself, so
+    /// linked placements of shared content can differ.
+    #[instrument(skip(self), fields(project_id = %self.project.id, track_id = %track_id, clip_id = %clip_id))]
+    pub fn patch_clip_playback(
+        &mut self,
+        track_id: Uuid,
+        clip_id: Uuid,
+        patch: ClipPlaybackPatch,
+    ) -> Result<Clip, EngineError> {
+        if let Some(velocity_scale) = patch.velocity_scale
+            && (!velocity_scale.is_finite() || velocity_scale <= 0.0)
+        {
+            return Err(EngineError::InvalidClipVelocityScale(velocity_scale));
+        }
+        // Very short loops would repeat content once per handful of ticks.
+        let min_loop_ticks = u64::from(self.project.ppq / 4).max(1);
+        if let Some(Some(loop_length_ticks)) = patch.loop_length_ticks
+            && loop_length_ticks > 0
+            && loop_length_ticks < min_loop_ticks
+        {
+            return Err(EngineError::InvalidClipLoopLength(loop_length_ticks));
+        }
+
+        let clip = self.find_clip_mut(track_id, clip_id)?;
+        if let Some(content_offset_ticks) = patch.content_offset_ticks {
+            clip.content_offset_ticks = content_offset_ticks;
+        }
+        if let Some(loop_length_ticks) = patch.loop_length_ticks {
+            clip.loop_length_ticks = loop_length_ticks.filter(|length| *length > 0);
+        }
+        if let Some(transpose_semitones) = patch.transpose_semitones {
+            clip.transpose_semitones =
+                transpose_semitones.clamp(-MAX_CLIP_TRANSPOSE, MAX_CLIP_TRANSPOSE);
+        }
+        if let Some(velocity_scale) = patch.velocity_scale {
+            clip.velocity_scale = velocity_scale.min(MAX_CLIP_VELOCITY_SCALE);
+        }
+        let updated_clip = clip.clone();
+
+        self.project.touch();
+        info!(
+            content_offset_ticks = updated_clip.content_offset_ticks,
+            loop_length_ticks = ?updated_clip.loop_length_ticks,
+            transpose_semitones = updated_clip.transpose_semitones,
+            velocity_scale = updated_clip.velocity_scale,
+            "clip playback patched"
+        );
+        Ok(updated_clip)
+    }
+
     #[instrument(skip(self), fields(project_id = %self.project.id, clip_id = %clip_id, track_id = %track_id))]
     pub fn move_clip(
         &mut self,
@@ -688,36 +1398,636 @@ impl Engine {
         Ok(updated_clip)
     }
 
-    #[instrument(skip(self, notes), fields(project_id = %self.project.id, track_id = %track_id, clip_id = %clip_id, notes = notes.len()))]
-    pub fn upsert_clip_notes(
+    /// Cuts a clip in two at an absolute tick. The left part keeps the clip id.
+    #[instrument(skip(self), fields(project_id = %self.project.id, track_id = %track_id, clip_id = %clip_id, tick))]
+    pub fn split_clip(
         &mut self,
         track_id: Uuid,
         clip_id: Uuid,
-        mut notes: Vec<MidiNote>,
-    ) -> Result<Clip, EngineError> {
-        let ppq = self.project.ppq;
-        for note in &mut notes {
-            sanitize_note(note);
+        tick: u64,
+    ) -> Result<(Clip, Clip), EngineError> {
+        let mut parts = self.cut_clip(track_id, clip_id, vec![tick])?;
+        let right = parts.pop().ok_or(EngineError::ClipNotFound(clip_id))?;
+        let left = parts.pop().ok_or(EngineError::ClipNotFound(clip_id))?;
+        self.project.touch();
+        info!(right_clip_id = %right.id, "clip split");
+        Ok((left, right))
+    }
+
+    /// Cuts a clip into `parts` pieces of equal length (the last absorbs any
+    /// remainder).
+    #[instrument(skip(self), fields(project_id = %self.project.id, track_id = %track_id, clip_id = %clip_id, parts))]
+    pub fn slice_clip(
+        &mut self,
+        track_id: Uuid,
+        clip_id: Uuid,
+        parts: u32,
+    ) -> Result<Vec<Clip>, EngineError> {
+        let clip = self.find_clip_mut(track_id, clip_id)?;
+        let part_ticks = clip.length_ticks / u64::from(parts.max(1));
+        if parts < 2 || part_ticks == 0 {
+            return Err(EngineError::InvalidSliceCount(parts));
         }
+        let ticks = (1..u64::from(parts))
+            .map(|part| clip.start_tick + part * part_ticks)
+            .collect();
 
-        let updated_clip = {
-            let clip = self.find_clip_mut(track_id, clip_id)?;
-            {
-                let target =
-                    clip_note_vec_mut(clip).ok_or(EngineError::UnsupportedClipPayload(clip_id))?;
-                *target = notes;
+        let slices = self.cut_clip(track_id, clip_id, ticks)?;
+        self.project.touch();
+        info!(slices = slices.len(), "clip sliced");
+        Ok(slices)
+    }
+
+    /// Cuts a clip at every multiple of `grid_ticks` on the timeline that falls
+    /// inside it.
+    #[instrument(skip(self), fields(project_id = %self.project.id, track_id = %track_id, clip_id = %clip_id, grid_ticks))]
+    pub fn slice_clip_to_grid(
+        &mut self,
+        track_id: Uuid,
+        clip_id: Uuid,
+        grid_ticks: u64,
+    ) -> Result<Vec<Clip>, EngineError> {
+        if grid_ticks == 0 {
+            return Err(EngineError::InvalidSliceGrid(grid_ticks));
+        }
+        let clip = self.find_clip_mut(track_id, clip_id)?;
+        let first = (clip.start_tick / grid_ticks + 1) * grid_ticks;
+        let ticks = (first..clip.end_tick())
+            .step_by(grid_ticks as usize)
+            .collect();
+
+        let slices = self.cut_clip(track_id, clip_id, ticks)?;
+        self.project.touch();
+        info!(slices = slices.len(), "clip sliced to grid");
+        Ok(slices)
+    }
+
+    /// Cuts an audio clip at each transient found at `sensitivity` (see
+    /// [`detect_transients`]).
+    #[instrument(skip(self), fields(project_id = %self.project.id, track_id = %track_id, clip_id = %clip_id, sensitivity))]
+    pub fn slice_audio_clip_at_transients(
+        &mut self,
+        track_id: Uuid,
+        clip_id: Uuid,
+        sensitivity: f32,
+    ) -> Result<Vec<Clip>, EngineError> {
+        let (bpm, ppq) = (self.project.bpm, self.project.ppq);
+        let clip = self.find_clip(track_id, clip_id)?;
+        let Some(ClipPayload::Audio(audio)) = self.project.resolved_payload(clip) else {
+            return Err(EngineError::UnsupportedAudioClip(clip_id));
+        };
+        let start_tick = clip.start_tick;
+        let ticks: Vec<u64> =
+            transient_slice_ticks(audio, clip.length_ticks, sensitivity, bpm, ppq)?
+                .into_iter()
+                .skip(1)
+                .map(|tick| start_tick + tick)
+                .collect();
+        if ticks.is_empty() {
+            return Err(EngineError::NoTransients(clip_id));
+        }
+
+        let slices = self.cut_clip(track_id, clip_id, ticks)?;
+        self.project.touch();
+        info!(slices = slices.len(), "audio clip sliced at transients");
+        Ok(slices)
+    }
+
+    /// Turns an audio clip into a sampler with one one-shot zone per transient
+    /// slice, on consecutive keys from `first_note`, plus a MIDI clip on
+    /// `target_track_id` that retriggers the slices in their original timing.
+    /// The sampler is named after the clip, numbered if the target already has an
+    /// instrument of that name. The slices play at source speed, so
+    /// the groove follows later tempo changes without changing pitch.
+    #[instrument(skip(self), fields(project_id = %self.project.id, track_id = %track_id, clip_id = %clip_id, target_track_id = %target_track_id, sensitivity, first_note))]
+    pub fn slice_audio_clip_to_sampler(
+        &mut self,
+        track_id: Uuid,
+        clip_id: Uuid,
+        target_track_id: Uuid,
+        sensitivity: f32,
+        first_note: u8,
+    ) -> Result<Clip, EngineError> {
+        let (bpm, ppq) = (self.project.bpm, self.project.ppq);
+        let target = self.find_track(target_track_id)?;
+        ensure_note_track(target)?;
+        let clip = self.find_clip(track_id, clip_id)?;
+        let Some(ClipPayload::Audio(audio)) = self.project.resolved_payload(clip) else {
+            return Err(EngineError::UnsupportedAudioClip(clip_id));
+        };
+        if audio.reverse {
+            return Err(EngineError::ReversedAudioSlices(clip_id));
+        }
+        let mut bounds = transient_slice_ticks(audio, clip.length_ticks, sensitivity, bpm, ppq)?;
+        if bounds.len() < 2 {
+            return Err(EngineError::NoTransients(clip_id));
+        }
+        let first_note = first_note.min(127);
+        if bounds.len() > usize::from(128 - first_note) {
+            return Err(EngineError::InvalidSliceCount(bounds.len() as u32));
+        }
+        bounds.push(clip.length_ticks);
+
+        let mut zones = Vec::with_capacity(bounds.len() - 1);
+        let mut notes = Vec::with_capacity(bounds.len() - 1);
+        for (index, slice) in bounds.windows(2).enumerate() {
+            let key = first_note + index as u8;
+            zones.push(SamplerZone {
+                source_path: audio.source_path.clone(),
+                root_note: key,
+                low_note: key,
+                high_note: key,
+                tune_cents: ((audio.pitch_ratio().log2() * 1_200.0) as f32)
+                    .clamp(-1_200.0, 1_200.0),
+                gain_db: audio.gain_db,
+                one_shot: true,
+                sample_start_seconds: audio.source_seconds_at(slice[0] as f64, bpm, ppq),
+                sample_end_seconds: audio.source_seconds_at(slice[1] as f64, bpm, ppq),
+                ..SamplerZone::default()
+            });
+            notes.push(MidiNote {
+                pitch: key,
+                velocity: 127,
+                start_tick: slice[0],
+                length_ticks: (slice[1] - slice[0]).max(1),
+                channel: 0,
+                release_velocity: None,
+            });
+        }
+        let slice_count = notes.len();
+        let name = clip.name.clone();
+        let sampler_name = unused_instrument_name(target, &name);
+        let (start_tick, length_ticks) = (clip.start_tick, clip.length_ticks);
+
+        self.upsert_sampler(
+            target_track_id,
+            SamplerInstrument {
+                name: sampler_name.clone(),
+                zones,
+                ..SamplerInstrument::default()
+            },
+        )?;
+        let midi_clip = self.add_clip(AddClipRequest {
+            track_id: target_track_id,
+            name,
+            start_tick,
+            length_ticks,
+            payload: ClipPayload::Midi(MidiClip {
+                instrument: Some(sampler_name),
+                notes,
+                controllers: Vec::new(),
+            }),
+        })?;
+
+        info!(slices = slice_count, "audio clip sliced to sampler");
+        Ok(midi_clip)
+    }
+
+    /// Transcribes a monophonic audio clip into a new MIDI clip, or a pattern
+    /// clip for `options.source_chip`, at the same position on
+    /// `target_track_id`. Pitches include the clip's pitch shift and resampling,
+    /// and velocities follow each note's level against the loudest note. Notes a
+    /// chip can't reach are moved by octaves into its range.
+    #[instrument(skip(self, options), fields(project_id = %self.project.id, track_id = %track_id, clip_id = %clip_id, target_track_id = %target_track_id))]
+    pub fn transcribe_audio_clip(
+        &mut self,
+        track_id: Uuid,
+        clip_id: Uuid,
+        target_track_id: Uuid,
+        options: &TranscriptionOptions,
+    ) -> Result<Clip, EngineError> {
+        let (bpm, ppq) = (self.project.bpm, self.project.ppq);
+        let groove = match &options.quantize {
+            Some(quantize) => self.quantize_groove(quantize)?,
+            None => None,
+        };
+        let source_chip = match options.source_chip.as_deref().map(str::trim) {
+            Some(source_chip) => {
+                let chip = VgmChip::for_source(source_chip)
+                    .ok_or_else(|| EngineError::UnknownSourceChip(source_chip.to_string()))?;
+                Some((source_chip.to_string(), chip))
+            }
+            None => None,
+        };
+        ensure_note_track(self.find_track(target_track_id)?)?;
+        let clip = self.find_clip(track_id, clip_id)?;
+        let Some(ClipPayload::Audio(audio)) = self.project.resolved_payload(clip) else {
+            return Err(EngineError::UnsupportedAudioClip(clip_id));
+        };
+
+        let decoded = decode_audio_file_mono(Path::new(&audio.source_path))?;
+        let detected = transcribe::segment_notes(
+            &transcribe::track_pitch(&decoded, options.threshold),
+            options.min_note_seconds.max(0.0),
+        );
+        let loudest = detected.iter().map(|note| note.level).fold(0.0, f32::max);
+        let (trim_start, trim_end) = clip_audio_trim(audio);
+        let seconds_per_tick = 60.0 / (bpm * f64::from(ppq));
+        let mut notes: Vec<MidiNote> = detected
+            .iter()
+            .filter_map(|note| {
+                let (from, to) = (
+                    note.start_seconds.max(trim_start),
+                    note.end_seconds.min(trim_end),
+                );
+                if to <= from {
+                    return None;
+                }
+                let (first, second) = (
+                    audio.clip_tick_at(from, bpm, ppq),
+                    audio.clip_tick_at(to, bpm, ppq),
+                );
+                let (start, end) = (first.min(second), first.max(second));
+                let playback_rate = match audio.stretch_mode {
+                    AudioStretchMode::Resample if end > start => {
+                        (to - from) / ((end - start) * seconds_per_tick)
+                    }
+                    _ => 1.0,
+                };
+                let pitch =
+                    (note.pitch + 12.0 * (playback_rate * audio.pitch_ratio()).log2()).round();
+                let start_tick = start.round().max(0.0) as u64;
+                if !(0.0..=127.0).contains(&pitch) || start_tick >= clip.length_ticks {
+                    return None;
+                }
+                Some(MidiNote {
+                    pitch: pitch as u8,
+                    velocity: transcribe::level_velocity(note.level, loudest),
+                    start_tick,
+                    length_ticks: (end.round() as u64)
+                        .saturating_sub(start_tick)
+                        .clamp(1, clip.length_ticks - start_tick),
+                    channel: 0,
+                    release_velocity: None,
+                })
+            })
+            .collect();
+        if notes.is_empty() {
+            return Err(EngineError::NoPitchedNotes(clip_id));
+        }
+        if let Some(quantize) = &options.quantize {
+            groove::quantize_notes(&mut notes, quantize, groove.as_ref());
+            for note in &mut notes {
+                sanitize_note(note);
+            }
+            notes.sort_by_key(|note| note.start_tick);
+        }
+        if let Some((_, chip)) = &source_chip {
+            let range = chip.note_range();
+            for note in &mut notes {
+                note.pitch = fold_into_range(note.pitch, &range);
+            }
+        }
+
+        let note_count = notes.len();
+        let payload = match source_chip {
+            Some((source_chip, _)) => ClipPayload::Pattern(PatternClip {
+                source_chip,
+                notes,
+                ..PatternClip::default()
+            }),
+            None => ClipPayload::Midi(MidiClip {
+                instrument: None,
+                notes,
+                controllers: Vec::new(),
+            }),
+        };
+        let transcribed = self.add_clip(AddClipRequest {
+            track_id: target_track_id,
+            name: clip.name.clone(),
+            start_tick: clip.start_tick,
+            length_ticks: clip.length_ticks,
+            payload,
+        })?;
+
+        info!(notes = note_count, "audio clip transcribed");
+        Ok(transcribed)
+    }
+
+    /// Joins adjacent clips of the same kind into the earliest one. Each clip's
+    /// offset, loop, transpose and velocity scale are baked into the result.
+    #[instrument(skip(self, clip_ids), fields(project_id = %self.project.id, track_id = %track_id, clips = clip_ids.len()))]
+    pub fn glue_clips(&mut self, track_id: Uuid, clip_ids: &[Uuid]) -> Result<Clip, EngineError> {
+        let (bpm, ppq) = (self.project.bpm, self.project.ppq);
+        let track = self.find_track_mut(track_id)?;
+        let mut clips = clip_ids
+            .iter()
+            .map(|clip_id| {
+                track
+                    .clips
+                    .iter()
+                    .find(|clip| clip.id == *clip_id)
+                    .cloned()
+                    .ok_or(EngineError::ClipNotFound(*clip_id))
+            })
+            .collect::<Result<Vec<_>, _>>()?;
+        clips.sort_by_key(|clip| clip.start_tick);
+        clips.dedup_by_key(|clip| clip.id);
+
+        let glued = glue_clip_contents(&clips, bpm, ppq)?;
+        let index = track
+            .clips
+            .iter()
+            .position(|clip| clip.id == glued.id)
+            .ok_or(EngineError::ClipNotFound(glued.id))?;
+        track.clips[index] = glued.clone();
+        track
+            .clips
+            .retain(|clip| clip.id == glued.id || !clip_ids.contains(&clip.id));
+
+        self.project.touch();
+        info!(clip_id = %glued.id, glued = clips.len(), "clips glued");
+        Ok(glued)
+    }
+
+    /// Opens `length_ticks` of empty time at `at_tick` on every track. Clips
+    /// straddling the insert point are split and their right part moves with
+    /// everything after it, as do the playhead and loop region. Inside an order
+    /// list the insert must cover whole rows, which open up as empty rows.
+    #[instrument(skip(self), fields(project_id = %self.project.id, at_tick, length_ticks))]
+    pub fn insert_time(&mut self, at_tick: u64, length_ticks: u64) -> Result<(), EngineError> {
+        if length_ticks == 0 {
+            return Err(EngineError::InvalidTimeRange {
+                start_tick: at_tick,
+                end_tick: at_tick,
+            });
+        }
+
+        let tracks = self.ripple_tracks(&[at_tick], |mut clip| {
+            if clip.start_tick >= at_tick {
+                clip.start_tick = clip.start_tick.saturating_add(length_ticks);
+            }
+            Some(clip)
+        })?;
+        let order_list = self.rippled_order_list(&tracks, at_tick, length_ticks, true)?;
+        self.project.tracks = tracks;
+        self.project.order_list = order_list;
+        self.ripple_transport(|tick| {
+            if tick >= at_tick {
+                tick.saturating_add(length_ticks)
+            } else {
+                tick
+            }
+        });
+
+        self.project.touch();
+        info!("time inserted");
+        Ok(())
+    }
+
+    /// Removes `start_tick..end_tick` from every track and pulls later material
+    /// back to close the gap. Clips straddling either edge are split first.
+    /// Inside an order list the range must cover whole rows, which are removed.
+    #[instrument(skip(self), fields(project_id = %self.project.id, start_tick, end_tick))]
+    pub fn delete_time(&mut self, start_tick: u64, end_tick: u64) -> Result<(), EngineError> {
+        if end_tick <= start_tick {
+            return Err(EngineError::InvalidTimeRange {
+                start_tick,
+                end_tick,
+            });
+        }
+        let removed = end_tick - start_tick;
+
+        let tracks = self.ripple_tracks(&[start_tick, end_tick], |mut clip| {
+            if clip.start_tick >= end_tick {
+                clip.start_tick -= removed;
+            } else if clip.start_tick >= start_tick {
+                return None;
+            }
+            Some(clip)
+        })?;
+        let order_list = self.rippled_order_list(&tracks, start_tick, removed, false)?;
+        self.project.tracks = tracks;
+        self.project.order_list = order_list;
+        self.ripple_transport(|tick| {
+            if tick >= end_tick {
+                tick - removed
+            } else {
+                tick.min(start_tick)
+            }
+        });
+
+        self.project.touch();
+        info!("time deleted");
+        Ok(())
+    }
+
+    /// Tracks with every clip cut at `boundaries` and each piece passed through
+    /// `place`, which may move or drop it. The project is left untouched so a
+    /// failed ripple changes nothing.
+    fn ripple_tracks(
+        &self,
+        boundaries: &[u64],
+        mut place: impl FnMut(Clip) -> Option<Clip>,
+    ) -> Result<Vec<Track>, EngineError> {
+        let (bpm, ppq) = (self.project.bpm, self.project.ppq);
+        let mut tracks = self.project.tracks.clone();
+        for track in &mut tracks {
+            let mut clips = Vec::with_capacity(track.clips.len());
+            for clip in track.clips.drain(..) {
+                let mut rest = clip;
+                for boundary in boundaries {
+                    if *boundary <= rest.start_tick || *boundary >= rest.end_tick() {
+                        continue;
+                    }
+                    let (left, right) =
+                        split_clip_content(&rest, boundary - rest.start_tick, bpm, ppq)?;
+                    clips.extend(place(left));
+                    rest = right;
+                }
+                clips.extend(place(rest));
+            }
+            track.clips = clips;
+        }
+        Ok(tracks)
+    }
+
+    /// The order list after inserting or removing `length_ticks` at `tick`.
+    /// Rows are laid out back to back from tick 0, so an edit inside the list
+    /// must cover whole rows; the loop row moves with the rows around it.
+    fn rippled_order_list(
+        &self,
+        tracks: &[Track],
+        tick: u64,
+        length_ticks: u64,
+        insert: bool,
+    ) -> Result<Option<OrderList>, EngineError> {
+        let Some(order_list) = &self.project.order_list else {
+            return Ok(None);
+        };
+        if tick >= order_list.length_ticks() {
+            return Ok(Some(order_list.clone()));
+        }
+        let row_length_ticks = order_list.row_length_ticks.max(1);
+        if !tick.is_multiple_of(row_length_ticks) || !length_ticks.is_multiple_of(row_length_ticks)
+        {
+            return Err(EngineError::MisalignedOrderListEdit {
+                tick,
+                length_ticks,
+                row_length_ticks,
+            });
+        }
+
+        let mut order_list = order_list.clone();
+        let row = (tick / row_length_ticks) as usize;
+        let count = length_ticks / row_length_ticks;
+        if insert {
+            if count > MAX_INSERTED_ORDER_ROWS {
+                return Err(EngineError::TooManyOrderRows {
+                    rows: count,
+                    max: MAX_INSERTED_ORDER_ROWS,
+                });
+            }
+            let count = count as usize;
+            let empty = OrderRow {
+                patterns: vec![None; order_list.channels.len()],
+            };
+            order_list
+                .rows
+                .splice(row..row, std::iter::repeat_n(empty, count));
+            order_list.loop_row = order_list.loop_row.map(|loop_row| {
+                if loop_row >= row {
+                    loop_row + count
+                } else {
+                    loop_row
+                }
+            });
+        } else {
+            let end = usize::try_from(count)
+                .map_or(order_list.rows.len(), |count| row.saturating_add(count))
+                .min(order_list.rows.len());
+            order_list.rows.drain(row..end);
+            // A deleted loop row hands the loop to the first row after the cut.
+            let rows = order_list.rows.len();
+            order_list.loop_row = order_list.loop_row.and_then(|loop_row| {
+                if loop_row >= end {
+                    Some(loop_row - (end - row))
+                } else if loop_row >= row {
+                    (row < rows).then_some(row)
+                } else {
+                    Some(loop_row)
+                }
+            });
+            order_list.placement_ids.retain(|id| {
+                tracks
+                    .iter()
+                    .any(|track| track.clips.iter().any(|clip| clip.id == *id))
+            });
+        }
+        Ok(Some(order_list))
+    }
+
+    fn ripple_transport(&mut self, shift: impl Fn(u64) -> u64) {
+        let transport = &mut self.project.transport;
+        transport.playhead_tick = shift(transport.playhead_tick);
+        let loop_length = transport
+            .loop_end_tick
+            .saturating_sub(transport.loop_start_tick);
+        transport.loop_start_tick = shift(transport.loop_start_tick);
+        transport.loop_end_tick = shift(transport.loop_end_tick);
+        if transport.loop_end_tick <= transport.loop_start_tick {
+            // The whole loop was deleted; keep its length at the cut.
+            transport.loop_end_tick = transport.loop_start_tick + loop_length.max(1);
+        }
+    }
+
+    /// Replaces a clip with the pieces between the given absolute ticks.
+    fn cut_clip(
+        &mut self,
+        track_id: Uuid,
+        clip_id: Uuid,
+        mut ticks: Vec<u64>,
+    ) -> Result<Vec<Clip>, EngineError> {
+        let (bpm, ppq) = (self.project.bpm, self.project.ppq);
+        let track = self.find_track_mut(track_id)?;
+        let index = track
+            .clips
+            .iter()
+            .position(|clip| clip.id == clip_id)
+            .ok_or(EngineError::ClipNotFound(clip_id))?;
+        let clip = &track.clips[index];
+        ticks.sort_unstable();
+        ticks.dedup();
+        if let Some(tick) = ticks
+            .iter()
+            .find(|tick| **tick <= clip.start_tick || **tick >= clip.end_tick())
+        {
+            return Err(EngineError::InvalidSplitTick {
+                clip_id,
+                tick: *tick,
+            });
+        }
+
+        let mut pieces = Vec::with_capacity(ticks.len() + 1);
+        let mut rest = clip.clone();
+        for tick in ticks {
+            let (left, right) = split_clip_content(&rest, tick - rest.start_tick, bpm, ppq)?;
+            pieces.push(left);
+            rest = right;
+        }
+        pieces.push(rest);
+        track.clips.splice(index..=index, pieces.iter().cloned());
+        debug!(pieces = pieces.len(), "clip cut");
+        Ok(pieces)
+    }
+
+    #[instrument(skip(self, notes), fields(project_id = %self.project.id, track_id = %track_id, clip_id = %clip_id, notes = notes.len()))]
+    pub fn upsert_clip_notes(
+        &mut self,
+        track_id: Uuid,
+        clip_id: Uuid,
+        mut notes: Vec<MidiNote>,
+    ) -> Result<Clip, EngineError> {
+        let ppq = self.project.ppq;
+        for note in &mut notes {
+            sanitize_note(note);
+        }
+
+        let updated_clip = self.edit_clip(track_id, clip_id, |clip| {
+            {
+                let target =
+                    clip_note_vec_mut(clip).ok_or(EngineError::UnsupportedClipPayload(clip_id))?;
+                *target = notes;
             }
             if let Some(pattern) = clip_pattern_mut(clip) {
                 sync_pattern_rows_from_notes(pattern, ppq)?;
             }
-            clip.clone()
-        };
+            Ok(())
+        })?;
 
         self.project.touch();
         info!("clip notes replaced");
         Ok(updated_clip)
     }
 
+    /// Replaces one controller lane of a MIDI clip. An empty point list removes
+    /// the lane.
+    #[instrument(skip(self, points), fields(project_id = %self.project.id, track_id = %track_id, clip_id = %clip_id, target = ?target, points = points.len()))]
+    pub fn upsert_controller_lane(
+        &mut self,
+        track_id: Uuid,
+        clip_id: Uuid,
+        target: ControllerTarget,
+        mut points: Vec<AutomationPoint>,
+    ) -> Result<Clip, EngineError> {
+        sanitize_controller_points(target, &mut points);
+        let updated_clip = self.edit_clip(track_id, clip_id, |clip| {
+            let ClipPayload::Midi(midi) = &mut clip.payload else {
+                return Err(EngineError::UnsupportedMidiClip(clip_id));
+            };
+            midi.controllers.retain(|lane| lane.target != target);
+            if !points.is_empty() {
+                midi.controllers.push(ControllerLane { target, points });
+                midi.controllers.sort_by_key(|lane| lane.target);
+            }
+            Ok(())
+        })?;
+
+        self.project.touch();
+        info!("controller lane updated");
+        Ok(updated_clip)
+    }
+
     #[instrument(skip(self, note), fields(project_id = %self.project.id, track_id = %track_id, clip_id = %clip_id))]
     pub fn add_clip_note(
         &mut self,
@@ -728,8 +2038,7 @@ impl Engine {
         let ppq = self.project.ppq;
         sanitize_note(&mut note);
 
-        let updated_clip = {
-            let clip = self.find_clip_mut(track_id, clip_id)?;
+        let updated_clip = self.edit_clip(track_id, clip_id, |clip| {
             {
                 let notes =
                     clip_note_vec_mut(clip).ok_or(EngineError::UnsupportedClipPayload(clip_id))?;
@@ -739,8 +2048,8 @@ impl Engine {
             if let Some(pattern) = clip_pattern_mut(clip) {
                 sync_pattern_rows_from_notes(pattern, ppq)?;
             }
-            clip.clone()
-        };
+            Ok(())
+        })?;
 
         self.project.touch();
         info!("note added to clip");
@@ -755,8 +2064,7 @@ impl Engine {
         note_index: usize,
     ) -> Result<Clip, EngineError> {
         let ppq = self.project.ppq;
-        let updated_clip = {
-            let clip = self.find_clip_mut(track_id, clip_id)?;
+        let updated_clip = self.edit_clip(track_id, clip_id, |clip| {
             {
                 let notes =
                     clip_note_vec_mut(clip).ok_or(EngineError::UnsupportedClipPayload(clip_id))?;
@@ -768,8 +2076,8 @@ impl Engine {
             if let Some(pattern) = clip_pattern_mut(clip) {
                 sync_pattern_rows_from_notes(pattern, ppq)?;
             }
-            clip.clone()
-        };
+            Ok(())
+        })?;
 
         self.project.touch();
         info!("note removed from clip");
@@ -784,8 +2092,7 @@ impl Engine {
         semitones: i16,
     ) -> Result<Clip, EngineError> {
         let ppq = self.project.ppq;
-        let updated_clip = {
-            let clip = self.find_clip_mut(track_id, clip_id)?;
+        let updated_clip = self.edit_clip(track_id, clip_id, |clip| {
             {
                 let notes =
                     clip_note_vec_mut(clip).ok_or(EngineError::UnsupportedClipPayload(clip_id))?;
@@ -799,8 +2106,8 @@ impl Engine {
             if let Some(pattern) = clip_pattern_mut(clip) {
                 sync_pattern_rows_from_notes(pattern, ppq)?;
             }
-            clip.clone()
-        };
+            Ok(())
+        })?;
 
         self.project.touch();
         info!("clip notes transposed");
@@ -813,22 +2120,29 @@ impl Engine {
         track_id: Uuid,
         clip_id: Uuid,
         grid_ticks: u64,
+    ) -> Result<Clip, EngineError> {
+        self.quantize_clip_notes_with(track_id, clip_id, &QuantizeOptions::grid(grid_ticks))
+    }
+
+    /// Quantize with strength, swing, triplet grids, end handling and an optional
+    /// project groove template.
+    #[instrument(skip(self), fields(project_id = %self.project.id, track_id = %track_id, clip_id = %clip_id, grid_ticks = options.grid_ticks, groove = ?options.groove))]
+    pub fn quantize_clip_notes_with(
+        &mut self,
+        track_id: Uuid,
+        clip_id: Uuid,
+        options: &QuantizeOptions,
     ) -> Result<Clip, EngineError> {
         let ppq = self.project.ppq;
-        if grid_ticks == 0 {
-            return Err(EngineError::InvalidQuantizeGrid(grid_ticks));
-        }
+        let groove = self.quantize_groove(options)?;
 
-        let updated_clip = {
-            let clip = self.find_clip_mut(track_id, clip_id)?;
+        let updated_clip = self.edit_clip(track_id, clip_id, |clip| {
             {
                 let notes =
                     clip_note_vec_mut(clip).ok_or(EngineError::UnsupportedClipPayload(clip_id))?;
 
+                groove::quantize_notes(notes, options, groove.as_ref());
                 for note in notes.iter_mut() {
-                    note.start_tick = round_to_grid(note.start_tick, grid_ticks);
-                    note.length_ticks =
-                        round_to_grid(note.length_ticks.max(1), grid_ticks).max(grid_ticks);
                     sanitize_note(note);
                 }
                 notes.sort_by_key(|candidate| candidate.start_tick);
@@ -836,14 +2150,355 @@ impl Engine {
             if let Some(pattern) = clip_pattern_mut(clip) {
                 sync_pattern_rows_from_notes(pattern, ppq)?;
             }
-            clip.clone()
-        };
+            Ok(())
+        })?;
 
         self.project.touch();
         info!("clip notes quantized");
         Ok(updated_clip)
     }
 
+    /// Captures a groove template from a clip's notes, or from the transients of an
+    /// audio clip, and stores it on the project under `name`, replacing any groove
+    /// of the same name.
+    #[instrument(skip(self), fields(project_id = %self.project.id, track_id = %track_id, clip_id = %clip_id, groove = %name, grid_ticks, steps))]
+    pub fn extract_clip_groove(
+        &mut self,
+        track_id: Uuid,
+        clip_id: Uuid,
+        name: &str,
+        grid_ticks: u64,
+        steps: usize,
+    ) -> Result<GrooveTemplate, EngineError> {
+        if grid_ticks == 0 {
+            return Err(EngineError::InvalidQuantizeGrid(grid_ticks));
+        }
+        if steps == 0 || steps > MAX_GROOVE_STEPS {
+            return Err(EngineError::InvalidGrooveSteps(steps));
+        }
+        let clip = self.find_clip(track_id, clip_id)?;
+
+        let onsets: Vec<(u64, f32)> = match self.project.resolved_payload(clip) {
+            Some(ClipPayload::Midi(MidiClip { notes, .. }))
+            | Some(ClipPayload::Pattern(PatternClip { notes, .. })) => clip
+                .arrange_notes(notes)
+                .iter()
+                .map(|note| (note.start_tick, f32::from(note.velocity)))
+                .collect(),
+            Some(ClipPayload::Audio(audio)) => audio_onsets(
+                audio,
+                GROOVE_TRANSIENT_SENSITIVITY,
+                self.project.bpm,
+                self.project.ppq,
+            )?,
+            _ => return Err(EngineError::UnsupportedClipPayload(clip_id)),
+        };
+        let onsets: Vec<(u64, f32)> = onsets
+            .into_iter()
+            .filter(|(tick, _)| *tick < clip.length_ticks)
+            .collect();
+        if onsets.is_empty() {
+            return Err(EngineError::EmptyGroove(clip_id));
+        }
+
+        let name = if name.trim().is_empty() {
+            clip.name.as_str()
+        } else {
+            name
+        };
+        let template = groove::groove_from_onsets(name, &onsets, grid_ticks, steps);
+        match self
+            .project
+            .grooves
+            .iter_mut()
+            .find(|groove| groove.name.eq_ignore_ascii_case(&template.name))
+        {
+            Some(existing) => *existing = template.clone(),
+            None => self.project.grooves.push(template.clone()),
+        }
+
+        self.project.touch();
+        info!(onsets = onsets.len(), "clip groove extracted");
+        Ok(template)
+    }
+
+    /// Randomizes timing, velocity and length of the selected notes (all notes when
+    /// `note_indices` is empty), reproducibly for a given seed.
+    #[instrument(skip(self, note_indices, options), fields(project_id = %self.project.id, track_id = %track_id, clip_id = %clip_id, notes = note_indices.len(), seed = options.seed))]
+    pub fn humanize_clip_notes(
+        &mut self,
+        track_id: Uuid,
+        clip_id: Uuid,
+        note_indices: &[usize],
+        options: &HumanizeOptions,
+    ) -> Result<Clip, EngineError> {
+        if !options.length_percent.is_finite() || !(0.0..=100.0).contains(&options.length_percent) {
+            return Err(EngineError::InvalidHumanizeAmount(options.length_percent));
+        }
+        let updated_clip =
+            self.edit_clip_note_selection(track_id, clip_id, note_indices, |notes| {
+                note_tools::humanize_notes(notes, options);
+            })?;
+
+        self.project.touch();
+        info!("clip notes humanized");
+        Ok(updated_clip)
+    }
+
+    #[instrument(skip(self, note_indices), fields(project_id = %self.project.id, track_id = %track_id, clip_id = %clip_id, notes = note_indices.len(), ?direction, spread_ticks))]
+    pub fn strum_clip_notes(
+        &mut self,
+        track_id: Uuid,
+        clip_id: Uuid,
+        note_indices: &[usize],
+        direction: StrumDirection,
+        spread_ticks: u64,
+    ) -> Result<Clip, EngineError> {
+        let updated_clip =
+            self.edit_clip_note_selection(track_id, clip_id, note_indices, |notes| {
+                note_tools::strum_notes(notes, direction, spread_ticks);
+            })?;
+
+        self.project.touch();
+        info!("clip notes strummed");
+        Ok(updated_clip)
+    }
+
+    #[instrument(skip(self, note_indices, shape), fields(project_id = %self.project.id, track_id = %track_id, clip_id = %clip_id, notes = note_indices.len(), from = shape.from, to = shape.to, curve = shape.curve))]
+    pub fn shape_clip_note_velocities(
+        &mut self,
+        track_id: Uuid,
+        clip_id: Uuid,
+        note_indices: &[usize],
+        shape: &VelocityShape,
+    ) -> Result<Clip, EngineError> {
+        if !shape.curve.is_finite() || shape.curve <= 0.0 {
+            return Err(EngineError::InvalidVelocityCurve(shape.curve));
+        }
+        let updated_clip =
+            self.edit_clip_note_selection(track_id, clip_id, note_indices, |notes| {
+                note_tools::shape_note_velocities(notes, shape);
+            })?;
+
+        self.project.touch();
+        info!("clip note velocities shaped");
+        Ok(updated_clip)
+    }
+
+    #[instrument(skip(self, note_indices), fields(project_id = %self.project.id, track_id = %track_id, clip_id = %clip_id, notes = note_indices.len()))]
+    pub fn legato_clip_notes(
+        &mut self,
+        track_id: Uuid,
+        clip_id: Uuid,
+        note_indices: &[usize],
+    ) -> Result<Clip, EngineError> {
+        let updated_clip = self.edit_clip_note_selection(
+            track_id,
+            clip_id,
+            note_indices,
+            note_tools::legato_notes,
+        )?;
+
+        self.project.touch();
+        info!("clip notes made legato");
+        Ok(updated_clip)
+    }
+
+    #[instrument(skip(self, note_indices), fields(project_id = %self.project.id, track_id = %track_id, clip_id = %clip_id, notes = note_indices.len(), length_ticks))]
+    pub fn set_clip_note_lengths(
+        &mut self,
+        track_id: Uuid,
+        clip_id: Uuid,
+        note_indices: &[usize],
+        length_ticks: u64,
+    ) -> Result<Clip, EngineError> {
+        if length_ticks == 0 {
+            return Err(EngineError::InvalidNoteLength(length_ticks));
+        }
+        let updated_clip =
+            self.edit_clip_note_selection(track_id, clip_id, note_indices, |notes| {
+                note_tools::set_note_lengths(notes, length_ticks);
+            })?;
+
+        self.project.touch();
+        info!("clip note lengths set");
+        Ok(updated_clip)
+    }
+
+    /// Replaces a clip's notes with an arpeggio of them, so single-voice tracks
+    /// can play chords as baked notes.
+    #[instrument(skip(self, options), fields(project_id = %self.project.id, track_id = %track_id, clip_id = %clip_id, mode = ?options.mode, rate_ticks = options.rate_ticks, octaves = options.octaves))]
+    pub fn arpeggiate_clip(
+        &mut self,
+        track_id: Uuid,
+        clip_id: Uuid,
+        options: &ArpeggiatorOptions,
+    ) -> Result<Clip, EngineError> {
+        if options.rate_ticks == 0 {
+            return Err(EngineError::InvalidArpeggioRate(options.rate_ticks));
+        }
+        if !(1..=MAX_ARPEGGIO_OCTAVES).contains(&options.octaves) {
+            return Err(EngineError::InvalidArpeggioOctaves(options.octaves));
+        }
+        if !options.gate_percent.is_finite()
+            || options.gate_percent <= 0.0
+            || options.gate_percent > MAX_ARPEGGIO_GATE_PERCENT
+        {
+            return Err(EngineError::InvalidArpeggioGate(options.gate_percent));
+        }
+        let updated_clip = self.replace_clip_notes(track_id, clip_id, |notes| {
+            note_tools::arpeggiate_notes(notes, options)
+        })?;
+
+        self.project.touch();
+        info!("clip notes arpeggiated");
+        Ok(updated_clip)
+    }
+
+    /// Replaces each note with a diatonic chord built on it in `key`.
+    #[instrument(skip(self), fields(project_id = %self.project.id, track_id = %track_id, clip_id = %clip_id, ?key, ?kind))]
+    pub fn generate_clip_chords(
+        &mut self,
+        track_id: Uuid,
+        clip_id: Uuid,
+        key: Key,
+        kind: ChordKind,
+    ) -> Result<Clip, EngineError> {
+        let key = sanitize_key(key)?;
+        let updated_clip = self.replace_clip_notes(track_id, clip_id, |notes| {
+            note_tools::chord_notes(notes, &key, kind)
+        })?;
+
+        self.project.touch();
+        info!("clip chords generated");
+        Ok(updated_clip)
+    }
+
+    #[instrument(skip(self), fields(project_id = %self.project.id, ?key))]
+    pub fn set_project_key(&mut self, key: Option<Key>) -> Result<Option<Key>, EngineError> {
+        let key = key.map(sanitize_key).transpose()?;
+        self.project.key = key.clone();
+        self.project.touch();
+        info!("project key updated");
+        Ok(key)
+    }
+
+    /// Sets or clears the key a clip uses instead of the project key.
+    #[instrument(skip(self), fields(project_id = %self.project.id, track_id = %track_id, clip_id = %clip_id, ?key))]
+    pub fn set_clip_key(
+        &mut self,
+        track_id: Uuid,
+        clip_id: Uuid,
+        key: Option<Key>,
+    ) -> Result<Clip, EngineError> {
+        let key = key.map(sanitize_key).transpose()?;
+        let clip = self.find_clip_mut(track_id, clip_id)?;
+        clip.key = key;
+        let updated_clip = clip.clone();
+
+        self.project.touch();
+        info!("clip key updated");
+        Ok(updated_clip)
+    }
+
+    /// Key in effect for a clip: its own, else the project key.
+    pub fn clip_key(&self, track_id: Uuid, clip_id: Uuid) -> Result<Option<Key>, EngineError> {
+        let clip = self.find_clip(track_id, clip_id)?;
+        Ok(self.project.key_for(clip).cloned())
+    }
+
+    /// [`Engine::add_clip_note`] with the pitch snapped into the clip's key.
+    #[instrument(skip(self, note), fields(project_id = %self.project.id, track_id = %track_id, clip_id = %clip_id))]
+    pub fn add_clip_note_in_key(
+        &mut self,
+        track_id: Uuid,
+        clip_id: Uuid,
+        mut note: MidiNote,
+    ) -> Result<Clip, EngineError> {
+        let key = self
+            .clip_key(track_id, clip_id)?
+            .ok_or(EngineError::KeyMissing(clip_id))?;
+        note.pitch = key.snap(note.pitch.min(127));
+        self.add_clip_note(track_id, clip_id, note)
+    }
+
+    /// [`Engine::upsert_clip_notes`] with every pitch snapped into the clip's key.
+    #[instrument(skip(self, notes), fields(project_id = %self.project.id, track_id = %track_id, clip_id = %clip_id, notes = notes.len()))]
+    pub fn upsert_clip_notes_in_key(
+        &mut self,
+        track_id: Uuid,
+        clip_id: Uuid,
+        mut notes: Vec<MidiNote>,
+    ) -> Result<Clip, EngineError> {
+        let key = self
+            .clip_key(track_id, clip_id)?
+            .ok_or(EngineError::KeyMissing(clip_id))?;
+        for note in &mut notes {
+            note.pitch = key.snap(note.pitch.min(127));
+        }
+        self.upsert_clip_notes(track_id, clip_id, notes)
+    }
+
+    /// Transposes by scale degrees of the clip's key rather than semitones.
+    #[instrument(skip(self), fields(project_id = %self.project.id, track_id = %track_id, clip_id = %clip_id, steps))]
+    pub fn transpose_clip_notes_diatonic(
+        &mut self,
+        track_id: Uuid,
+        clip_id: Uuid,
+        steps: i16,
+    ) -> Result<Clip, EngineError> {
+        let key = self
+            .clip_key(track_id, clip_id)?
+            .ok_or(EngineError::KeyMissing(clip_id))?;
+        let updated_clip = self.edit_clip_note_selection(track_id, clip_id, &[], |notes| {
+            for note in notes {
+                note.pitch = key.transpose(note.pitch, i64::from(steps));
+            }
+        })?;
+
+        self.project.touch();
+        info!("clip notes transposed diatonically");
+        Ok(updated_clip)
+    }
+
+    /// Estimates the key a clip's notes are in, after clip transpose.
+    #[instrument(skip(self), fields(project_id = %self.project.id, track_id = %track_id, clip_id = %clip_id))]
+    pub fn detect_clip_key(
+        &self,
+        track_id: Uuid,
+        clip_id: Uuid,
+    ) -> Result<Option<KeyEstimate>, EngineError> {
+        let clip = self.find_clip(track_id, clip_id)?;
+        let notes = match self.project.resolved_payload(clip) {
+            Some(ClipPayload::Midi(MidiClip { notes, .. }))
+            | Some(ClipPayload::Pattern(PatternClip { notes, .. })) => clip.arrange_notes(notes),
+            _ => return Err(EngineError::UnsupportedClipPayload(clip_id)),
+        };
+        Ok(theory::detect_key(&notes))
+    }
+
+    /// Estimates the project key from every enabled MIDI and pattern clip,
+    /// leaving out drum-channel notes.
+    #[instrument(skip(self), fields(project_id = %self.project.id))]
+    pub fn detect_project_key(&self) -> Option<KeyEstimate> {
+        let notes: Vec<MidiNote> = self
+            .project
+            .tracks
+            .iter()
+            .flat_map(|track| &track.clips)
+            .filter(|clip| !clip.disabled)
+            .flat_map(|clip| match self.project.resolved_payload(clip) {
+                Some(ClipPayload::Midi(MidiClip { notes, .. }))
+                | Some(ClipPayload::Pattern(PatternClip { notes, .. })) => {
+                    clip.arrange_notes(notes)
+                }
+                _ => Vec::new(),
+            })
+            .filter(|note| note.channel != STEP_DRUM_CHANNEL)
+            .collect();
+        theory::detect_key(&notes)
+    }
+
     #[instrument(skip(self, rows), fields(project_id = %self.project.id, track_id = %track_id, clip_id = %clip_id, rows = rows.len(), lines_per_beat = ?lines_per_beat))]
     pub fn upsert_pattern_rows(
         &mut self,
@@ -853,8 +2508,7 @@ impl Engine {
         lines_per_beat: Option<u16>,
     ) -> Result<Clip, EngineError> {
         let ppq = self.project.ppq;
-        let updated_clip = {
-            let clip = self.find_clip_mut(track_id, clip_id)?;
+        let updated_clip = self.edit_clip(track_id, clip_id, |clip| {
             let pattern =
                 clip_pattern_mut(clip).ok_or(EngineError::UnsupportedPatternClip(clip_id))?;
             if let Some(lines_per_beat) = lines_per_beat {
@@ -866,14 +2520,83 @@ impl Engine {
             }
             pattern.rows = rows;
             normalize_pattern_clip(pattern, ppq)?;
-            clip.clone()
-        };
+            Ok(())
+        })?;
 
         self.project.touch();
         info!("pattern rows replaced");
         Ok(updated_clip)
     }
 
+    /// Replaces a pattern clip's rows with a Euclidean rhythm, one step per row.
+    #[instrument(skip(self, rhythm), fields(project_id = %self.project.id, track_id = %track_id, clip_id = %clip_id, hits = rhythm.hits, steps = rhythm.steps, rotation = rhythm.rotation))]
+    pub fn generate_euclidean_rows(
+        &mut self,
+        track_id: Uuid,
+        clip_id: Uuid,
+        rhythm: &EuclideanRhythm,
+    ) -> Result<Clip, EngineError> {
+        if !(1..=MAX_EUCLIDEAN_STEPS).contains(&rhythm.steps) || rhythm.hits > rhythm.steps {
+            return Err(EngineError::InvalidEuclideanRhythm {
+                hits: rhythm.hits,
+                steps: rhythm.steps,
+            });
+        }
+        let updated_clip = self.generate_pattern_rows(track_id, clip_id, |_, row_count| {
+            Ok(generators::euclidean_rows(rhythm, row_count))
+        })?;
+
+        self.project.touch();
+        info!("euclidean pattern rows generated");
+        Ok(updated_clip)
+    }
+
+    /// Replaces a pattern clip's rows with seeded random hits.
+    #[instrument(skip(self, fill), fields(project_id = %self.project.id, track_id = %track_id, clip_id = %clip_id, seed = fill.seed, chances = fill.chances.len(), pitches = fill.pitches.len()))]
+    pub fn generate_probability_rows(
+        &mut self,
+        track_id: Uuid,
+        clip_id: Uuid,
+        fill: &ProbabilityFill,
+    ) -> Result<Clip, EngineError> {
+        if fill.chances.is_empty() || fill.pitches.is_empty() {
+            return Err(EngineError::EmptyProbabilityFill);
+        }
+        if let Some(chance) = fill
+            .chances
+            .iter()
+            .find(|chance| !chance.is_finite() || !(0.0..=100.0).contains(*chance))
+        {
+            return Err(EngineError::InvalidFillChance(*chance));
+        }
+        let updated_clip = self.generate_pattern_rows(track_id, clip_id, |_, row_count| {
+            Ok(generators::probability_rows(fill, row_count))
+        })?;
+
+        self.project.touch();
+        info!("probability pattern rows generated");
+        Ok(updated_clip)
+    }
+
+    /// Continues a pattern clip's melody to the end of the clip with a Markov
+    /// chain learned from its own notes.
+    #[instrument(skip(self), fields(project_id = %self.project.id, track_id = %track_id, clip_id = %clip_id, seed))]
+    pub fn continue_pattern_markov(
+        &mut self,
+        track_id: Uuid,
+        clip_id: Uuid,
+        seed: u64,
+    ) -> Result<Clip, EngineError> {
+        let updated_clip = self.generate_pattern_rows(track_id, clip_id, |rows, row_count| {
+            generators::markov_continuation(rows, row_count, seed)
+                .ok_or(EngineError::MarkovSourceTooShort(clip_id))
+        })?;
+
+        self.project.touch();
+        info!("pattern melody continued");
+        Ok(updated_clip)
+    }
+
     #[instrument(skip(self, macros), fields(project_id = %self.project.id, track_id = %track_id, clip_id = %clip_id, macros = macros.len()))]
     pub fn upsert_pattern_macros(
         &mut self,
@@ -881,8 +2604,7 @@ impl Engine {
         clip_id: Uuid,
         mut macros: Vec<ChipMacroLane>,
     ) -> Result<Clip, EngineError> {
-        let updated_clip = {
-            let clip = self.find_clip_mut(track_id, clip_id)?;
+        let updated_clip = self.edit_clip(track_id, clip_id, |clip| {
             let pattern =
                 clip_pattern_mut(clip).ok_or(EngineError::UnsupportedPatternClip(clip_id))?;
 
@@ -890,11 +2612,73 @@ impl Engine {
                 sanitize_chip_macro_lane(lane);
             }
             pattern.macros = macros;
-            clip.clone()
-        };
+            Ok(())
+        })?;
+
+        self.project.touch();
+        info!("pattern macros replaced");
+        Ok(updated_clip)
+    }
+
+    #[instrument(skip(self, rows), fields(project_id = %self.project.id, track_id = %track_id, clip_id = %clip_id, rows = rows.len(), steps_per_beat = ?steps_per_beat, step_count = ?step_count))]
+    pub fn upsert_step_rows(
+        &mut self,
+        track_id: Uuid,
+        clip_id: Uuid,
+        rows: Vec<StepRow>,
+        steps_per_beat: Option<u16>,
+        step_count: Option<u16>,
+    ) -> Result<Clip, EngineError> {
+        let updated_clip = self.edit_clip(track_id, clip_id, |clip| {
+            let ClipPayload::Steps(steps) = &mut clip.payload else {
+                return Err(EngineError::UnsupportedStepClip(clip_id));
+            };
+            let mut candidate = steps.clone();
+            if let Some(steps_per_beat) = steps_per_beat {
+                candidate.steps_per_beat = steps_per_beat;
+            }
+            if let Some(step_count) = step_count {
+                candidate.step_count = step_count;
+            }
+            candidate.rows = rows;
+            sanitize_step_clip(&mut candidate)?;
+            *steps = candidate;
+            Ok(())
+        })?;
+
+        self.project.touch();
+        info!("step rows replaced");
+        Ok(updated_clip)
+    }
+
+    #[instrument(skip(self, step), fields(project_id = %self.project.id, track_id = %track_id, clip_id = %clip_id, row_index, step_index))]
+    pub fn set_step(
+        &mut self,
+        track_id: Uuid,
+        clip_id: Uuid,
+        row_index: usize,
+        step_index: usize,
+        mut step: Step,
+    ) -> Result<Clip, EngineError> {
+        sanitize_step(&mut step);
+        let updated_clip = self.edit_clip(track_id, clip_id, |clip| {
+            let ClipPayload::Steps(steps) = &mut clip.payload else {
+                return Err(EngineError::UnsupportedStepClip(clip_id));
+            };
+            let target = steps
+                .rows
+                .get_mut(row_index)
+                .and_then(|row| row.steps.get_mut(step_index))
+                .ok_or(EngineError::InvalidStepIndex {
+                    row: row_index,
+                    step: step_index,
+                })?;
+            *target = step;
+            Ok(())
+        })?;
 
         self.project.touch();
-        info!("pattern macros replaced");
+        info!("step updated");
         Ok(updated_clip)
     }
 
@@ -937,6 +2721,68 @@ impl Engine {
         Ok(project)
     }
 
+    /// Replaces the project with one built from a FamiTracker or Furnace text export.
+    #[instrument(skip(self), fields(path = %path.display()))]
+    pub fn import_tracker_text(&mut self, path: &Path) -> Result<Project, EngineError> {
+        let project = tracker_import::import_tracker_text(path)?;
+        self.replace_project(project.clone());
+        Ok(project)
+    }
+
+    /// Replaces the project with one built from a MOD, XM or IT module, writing its
+    /// samples into `sample_dir`.
+    #[instrument(skip(self), fields(path = %path.display(), sample_dir = %sample_dir.display()))]
+    pub fn import_module(
+        &mut self,
+        path: &Path,
+        sample_dir: &Path,
+    ) -> Result<Project, EngineError> {
+        let project = module_import::import_module(path, sample_dir)?;
+        self.replace_project(project.clone());
+        Ok(project)
+    }
+
+    /// Appends the tracks of a Standard MIDI File to the current project. A project
+    /// without tracks also takes the file's tempo and time signature.
+    #[instrument(skip(self, options), fields(project_id = %self.project.id, path = %path.display()))]
+    pub fn import_midi(
+        &mut self,
+        path: &Path,
+        options: &MidiImportOptions,
+    ) -> Result<Vec<Track>, EngineError> {
+        let imported = midi_import::import_midi_with_options(path, options)?;
+        if self.project.tracks.is_empty() {
+            self.project.bpm = imported.bpm;
+            self.project.time_signature = imported.time_signature;
+        }
+        self.project.tracks.extend(imported.tracks.iter().cloned());
+        self.project.touch();
+        info!(tracks = imported.tracks.len(), "midi file merged");
+        Ok(imported.tracks)
+    }
+
+    /// Replaces the project tuning used by every voice and by MIDI and VGM export.
+    #[instrument(skip(self, tuning), fields(project_id = %self.project.id, name = %tuning.name, reference_hz = tuning.reference_hz))]
+    pub fn set_tuning(&mut self, tuning: Tuning) -> Result<Tuning, EngineError> {
+        validate_tuning(&tuning)?;
+        self.project.tuning = tuning.clone();
+        self.project.touch();
+        info!("project tuning updated");
+        Ok(tuning)
+    }
+
+    /// Loads a Scala `.scl` scale, with an optional `.kbm` keyboard mapping, as
+    /// the project tuning.
+    #[instrument(skip(self), fields(project_id = %self.project.id, scl = %scl_path.display()))]
+    pub fn import_scala_tuning(
+        &mut self,
+        scl_path: &Path,
+        kbm_path: Option<&Path>,
+    ) -> Result<Tuning, EngineError> {
+        let tuning = tuning::load_scala_tuning(scl_path, kbm_path)?;
+        self.set_tuning(tuning)
+    }
+
     #[instrument(skip(self), fields(project_id = %self.project.id, autosave_dir = %autosave_dir.display()))]
     pub fn autosave(&self, autosave_dir: &Path) -> Result<PathBuf, EngineError> {
         let autosave_path = persistence::autosave_project(&self.project, autosave_dir)?;
@@ -960,10 +2806,19 @@ impl Engine {
             ExportKind::StemWav => {
                 let _paths = export::export_stem_wav(&self.project, output_path, render_mode)?;
             }
+            ExportKind::Vgm => vgm::export_vgm(&self.project, output_path)?,
         }
         Ok(())
     }
 
+    fn find_track(&self, track_id: Uuid) -> Result<&Track, EngineError> {
+        self.project
+            .tracks
+            .iter()
+            .find(|track| track.id == track_id)
+            .ok_or(EngineError::TrackNotFound(track_id))
+    }
+
     fn find_track_mut(&mut self, track_id: Uuid) -> Result<&mut Track, EngineError> {
         self.project
             .tracks
@@ -972,6 +2827,198 @@ impl Engine {
             .ok_or(EngineError::TrackNotFound(track_id))
     }
 
+    /// Swaps a note clip's notes for the ones `generate` derives from them.
+    fn replace_clip_notes(
+        &mut self,
+        track_id: Uuid,
+        clip_id: Uuid,
+        generate: impl FnOnce(&[MidiNote]) -> Vec<MidiNote>,
+    ) -> Result<Clip, EngineError> {
+        let ppq = self.project.ppq;
+        self.edit_clip(track_id, clip_id, |clip| {
+            {
+                let notes =
+                    clip_note_vec_mut(clip).ok_or(EngineError::UnsupportedClipPayload(clip_id))?;
+                *notes = generate(notes);
+                for note in notes.iter_mut() {
+                    sanitize_note(note);
+                }
+                notes.sort_by_key(|candidate| candidate.start_tick);
+            }
+            if let Some(pattern) = clip_pattern_mut(clip) {
+                sync_pattern_rows_from_notes(pattern, ppq)?;
+            }
+            Ok(())
+        })
+    }
+
+    /// Runs a note tool over the notes at `note_indices`, or every note when the
+    /// selection is empty, then re-sorts the clip and refreshes pattern rows.
+    fn edit_clip_note_selection(
+        &mut self,
+        track_id: Uuid,
+        clip_id: Uuid,
+        note_indices: &[usize],
+        edit: impl FnOnce(&mut [MidiNote]),
+    ) -> Result<Clip, EngineError> {
+        let ppq = self.project.ppq;
+        self.edit_clip(track_id, clip_id, |clip| {
+            {
+                let notes =
+                    clip_note_vec_mut(clip).ok_or(EngineError::UnsupportedClipPayload(clip_id))?;
+                if note_indices.is_empty() {
+                    edit(notes);
+                } else {
+                    let mut indices = note_indices.to_vec();
+                    indices.sort_unstable();
+                    indices.dedup();
+                    if let Some(invalid) = indices.iter().find(|index| **index >= notes.len()) {
+                        return Err(EngineError::InvalidNoteIndex(*invalid));
+                    }
+                    let mut selected: Vec<MidiNote> =
+                        indices.iter().map(|index| notes[*index].clone()).collect();
+                    edit(&mut selected);
+                    for (index, note) in indices.into_iter().zip(selected) {
+                        notes[index] = note;
+                    }
+                }
+                for note in notes.iter_mut() {
+                    sanitize_note(note);
+                }
+                notes.sort_by_key(|candidate| candidate.start_tick);
+            }
+            if let Some(pattern) = clip_pattern_mut(clip) {
+                sync_pattern_rows_from_notes(pattern, ppq)?;
+            }
+            Ok(())
+        })
+    }
+
+    /// Applies an edit to a clip. Linked placements are edited through their shared
+    /// pool content so every instance picks up the change.
+    fn edit_clip(
+        &mut self,
+        track_id: Uuid,
+        clip_id: Uuid,
+        edit: impl FnOnce(&mut Clip) -> Result<(), EngineError>,
+    ) -> Result<Clip, EngineError> {
+        let content_id = match &self.find_clip_mut(track_id, clip_id)?.payload {
+            ClipPayload::Linked(link) => link.content_id,
+            _ => {
+                let clip = self.find_clip_mut(track_id, clip_id)?;
+                edit(clip)?;
+                return Ok(clip.clone());
+            }
+        };
+
+        let entry_index = self
+            .project
+            .clip_pool
+            .iter()
+            .position(|entry| entry.id == content_id)
+            .ok_or(EngineError::PoolContentNotFound(content_id))?;
+        let mut working = self.find_clip_mut(track_id, clip_id)?.clone();
+        working.payload = self.project.clip_pool[entry_index].payload.clone();
+        edit(&mut working)?;
+
+        self.project.clip_pool[entry_index].payload = working.payload;
+        working.payload = ClipPayload::Linked(LinkedClip { content_id });
+        let clip = self.find_clip_mut(track_id, clip_id)?;
+        *clip = working;
+        Ok(clip.clone())
+    }
+
+    /// Validates quantize options and resolves their groove template.
+    fn quantize_groove(
+        &self,
+        options: &QuantizeOptions,
+    ) -> Result<Option<GrooveTemplate>, EngineError> {
+        if options.grid_ticks == 0 {
+            return Err(EngineError::InvalidQuantizeGrid(options.grid_ticks));
+        }
+        for amount in [options.strength_percent, options.swing_percent] {
+            if !amount.is_finite() || !(0.0..=100.0).contains(&amount) {
+                return Err(EngineError::InvalidQuantizeAmount(amount));
+            }
+        }
+        options
+            .groove
+            .as_deref()
+            .map(|name| {
+                self.project
+                    .groove(name)
+                    .cloned()
+                    .ok_or_else(|| EngineError::GrooveNotFound(name.to_string()))
+            })
+            .transpose()
+    }
+
+    fn edit_audio_warp(
+        &mut self,
+        track_id: Uuid,
+        clip_id: Uuid,
+        warp: impl FnOnce(&mut AudioClip, u16),
+    ) -> Result<Clip, EngineError> {
+        let bpm = self.project.bpm;
+        let ppq = self.project.ppq;
+        self.edit_clip(track_id, clip_id, |clip| {
+            let ClipPayload::Audio(audio) = &mut clip.payload else {
+                return Err(EngineError::UnsupportedAudioClip(clip_id));
+            };
+            warp(audio, ppq);
+            sanitize_audio_clip(audio, bpm, ppq)?;
+            clip.length_ticks = audio.timeline_length_ticks(bpm, ppq).max(1);
+            Ok(())
+        })
+    }
+
+    /// Rebuilds a pattern clip's rows with `generate`, which receives the current
+    /// rows and the number of rows that fit in the clip at its `lines_per_beat`.
+    fn generate_pattern_rows(
+        &mut self,
+        track_id: Uuid,
+        clip_id: Uuid,
+        generate: impl FnOnce(&[TrackerRow], u32) -> Result<Vec<TrackerRow>, EngineError>,
+    ) -> Result<Clip, EngineError> {
+        let ppq = self.project.ppq;
+        self.edit_clip(track_id, clip_id, |clip| {
+            let length_ticks = clip.length_ticks;
+            let pattern =
+                clip_pattern_mut(clip).ok_or(EngineError::UnsupportedPatternClip(clip_id))?;
+            if pattern.lines_per_beat == 0 {
+                return Err(EngineError::InvalidTrackerLinesPerBeat(
+                    pattern.lines_per_beat,
+                ));
+            }
+            let ticks_per_row = tracker_rows_to_ticks(1, pattern.lines_per_beat, ppq).max(1);
+            let rows = length_ticks / ticks_per_row;
+            if rows > MAX_GENERATED_PATTERN_ROWS {
+                return Err(EngineError::TooManyPatternRows {
+                    rows,
+                    max: MAX_GENERATED_PATTERN_ROWS,
+                });
+            }
+            let row_count = (rows as u32).max(1);
+
+            let mut rows = generate(&pattern.rows, row_count)?;
+            for row in &mut rows {
+                sanitize_tracker_row(row);
+            }
+            pattern.rows = rows;
+            // Generated rows are the source of truth, even when they are empty.
+            pattern.notes.clear();
+            normalize_pattern_clip(pattern, ppq)
+        })
+    }
+
+    fn find_clip(&self, track_id: Uuid, clip_id: Uuid) -> Result<&Clip, EngineError> {
+        self.find_track(track_id)?
+            .clips
+            .iter()
+            .find(|clip| clip.id == clip_id)
+            .ok_or(EngineError::ClipNotFound(clip_id))
+    }
+
     fn find_clip_mut(&mut self, track_id: Uuid, clip_id: Uuid) -> Result<&mut Clip, EngineError> {
         let track = self.find_track_mut(track_id)?;
 
@@ -987,27 +3034,50 @@ fn clip_note_vec_mut(clip: &mut Clip) -> Option<&mut Vec<MidiNote>> {
     match &mut clip.payload {
         ClipPayload::Midi(midi) => Some(&mut midi.notes),
         ClipPayload::Pattern(pattern) => Some(&mut pattern.notes),
-        ClipPayload::Audio(_) | ClipPayload::Automation(_) => None,
+        ClipPayload::Steps(_)
+        | ClipPayload::Audio(_)
+        | ClipPayload::Automation(_)
+        | ClipPayload::Linked(_) => None,
     }
 }
 
+/// Clips an order list owns on its channel tracks: anything that plays notes.
+fn is_order_payload(payload: &ClipPayload) -> bool {
+    matches!(
+        payload,
+        ClipPayload::Midi(_)
+            | ClipPayload::Pattern(_)
+            | ClipPayload::Steps(_)
+            | ClipPayload::Linked(_)
+    )
+}
+
 fn clip_pattern_mut(clip: &mut Clip) -> Option<&mut PatternClip> {
     match &mut clip.payload {
         ClipPayload::Pattern(pattern) => Some(pattern),
-        ClipPayload::Midi(_) | ClipPayload::Audio(_) | ClipPayload::Automation(_) => None,
+        ClipPayload::Midi(_)
+        | ClipPayload::Steps(_)
+        | ClipPayload::Audio(_)
+        | ClipPayload::Automation(_)
+        | ClipPayload::Linked(_) => None,
     }
 }
 
 fn clip_automation_mut(clip: &mut Clip) -> Option<&mut AutomationClip> {
     match &mut clip.payload {
         ClipPayload::Automation(automation) => Some(automation),
-        ClipPayload::Midi(_) | ClipPayload::Audio(_) | ClipPayload::Pattern(_) => None,
+        ClipPayload::Midi(_)
+        | ClipPayload::Audio(_)
+        | ClipPayload::Pattern(_)
+        | ClipPayload::Steps(_)
+        | ClipPayload::Linked(_) => None,
     }
 }
 
 fn sanitize_note(note: &mut MidiNote) {
     note.pitch = note.pitch.min(127);
     note.velocity = note.velocity.min(127);
+    note.release_velocity = note.release_velocity.map(|velocity| velocity.min(127));
     note.channel = note.channel.min(15);
     note.length_ticks = note.length_ticks.max(1);
 }
@@ -1025,6 +3095,42 @@ fn sanitize_tracker_row(row: &mut TrackerRow) {
     }
 }
 
+fn sanitize_step_clip(steps: &mut StepSequencerClip) -> Result<(), EngineError> {
+    if steps.steps_per_beat == 0 {
+        return Err(EngineError::InvalidStepsPerBeat(steps.steps_per_beat));
+    }
+    steps.steps_per_beat = steps.steps_per_beat.min(64);
+    steps.step_count = steps.step_count.clamp(1, 256);
+    steps.source_chip = steps.source_chip.trim().to_string();
+
+    let step_count = usize::from(steps.step_count);
+    for row in &mut steps.rows {
+        row.pitch = row.pitch.min(127);
+        if row
+            .instrument
+            .as_deref()
+            .is_some_and(|name| name.trim().is_empty())
+        {
+            row.instrument = None;
+        }
+        row.steps.resize_with(step_count, Step::default);
+        for step in &mut row.steps {
+            sanitize_step(step);
+        }
+    }
+    Ok(())
+}
+
+fn sanitize_step(step: &mut Step) {
+    step.velocity = step.velocity.min(127);
+    step.probability = if step.probability.is_finite() {
+        step.probability.clamp(0.0, 1.0)
+    } else {
+        1.0
+    };
+    step.ratchet = step.ratchet.clamp(1, 8);
+}
+
 fn sanitize_chip_macro_lane(lane: &mut ChipMacroLane) {
     lane.target = lane.target.trim().to_ascii_lowercase();
     lane.values.truncate(256);
@@ -1045,6 +3151,115 @@ fn sanitize_chip_macro_lane(lane: &mut ChipMacroLane) {
     }
 }
 
+fn sanitize_synth_patch(patch: &mut SynthPatch) {
+    patch.name = patch.name.trim().to_string();
+    if patch.name.is_empty() {
+        patch.name = "Patch".to_string();
+    }
+    patch.oscillators.truncate(4);
+    for oscillator in &mut patch.oscillators {
+        oscillator.level = oscillator.level.clamp(0.0, 1.0);
+        oscillator.octave = oscillator.octave.clamp(-4, 4);
+        oscillator.detune_cents = oscillator.detune_cents.clamp(-1_200.0, 1_200.0);
+        oscillator.pulse_width = oscillator.pulse_width.clamp(0.05, 0.95);
+    }
+    patch.unison_voices = patch.unison_voices.clamp(1, 8);
+    patch.unison_detune_cents = patch.unison_detune_cents.clamp(0.0, 100.0);
+    for envelope in [&mut patch.amp_envelope, &mut patch.filter_envelope] {
+        envelope.attack_seconds = envelope.attack_seconds.clamp(0.0, 10.0);
+        envelope.decay_seconds = envelope.decay_seconds.clamp(0.0, 10.0);
+        envelope.sustain_level = envelope.sustain_level.clamp(0.0, 1.0);
+        envelope.release_seconds = envelope.release_seconds.clamp(0.0, 10.0);
+    }
+    patch.filter.cutoff_hz = patch.filter.cutoff_hz.clamp(20.0, 20_000.0);
+    patch.filter.resonance = patch.filter.resonance.clamp(0.0, 1.0);
+    patch.filter.envelope_octaves = patch.filter.envelope_octaves.clamp(-8.0, 8.0);
+    patch.filter.key_tracking = patch.filter.key_tracking.clamp(0.0, 1.0);
+    patch.lfo.rate_hz = patch.lfo.rate_hz.clamp(0.0, 50.0);
+    patch.lfo.depth = patch.lfo.depth.clamp(-24.0, 24.0);
+    patch.glide_seconds = patch.glide_seconds.clamp(0.0, 5.0);
+    patch.gain_db = patch.gain_db.clamp(-96.0, 12.0);
+}
+
+/// Moves `pitch` by whole octaves until it lies in `range`, which must span at
+/// least an octave.
+fn fold_into_range(pitch: u8, range: &RangeInclusive<u8>) -> u8 {
+    let mut pitch = pitch;
+    while pitch < *range.start() {
+        pitch += 12;
+    }
+    while pitch > *range.end() {
+        pitch -= 12;
+    }
+    pitch
+}
+
+fn ensure_note_track(track: &Track) -> Result<(), EngineError> {
+    if matches!(track.kind, TrackKind::Midi | TrackKind::Chip) {
+        Ok(())
+    } else {
+        Err(EngineError::InvalidNoteTrack {
+            track_id: track.id,
+            kind: track.kind.clone(),
+        })
+    }
+}
+
+/// `name`, numbered past any sampler or synth patch the track already calls that,
+/// so a new instrument never replaces or hides an existing one.
+fn unused_instrument_name(track: &Track, name: &str) -> String {
+    let base = match name.trim() {
+        "" => "Sampler",
+        trimmed => trimmed,
+    };
+    let taken = |candidate: &str| {
+        track.sampler(Some(candidate)).is_some() || track.synth_patch(Some(candidate)).is_some()
+    };
+    if !taken(base) {
+        return base.to_string();
+    }
+    (2..)
+        .map(|index| format!("{base} {index}"))
+        .find(|candidate| !taken(candidate))
+        .expect("a free instrument name should exist")
+}
+
+fn sanitize_sampler(sampler: &mut SamplerInstrument) {
+    sampler.name = sampler.name.trim().to_string();
+    if sampler.name.is_empty() {
+        sampler.name = "Sampler".to_string();
+    }
+    sampler.gain_db = sampler.gain_db.clamp(-96.0, 12.0);
+    let envelope = &mut sampler.amp_envelope;
+    envelope.attack_seconds = envelope.attack_seconds.clamp(0.0, 10.0);
+    envelope.decay_seconds = envelope.decay_seconds.clamp(0.0, 10.0);
+    envelope.sustain_level = envelope.sustain_level.clamp(0.0, 1.0);
+    envelope.release_seconds = envelope.release_seconds.clamp(0.0, 10.0);
+
+    sampler
+        .zones
+        .retain(|zone| !zone.source_path.trim().is_empty());
+    for zone in &mut sampler.zones {
+        zone.root_note = zone.root_note.min(127);
+        zone.low_note = zone.low_note.min(127);
+        zone.high_note = zone.high_note.min(127);
+        if zone.low_note > zone.high_note {
+            std::mem::swap(&mut zone.low_note, &mut zone.high_note);
+        }
+        zone.low_velocity = zone.low_velocity.min(127);
+        zone.high_velocity = zone.high_velocity.min(127);
+        if zone.low_velocity > zone.high_velocity {
+            std::mem::swap(&mut zone.low_velocity, &mut zone.high_velocity);
+        }
+        zone.tune_cents = zone.tune_cents.clamp(-1_200.0, 1_200.0);
+        zone.gain_db = zone.gain_db.clamp(-96.0, 12.0);
+        zone.loop_start_seconds = zone.loop_start_seconds.max(0.0);
+        zone.loop_end_seconds = zone.loop_end_seconds.max(zone.loop_start_seconds);
+        zone.sample_start_seconds = zone.sample_start_seconds.max(0.0);
+        zone.sample_end_seconds = zone.sample_end_seconds.max(0.0);
+    }
+}
+
 fn sanitize_track_send(send: &mut TrackSend) {
     if send.id.is_nil() {
         send.id = Uuid::new_v4();
@@ -1058,6 +3273,75 @@ fn sanitize_automation_points(points: &mut Vec<AutomationPoint>) {
     points.sort_by_key(|point| point.tick);
 }
 
+/// Keeps one lane per controller, dropping empty lanes.
+fn sanitize_controller_lanes(lanes: &mut Vec<ControllerLane>) {
+    let mut seen = Vec::with_capacity(lanes.len());
+    lanes.retain_mut(|lane| {
+        sanitize_controller_points(lane.target, &mut lane.points);
+        if lane.points.is_empty() || seen.contains(&lane.target) {
+            return false;
+        }
+        seen.push(lane.target);
+        true
+    });
+}
+
+fn sanitize_controller_points(target: ControllerTarget, points: &mut Vec<AutomationPoint>) {
+    sanitize_automation_points(points);
+    let (min, max) = target.value_range();
+    for point in points {
+        point.value = point.value.clamp(min, max);
+    }
+}
+
+/// Checks the root and puts custom scale intervals in ascending order.
+fn sanitize_key(mut key: Key) -> Result<Key, EngineError> {
+    if key.root > 11 {
+        return Err(EngineError::InvalidKeyRoot(key.root));
+    }
+    if let ScaleMode::Custom(intervals) = &mut key.mode {
+        intervals.sort_unstable();
+        intervals.dedup();
+        if intervals.first() != Some(&0) || intervals.last().is_some_and(|last| *last > 11) {
+            return Err(EngineError::InvalidScale(format!(
+                "custom intervals must include 0 and stay below 12: {intervals:?}"
+            )));
+        }
+    }
+    Ok(key)
+}
+
+fn validate_tuning(tuning: &Tuning) -> Result<(), EngineError> {
+    if !tuning.reference_hz.is_finite() || !(1.0..=20_000.0).contains(&tuning.reference_hz) {
+        return Err(EngineError::InvalidTuning(format!(
+            "reference pitch out of range: {}",
+            tuning.reference_hz
+        )));
+    }
+    if tuning.reference_note > 127 || tuning.root_note > 127 || tuning.last_note > 127 {
+        return Err(EngineError::InvalidTuning(
+            "tuning keys must be midi keys".to_string(),
+        ));
+    }
+    if tuning.first_note > tuning.last_note {
+        return Err(EngineError::InvalidTuning(format!(
+            "keyboard mapping range is inverted: {}..={}",
+            tuning.first_note, tuning.last_note
+        )));
+    }
+    if tuning.scale_cents.iter().any(|cents| !cents.is_finite())
+        || tuning
+            .scale_cents
+            .last()
+            .is_some_and(|period| *period <= 0.0)
+    {
+        return Err(EngineError::InvalidTuning(
+            "scale degrees must be finite with a positive period".to_string(),
+        ));
+    }
+    Ok(())
+}
+
 fn sanitize_automation_target_id(target_parameter_id: String, track_id: Uuid) -> String {
     let trimmed = target_parameter_id.trim();
     if trimmed.is_empty() {
@@ -1067,7 +3351,10 @@ fn sanitize_automation_target_id(target_parameter_id: String, track_id: Uuid) ->
     }
 }
 
-fn normalize_pattern_clip(pattern: &mut PatternClip, ppq: u16) -> Result<(), EngineError> {
+pub(crate) fn normalize_pattern_clip(
+    pattern: &mut PatternClip,
+    ppq: u16,
+) -> Result<(), EngineError> {
     if pattern.lines_per_beat == 0 {
         return Err(EngineError::InvalidTrackerLinesPerBeat(
             pattern.lines_per_beat,
@@ -1147,13 +3434,14 @@ fn tracker_rows_to_notes(
             start_tick: tracker_rows_to_ticks(row.row, lines_per_beat, ppq),
             length_ticks: row_length_ticks,
             channel: 0,
+            release_velocity: None,
         });
     }
     notes.sort_by_key(|note| note.start_tick);
     Ok(notes)
 }
 
-fn sanitize_audio_clip(audio: &mut AudioClip) -> Result<(), EngineError> {
+fn sanitize_audio_clip(audio: &mut AudioClip, bpm: f64, ppq: u16) -> Result<(), EngineError> {
     audio.gain_db = audio.gain_db.clamp(-96.0, 12.0);
     audio.pan = audio.pan.clamp(-1.0, 1.0);
     audio.source_duration_seconds = audio.source_duration_seconds.max(0.0);
@@ -1166,6 +3454,16 @@ fn sanitize_audio_clip(audio: &mut AudioClip) -> Result<(), EngineError> {
         return Err(EngineError::InvalidAudioStretchRatio(audio.stretch_ratio));
     }
     audio.stretch_ratio = audio.stretch_ratio.max(0.01);
+    if let Some(pitch) = [audio.pitch_semitones, audio.pitch_cents]
+        .into_iter()
+        .find(|pitch| !pitch.is_finite())
+    {
+        return Err(EngineError::InvalidAudioPitchShift(pitch));
+    }
+    audio.pitch_semitones = audio
+        .pitch_semitones
+        .clamp(-MAX_AUDIO_PITCH_SEMITONES, MAX_AUDIO_PITCH_SEMITONES);
+    audio.pitch_cents = audio.pitch_cents.clamp(-100.0, 100.0);
 
     if audio.trim_end_seconds < audio.trim_start_seconds {
         return Err(EngineError::InvalidAudioTrimRange {
@@ -1181,8 +3479,9 @@ fn sanitize_audio_clip(audio: &mut AudioClip) -> Result<(), EngineError> {
         audio.trim_end_seconds = audio.source_duration_seconds;
     }
 
-    if audio.fade_in_seconds + audio.fade_out_seconds > audio.effective_duration_seconds() {
-        let available = audio.effective_duration_seconds();
+    sanitize_warp_markers(&mut audio.warp_markers)?;
+    let available = ticks_to_seconds(audio.timeline_length_ticks(bpm, ppq), bpm, ppq);
+    if audio.fade_in_seconds + audio.fade_out_seconds > available {
         if available > 0.0 {
             let scale = available / (audio.fade_in_seconds + audio.fade_out_seconds);
             audio.fade_in_seconds *= scale;
@@ -1196,6 +3495,34 @@ fn sanitize_audio_clip(audio: &mut AudioClip) -> Result<(), EngineError> {
     Ok(())
 }
 
+/// Sorts markers by tick and checks that source time moves forward with them.
+/// A single marker pins nothing and is rejected.
+fn sanitize_warp_markers(markers: &mut [WarpMarker]) -> Result<(), EngineError> {
+    if markers.len() == 1 {
+        return Err(EngineError::InvalidWarpMarkers(
+            "at least two markers are required".to_string(),
+        ));
+    }
+    if let Some(marker) = markers
+        .iter()
+        .find(|marker| !marker.source_seconds.is_finite() || marker.source_seconds < 0.0)
+    {
+        return Err(EngineError::InvalidWarpMarkers(format!(
+            "invalid source position: {}",
+            marker.source_seconds
+        )));
+    }
+    markers.sort_by_key(|marker| marker.tick);
+    if markers.windows(2).any(|pair| {
+        pair[0].tick == pair[1].tick || pair[0].source_seconds >= pair[1].source_seconds
+    }) {
+        return Err(EngineError::InvalidWarpMarkers(
+            "markers must move forward in both source time and ticks".to_string(),
+        ));
+    }
+    Ok(())
+}
+
 fn validate_bus_target(
     tracks: &[Track],
     track_id: Uuid,
@@ -1336,6 +3663,397 @@ fn populate_builtin_effect_defaults(effect: &mut EffectSpec) {
     effect.params = params;
 }
 
-fn round_to_grid(value: u64, grid: u64) -> u64 {
-    ((value.saturating_add(grid / 2)) / grid) * grid
+/// Splits a clip `at` ticks from its start. Looping, step and linked clips share
+/// their content and the right part just starts further into it; other clips
+/// have their notes, points or audio range partitioned.
+fn split_clip_content(
+    clip: &Clip,
+    at: u64,
+    bpm: f64,
+    ppq: u16,
+) -> Result<(Clip, Clip), EngineError> {
+    let mut left = Clip {
+        length_ticks: at,
+        ..clip.clone()
+    };
+    let mut right = Clip {
+        id: Uuid::new_v4(),
+        start_tick: clip.start_tick + at,
+        length_ticks: clip.length_ticks - at,
+        content_offset_ticks: clip.content_tick(at),
+        ..clip.clone()
+    };
+    if clip.content_loop_ticks().is_some()
+        || matches!(clip.payload, ClipPayload::Steps(_) | ClipPayload::Linked(_))
+    {
+        return Ok((left, right));
+    }
+
+    let split = clip.content_tick(at);
+    right.content_offset_ticks = 0;
+    match (&mut left.payload, &mut right.payload) {
+        (ClipPayload::Midi(left_midi), ClipPayload::Midi(right_midi)) => {
+            (left_midi.notes, right_midi.notes) = partition_notes(&left_midi.notes, split);
+            for (left_lane, right_lane) in left_midi
+                .controllers
+                .iter_mut()
+                .zip(right_midi.controllers.iter_mut())
+            {
+                let value = left_lane.value_at(split);
+                (left_lane.points, right_lane.points) =
+                    partition_points(&left_lane.points, split, value);
+            }
+        }
+        (ClipPayload::Pattern(left_pattern), ClipPayload::Pattern(right_pattern)) => {
+            (left_pattern.notes, right_pattern.notes) = partition_notes(&left_pattern.notes, split);
+            sync_pattern_rows_from_notes(left_pattern, ppq)?;
+            sync_pattern_rows_from_notes(right_pattern, ppq)?;
+        }
+        (ClipPayload::Automation(left_automation), ClipPayload::Automation(right_automation)) => {
+            let value = automation_value_at(&left_automation.points, split);
+            (left_automation.points, right_automation.points) =
+                partition_points(&left_automation.points, split, value);
+        }
+        (ClipPayload::Audio(left_audio), ClipPayload::Audio(right_audio)) => {
+            let cut = left_audio.source_seconds_at(at as f64, bpm, ppq);
+            if left_audio.reverse {
+                left_audio.trim_start_seconds = cut;
+                right_audio.trim_end_seconds = cut;
+            } else {
+                left_audio.trim_end_seconds = cut;
+                right_audio.trim_start_seconds = cut;
+            }
+            left_audio.fade_out_seconds = 0.0;
+            right_audio.fade_in_seconds = 0.0;
+        }
+        _ => {}
+    }
+    Ok((left, right))
+}
+
+/// Notes before `split` stay left; later notes move right, rebased to zero. A
+/// note held across the split is cut in two.
+fn partition_notes(notes: &[MidiNote], split: u64) -> (Vec<MidiNote>, Vec<MidiNote>) {
+    let mut left = Vec::new();
+    let mut right = Vec::new();
+    for note in notes {
+        if note.start_tick >= split {
+            right.push(MidiNote {
+                start_tick: note.start_tick - split,
+                ..note.clone()
+            });
+            continue;
+        }
+        if note.end_tick() > split {
+            right.push(MidiNote {
+                start_tick: 0,
+                length_ticks: note.end_tick() - split,
+                ..note.clone()
+            });
+            left.push(MidiNote {
+                length_ticks: split - note.start_tick,
+                ..note.clone()
+            });
+            continue;
+        }
+        left.push(note.clone());
+    }
+    (left, right)
+}
+
+/// Partitions automation-style points at `split`, pinning `value` on both sides
+/// of the cut so the curve does not jump.
+fn partition_points(
+    points: &[AutomationPoint],
+    split: u64,
+    value: f32,
+) -> (Vec<AutomationPoint>, Vec<AutomationPoint>) {
+    if points.is_empty() {
+        return (Vec::new(), Vec::new());
+    }
+    let mut left: Vec<AutomationPoint> = points
+        .iter()
+        .filter(|point| point.tick <= split)
+        .cloned()
+        .collect();
+    if left.last().is_none_or(|point| point.tick < split) {
+        left.push(AutomationPoint { tick: split, value });
+    }
+    let mut right = vec![AutomationPoint { tick: 0, value }];
+    right.extend(
+        points
+            .iter()
+            .filter(|point| point.tick > split)
+            .map(|point| AutomationPoint {
+                tick: point.tick - split,
+                value: point.value,
+            }),
+    );
+    (left, right)
+}
+
+/// Linear interpolation between automation points, holding the end values.
+fn automation_value_at(points: &[AutomationPoint], tick: u64) -> f32 {
+    let next_index = points.partition_point(|point| point.tick <= tick);
+    match (
+        next_index
+            .checked_sub(1)
+            .and_then(|index| points.get(index)),
+        points.get(next_index),
+    ) {
+        (Some(previous), Some(next)) => {
+            let span = next.tick.saturating_sub(previous.tick).max(1) as f32;
+            let progress = tick.saturating_sub(previous.tick) as f32 / span;
+            previous.value + (next.value - previous.value) * progress
+        }
+        (Some(point), None) | (None, Some(point)) => point.value,
+        (None, None) => 0.0,
+    }
+}
+
+/// Trim range in seconds, treating an unset end as the end of the source.
+fn clip_audio_trim(audio: &AudioClip) -> (f64, f64) {
+    let (trim_start, trim_end) = audio.normalized_trim_range();
+    if trim_end > trim_start {
+        (trim_start, trim_end)
+    } else {
+        (trim_start, audio.source_duration_seconds.max(trim_start))
+    }
+}
+
+/// Transients inside an audio clip's trimmed range as `(clip tick, strength)`.
+fn audio_onsets(
+    audio: &AudioClip,
+    sensitivity: f32,
+    bpm: f64,
+    ppq: u16,
+) -> Result<Vec<(u64, f32)>, EngineError> {
+    let decoded = decode_audio_file_mono(Path::new(&audio.source_path))?;
+    let (trim_start, trim_end) = clip_audio_trim(audio);
+    let rate = f64::from(decoded.sample_rate.max(1));
+    Ok(detect_transients(&decoded, sensitivity)
+        .into_iter()
+        .filter_map(|transient| {
+            let source_seconds = transient.frame as f64 / rate;
+            if !(trim_start..trim_end).contains(&source_seconds) {
+                return None;
+            }
+            let clip_tick = audio
+                .clip_tick_at(source_seconds, bpm, ppq)
+                .round()
+                .max(0.0);
+            Some((clip_tick as u64, transient.strength))
+        })
+        .collect())
+}
+
+/// Clip ticks where transient slices of `audio` start, always beginning with
+/// `0` and kept inside `length_ticks`.
+fn transient_slice_ticks(
+    audio: &AudioClip,
+    length_ticks: u64,
+    sensitivity: f32,
+    bpm: f64,
+    ppq: u16,
+) -> Result<Vec<u64>, EngineError> {
+    let mut ticks: Vec<u64> = std::iter::once(0)
+        .chain(
+            audio_onsets(audio, sensitivity, bpm, ppq)?
+                .into_iter()
+                .map(|(tick, _)| tick)
+                .filter(|tick| *tick < length_ticks),
+        )
+        .collect();
+    ticks.sort_unstable();
+    ticks.dedup();
+    Ok(ticks)
+}
+
+/// Builds one clip from adjacent clips sorted by start tick.
+fn glue_clip_contents(clips: &[Clip], bpm: f64, ppq: u16) -> Result<Clip, EngineError> {
+    let (Some(first), Some(last)) = (clips.first(), clips.last()) else {
+        return Err(EngineError::InvalidGlue("no clips selected".to_string()));
+    };
+    if clips.len() < 2 {
+        return Err(EngineError::InvalidGlue(
+            "at least two clips are required".to_string(),
+        ));
+    }
+    if let Some(pair) = clips
+        .windows(2)
+        .find(|pair| pair[0].end_tick() != pair[1].start_tick)
+    {
+        return Err(EngineError::InvalidGlue(format!(
+            "clip {} does not end where clip {} starts",
+            pair[0].id, pair[1].id
+        )));
+    }
+
+    let shifted = |clip: &Clip, tick: u64| tick + (clip.start_tick - first.start_tick);
+    let mut glued = Clip {
+        length_ticks: last.end_tick() - first.start_tick,
+        content_offset_ticks: 0,
+        loop_length_ticks: None,
+        transpose_semitones: 0,
+        velocity_scale: DEFAULT_CLIP_VELOCITY_SCALE,
+        key: None,
+        ..first.clone()
+    };
+    let mismatch = || EngineError::InvalidGlue("clips differ in kind or source".to_string());
+    match &mut glued.payload {
+        ClipPayload::Midi(midi) => {
+            midi.notes.clear();
+            midi.controllers.clear();
+            for clip in clips {
+                let ClipPayload::Midi(part) = &clip.payload else {
+                    return Err(mismatch());
+                };
+                if part.instrument != midi.instrument {
+                    return Err(mismatch());
+                }
+                midi.notes
+                    .extend(
+                        clip.arrange_notes(&part.notes)
+                            .into_iter()
+                            .map(|note| MidiNote {
+                                start_tick: shifted(clip, note.start_tick),
+                                ..note
+                            }),
+                    );
+                for lane in &part.controllers {
+                    let points = glued_points(clip, &lane.points, shifted);
+                    match midi
+                        .controllers
+                        .iter_mut()
+                        .find(|glued| glued.target == lane.target)
+                    {
+                        Some(glued) => glued.points.extend(points),
+                        None => midi.controllers.push(ControllerLane {
+                            target: lane.target,
+                            points,
+                        }),
+                    }
+                }
+            }
+            sanitize_controller_lanes(&mut midi.controllers);
+            midi.controllers.sort_by_key(|lane| lane.target);
+        }
+        ClipPayload::Pattern(pattern) => {
+            pattern.notes.clear();
+            for clip in clips {
+                let ClipPayload::Pattern(part) = &clip.payload else {
+                    return Err(mismatch());
+                };
+                if part.source_chip != pattern.source_chip
+                    || part.lines_per_beat != pattern.lines_per_beat
+                {
+                    return Err(mismatch());
+                }
+                pattern
+                    .notes
+                    .extend(
+                        clip.arrange_notes(&part.notes)
+                            .into_iter()
+                            .map(|note| MidiNote {
+                                start_tick: shifted(clip, note.start_tick),
+                                ..note
+                            }),
+                    );
+            }
+            sync_pattern_rows_from_notes(pattern, ppq)?;
+        }
+        ClipPayload::Automation(automation) => {
+            automation.points.clear();
+            for clip in clips {
+                let ClipPayload::Automation(part) = &clip.payload else {
+                    return Err(mismatch());
+                };
+                if part.target_parameter_id != automation.target_parameter_id {
+                    return Err(mismatch());
+                }
+                automation
+                    .points
+                    .extend(glued_points(clip, &part.points, shifted));
+            }
+            sanitize_automation_points(&mut automation.points);
+        }
+        ClipPayload::Audio(audio) => {
+            let tolerance = 1.0 / f64::from(audio.source_sample_rate.max(1));
+            let mut range = clip_audio_trim(audio);
+            for pair in clips.windows(2) {
+                let (ClipPayload::Audio(earlier), ClipPayload::Audio(later)) =
+                    (&pair[0].payload, &pair[1].payload)
+                else {
+                    return Err(mismatch());
+                };
+                let (earlier_start, earlier_end) = clip_audio_trim(earlier);
+                let (later_start, later_end) = clip_audio_trim(later);
+                let contiguous = if audio.reverse {
+                    (later_end - earlier_start).abs() <= tolerance
+                } else {
+                    (later_start - earlier_end).abs() <= tolerance
+                };
+                if later.source_path != audio.source_path
+                    || later.reverse != audio.reverse
+                    || (later.stretch_ratio - audio.stretch_ratio).abs() > f32::EPSILON
+                    || later.stretch_mode != audio.stretch_mode
+                    || later.pitch_ratio() != audio.pitch_ratio()
+                    || later.warp_markers != audio.warp_markers
+                    || !contiguous
+                {
+                    return Err(EngineError::InvalidGlue(
+                        "audio clips must be contiguous ranges of the same source".to_string(),
+                    ));
+                }
+                range = (range.0.min(later_start), range.1.max(later_end));
+                audio.fade_out_seconds = later.fade_out_seconds;
+            }
+            (audio.trim_start_seconds, audio.trim_end_seconds) = range;
+            glued.length_ticks = audio.timeline_length_ticks(bpm, ppq).max(1);
+        }
+        ClipPayload::Steps(_) | ClipPayload::Linked(_) => {
+            return Err(EngineError::InvalidGlue(
+                "step and linked clips cannot be glued".to_string(),
+            ));
+        }
+    }
+    Ok(glued)
+}
+
+/// Points of one glued clip as they play within it, on the glued clip's timeline.
+fn glued_points(
+    clip: &Clip,
+    points: &[AutomationPoint],
+    shifted: impl Fn(&Clip, u64) -> u64,
+) -> Vec<AutomationPoint> {
+    points
+        .iter()
+        .flat_map(|point| {
+            clip.clip_ticks_of(point.tick)
+                .into_iter()
+                .map(|tick| AutomationPoint {
+                    tick: shifted(clip, tick),
+                    value: point.value,
+                })
+        })
+        .collect()
+}
+
+fn sanitize_order_list(order_list: &mut OrderList) -> Result<(), EngineError> {
+    if order_list.row_length_ticks == 0 {
+        return Err(EngineError::InvalidOrderRowLength(
+            order_list.row_length_ticks,
+        ));
+    }
+    if let Some(loop_row) = order_list.loop_row
+        && loop_row >= order_list.rows.len()
+    {
+        return Err(EngineError::InvalidOrderLoopRow(loop_row));
+    }
+
+    let channel_count = order_list.channels.len();
+    for row in &mut order_list.rows {
+        row.patterns.resize(channel_count, None);
+    }
+    Ok(())
 }
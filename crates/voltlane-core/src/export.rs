@@ -1,5 +1,6 @@
 use std::{
     collections::{HashMap, VecDeque},
+    f64::consts::TAU,
     fs,
     path::{Path, PathBuf},
     process::Command,
@@ -7,7 +8,7 @@ use std::{
 
 use anyhow::{Context, Result};
 use midly::{
-    Format, Header, MetaMessage, MidiMessage, Smf, Timing, TrackEvent, TrackEventKind,
+    Format, Header, MetaMessage, MidiMessage, PitchBend, Smf, Timing, TrackEvent, TrackEventKind,
     num::{u4, u7, u15, u24, u28},
 };
 use tracing::{debug, info, instrument, warn};
@@ -16,13 +17,34 @@ use uuid::Uuid;
 use crate::{
     assets::{DecodedAudio, decode_audio_file_mono},
     engine::RenderMode,
+    midi_import::{DRUM_KIT_INSTRUMENT, GM_DRUM_CHANNEL, gm_program_number},
     model::{
-        AudioClip, ChipMacroLane, ClipPayload, EffectSpec, MidiNote, PatternClip, Project, Track,
-        TrackKind,
+        AudioClip, AudioStretchMode, ChipMacroLane, Clip, ClipPayload, ControllerLane,
+        ControllerTarget, EffectSpec, MidiClip, MidiNote, PITCH_BEND_RANGE_SEMITONES, PatternClip,
+        Project, SamplerInstrument, StepSequencerClip, SynthPatch, Track, TrackKind, Tuning,
+        equal_tempered_hz,
     },
-    time::ticks_to_samples,
+    sampler::{SamplerNote, render_sampler_note},
+    sequencer::step_notes,
+    stretch,
+    synth::{EXPRESSION_BLOCK_FRAMES, NoteExpression, PatchNote, render_patch_note},
+    time::{samples_to_ticks, ticks_to_samples},
+    vgm::{GAMEBOY_TONE_CLOCK, NES_APU_CLOCK, SN76489_CLOCK, period_for},
 };
 
+const GM_VOLUME_CC: u8 = 7;
+const GM_PAN_CC: u8 = 10;
+const GM_MOD_WHEEL_CC: u8 = 1;
+const GM_EXPRESSION_CC: u8 = 11;
+const GM_SUSTAIN_CC: u8 = 64;
+/// Intermediate controller events written per beat while a lane ramps.
+const CONTROLLER_RAMP_STEPS_PER_BEAT: u64 = 8;
+const MOD_WHEEL_VIBRATO_HZ: f64 = 5.5;
+const MOD_WHEEL_VIBRATO_SEMITONES: f32 = 0.5;
+const GM_SQUARE_LEAD_PROGRAM: u8 = 80;
+/// Every channel except the GM percussion channel, in allocation order.
+const MELODIC_CHANNELS: [u8; 15] = [0, 1, 2, 3, 4, 5, 6, 7, 8, 10, 11, 12, 13, 14, 15];
+
 #[derive(Debug, Clone)]
 struct SynthEvent {
     start_sample: usize,
@@ -33,6 +55,16 @@ struct SynthEvent {
     release_frames: usize,
     waveform: Waveform,
     color: VoiceColor,
+    expression: NoteExpression,
+}
+
+/// Controller lanes of the MIDI clip a note plays in. Pattern and step notes play
+/// without any. Lanes are read in content time, so they follow the clip's offset
+/// and loop.
+#[derive(Debug, Clone, Copy)]
+struct ClipControllers<'a> {
+    lanes: &'a [ControllerLane],
+    placement: Option<&'a Clip>,
 }
 
 #[derive(Debug, Clone, Copy)]
@@ -76,7 +108,10 @@ struct RenderStats {
 #[instrument(skip(project), fields(project_id = %project.id))]
 pub fn render_project_samples(project: &Project, tail_seconds: f64) -> Vec<f32> {
     let sample_rate = project.sample_rate.max(8_000);
-    let end_tick = project.max_tick();
+    let end_tick = project
+        .song_loop_ticks()
+        .map_or(0, |(_, loop_end_tick)| loop_end_tick)
+        .max(project.max_tick());
     let end_samples = ticks_to_samples(end_tick, project.bpm, project.ppq, sample_rate);
     let tail_samples = (tail_seconds.max(0.0) * f64::from(sample_rate)).round() as u64;
     let total_frames = end_samples
@@ -213,10 +248,75 @@ pub fn export_wav(project: &Project, path: &Path, render_mode: RenderMode) -> Re
     }
 
     writer.finalize().context("failed to finalize wav file")?;
+    if let Some((loop_start_frame, loop_end_frame)) = song_loop_frames(project) {
+        append_wav_loop_chunk(path, project.sample_rate, loop_start_frame, loop_end_frame)?;
+        debug!(loop_start_frame, loop_end_frame, "wav loop points written");
+    }
     info!("wav export completed");
     Ok(())
 }
 
+/// Song loop region in output frames, derived from the order list loop row.
+#[must_use]
+pub fn song_loop_frames(project: &Project) -> Option<(u64, u64)> {
+    let (loop_start_tick, loop_end_tick) = project.song_loop_ticks()?;
+    let sample_rate = project.sample_rate.max(8_000);
+    Some((
+        ticks_to_samples(loop_start_tick, project.bpm, project.ppq, sample_rate),
+        ticks_to_samples(loop_end_tick, project.bpm, project.ppq, sample_rate),
+    ))
+}
+
+/// Appends a RIFF `smpl` chunk with a single forward loop so samplers and game
+/// engines pick up the song loop from the exported file.
+fn append_wav_loop_chunk(
+    path: &Path,
+    sample_rate: u32,
+    loop_start_frame: u64,
+    loop_end_frame: u64,
+) -> Result<()> {
+    let mut bytes =
+        fs::read(path).with_context(|| format!("failed to reopen wav file: {}", path.display()))?;
+    if bytes.len() < 12 || &bytes[0..4] != b"RIFF" {
+        return Err(anyhow::anyhow!("wav file is missing its RIFF header"));
+    }
+
+    let sample_period = 1_000_000_000 / sample_rate.max(1);
+    let loop_start = loop_start_frame.min(u64::from(u32::MAX)) as u32;
+    let loop_end = loop_end_frame
+        .saturating_sub(1)
+        .max(loop_start_frame)
+        .min(u64::from(u32::MAX)) as u32;
+    let fields: [u32; 15] = [
+        0,             // manufacturer
+        0,             // product
+        sample_period, // sample period in nanoseconds
+        60,            // MIDI unity note
+        0,             // MIDI pitch fraction
+        0,             // SMPTE format
+        0,             // SMPTE offset
+        1,             // loop count
+        0,             // sampler data size
+        0,             // cue point id
+        0,             // loop type: forward
+        loop_start,
+        loop_end, // inclusive end frame
+        0,        // fraction
+        0,        // play count: infinite
+    ];
+
+    bytes.extend_from_slice(b"smpl");
+    bytes.extend_from_slice(&((fields.len() * 4) as u32).to_le_bytes());
+    for field in fields {
+        bytes.extend_from_slice(&field.to_le_bytes());
+    }
+    let riff_size = (bytes.len() - 8) as u32;
+    bytes[4..8].copy_from_slice(&riff_size.to_le_bytes());
+
+    fs::write(path, bytes)
+        .with_context(|| format!("failed to write wav loop chunk: {}", path.display()))
+}
+
 #[instrument(skip(project), fields(project_id = %project.id, path = %path.display()))]
 pub fn export_midi(project: &Project, path: &Path) -> Result<()> {
     if let Some(parent) = path.parent() {
@@ -331,52 +431,115 @@ pub fn export_stem_wav(
 
 #[instrument(skip(project), fields(project_id = %project.id))]
 pub fn midi_bytes(project: &Project) -> Result<Vec<u8>> {
+    let tuning_sysex = mts_note_tuning_messages(&project.tuning);
     let mut tracks = Vec::new();
-    tracks.push(build_tempo_track(project));
+    tracks.push(build_tempo_track(project, &tuning_sysex));
 
-    for (track_index, track) in project.tracks.iter().enumerate() {
+    let mut mix_automation = mix_automation_events(project);
+    let mut melodic_tracks = 0_usize;
+    for track in &project.tracks {
         if !track.enabled || track.mute || track.hidden {
             continue;
         }
 
-        let mut absolute_events = Vec::new();
+        let drums = is_drum_track(project, track);
+        let channel = if drums {
+            GM_DRUM_CHANNEL
+        } else {
+            MELODIC_CHANNELS[melodic_tracks % MELODIC_CHANNELS.len()]
+        };
+
+        let mut note_events = Vec::new();
+        let mut program_changes = Vec::new();
         for clip in &track.clips {
             if clip.disabled {
                 continue;
             }
+            let Some(payload) = project.resolved_payload(clip) else {
+                warn!(clip_id = %clip.id, "linked clip content missing from pool, skipping");
+                continue;
+            };
 
-            match &clip.payload {
+            match payload {
                 ClipPayload::Midi(midi_clip) => {
-                    for note in &midi_clip.notes {
-                        absolute_events.extend(note_to_midi_events(note, clip.start_tick));
+                    if let Some(program) =
+                        midi_clip.instrument.as_deref().and_then(gm_program_number)
+                    {
+                        program_changes.push((clip.start_tick, program));
+                    }
+                    for note in clip.arrange_notes(&midi_clip.notes) {
+                        note_events.extend(note_to_midi_events(&note, clip.start_tick, channel));
+                    }
+                    for lane in &midi_clip.controllers {
+                        note_events.extend(controller_lane_events(
+                            lane,
+                            clip,
+                            channel,
+                            project.ppq,
+                        ));
                     }
                 }
                 ClipPayload::Pattern(pattern_clip) => {
-                    for note in &pattern_clip.notes {
-                        let macro_note = apply_pattern_macros(note, pattern_clip, project.ppq);
-                        absolute_events.extend(note_to_midi_events(&macro_note, clip.start_tick));
+                    let macro_notes: Vec<MidiNote> = pattern_clip
+                        .notes
+                        .iter()
+                        .map(|note| apply_pattern_macros(note, pattern_clip, project.ppq))
+                        .collect();
+                    for note in clip.arrange_notes(&macro_notes) {
+                        note_events.extend(note_to_midi_events(&note, clip.start_tick, channel));
+                    }
+                }
+                ClipPayload::Steps(step_clip) => {
+                    let content: Vec<MidiNote> =
+                        step_notes(step_clip, clip.content_length_ticks(), project.ppq)
+                            .into_iter()
+                            .map(|step_note| step_note.note)
+                            .collect();
+                    for note in clip.arrange_notes(&content) {
+                        note_events.extend(note_to_midi_events(&note, clip.start_tick, channel));
                     }
                 }
-                ClipPayload::Audio(_) | ClipPayload::Automation(_) => {}
+                ClipPayload::Audio(_) | ClipPayload::Automation(_) | ClipPayload::Linked(_) => {}
             }
         }
 
-        if absolute_events.is_empty() {
+        if note_events.is_empty() {
             continue;
         }
+        if !drums {
+            melodic_tracks += 1;
+        }
 
+        program_changes.sort_by_key(|(tick, _)| *tick);
+        let default_program = if matches!(track.kind, TrackKind::Chip) {
+            GM_SQUARE_LEAD_PROGRAM
+        } else {
+            0
+        };
+        let mut program = program_changes
+            .first()
+            .map_or(default_program, |(_, program)| *program);
+        let mut absolute_events = vec![
+            program_change_event(0, channel, program),
+            control_change_event(0, channel, GM_VOLUME_CC, gain_db_to_cc(track.gain_db)),
+            control_change_event(0, channel, GM_PAN_CC, pan_to_cc(track.pan)),
+        ];
+        for (tick, next_program) in program_changes {
+            if next_program != program {
+                absolute_events.push(program_change_event(tick, channel, next_program));
+                program = next_program;
+            }
+        }
+        for (tick, controller, value) in mix_automation.remove(&track.id).unwrap_or_default() {
+            absolute_events.push(control_change_event(tick, channel, controller, value));
+        }
+        absolute_events.extend(note_events);
         absolute_events.sort_by_key(|event| (event.tick, event.order));
 
         let mut track_events = Vec::with_capacity(absolute_events.len() + 2);
-        let program = (track_index % 128) as u8;
         track_events.push(TrackEvent {
             delta: u28::from(0_u32),
-            kind: TrackEventKind::Midi {
-                channel: u4::from(0),
-                message: MidiMessage::ProgramChange {
-                    program: u7::from(program),
-                },
-            },
+            kind: TrackEventKind::Meta(MetaMessage::TrackName(track.name.as_bytes())),
         });
 
         let mut previous_tick = 0_u64;
@@ -411,28 +574,258 @@ pub fn midi_bytes(project: &Project) -> Result<Vec<u8>> {
     Ok(bytes)
 }
 
-fn build_tempo_track(project: &Project) -> Vec<TrackEvent<'static>> {
+/// A track plays the GM drum kit when it holds step sequencer clips or any of its
+/// MIDI clips selects the drum kit instrument.
+fn is_drum_track(project: &Project, track: &Track) -> bool {
+    track
+        .clips
+        .iter()
+        .any(|clip| match project.resolved_payload(clip) {
+            Some(ClipPayload::Steps(_)) => true,
+            Some(ClipPayload::Midi(midi_clip)) => midi_clip
+                .instrument
+                .as_deref()
+                .is_some_and(|name| name.trim().eq_ignore_ascii_case(DRUM_KIT_INSTRUMENT)),
+            _ => false,
+        })
+}
+
+/// Gain and pan automation as `(tick, controller, value)` CC events, keyed by the
+/// track the automation targets.
+fn mix_automation_events(project: &Project) -> HashMap<Uuid, Vec<(u64, u8, u8)>> {
+    let mut events: HashMap<Uuid, Vec<(u64, u8, u8)>> = HashMap::new();
+    for clip in project.tracks.iter().flat_map(|track| track.clips.iter()) {
+        let Some(ClipPayload::Automation(automation)) = project.resolved_payload(clip) else {
+            continue;
+        };
+        if clip.disabled {
+            continue;
+        }
+        let mut parts = automation.target_parameter_id.split(':');
+        let (Some("track"), Some(track_id), Some(parameter), None) =
+            (parts.next(), parts.next(), parts.next(), parts.next())
+        else {
+            continue;
+        };
+        let Ok(track_id) = Uuid::parse_str(track_id) else {
+            continue;
+        };
+        let (controller, to_cc): (u8, fn(f32) -> u8) = match parameter {
+            "gain_db" => (GM_VOLUME_CC, gain_db_to_cc),
+            "pan" => (GM_PAN_CC, pan_to_cc),
+            _ => continue,
+        };
+        events
+            .entry(track_id)
+            .or_default()
+            .extend(automation.points.iter().flat_map(|point| {
+                clip.clip_ticks_of(point.tick).into_iter().map(move |tick| {
+                    (
+                        clip.start_tick.saturating_add(tick),
+                        controller,
+                        to_cc(point.value),
+                    )
+                })
+            }));
+    }
+    events
+}
+
+/// Maps track gain onto CC7 using the GM volume curve (40 * log10(cc / 127) dB).
+fn gain_db_to_cc(gain_db: f32) -> u8 {
+    (127.0 * 10.0_f32.powf(gain_db / 40.0))
+        .round()
+        .clamp(0.0, 127.0) as u8
+}
+
+/// Maps pan onto CC10 with centre at 64, hard left at 0 and hard right at 127.
+fn pan_to_cc(pan: f32) -> u8 {
+    let pan = pan.clamp(-1.0, 1.0);
+    let span = if pan < 0.0 { 64.0 } else { 63.0 };
+    (64.0 + pan * span).round().clamp(0.0, 127.0) as u8
+}
+
+fn program_change_event(tick: u64, channel: u8, program: u8) -> AbsoluteMidiEvent {
+    AbsoluteMidiEvent {
+        tick,
+        order: 0,
+        kind: TrackEventKind::Midi {
+            channel: u4::from(channel),
+            message: MidiMessage::ProgramChange {
+                program: u7::from(program.min(127)),
+            },
+        },
+    }
+}
+
+fn control_change_event(tick: u64, channel: u8, controller: u8, value: u8) -> AbsoluteMidiEvent {
+    AbsoluteMidiEvent {
+        tick,
+        order: 0,
+        kind: TrackEventKind::Midi {
+            channel: u4::from(channel),
+            message: MidiMessage::Controller {
+                controller: u7::from(controller),
+                value: u7::from(value.min(127)),
+            },
+        },
+    }
+}
+
+/// Writes a clip controller lane as CC or pitch bend events, adding intermediate
+/// steps where the lane ramps between points. A slipped or looping clip restates
+/// the lane value at its left edge and at each loop start.
+fn controller_lane_events(
+    lane: &ControllerLane,
+    clip: &Clip,
+    channel: u8,
+    ppq: u16,
+) -> Vec<AbsoluteMidiEvent> {
+    let ramp_step = (u64::from(ppq) / CONTROLLER_RAMP_STEPS_PER_BEAT).max(1);
+    let points = &lane.points;
+    if points.is_empty() {
+        return Vec::new();
+    }
+
+    let mut ticks = Vec::new();
+    if clip.content_loop_ticks().is_some() {
+        ticks.push(0);
+    }
+    for (index, point) in points.iter().enumerate() {
+        ticks.push(point.tick);
+        let Some(next) = points.get(index + 1) else {
+            continue;
+        };
+        if lane.target != ControllerTarget::Sustain
+            && (next.value - point.value).abs() > f32::EPSILON
+        {
+            ticks.extend((point.tick + ramp_step..next.tick).step_by(ramp_step as usize));
+        }
+    }
+
+    let mut placed: Vec<(u64, u64)> = ticks
+        .into_iter()
+        .flat_map(|tick| {
+            clip.clip_ticks_of(tick)
+                .into_iter()
+                .map(move |clip_tick| (clip_tick, tick))
+        })
+        .collect();
+    if clip.content_offset_ticks > 0 {
+        placed.push((0, clip.content_tick(0)));
+    }
+    placed.sort_unstable();
+    placed.dedup_by_key(|(clip_tick, _)| *clip_tick);
+
+    let mut events: Vec<AbsoluteMidiEvent> = Vec::with_capacity(placed.len());
+    for (clip_tick, tick) in placed {
+        let event = controller_event(
+            clip.start_tick.saturating_add(clip_tick),
+            channel,
+            lane.target,
+            lane.value_at(tick),
+        );
+        if events
+            .last()
+            .is_some_and(|previous| previous.kind == event.kind)
+        {
+            continue;
+        }
+        events.push(event);
+    }
+    events
+}
+
+fn controller_event(
+    tick: u64,
+    channel: u8,
+    target: ControllerTarget,
+    value: f32,
+) -> AbsoluteMidiEvent {
+    let to_cc = |value: f32| (value.clamp(0.0, 1.0) * 127.0).round() as u8;
+    match target {
+        ControllerTarget::ModWheel => {
+            control_change_event(tick, channel, GM_MOD_WHEEL_CC, to_cc(value))
+        }
+        ControllerTarget::Expression => {
+            control_change_event(tick, channel, GM_EXPRESSION_CC, to_cc(value))
+        }
+        ControllerTarget::Sustain => {
+            let value = if value >= 0.5 { 127 } else { 0 };
+            control_change_event(tick, channel, GM_SUSTAIN_CC, value)
+        }
+        ControllerTarget::PitchBend => AbsoluteMidiEvent {
+            tick,
+            order: 0,
+            kind: TrackEventKind::Midi {
+                channel: u4::from(channel),
+                message: MidiMessage::PitchBend {
+                    bend: PitchBend::from_f32(value.clamp(-1.0, 1.0)),
+                },
+            },
+        },
+    }
+}
+
+fn build_tempo_track<'a>(project: &'a Project, tuning_sysex: &'a [Vec<u8>]) -> Vec<TrackEvent<'a>> {
     let bpm = project.bpm.max(10.0);
     let micros_per_quarter = (60_000_000.0 / bpm).round() as u32;
+    let numerator = project.time_signature.numerator.max(1);
+    let denominator_power = project.time_signature.denominator.max(1).ilog2() as u8;
 
-    vec![
+    let mut events = vec![
         TrackEvent {
             delta: u28::from(0_u32),
-            kind: TrackEventKind::Meta(MetaMessage::Tempo(u24::from(micros_per_quarter))),
+            kind: TrackEventKind::Meta(MetaMessage::TrackName(project.title.as_bytes())),
         },
         TrackEvent {
             delta: u28::from(0_u32),
-            kind: TrackEventKind::Meta(MetaMessage::TimeSignature(4, 2, 24, 8)),
+            kind: TrackEventKind::Meta(MetaMessage::Tempo(u24::from(micros_per_quarter))),
         },
         TrackEvent {
             delta: u28::from(0_u32),
-            kind: TrackEventKind::Meta(MetaMessage::EndOfTrack),
+            kind: TrackEventKind::Meta(MetaMessage::TimeSignature(
+                numerator,
+                denominator_power,
+                24,
+                8,
+            )),
         },
-    ]
+    ];
+    events.extend(tuning_sysex.iter().map(|message| TrackEvent {
+        delta: u28::from(0_u32),
+        kind: TrackEventKind::SysEx(message),
+    }));
+
+    // Game music players look for these marker names to find the loop-back point.
+    if let Some((loop_start_tick, loop_end_tick)) = project.song_loop_ticks() {
+        let start_delta = loop_start_tick.min(u64::from(u32::MAX)) as u32;
+        let end_delta = loop_end_tick
+            .saturating_sub(loop_start_tick)
+            .min(u64::from(u32::MAX)) as u32;
+        events.push(TrackEvent {
+            delta: u28::from(start_delta),
+            kind: TrackEventKind::Meta(MetaMessage::Marker(b"loopStart")),
+        });
+        events.push(TrackEvent {
+            delta: u28::from(end_delta),
+            kind: TrackEventKind::Meta(MetaMessage::Marker(b"loopEnd")),
+        });
+    }
+
+    events.push(TrackEvent {
+        delta: u28::from(0_u32),
+        kind: TrackEventKind::Meta(MetaMessage::EndOfTrack),
+    });
+    events
 }
 
-fn note_to_midi_events(note: &MidiNote, clip_start_tick: u64) -> [AbsoluteMidiEvent; 2] {
-    let channel = note.channel.min(15);
+fn note_to_midi_events(
+    note: &MidiNote,
+    clip_start_tick: u64,
+    channel: u8,
+) -> [AbsoluteMidiEvent; 2] {
+    let channel = channel.min(15);
     let pitch = note.pitch.min(127);
     let velocity = note.velocity.min(127);
 
@@ -458,7 +851,7 @@ fn note_to_midi_events(note: &MidiNote, clip_start_tick: u64) -> [AbsoluteMidiEv
                 channel: u4::from(channel),
                 message: MidiMessage::NoteOff {
                     key: u7::from(pitch),
-                    vel: u7::from(0),
+                    vel: u7::from(note.release_velocity.unwrap_or(0).min(127)),
                 },
             },
         },
@@ -470,7 +863,7 @@ fn render_track_source_buffers(
     frame_count: usize,
     stats: &mut RenderStats,
 ) -> HashMap<Uuid, Vec<f32>> {
-    let mut decoded_cache: HashMap<String, DecodedAudio> = HashMap::new();
+    let mut decoded_cache: HashMap<String, Option<DecodedAudio>> = HashMap::new();
     let mut buffers = HashMap::new();
 
     for track in &project.tracks {
@@ -483,26 +876,16 @@ fn render_track_source_buffers(
             if clip.disabled {
                 continue;
             }
+            let Some(payload) = project.resolved_payload(clip) else {
+                warn!(clip_id = %clip.id, "linked clip content missing from pool, skipping");
+                continue;
+            };
 
-            match &clip.payload {
+            match payload {
                 ClipPayload::Audio(audio_clip) => {
-                    if !decoded_cache.contains_key(&audio_clip.source_path) {
-                        match decode_audio_file_mono(Path::new(&audio_clip.source_path)) {
-                            Ok(decoded) => {
-                                decoded_cache.insert(audio_clip.source_path.clone(), decoded);
-                            }
-                            Err(error) => {
-                                warn!(
-                                    path = %audio_clip.source_path,
-                                    ?error,
-                                    "failed to decode audio clip source while rendering, skipping clip"
-                                );
-                                continue;
-                            }
-                        }
-                    }
-
-                    let Some(decoded) = decoded_cache.get(&audio_clip.source_path) else {
+                    let Some(decoded) =
+                        cached_decoded_audio(&mut decoded_cache, &audio_clip.source_path)
+                    else {
                         continue;
                     };
                     if decoded.samples.is_empty() {
@@ -521,6 +904,31 @@ fn render_track_source_buffers(
                     stats.rendered_audio_clips += 1;
                 }
                 ClipPayload::Midi(midi_clip) => {
+                    let controllers = ClipControllers::of(midi_clip, clip);
+                    let notes = clip.arrange_notes(&midi_clip.notes);
+                    if let Some(patch) = track.synth_patch(midi_clip.instrument.as_deref()) {
+                        stats.rendered_notes += render_synth_patch_notes(
+                            patch,
+                            &notes,
+                            controllers,
+                            clip.start_tick,
+                            project,
+                            &mut track_buffer,
+                        );
+                        continue;
+                    }
+                    if let Some(sampler) = track.sampler(midi_clip.instrument.as_deref()) {
+                        stats.rendered_notes += render_sampler_notes(
+                            sampler,
+                            &notes,
+                            controllers,
+                            clip.start_tick,
+                            project,
+                            &mut decoded_cache,
+                            &mut track_buffer,
+                        );
+                        continue;
+                    }
                     let waveform = if matches!(track.kind, TrackKind::Chip) {
                         Waveform::Pulse { duty_cycle: 0.5 }
                     } else {
@@ -531,25 +939,71 @@ fn render_track_source_buffers(
                     } else {
                         VoiceColor::Clean
                     };
-                    for note in &midi_clip.notes {
-                        let event =
-                            synth_event_for_note(note, clip.start_tick, project, waveform, color);
+                    for note in &notes {
+                        let Some(frequency_hz) = project.tuning.frequency_hz(note.pitch.min(127))
+                        else {
+                            continue;
+                        };
+                        let note = controllers.sustained(note);
+                        let mut event = synth_event_for_note(
+                            &note,
+                            frequency_hz,
+                            clip.start_tick,
+                            project,
+                            waveform,
+                            color,
+                        );
+                        event.expression = controllers.expression(
+                            &note,
+                            event.end_sample.saturating_sub(event.start_sample),
+                            project,
+                        );
+                        event.release_frames = (event.release_frames as f32
+                            * event.expression.release_scale)
+                            .round() as usize;
                         render_synth_event(&event, &mut track_buffer);
                         stats.rendered_notes += 1;
                     }
                 }
                 ClipPayload::Pattern(pattern_clip) => {
+                    if let Some(sampler) = track.sampler(pattern_clip.instrument.as_deref()) {
+                        let notes: Vec<MidiNote> = pattern_clip
+                            .notes
+                            .iter()
+                            .map(|note| apply_pattern_macros(note, pattern_clip, project.ppq))
+                            .collect();
+                        stats.rendered_notes += render_sampler_notes(
+                            sampler,
+                            &clip.arrange_notes(&notes),
+                            ClipControllers::none(),
+                            clip.start_tick,
+                            project,
+                            &mut decoded_cache,
+                            &mut track_buffer,
+                        );
+                        continue;
+                    }
                     let backend = chip_backend_for_source(&pattern_clip.source_chip);
                     render_pattern_clip(
+                        clip,
                         pattern_clip,
                         backend,
-                        clip.start_tick,
                         project,
                         &mut track_buffer,
                         stats,
                     );
                 }
-                ClipPayload::Automation(_) => {}
+                ClipPayload::Steps(step_clip) => {
+                    stats.rendered_notes += render_step_clip(
+                        track,
+                        clip,
+                        step_clip,
+                        project,
+                        &mut decoded_cache,
+                        &mut track_buffer,
+                    );
+                }
+                ClipPayload::Automation(_) | ClipPayload::Linked(_) => {}
             }
         }
 
@@ -564,10 +1018,12 @@ fn render_track_source_buffers(
     buffers
 }
 
+/// Renders pattern notes through the chip backend. Macros and waveform choice
+/// follow each note's content position; the clip then places every copy.
 fn render_pattern_clip(
+    clip: &Clip,
     pattern: &PatternClip,
     backend: ChipBackend,
-    clip_start_tick: u64,
     project: &Project,
     buffer: &mut [f32],
     stats: &mut RenderStats,
@@ -578,26 +1034,311 @@ fn render_pattern_clip(
             .map(|value| chip_backend_duty_cycle(backend, value))
             .unwrap_or_else(|| chip_backend_default_duty(backend));
         let waveform = chip_waveform_for_note(pattern, backend, note, project.ppq, duty_cycle);
-        let color = chip_backend_color(backend);
-        let mut event =
-            synth_event_for_note(&macro_note, clip_start_tick, project, waveform, color);
-        event.amplitude *= chip_backend_level(backend);
-        event.attack_frames = 8;
-        event.release_frames = 64;
-        render_synth_event(&event, buffer);
-        stats.rendered_notes += 1;
+        for placed in clip.arrange_notes(std::slice::from_ref(&macro_note)) {
+            let frequency_hz = if matches!(waveform, Waveform::Noise { .. }) {
+                equal_tempered_hz(placed.pitch.min(127))
+            } else {
+                let Some(frequency_hz) = project.tuning.frequency_hz(placed.pitch.min(127)) else {
+                    continue;
+                };
+                chip_backend_frequency(backend, waveform, frequency_hz)
+            };
+            let color = chip_backend_color(backend);
+            let mut event = synth_event_for_note(
+                &placed,
+                frequency_hz,
+                clip.start_tick,
+                project,
+                waveform,
+                color,
+            );
+            event.amplitude *= chip_backend_level(backend);
+            event.attack_frames = 8;
+            event.release_frames = 64;
+            render_synth_event(&event, buffer);
+            stats.rendered_notes += 1;
+        }
+    }
+}
+
+fn cached_decoded_audio<'a>(
+    cache: &'a mut HashMap<String, Option<DecodedAudio>>,
+    source_path: &str,
+) -> Option<&'a DecodedAudio> {
+    cache
+        .entry(source_path.to_string())
+        .or_insert_with(|| match decode_audio_file_mono(Path::new(source_path)) {
+            Ok(decoded) => Some(decoded),
+            Err(error) => {
+                warn!(
+                    path = %source_path,
+                    ?error,
+                    "failed to decode audio source while rendering, skipping"
+                );
+                None
+            }
+        })
+        .as_ref()
+}
+
+impl<'a> ClipControllers<'a> {
+    fn of(midi_clip: &'a MidiClip, clip: &'a Clip) -> Self {
+        Self {
+            lanes: &midi_clip.controllers,
+            placement: Some(clip),
+        }
+    }
+
+    fn none() -> Self {
+        Self {
+            lanes: &[],
+            placement: None,
+        }
+    }
+
+    fn content_tick(&self, clip_tick: u64) -> u64 {
+        self.placement
+            .map_or(clip_tick, |clip| clip.content_tick(clip_tick))
+    }
+
+    fn lane(&self, target: ControllerTarget) -> Option<&'a ControllerLane> {
+        self.lanes.iter().find(|lane| lane.target == target)
+    }
+
+    /// The clip-relative note with its gate held until the sustain pedal lifts
+    /// (or the clip ends) when the pedal is down at its note-off.
+    fn sustained(&self, note: &MidiNote) -> MidiNote {
+        let end_tick = note.end_tick();
+        let Some(sustain) = self.lane(ControllerTarget::Sustain) else {
+            return note.clone();
+        };
+        let content_end = self.content_tick(end_tick);
+        if !sustain.pedal_down_at(content_end) {
+            return note.clone();
+        }
+        let clip_length_ticks = self.placement.map_or(0, |clip| clip.length_ticks);
+        let release_tick = sustain
+            .points
+            .iter()
+            .find(|point| point.tick > content_end && point.value < 0.5)
+            .map_or(clip_length_ticks.max(end_tick), |point| {
+                end_tick.saturating_add(point.tick - content_end)
+            });
+        MidiNote {
+            length_ticks: release_tick.saturating_sub(note.start_tick),
+            ..note.clone()
+        }
+    }
+
+    /// Samples pitch bend, mod wheel vibrato and expression over a note gate.
+    fn expression(&self, note: &MidiNote, gate_frames: usize, project: &Project) -> NoteExpression {
+        let mut expression = NoteExpression {
+            release_scale: NoteExpression::release_scale_for_velocity(note.release_velocity),
+            ..NoteExpression::default()
+        };
+        let bend = self.lane(ControllerTarget::PitchBend);
+        let mod_wheel = self.lane(ControllerTarget::ModWheel);
+        let level = self.lane(ControllerTarget::Expression);
+        if bend.is_none() && mod_wheel.is_none() && level.is_none() {
+            return expression;
+        }
+
+        let sample_rate = f64::from(project.sample_rate.max(1));
+        let blocks = gate_frames.div_ceil(EXPRESSION_BLOCK_FRAMES) + 1;
+        for block in 0..blocks {
+            let frame = block * EXPRESSION_BLOCK_FRAMES;
+            let tick = self.content_tick(note.start_tick.saturating_add(samples_to_ticks(
+                frame as u64,
+                project.bpm,
+                project.ppq,
+                project.sample_rate,
+            )));
+            if bend.is_some() || mod_wheel.is_some() {
+                let bend_semitones = bend.map_or(0.0, |lane| {
+                    lane.value_at(tick).clamp(-1.0, 1.0) * PITCH_BEND_RANGE_SEMITONES
+                });
+                let vibrato = mod_wheel.map_or(0.0, |lane| {
+                    let phase = TAU * MOD_WHEEL_VIBRATO_HZ * frame as f64 / sample_rate;
+                    lane.value_at(tick).clamp(0.0, 1.0)
+                        * MOD_WHEEL_VIBRATO_SEMITONES
+                        * phase.sin() as f32
+                });
+                expression.pitch_semitones.push(bend_semitones + vibrato);
+            }
+            if let Some(lane) = level {
+                expression.gain.push(lane.value_at(tick).clamp(0.0, 1.0));
+            }
+        }
+        expression
+    }
+}
+
+fn render_sampler_notes(
+    sampler: &SamplerInstrument,
+    notes: &[MidiNote],
+    controllers: ClipControllers<'_>,
+    clip_start_tick: u64,
+    project: &Project,
+    decoded_cache: &mut HashMap<String, Option<DecodedAudio>>,
+    buffer: &mut [f32],
+) -> usize {
+    let mut rendered = 0_usize;
+    for note in notes {
+        let Some(frequency_hz) = project.tuning.frequency_hz(note.pitch.min(127)) else {
+            continue;
+        };
+        let note = &controllers.sustained(note);
+        let start_tick = clip_start_tick.saturating_add(note.start_tick);
+        let end_tick = clip_start_tick.saturating_add(note.end_tick());
+        let start_frame =
+            ticks_to_samples(start_tick, project.bpm, project.ppq, project.sample_rate) as usize;
+        let end_frame =
+            ticks_to_samples(end_tick, project.bpm, project.ppq, project.sample_rate) as usize;
+        let gate_frames = end_frame.saturating_sub(start_frame).max(1);
+        let sampler_note = SamplerNote {
+            pitch: note.pitch.min(127),
+            velocity: note.velocity.min(127),
+            frequency_hz,
+            start_frame,
+            gate_frames,
+            expression: controllers.expression(note, gate_frames, project),
+        };
+
+        let mut sounded = false;
+        for zone in sampler.zones_for(sampler_note.pitch, sampler_note.velocity) {
+            let Some(decoded) = cached_decoded_audio(decoded_cache, &zone.source_path) else {
+                continue;
+            };
+            if render_sampler_note(
+                sampler,
+                zone,
+                decoded,
+                &sampler_note,
+                project.sample_rate,
+                buffer,
+            ) > 0
+            {
+                sounded = true;
+            }
+        }
+        if sounded {
+            rendered += 1;
+        }
+    }
+    rendered
+}
+
+fn render_step_clip(
+    track: &Track,
+    clip: &Clip,
+    step_clip: &StepSequencerClip,
+    project: &Project,
+    decoded_cache: &mut HashMap<String, Option<DecodedAudio>>,
+    buffer: &mut [f32],
+) -> usize {
+    let clip_start_tick = clip.start_tick;
+    let notes = step_notes(step_clip, clip.content_length_ticks(), project.ppq);
+    let backend = chip_backend_for_source(&step_clip.source_chip);
+    let mut rendered = 0_usize;
+
+    for (row_index, row) in step_clip.rows.iter().enumerate() {
+        let row_notes: Vec<MidiNote> = notes
+            .iter()
+            .filter(|step_note| step_note.row_index == row_index)
+            .map(|step_note| step_note.note.clone())
+            .collect();
+        let row_notes = clip.arrange_notes(&row_notes);
+        if row_notes.is_empty() {
+            continue;
+        }
+
+        if let Some(sampler) = track.sampler(row.instrument.as_deref()) {
+            rendered += render_sampler_notes(
+                sampler,
+                &row_notes,
+                ClipControllers::none(),
+                clip_start_tick,
+                project,
+                decoded_cache,
+                buffer,
+            );
+            continue;
+        }
+
+        for note in &row_notes {
+            let seed = 0xD2A4_u32
+                .wrapping_mul(u32::from(note.pitch).saturating_add(1))
+                .wrapping_add(row_index as u32);
+            let mut event = synth_event_for_note(
+                note,
+                equal_tempered_hz(note.pitch.min(127)),
+                clip_start_tick,
+                project,
+                Waveform::Noise { seed },
+                chip_backend_color(backend),
+            );
+            event.amplitude *= chip_backend_level(backend);
+            event.attack_frames = 4;
+            event.release_frames = 96;
+            render_synth_event(&event, buffer);
+            rendered += 1;
+        }
+    }
+    rendered
+}
+
+fn render_synth_patch_notes(
+    patch: &SynthPatch,
+    notes: &[MidiNote],
+    controllers: ClipControllers<'_>,
+    clip_start_tick: u64,
+    project: &Project,
+    buffer: &mut [f32],
+) -> usize {
+    let mut ordered: Vec<MidiNote> = notes
+        .iter()
+        .map(|note| controllers.sustained(note))
+        .collect();
+    ordered.sort_by_key(|note| (note.start_tick, note.pitch));
+
+    let mut previous_frequency = None;
+    let mut rendered = 0_usize;
+    for note in &ordered {
+        let Some(frequency_hz) = project.tuning.frequency_hz(note.pitch.min(127)) else {
+            continue;
+        };
+        let start_tick = clip_start_tick.saturating_add(note.start_tick);
+        let end_tick = clip_start_tick.saturating_add(note.end_tick());
+        let start_frame =
+            ticks_to_samples(start_tick, project.bpm, project.ppq, project.sample_rate) as usize;
+        let end_frame =
+            ticks_to_samples(end_tick, project.bpm, project.ppq, project.sample_rate) as usize;
+        let gate_frames = end_frame.saturating_sub(start_frame).max(1);
+        let patch_note = PatchNote {
+            pitch: note.pitch.min(127),
+            velocity: note.velocity,
+            frequency_hz,
+            glide_from_hz: previous_frequency,
+            start_frame,
+            gate_frames,
+            expression: controllers.expression(note, gate_frames, project),
+        };
+        render_patch_note(patch, &patch_note, project.sample_rate, buffer);
+        previous_frequency = Some(frequency_hz);
+        rendered += 1;
     }
+    rendered
 }
 
 fn synth_event_for_note(
     note: &MidiNote,
+    frequency_hz: f64,
     clip_start_tick: u64,
     project: &Project,
     waveform: Waveform,
     color: VoiceColor,
 ) -> SynthEvent {
-    let phase_increment =
-        frequency_to_phase_increment(note_frequency_hz(note.pitch), project.sample_rate);
+    let phase_increment = frequency_to_phase_increment(frequency_hz, project.sample_rate);
     let clip_note_start = clip_start_tick.saturating_add(note.start_tick);
     let clip_note_end = clip_start_tick.saturating_add(note.end_tick());
     let start_sample = ticks_to_samples(
@@ -623,6 +1364,7 @@ fn synth_event_for_note(
         release_frames: 72,
         waveform,
         color,
+        expression: NoteExpression::default(),
     }
 }
 
@@ -671,8 +1413,10 @@ fn render_synth_event(event: &SynthEvent, buffer: &mut [f32]) {
         };
 
         let colored = color_sample(raw, event.color);
-        *frame += colored * event.amplitude * envelope;
-        phase = phase.wrapping_add(event.phase_increment);
+        *frame += colored * event.amplitude * event.expression.gain(index) * envelope;
+        let phase_increment =
+            f64::from(event.phase_increment) * event.expression.pitch_ratio(index);
+        phase = phase.wrapping_add(phase_increment as u32);
     }
 }
 
@@ -995,7 +1739,6 @@ fn mix_audio_clip_samples(
     if source_end <= source_start {
         return;
     }
-    let source_frames = source_end.saturating_sub(source_start).max(1);
 
     let fade_in_frames =
         (audio.fade_in_seconds.max(0.0) * f64::from(project.sample_rate)).round() as usize;
@@ -1005,26 +1748,158 @@ fn mix_audio_clip_samples(
     let pan_gain = pan_to_mono_gain(audio.pan);
     let clip_gain = db_to_gain(audio.gain_db) * pan_gain;
 
-    for frame_index in 0..output_frames {
-        let ratio = if output_frames > 1 {
-            frame_index as f64 / (output_frames - 1) as f64
-        } else {
-            0.0
-        };
-        let source_offset = ratio * source_frames.saturating_sub(1) as f64;
-        let source_index = if audio.reverse {
-            source_end.saturating_sub(1) as f64 - source_offset
-        } else {
-            source_start as f64 + source_offset
-        };
+    let pieces = audio_clip_pieces(
+        project,
+        audio,
+        clip_length_ticks,
+        output_frames,
+        source_sample_rate,
+        (source_start, source_end),
+    );
+    let mut rendered = Vec::with_capacity(output_frames);
+    for piece in &pieces {
+        if rendered.len() >= output_frames {
+            break;
+        }
+        render_audio_piece(
+            audio,
+            source_samples,
+            piece,
+            source_sample_rate,
+            project.sample_rate,
+            &mut rendered,
+        );
+    }
 
-        let source_sample = sample_linear(source_samples, source_index);
+    for (frame_index, source_sample) in rendered.into_iter().take(output_frames).enumerate() {
         let envelope = fade_envelope(frame_index, output_frames, fade_in_frames, fade_out_frames);
         buffer[start_frame + frame_index] += source_sample * clip_gain * envelope;
     }
 }
 
-fn sample_linear(samples: &[f32], index: f64) -> f32 {
+/// A run of clip output frames that reads the source linearly from frame `from`
+/// to frame `to`, both inclusive; `from > to` plays backwards.
+struct AudioPiece {
+    frames: usize,
+    from: f64,
+    to: f64,
+}
+
+/// Splits an audio clip's output at its warp markers. Unwarped clips are one
+/// piece spanning the whole trimmed source.
+fn audio_clip_pieces(
+    project: &Project,
+    audio: &AudioClip,
+    clip_length_ticks: u64,
+    output_frames: usize,
+    source_sample_rate: u32,
+    (source_start, source_end): (usize, usize),
+) -> Vec<AudioPiece> {
+    let first = source_start as f64;
+    let last = source_end.saturating_sub(1) as f64;
+    if !audio.is_warped() {
+        let (from, to) = if audio.reverse {
+            (last, first)
+        } else {
+            (first, last)
+        };
+        return vec![AudioPiece {
+            frames: output_frames,
+            from,
+            to,
+        }];
+    }
+
+    let mut boundaries: Vec<u64> = audio
+        .warp_markers
+        .iter()
+        .map(|marker| {
+            audio
+                .clip_tick_at(marker.source_seconds, project.bpm, project.ppq)
+                .round()
+        })
+        .filter(|tick| *tick > 0.0 && *tick < clip_length_ticks as f64)
+        .map(|tick| tick as u64)
+        .collect();
+    boundaries.push(0);
+    boundaries.push(clip_length_ticks);
+    boundaries.sort_unstable();
+    boundaries.dedup();
+
+    let frame_at =
+        |tick: u64| ticks_to_samples(tick, project.bpm, project.ppq, project.sample_rate) as usize;
+    let source_at = |tick: u64| {
+        (audio.source_seconds_at(tick as f64, project.bpm, project.ppq)
+            * f64::from(source_sample_rate))
+        .clamp(first, last)
+    };
+    boundaries
+        .windows(2)
+        .map(|pair| AudioPiece {
+            frames: frame_at(pair[1]).saturating_sub(frame_at(pair[0])),
+            from: source_at(pair[0]),
+            to: source_at(pair[1]),
+        })
+        .collect()
+}
+
+/// Appends one piece to `output`. The source is read directly when that gives
+/// the clip's pitch: the stretched pitch in [`AudioStretchMode::Resample`] with
+/// no pitch shift. Otherwise it is first re-timed with [`stretch::time_stretch`]
+/// so that reading it onto the piece gives the original pitch in
+/// [`AudioStretchMode::PreservePitch`], or the stretched one, times the pitch
+/// shift.
+fn render_audio_piece(
+    audio: &AudioClip,
+    source_samples: &[f32],
+    piece: &AudioPiece,
+    source_sample_rate: u32,
+    project_sample_rate: u32,
+    output: &mut Vec<f32>,
+) {
+    let low = piece.from.min(piece.to).round() as usize;
+    let high = (piece.from.max(piece.to).round() as usize + 1).min(source_samples.len());
+    let source_frames = high.saturating_sub(low);
+    let pitch_ratio = audio.pitch_ratio();
+    let target_frames = match audio.stretch_mode {
+        AudioStretchMode::Resample => source_frames as f64 * pitch_ratio,
+        AudioStretchMode::PreservePitch => {
+            piece.frames as f64 * f64::from(source_sample_rate)
+                / f64::from(project_sample_rate.max(1))
+                * pitch_ratio
+        }
+    };
+    let target_frames = target_frames.round() as usize;
+
+    let read = |samples: &[f32], from: f64, to: f64, output: &mut Vec<f32>| {
+        for frame_index in 0..piece.frames {
+            let ratio = if piece.frames > 1 {
+                frame_index as f64 / (piece.frames - 1) as f64
+            } else {
+                0.0
+            };
+            output.push(sample_linear(samples, from + ratio * (to - from)));
+        }
+    };
+    if target_frames.abs_diff(source_frames) <= 1 || source_frames == 0 {
+        read(source_samples, piece.from, piece.to, output);
+        return;
+    }
+
+    let mut segment = source_samples[low..high].to_vec();
+    if piece.from > piece.to {
+        segment.reverse();
+    }
+    let stretched = stretch::time_stretch(&segment, target_frames.max(1), source_sample_rate);
+    read(
+        &stretched,
+        0.0,
+        stretched.len().saturating_sub(1) as f64,
+        output,
+    );
+}
+
+pub(crate) fn sample_linear(samples: &[f32], index: f64) -> f32 {
     if samples.is_empty() {
         return 0.0;
     }
@@ -1101,7 +1976,7 @@ fn exp_smoothing_coeff(time_seconds: f32, sample_rate: u32) -> f32 {
         .clamp(0.0, 1.0)
 }
 
-fn apply_pattern_macros(note: &MidiNote, pattern: &PatternClip, ppq: u16) -> MidiNote {
+pub(crate) fn apply_pattern_macros(note: &MidiNote, pattern: &PatternClip, ppq: u16) -> MidiNote {
     let mut output = note.clone();
 
     if let Some(arpeggio) = macro_lane(pattern, "arpeggio")
@@ -1298,9 +2173,72 @@ fn frequency_to_phase_increment(frequency_hz: f64, sample_rate: u32) -> u32 {
     increment.clamp(1.0, f64::from(u32::MAX)) as u32
 }
 
-fn note_frequency_hz(pitch: u8) -> f64 {
-    let semitone_offset = f64::from(i16::from(pitch) - 69);
-    440.0 * 2_f64.powf(semitone_offset / 12.0)
+/// MIDI Tuning Standard real-time single note tuning changes retuning every key
+/// to the project tuning, as sysex payloads without the leading `F0`. Standard
+/// tuning needs no messages.
+fn mts_note_tuning_messages(tuning: &Tuning) -> Vec<Vec<u8>> {
+    if tuning.is_standard() {
+        return Vec::new();
+    }
+    let keys: Vec<[u8; 4]> = (0_u8..=127)
+        .map(|key| {
+            let Some(frequency_hz) = tuning.frequency_hz(key) else {
+                // 7F 7F 7F means "no change" for this key.
+                return [key, 0x7F, 0x7F, 0x7F];
+            };
+            let semitones = (69.0 + 12.0 * (frequency_hz / 440.0).log2()).clamp(0.0, 127.0);
+            let mut base = semitones.floor();
+            let mut fraction = ((semitones - base) * 16_384.0).round() as u16;
+            if fraction >= 16_384 {
+                base += 1.0;
+                fraction = 0;
+            }
+            if base >= 127.0 {
+                base = 127.0;
+                fraction = fraction.min(16_382);
+            }
+            [
+                key,
+                base as u8,
+                (fraction >> 7) as u8,
+                (fraction & 0x7F) as u8,
+            ]
+        })
+        .collect();
+
+    // The key count is a 7-bit field, so the 128 keys go out in two messages.
+    keys.chunks(64)
+        .map(|chunk| {
+            let mut message = vec![0x7F, 0x7F, 0x08, 0x02, 0x00, chunk.len() as u8];
+            message.extend(chunk.iter().flatten());
+            message.push(0xF7);
+            message
+        })
+        .collect()
+}
+
+/// Snaps a tone to the nearest pitch the chip's period register can produce.
+fn chip_backend_frequency(backend: ChipBackend, waveform: Waveform, frequency_hz: f64) -> f64 {
+    match backend {
+        ChipBackend::NesApu => {
+            let (divider, min) = if matches!(waveform, Waveform::Triangle) {
+                (32.0, 3)
+            } else {
+                (16.0, 9)
+            };
+            let period = period_for(NES_APU_CLOCK, divider, frequency_hz, min, 2_048);
+            f64::from(NES_APU_CLOCK) / (divider * f64::from(period))
+        }
+        ChipBackend::GameBoyApu => {
+            let period = period_for(GAMEBOY_TONE_CLOCK, 1.0, frequency_hz, 1, 2_047);
+            f64::from(GAMEBOY_TONE_CLOCK) / f64::from(period)
+        }
+        ChipBackend::Sn76489 => {
+            let period = period_for(SN76489_CLOCK, 32.0, frequency_hz, 1, 1_023);
+            f64::from(SN76489_CLOCK) / (32.0 * f64::from(period))
+        }
+        ChipBackend::Generic => frequency_hz,
+    }
 }
 
 fn pulse_osc(phase: u32, duty_cycle: f32) -> f32 {
@@ -1330,6 +2268,6 @@ fn color_sample(sample: f32, color: VoiceColor) -> f32 {
 }
 
 fn lfsr_step(state: u32) -> u32 {
-    let bit = ((state >> 0) ^ (state >> 1)) & 1;
+    let bit = (state ^ (state >> 1)) & 1;
     (state >> 1) | (bit << 30)
 }
@@ -25,43 +25,49 @@ pub fn demo_project() -> Project {
     lead_track.clips.push(Clip {
         id: Uuid::parse_str("fbf41a8f-c5b4-464b-a9f3-6e62eebf6efb")
             .expect("fixture lead clip id should be valid"),
-        name: "Lead phrase".to_string(),
-        start_tick: 0,
-        length_ticks: 1_920,
-        disabled: false,
-        payload: ClipPayload::Midi(MidiClip {
-            instrument: Some("Pulse Lead".to_string()),
-            notes: vec![
-                MidiNote {
-                    pitch: 72,
-                    velocity: 118,
-                    start_tick: 0,
-                    length_ticks: 240,
-                    channel: 0,
-                },
-                MidiNote {
-                    pitch: 74,
-                    velocity: 118,
-                    start_tick: 240,
-                    length_ticks: 240,
-                    channel: 0,
-                },
-                MidiNote {
-                    pitch: 79,
-                    velocity: 110,
-                    start_tick: 480,
-                    length_ticks: 720,
-                    channel: 0,
-                },
-                MidiNote {
-                    pitch: 81,
-                    velocity: 104,
-                    start_tick: 1_200,
-                    length_ticks: 720,
-                    channel: 0,
-                },
-            ],
-        }),
+        ..Clip::new(
+            "Lead phrase",
+            0,
+            1_920,
+            ClipPayload::Midi(MidiClip {
+                instrument: Some("Pulse Lead".to_string()),
+                notes: vec![
+                    MidiNote {
+                        pitch: 72,
+                        velocity: 118,
+                        start_tick: 0,
+                        length_ticks: 240,
+                        channel: 0,
+                        release_velocity: None,
+                    },
+                    MidiNote {
+                        pitch: 74,
+                        velocity: 118,
+                        start_tick: 240,
+                        length_ticks: 240,
+                        channel: 0,
+                        release_velocity: None,
+                    },
+                    MidiNote {
+                        pitch: 79,
+                        velocity: 110,
+                        start_tick: 480,
+                        length_ticks: 720,
+                        channel: 0,
+                        release_velocity: None,
+                    },
+                    MidiNote {
+                        pitch: 81,
+                        velocity: 104,
+                        start_tick: 1_200,
+                        length_ticks: 720,
+                        channel: 0,
+                        release_velocity: None,
+                    },
+                ],
+                controllers: Vec::new(),
+            }),
+        )
     });
 
     let mut chip_track = Track::new("Chip Bass", "#f77f00", TrackKind::Chip);
@@ -70,46 +76,52 @@ pub fn demo_project() -> Project {
     chip_track.clips.push(Clip {
         id: Uuid::parse_str("0caa5e8d-6ec2-4b74-9e87-d7f60111f3f2")
             .expect("fixture chip clip id should be valid"),
-        name: "Bassline".to_string(),
-        start_tick: 0,
-        length_ticks: 1_920,
-        disabled: false,
-        payload: ClipPayload::Pattern(PatternClip {
-            source_chip: "gameboy_apu".to_string(),
-            notes: vec![
-                MidiNote {
-                    pitch: 36,
-                    velocity: 100,
-                    start_tick: 0,
-                    length_ticks: 480,
-                    channel: 1,
-                },
-                MidiNote {
-                    pitch: 43,
-                    velocity: 95,
-                    start_tick: 480,
-                    length_ticks: 480,
-                    channel: 1,
-                },
-                MidiNote {
-                    pitch: 41,
-                    velocity: 95,
-                    start_tick: 960,
-                    length_ticks: 480,
-                    channel: 1,
-                },
-                MidiNote {
-                    pitch: 38,
-                    velocity: 98,
-                    start_tick: 1_440,
-                    length_ticks: 480,
-                    channel: 1,
-                },
-            ],
-            rows: Vec::new(),
-            macros: Vec::new(),
-            lines_per_beat: crate::model::DEFAULT_TRACKER_LINES_PER_BEAT,
-        }),
+        ..Clip::new(
+            "Bassline",
+            0,
+            1_920,
+            ClipPayload::Pattern(PatternClip {
+                source_chip: "gameboy_apu".to_string(),
+                instrument: None,
+                notes: vec![
+                    MidiNote {
+                        pitch: 36,
+                        velocity: 100,
+                        start_tick: 0,
+                        length_ticks: 480,
+                        channel: 1,
+                        release_velocity: None,
+                    },
+                    MidiNote {
+                        pitch: 43,
+                        velocity: 95,
+                        start_tick: 480,
+                        length_ticks: 480,
+                        channel: 1,
+                        release_velocity: None,
+                    },
+                    MidiNote {
+                        pitch: 41,
+                        velocity: 95,
+                        start_tick: 960,
+                        length_ticks: 480,
+                        channel: 1,
+                        release_velocity: None,
+                    },
+                    MidiNote {
+                        pitch: 38,
+                        velocity: 98,
+                        start_tick: 1_440,
+                        length_ticks: 480,
+                        channel: 1,
+                        release_velocity: None,
+                    },
+                ],
+                rows: Vec::new(),
+                macros: Vec::new(),
+                lines_per_beat: crate::model::DEFAULT_TRACKER_LINES_PER_BEAT,
+            }),
+        )
     });
 
     project.tracks.extend([lead_track, chip_track]);
@@ -0,0 +1,139 @@
+use std::collections::BTreeMap;
+
+use serde::{Deserialize, Serialize};
+
+use crate::{model::TrackerRow, note_tools::roll};
+
+/// `hits` onsets spread as evenly as possible over `steps` rows, repeated for
+/// the length of the clip.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+pub struct EuclideanRhythm {
+    pub hits: u32,
+    pub steps: u32,
+    /// Steps the pattern is shifted later by, wrapping around the cycle.
+    pub rotation: u32,
+    pub pitch: u8,
+    pub velocity: u8,
+}
+
+/// Seeded random hits. Each row rolls against the chance of its step, and hits
+/// pick one of `pitches`.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct ProbabilityFill {
+    pub seed: u64,
+    /// Hit chance per step in percent, cycled over the rows.
+    pub chances: Vec<f32>,
+    pub pitches: Vec<u8>,
+    pub velocity: u8,
+}
+
+/// Onset flags for one cycle of a Euclidean rhythm.
+#[must_use]
+pub fn euclidean_steps(hits: u32, steps: u32, rotation: u32) -> Vec<bool> {
+    if steps == 0 {
+        return Vec::new();
+    }
+    let hits = u64::from(hits.min(steps));
+    let steps = u64::from(steps);
+    let rotation = u64::from(rotation) % steps;
+    (0..steps)
+        .map(|step| {
+            let source = (step + steps - rotation) % steps;
+            (source * hits) % steps < hits
+        })
+        .collect()
+}
+
+#[must_use]
+pub fn euclidean_rows(rhythm: &EuclideanRhythm, row_count: u32) -> Vec<TrackerRow> {
+    let cycle = euclidean_steps(rhythm.hits, rhythm.steps, rhythm.rotation);
+    if cycle.is_empty() {
+        return Vec::new();
+    }
+    (0..row_count)
+        .filter(|row| cycle[*row as usize % cycle.len()])
+        .map(|row| gated_row(row, rhythm.pitch, rhythm.velocity))
+        .collect()
+}
+
+#[must_use]
+pub fn probability_rows(fill: &ProbabilityFill, row_count: u32) -> Vec<TrackerRow> {
+    if fill.chances.is_empty() || fill.pitches.is_empty() {
+        return Vec::new();
+    }
+    (0..row_count)
+        .filter_map(|row| {
+            let index = row as usize;
+            let chance = fill.chances[index % fill.chances.len()].clamp(0.0, 100.0);
+            if unit(fill.seed, index, 0) * 100.0 >= f64::from(chance) {
+                return None;
+            }
+            let pick = (unit(fill.seed, index, 1) * fill.pitches.len() as f64) as usize;
+            let pitch = fill.pitches[pick.min(fill.pitches.len() - 1)];
+            Some(gated_row(row, pitch, fill.velocity))
+        })
+        .collect()
+}
+
+/// Extends the gated notes in `rows` up to `row_count` with a first-order
+/// Markov chain over their pitches. Each step copies the pitch, velocity and
+/// row gap of a note that followed the current pitch in the source; pitches
+/// that never led anywhere restart from any source transition. Returns `None`
+/// when there are fewer than two notes to learn from.
+#[must_use]
+pub fn markov_continuation(
+    rows: &[TrackerRow],
+    row_count: u32,
+    seed: u64,
+) -> Option<Vec<TrackerRow>> {
+    let mut played: Vec<&TrackerRow> = rows
+        .iter()
+        .filter(|row| row.gate && row.note.is_some())
+        .collect();
+    played.sort_by_key(|row| row.row);
+    let last = *played.last()?;
+
+    let mut transitions: BTreeMap<u8, Vec<(u8, u32, u8)>> = BTreeMap::new();
+    let mut all = Vec::new();
+    for pair in played.windows(2) {
+        let (Some(from), Some(to)) = (pair[0].note, pair[1].note) else {
+            continue;
+        };
+        let step = (to, (pair[1].row - pair[0].row).max(1), pair[1].velocity);
+        transitions.entry(from).or_default().push(step);
+        all.push(step);
+    }
+    if all.is_empty() {
+        return None;
+    }
+
+    let mut continued = rows.to_vec();
+    let mut pitch = last.note?;
+    let mut row = last.row;
+    for index in 0.. {
+        let choices = transitions.get(&pitch).unwrap_or(&all);
+        let pick = (unit(seed, index, 0) * choices.len() as f64) as usize;
+        let (next, gap, velocity) = choices[pick.min(choices.len() - 1)];
+        row = row.saturating_add(gap);
+        if row >= row_count {
+            break;
+        }
+        continued.push(gated_row(row, next, velocity));
+        pitch = next;
+    }
+    Some(continued)
+}
+
+fn gated_row(row: u32, pitch: u8, velocity: u8) -> TrackerRow {
+    TrackerRow {
+        row,
+        note: Some(pitch),
+        velocity,
+        gate: true,
+        ..TrackerRow::default()
+    }
+}
+
+fn unit(seed: u64, index: usize, salt: u64) -> f64 {
+    (roll(seed, index, salt) + 1.0) / 2.0
+}
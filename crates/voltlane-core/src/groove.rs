@@ -0,0 +1,172 @@
+use serde::{Deserialize, Serialize};
+
+use crate::model::{GrooveSlot, GrooveTemplate, MidiNote};
+
+const MIN_GROOVE_VELOCITY_SCALE: f32 = 0.25;
+const MAX_GROOVE_VELOCITY_SCALE: f32 = 4.0;
+
+/// What quantize does with the back of each note.
+#[derive(Debug, Clone, Copy, Default, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum NoteEndQuantize {
+    /// Lengths are kept, so ends move with their starts.
+    Keep,
+    /// Lengths are rounded to whole grid steps.
+    #[default]
+    Length,
+    /// Ends snap to the grid independently of their starts.
+    End,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct QuantizeOptions {
+    pub grid_ticks: u64,
+    /// How far notes move toward their target, from 0 (not at all) to 100.
+    pub strength_percent: f32,
+    /// Delays every other grid line by up to half a grid step at 100.
+    pub swing_percent: f32,
+    /// Divides the grid into thirds of two steps instead of halves.
+    pub triplet: bool,
+    pub ends: NoteEndQuantize,
+    /// Project groove template applied on top of the grid.
+    pub groove: Option<String>,
+}
+
+impl QuantizeOptions {
+    /// Hard quantize of starts and lengths to `grid_ticks`.
+    #[must_use]
+    pub fn grid(grid_ticks: u64) -> Self {
+        Self {
+            grid_ticks,
+            strength_percent: 100.0,
+            swing_percent: 0.0,
+            triplet: false,
+            ends: NoteEndQuantize::Length,
+            groove: None,
+        }
+    }
+
+    /// Grid step after the triplet division.
+    #[must_use]
+    pub fn step_ticks(&self) -> u64 {
+        if self.triplet {
+            (self.grid_ticks * 2 / 3).max(1)
+        } else {
+            self.grid_ticks
+        }
+    }
+}
+
+/// Moves notes toward the (swung, grooved) grid. Notes are neither sanitized nor
+/// re-sorted; the caller owns that.
+pub fn quantize_notes(
+    notes: &mut [MidiNote],
+    options: &QuantizeOptions,
+    groove: Option<&GrooveTemplate>,
+) {
+    let step = options.step_ticks();
+    if step == 0 {
+        return;
+    }
+    let strength = f64::from(options.strength_percent.clamp(0.0, 100.0)) / 100.0;
+    let swing = f64::from(options.swing_percent.clamp(0.0, 100.0)) / 100.0;
+    let target = |tick: u64| -> (u64, Option<GrooveSlot>) {
+        let line = tick.saturating_add(step / 2) / step;
+        let mut target = line * step;
+        if line % 2 == 1 {
+            target += (step as f64 * swing / 2.0).round() as u64;
+        }
+        let slot = groove
+            .and_then(|groove| groove.slot_at(line * step))
+            .copied();
+        if let Some(slot) = slot {
+            target = target.saturating_add_signed(slot.offset_ticks);
+        }
+        (target, slot)
+    };
+    let toward = |from: u64, to: u64| -> u64 {
+        let moved = from as f64 + (to as f64 - from as f64) * strength;
+        moved.round().max(0.0) as u64
+    };
+
+    for note in notes.iter_mut() {
+        let (start_target, slot) = target(note.start_tick);
+        let start = toward(note.start_tick, start_target);
+        let end = note.start_tick.saturating_add(note.length_ticks.max(1));
+        note.length_ticks = match options.ends {
+            NoteEndQuantize::Keep => note.length_ticks,
+            NoteEndQuantize::Length => {
+                let rounded =
+                    (note.length_ticks.max(1).saturating_add(step / 2) / step * step).max(step);
+                toward(note.length_ticks, rounded)
+            }
+            NoteEndQuantize::End => {
+                let end_target = target(end).0.max(start_target + step);
+                toward(end, end_target).saturating_sub(start)
+            }
+        }
+        .max(1);
+        note.start_tick = start;
+
+        if let Some(slot) = slot {
+            let scale = 1.0 + (f64::from(slot.velocity_scale) - 1.0) * strength;
+            note.velocity = (f64::from(note.velocity) * scale).round().clamp(1.0, 127.0) as u8;
+        }
+    }
+}
+
+/// Captures the timing and accents of `notes` against `grid_ticks`, one slot per
+/// step over a cycle of `steps`.
+#[must_use]
+pub fn groove_from_notes(
+    name: &str,
+    notes: &[MidiNote],
+    grid_ticks: u64,
+    steps: usize,
+) -> GrooveTemplate {
+    let onsets: Vec<(u64, f32)> = notes
+        .iter()
+        .map(|note| (note.start_tick, f32::from(note.velocity)))
+        .collect();
+    groove_from_onsets(name, &onsets, grid_ticks, steps)
+}
+
+/// Captures a groove from `(tick, level)` onsets, such as detected audio
+/// transients. Steps without an onset stay straight and unaccented.
+#[must_use]
+pub fn groove_from_onsets(
+    name: &str,
+    onsets: &[(u64, f32)],
+    grid_ticks: u64,
+    steps: usize,
+) -> GrooveTemplate {
+    let mut slots = vec![GrooveSlot::default(); steps];
+    if grid_ticks > 0 && steps > 0 && !onsets.is_empty() {
+        let mut sums = vec![(0_i64, 0.0_f32, 0_i64); steps];
+        for (tick, level) in onsets {
+            let line = tick.saturating_add(grid_ticks / 2) / grid_ticks;
+            let sum = &mut sums[(line % steps as u64) as usize];
+            sum.0 += *tick as i64 - (line * grid_ticks) as i64;
+            sum.1 += level.max(0.0);
+            sum.2 += 1;
+        }
+        let average_level =
+            onsets.iter().map(|(_, level)| level.max(0.0)).sum::<f32>() / onsets.len() as f32;
+        for (slot, (deviation, level, count)) in slots.iter_mut().zip(sums) {
+            if count == 0 {
+                continue;
+            }
+            slot.offset_ticks = (deviation as f64 / count as f64).round() as i64;
+            if average_level > 0.0 {
+                slot.velocity_scale = (level / count as f32 / average_level)
+                    .clamp(MIN_GROOVE_VELOCITY_SCALE, MAX_GROOVE_VELOCITY_SCALE);
+            }
+        }
+    }
+
+    GrooveTemplate {
+        name: name.trim().to_string(),
+        grid_ticks,
+        slots,
+    }
+}
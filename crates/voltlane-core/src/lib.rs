@@ -1,27 +1,57 @@
 pub mod assets;
+pub mod audio_features;
 pub mod diagnostics;
 pub mod engine;
 pub mod export;
 pub mod fixtures;
+pub mod generators;
+pub mod groove;
+pub mod midi_import;
 pub mod model;
+pub mod module_import;
+pub mod note_tools;
 pub mod parity;
 pub mod persistence;
+pub mod sampler;
+pub mod sequencer;
+pub mod stretch;
+pub mod synth;
+pub mod theory;
 pub mod time;
+pub mod tracker_import;
+pub mod transcribe;
+pub mod tuning;
+pub mod vgm;
 
 pub use assets::{
-    AudioAnalysis, AudioAssetEntry, AudioWaveformPeaks, DecodedAudio, analyze_audio_file,
-    analyze_audio_file_with_cache, decode_audio_file_mono, scan_audio_assets,
+    AudioAnalysis, AudioAssetEntry, AudioWaveformPeaks, DecodedAudio, Transient,
+    analyze_audio_file, analyze_audio_file_with_cache, decode_audio_file_mono, detect_transients,
+    scan_audio_assets,
 };
+pub use audio_features::{AudioFeatures, analyze_audio_features};
 pub use diagnostics::{
     TelemetryGuard, init_tracing, init_tracing_with_file_prefix, init_tracing_with_options,
 };
 pub use engine::{
-    AddClipRequest, AddTrackRequest, AudioClipPatch, Engine, EngineError, ExportKind, RenderMode,
-    TrackMixPatch, TrackStatePatch,
+    AddClipRequest, AddTrackRequest, AudioClipPatch, ClipPlaybackPatch, Engine, EngineError,
+    ExportKind, RenderMode, TrackMixPatch, TrackStatePatch,
 };
+pub use generators::{EuclideanRhythm, ProbabilityFill};
+pub use groove::{NoteEndQuantize, QuantizeOptions};
 pub use model::{
-    AudioClip, AutomationClip, AutomationPoint, ChipMacroLane, Clip, ClipPayload,
-    DEFAULT_TRACKER_LINES_PER_BEAT, EffectSpec, MidiClip, MidiNote, PatternClip, Project, Track,
-    TrackKind, TrackSend, TrackerRow, Transport,
+    AdsrEnvelope, AudioClip, AudioStretchMode, AutomationClip, AutomationPoint, ChipMacroLane,
+    Clip, ClipPayload, ClipPoolEntry, ControllerLane, ControllerTarget,
+    DEFAULT_TRACKER_LINES_PER_BEAT, EffectSpec, GrooveSlot, GrooveTemplate, LinkedClip, MidiClip,
+    MidiNote, OrderList, OrderRow, PatternClip, Project, SamplerInstrument, SamplerZone, Step,
+    StepRow, StepSequencerClip, SynthFilter, SynthFilterMode, SynthLfo, SynthLfoTarget,
+    SynthOscillator, SynthPatch, SynthWaveform, TimeSignature, Track, TrackKind, TrackSend,
+    TrackerRow, Transport, Tuning, WarpMarker,
+};
+pub use note_tools::{
+    ArpeggiatorOptions, ArpeggioMode, HumanizeOptions, StrumDirection, VelocityShape,
 };
 pub use parity::{ParityReport, generate_parity_report};
+pub use theory::{
+    ChordKind, Key, KeyEstimate, ScaleMode, detect_key, detect_key_from_pitch_classes,
+};
+pub use transcribe::{DetectedNote, PitchFrame, TranscriptionOptions};
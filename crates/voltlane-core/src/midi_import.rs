@@ -0,0 +1,511 @@
+use std::{
+    collections::{BTreeMap, HashMap, VecDeque},
+    fs,
+    path::Path,
+};
+
+use anyhow::{Context, Result, bail};
+use midly::{Format, MetaMessage, MidiMessage, Smf, Timing, TrackEventKind};
+use serde::{Deserialize, Serialize};
+use tracing::{debug, info, instrument};
+
+use crate::{
+    engine::normalize_pattern_clip,
+    model::{
+        AutomationPoint, Clip, ClipPayload, ControllerLane, ControllerTarget, DEFAULT_SAMPLE_RATE,
+        MidiClip, MidiNote, PatternClip, Project, TimeSignature, Track, TrackKind,
+    },
+};
+
+const DEFAULT_MIDI_BPM: f64 = 120.0;
+/// Zero-based General MIDI percussion channel (channel 10).
+pub const GM_DRUM_CHANNEL: u8 = 9;
+/// Clip instrument name used for notes on the GM percussion channel.
+pub const DRUM_KIT_INSTRUMENT: &str = "Drum Kit";
+const CONTROLLER_TARGETS: [ControllerTarget; 4] = [
+    ControllerTarget::ModWheel,
+    ControllerTarget::Expression,
+    ControllerTarget::Sustain,
+    ControllerTarget::PitchBend,
+];
+const TRACK_COLORS: [&str; 6] = [
+    "#4fb8ff", "#ffb347", "#9be564", "#ff5f7e", "#8f6bff", "#52e1c4",
+];
+
+/// General MIDI level 1 program names, indexed by program number.
+pub const GM_PROGRAM_NAMES: [&str; 128] = [
+    "Acoustic Grand Piano",
+    "Bright Acoustic Piano",
+    "Electric Grand Piano",
+    "Honky-tonk Piano",
+    "Electric Piano 1",
+    "Electric Piano 2",
+    "Harpsichord",
+    "Clavinet",
+    "Celesta",
+    "Glockenspiel",
+    "Music Box",
+    "Vibraphone",
+    "Marimba",
+    "Xylophone",
+    "Tubular Bells",
+    "Dulcimer",
+    "Drawbar Organ",
+    "Percussive Organ",
+    "Rock Organ",
+    "Church Organ",
+    "Reed Organ",
+    "Accordion",
+    "Harmonica",
+    "Tango Accordion",
+    "Acoustic Guitar (nylon)",
+    "Acoustic Guitar (steel)",
+    "Electric Guitar (jazz)",
+    "Electric Guitar (clean)",
+    "Electric Guitar (muted)",
+    "Overdriven Guitar",
+    "Distortion Guitar",
+    "Guitar Harmonics",
+    "Acoustic Bass",
+    "Electric Bass (finger)",
+    "Electric Bass (pick)",
+    "Fretless Bass",
+    "Slap Bass 1",
+    "Slap Bass 2",
+    "Synth Bass 1",
+    "Synth Bass 2",
+    "Violin",
+    "Viola",
+    "Cello",
+    "Contrabass",
+    "Tremolo Strings",
+    "Pizzicato Strings",
+    "Orchestral Harp",
+    "Timpani",
+    "String Ensemble 1",
+    "String Ensemble 2",
+    "Synth Strings 1",
+    "Synth Strings 2",
+    "Choir Aahs",
+    "Voice Oohs",
+    "Synth Voice",
+    "Orchestra Hit",
+    "Trumpet",
+    "Trombone",
+    "Tuba",
+    "Muted Trumpet",
+    "French Horn",
+    "Brass Section",
+    "Synth Brass 1",
+    "Synth Brass 2",
+    "Soprano Sax",
+    "Alto Sax",
+    "Tenor Sax",
+    "Baritone Sax",
+    "Oboe",
+    "English Horn",
+    "Bassoon",
+    "Clarinet",
+    "Piccolo",
+    "Flute",
+    "Recorder",
+    "Pan Flute",
+    "Blown Bottle",
+    "Shakuhachi",
+    "Whistle",
+    "Ocarina",
+    "Lead 1 (square)",
+    "Lead 2 (sawtooth)",
+    "Lead 3 (calliope)",
+    "Lead 4 (chiff)",
+    "Lead 5 (charang)",
+    "Lead 6 (voice)",
+    "Lead 7 (fifths)",
+    "Lead 8 (bass + lead)",
+    "Pad 1 (new age)",
+    "Pad 2 (warm)",
+    "Pad 3 (polysynth)",
+    "Pad 4 (choir)",
+    "Pad 5 (bowed)",
+    "Pad 6 (metallic)",
+    "Pad 7 (halo)",
+    "Pad 8 (sweep)",
+    "FX 1 (rain)",
+    "FX 2 (soundtrack)",
+    "FX 3 (crystal)",
+    "FX 4 (atmosphere)",
+    "FX 5 (brightness)",
+    "FX 6 (goblins)",
+    "FX 7 (echoes)",
+    "FX 8 (sci-fi)",
+    "Sitar",
+    "Banjo",
+    "Shamisen",
+    "Koto",
+    "Kalimba",
+    "Bagpipe",
+    "Fiddle",
+    "Shanai",
+    "Tinkle Bell",
+    "Agogo",
+    "Steel Drums",
+    "Woodblock",
+    "Taiko Drum",
+    "Melodic Tom",
+    "Synth Drum",
+    "Reverse Cymbal",
+    "Guitar Fret Noise",
+    "Breath Noise",
+    "Seashore",
+    "Bird Tweet",
+    "Telephone Ring",
+    "Helicopter",
+    "Applause",
+    "Gunshot",
+];
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize, PartialEq)]
+#[serde(default)]
+pub struct MidiImportOptions {
+    /// Zero-based MIDI channels whose notes become tracker patterns instead of
+    /// MIDI clips.
+    pub pattern_channels: Vec<u8>,
+    /// Chip source for converted patterns, e.g. `nes_2a03_pulse`.
+    pub pattern_chip: String,
+}
+
+#[derive(Debug, Default)]
+struct ChannelLane {
+    notes: Vec<MidiNote>,
+    programs: Vec<(u64, u8)>,
+    controllers: Vec<(u64, ControllerTarget, f32)>,
+}
+
+/// Builds a project from a format 0 or format 1 Standard MIDI File with default
+/// options, so every channel becomes a MIDI clip track.
+#[instrument(fields(path = %path.display()))]
+pub fn import_midi(path: &Path) -> Result<Project> {
+    import_midi_with_options(path, &MidiImportOptions::default())
+}
+
+#[instrument(skip(options), fields(path = %path.display(), pattern_channels = ?options.pattern_channels))]
+pub fn import_midi_with_options(path: &Path, options: &MidiImportOptions) -> Result<Project> {
+    let bytes =
+        fs::read(path).with_context(|| format!("failed to read midi file: {}", path.display()))?;
+    parse_midi(&bytes, options)
+}
+
+/// Converts SMF bytes into a project. Each channel used by an SMF track becomes
+/// its own project track, split into clips wherever the program changes. Only the
+/// first tempo and time signature are kept since projects have a single tempo.
+pub fn parse_midi(bytes: &[u8], options: &MidiImportOptions) -> Result<Project> {
+    if !options.pattern_channels.is_empty() && options.pattern_chip.trim().is_empty() {
+        bail!("a pattern chip is required when converting channels to patterns");
+    }
+
+    let smf = Smf::parse(bytes).context("failed to parse midi file")?;
+    let Timing::Metrical(file_ppq) = smf.header.timing else {
+        bail!("timecode-based midi files are not supported");
+    };
+    if smf.header.format == Format::Sequential {
+        bail!("format 2 midi files are not supported");
+    }
+
+    let mut project = Project::new("", DEFAULT_MIDI_BPM, DEFAULT_SAMPLE_RATE);
+    let file_ppq = u64::from(file_ppq.as_int().max(1));
+    let ppq = u64::from(project.ppq);
+    let scale = |tick: u64| (u128::from(tick) * u128::from(ppq) / u128::from(file_ppq)) as u64;
+
+    let mut tempo = None;
+    let mut time_signature = None;
+    let mut ignored_tempo_changes = 0_usize;
+    let mut imported = Vec::new();
+    for (track_index, events) in smf.tracks.iter().enumerate() {
+        let mut tick = 0_u64;
+        let mut name = None;
+        let mut lanes: BTreeMap<u8, ChannelLane> = BTreeMap::new();
+        let mut held: HashMap<(u8, u8), VecDeque<(u64, u8)>> = HashMap::new();
+
+        for event in events {
+            tick += u64::from(event.delta.as_int());
+            match event.kind {
+                TrackEventKind::Meta(MetaMessage::TrackName(raw)) if name.is_none() => {
+                    let text = String::from_utf8_lossy(raw).trim().to_string();
+                    name = (!text.is_empty()).then_some(text);
+                }
+                TrackEventKind::Meta(MetaMessage::Tempo(micros)) => {
+                    if tempo.is_none() {
+                        tempo = Some(60_000_000.0 / f64::from(micros.as_int().max(1)));
+                    } else {
+                        ignored_tempo_changes += 1;
+                    }
+                }
+                TrackEventKind::Meta(MetaMessage::TimeSignature(numerator, power, _, _))
+                    if time_signature.is_none() =>
+                {
+                    time_signature = Some(TimeSignature {
+                        numerator: numerator.max(1),
+                        denominator: 1_u8.checked_shl(u32::from(power)).unwrap_or(4),
+                    });
+                }
+                TrackEventKind::Midi { channel, message } => {
+                    let channel = channel.as_int();
+                    match message {
+                        MidiMessage::NoteOn { key, vel } if vel.as_int() > 0 => {
+                            held.entry((channel, key.as_int()))
+                                .or_default()
+                                .push_back((tick, vel.as_int()));
+                        }
+                        MidiMessage::NoteOn { key, .. } | MidiMessage::NoteOff { key, .. } => {
+                            let pitch = key.as_int();
+                            let release_velocity = match message {
+                                MidiMessage::NoteOff { vel, .. } if vel.as_int() > 0 => {
+                                    Some(vel.as_int())
+                                }
+                                _ => None,
+                            };
+                            if let Some((start, velocity)) = held
+                                .get_mut(&(channel, pitch))
+                                .and_then(VecDeque::pop_front)
+                            {
+                                lanes.entry(channel).or_default().notes.push(MidiNote {
+                                    pitch,
+                                    velocity,
+                                    start_tick: scale(start),
+                                    length_ticks: scale(tick - start).max(1),
+                                    channel,
+                                    release_velocity,
+                                });
+                            }
+                        }
+                        MidiMessage::Controller { controller, value } => {
+                            let value = f32::from(value.as_int()) / 127.0;
+                            let point = match controller.as_int() {
+                                1 => Some((ControllerTarget::ModWheel, value)),
+                                11 => Some((ControllerTarget::Expression, value)),
+                                64 => Some((ControllerTarget::Sustain, value.round())),
+                                _ => None,
+                            };
+                            if let Some((target, value)) = point {
+                                lanes.entry(channel).or_default().controllers.push((
+                                    scale(tick),
+                                    target,
+                                    value,
+                                ));
+                            }
+                        }
+                        MidiMessage::PitchBend { bend } => {
+                            lanes.entry(channel).or_default().controllers.push((
+                                scale(tick),
+                                ControllerTarget::PitchBend,
+                                bend.as_f32(),
+                            ));
+                        }
+                        MidiMessage::ProgramChange { program } => {
+                            lanes
+                                .entry(channel)
+                                .or_default()
+                                .programs
+                                .push((scale(tick), program.as_int()));
+                        }
+                        _ => {}
+                    }
+                }
+                _ => {}
+            }
+        }
+
+        // Notes still held at the end of the track ring until the track ends.
+        for ((channel, pitch), starts) in held {
+            for (start, velocity) in starts {
+                lanes.entry(channel).or_default().notes.push(MidiNote {
+                    pitch,
+                    velocity,
+                    start_tick: scale(start),
+                    length_ticks: scale(tick - start).max(1),
+                    channel,
+                    release_velocity: None,
+                });
+            }
+        }
+
+        if track_index == 0
+            && project.title.is_empty()
+            && let Some(name) = &name
+        {
+            project.title.clone_from(name);
+        }
+        lanes.retain(|_, lane| !lane.notes.is_empty());
+        imported.push((track_index, name, lanes));
+    }
+
+    if ignored_tempo_changes > 0 {
+        debug!(
+            ignored_tempo_changes,
+            "midi tempo changes ignored, projects use a single tempo"
+        );
+    }
+    if project.title.is_empty() {
+        project.title = "Imported MIDI".to_string();
+    }
+    project.bpm = tempo.unwrap_or(DEFAULT_MIDI_BPM).clamp(20.0, 999.0);
+    project.time_signature = time_signature.unwrap_or_default();
+    let bar_ticks = project.time_signature.bar_ticks(project.ppq).max(1);
+
+    for (track_index, name, lanes) in imported {
+        let split_channels = lanes.len() > 1 || name.is_none();
+        let base = name.unwrap_or_else(|| format!("Track {:02}", track_index + 1));
+        for (channel, mut lane) in lanes {
+            lane.notes.sort_by_key(|note| (note.start_tick, note.pitch));
+            let as_pattern = options.pattern_channels.contains(&channel);
+            let track_name = if split_channels {
+                format!("{base} Ch {:02}", channel + 1)
+            } else {
+                base.clone()
+            };
+            let mut track = Track::new(
+                track_name,
+                TRACK_COLORS[project.tracks.len() % TRACK_COLORS.len()],
+                if as_pattern {
+                    TrackKind::Chip
+                } else {
+                    TrackKind::Midi
+                },
+            );
+
+            for (segment_start, program, notes) in program_segments(&lane) {
+                let first_start = notes.first().map_or(0, |note| note.start_tick);
+                let start_tick = (first_start / bar_ticks * bar_ticks).max(segment_start);
+                let end_tick = notes.iter().map(MidiNote::end_tick).max().unwrap_or(0);
+                let length_ticks = end_tick.div_ceil(bar_ticks) * bar_ticks - start_tick;
+                let notes: Vec<MidiNote> = notes
+                    .into_iter()
+                    .map(|note| MidiNote {
+                        start_tick: note.start_tick - start_tick,
+                        ..note
+                    })
+                    .collect();
+
+                let payload = if as_pattern {
+                    let mut pattern = PatternClip {
+                        source_chip: options.pattern_chip.trim().to_string(),
+                        notes,
+                        ..PatternClip::default()
+                    };
+                    normalize_pattern_clip(&mut pattern, project.ppq)?;
+                    ClipPayload::Pattern(pattern)
+                } else {
+                    ClipPayload::Midi(MidiClip {
+                        instrument: if channel == GM_DRUM_CHANNEL {
+                            Some(DRUM_KIT_INSTRUMENT.to_string())
+                        } else {
+                            program.map(|program| gm_program_name(program).to_string())
+                        },
+                        notes,
+                        controllers: clip_controllers(&lane, start_tick, start_tick + length_ticks),
+                    })
+                };
+                track.clips.push(Clip::new(
+                    track.name.clone(),
+                    start_tick,
+                    length_ticks.max(1),
+                    payload,
+                ));
+            }
+            project.tracks.push(track);
+        }
+    }
+
+    info!(
+        format = ?smf.header.format,
+        tracks = project.tracks.len(),
+        notes = project.note_count(),
+        "midi file imported"
+    );
+    Ok(project)
+}
+
+#[must_use]
+pub fn gm_program_name(program: u8) -> &'static str {
+    GM_PROGRAM_NAMES[usize::from(program.min(127))]
+}
+
+/// Resolves a clip instrument name to its GM program, ignoring case.
+#[must_use]
+pub fn gm_program_number(name: &str) -> Option<u8> {
+    let name = name.trim();
+    GM_PROGRAM_NAMES
+        .iter()
+        .position(|candidate| candidate.eq_ignore_ascii_case(name))
+        .map(|program| program as u8)
+}
+
+/// Controller lanes for a clip spanning `start_tick..=end_tick`, with the value
+/// already in effect at the clip start carried in as its first point.
+fn clip_controllers(lane: &ChannelLane, start_tick: u64, end_tick: u64) -> Vec<ControllerLane> {
+    CONTROLLER_TARGETS
+        .into_iter()
+        .filter_map(|target| {
+            let events = lane
+                .controllers
+                .iter()
+                .filter(|(_, event_target, _)| *event_target == target);
+            let carried =
+                events
+                    .clone()
+                    .rfind(|(tick, _, _)| *tick < start_tick)
+                    .map(|(_, _, value)| AutomationPoint {
+                        tick: 0,
+                        value: *value,
+                    });
+            let points: Vec<AutomationPoint> = carried
+                .into_iter()
+                .chain(
+                    events
+                        .filter(|(tick, _, _)| (start_tick..=end_tick).contains(tick))
+                        .map(|(tick, _, value)| AutomationPoint {
+                            tick: tick - start_tick,
+                            value: *value,
+                        }),
+                )
+                .collect();
+            (!points.is_empty()).then_some(ControllerLane { target, points })
+        })
+        .collect()
+}
+
+/// Splits a channel's notes at program changes, pairing each run of notes with
+/// the program active when it starts.
+fn program_segments(lane: &ChannelLane) -> Vec<(u64, Option<u8>, Vec<MidiNote>)> {
+    let first_note = lane.notes.first().map_or(0, |note| note.start_tick);
+    let mut boundaries: Vec<(u64, Option<u8>)> = vec![(
+        0,
+        lane.programs
+            .iter()
+            .rev()
+            .find(|(tick, _)| *tick <= first_note)
+            .map(|(_, program)| *program),
+    )];
+    for (tick, program) in &lane.programs {
+        if *tick > first_note {
+            boundaries.push((*tick, Some(*program)));
+        }
+    }
+
+    let mut segments = Vec::with_capacity(boundaries.len());
+    for (index, (start, program)) in boundaries.iter().enumerate() {
+        let end = boundaries
+            .get(index + 1)
+            .map_or(u64::MAX, |(next, _)| *next);
+        let notes: Vec<MidiNote> = lane
+            .notes
+            .iter()
+            .filter(|note| note.start_tick >= *start && note.start_tick < end)
+            .cloned()
+            .collect();
+        if !notes.is_empty() {
+            segments.push((*start, *program, notes));
+        }
+    }
+    segments
+}
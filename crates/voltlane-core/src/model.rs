@@ -4,11 +4,19 @@ use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
 use uuid::Uuid;
 
+use crate::{theory::Key, time::seconds_to_ticks};
+
 pub const DEFAULT_PPQ: u16 = 480;
 pub const DEFAULT_SAMPLE_RATE: u32 = 48_000;
 pub const DEFAULT_TRACKER_LINES_PER_BEAT: u16 = 4;
 pub const DEFAULT_TRACK_GAIN_DB: f32 = 0.0;
 pub const DEFAULT_TRACK_PAN: f32 = 0.0;
+pub const DEFAULT_CLIP_VELOCITY_SCALE: f32 = 1.0;
+pub const DEFAULT_STEPS_PER_BEAT: u16 = 4;
+pub const DEFAULT_STEP_COUNT: u16 = 16;
+pub const PITCH_BEND_RANGE_SEMITONES: f32 = 2.0;
+pub const DEFAULT_REFERENCE_NOTE: u8 = 69;
+pub const DEFAULT_REFERENCE_PITCH_HZ: f64 = 440.0;
 
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
 pub struct Project {
@@ -20,6 +28,18 @@ pub struct Project {
     pub sample_rate: u32,
     pub transport: Transport,
     pub tracks: Vec<Track>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub clip_pool: Vec<ClipPoolEntry>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub order_list: Option<OrderList>,
+    #[serde(default, skip_serializing_if = "TimeSignature::is_common_time")]
+    pub time_signature: TimeSignature,
+    #[serde(default, skip_serializing_if = "Tuning::is_standard")]
+    pub tuning: Tuning,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub key: Option<Key>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub grooves: Vec<GrooveTemplate>,
     pub created_at: DateTime<Utc>,
     pub updated_at: DateTime<Utc>,
 }
@@ -37,6 +57,12 @@ impl Project {
             sample_rate,
             transport: Transport::default(),
             tracks: Vec::new(),
+            clip_pool: Vec::new(),
+            order_list: None,
+            time_signature: TimeSignature::default(),
+            tuning: Tuning::default(),
+            key: None,
+            grooves: Vec::new(),
             created_at: now,
             updated_at: now,
         }
@@ -56,10 +82,43 @@ impl Project {
         self.tracks
             .iter()
             .flat_map(|track| track.clips.iter())
-            .map(Clip::note_count)
+            .filter_map(|clip| self.resolved_payload(clip))
+            .map(ClipPayload::note_count)
             .sum()
     }
 
+    #[must_use]
+    pub fn pool_entry(&self, content_id: Uuid) -> Option<&ClipPoolEntry> {
+        self.clip_pool.iter().find(|entry| entry.id == content_id)
+    }
+
+    /// Content a clip plays: its own payload, or the shared pool entry for linked
+    /// placements. Returns `None` when a link points at missing pool content.
+    #[must_use]
+    pub fn resolved_payload<'a>(&'a self, clip: &'a Clip) -> Option<&'a ClipPayload> {
+        match &clip.payload {
+            ClipPayload::Linked(link) => {
+                self.pool_entry(link.content_id).map(|entry| &entry.payload)
+            }
+            payload => Some(payload),
+        }
+    }
+
+    /// Key governing `clip`: its own key, else the project key.
+    #[must_use]
+    pub fn key_for<'a>(&'a self, clip: &'a Clip) -> Option<&'a Key> {
+        clip.key.as_ref().or(self.key.as_ref())
+    }
+
+    /// Groove template by name, ignoring case.
+    #[must_use]
+    pub fn groove(&self, name: &str) -> Option<&GrooveTemplate> {
+        let name = name.trim();
+        self.grooves
+            .iter()
+            .find(|groove| groove.name.eq_ignore_ascii_case(name))
+    }
+
     #[must_use]
     pub fn max_tick(&self) -> u64 {
         self.tracks
@@ -69,6 +128,237 @@ impl Project {
             .max()
             .unwrap_or_default()
     }
+
+    /// Loop-back region of the song as `(start_tick, end_tick)`, taken from the
+    /// order list when it defines a loop row.
+    #[must_use]
+    pub fn song_loop_ticks(&self) -> Option<(u64, u64)> {
+        self.order_list.as_ref().and_then(OrderList::loop_ticks)
+    }
+}
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+pub struct TimeSignature {
+    pub numerator: u8,
+    /// Note value of one beat, a power of two.
+    pub denominator: u8,
+}
+
+impl Default for TimeSignature {
+    fn default() -> Self {
+        Self {
+            numerator: 4,
+            denominator: 4,
+        }
+    }
+}
+
+impl TimeSignature {
+    #[must_use]
+    pub fn is_common_time(&self) -> bool {
+        *self == Self::default()
+    }
+
+    #[must_use]
+    pub fn bar_ticks(&self, ppq: u16) -> u64 {
+        u64::from(ppq) * 4 * u64::from(self.numerator.max(1)) / u64::from(self.denominator.max(1))
+    }
+}
+
+/// Timing and accent feel captured from a performance, one slot per grid step
+/// over a repeating cycle.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct GrooveTemplate {
+    pub name: String,
+    pub grid_ticks: u64,
+    pub slots: Vec<GrooveSlot>,
+}
+
+impl GrooveTemplate {
+    #[must_use]
+    pub fn cycle_ticks(&self) -> u64 {
+        self.grid_ticks.saturating_mul(self.slots.len() as u64)
+    }
+
+    /// Slot governing the grid line nearest `tick`.
+    #[must_use]
+    pub fn slot_at(&self, tick: u64) -> Option<&GrooveSlot> {
+        if self.grid_ticks == 0 || self.slots.is_empty() {
+            return None;
+        }
+        let line = tick.saturating_add(self.grid_ticks / 2) / self.grid_ticks;
+        self.slots.get((line % self.slots.len() as u64) as usize)
+    }
+}
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq)]
+pub struct GrooveSlot {
+    /// Ticks the performance sits ahead of (negative) or behind the grid line.
+    pub offset_ticks: i64,
+    /// Accent relative to the performance's average level.
+    pub velocity_scale: f32,
+}
+
+impl Default for GrooveSlot {
+    fn default() -> Self {
+        Self {
+            offset_ticks: 0,
+            velocity_scale: 1.0,
+        }
+    }
+}
+
+/// Project tuning: a reference pitch plus an optional Scala scale and keyboard
+/// mapping. The default is 12-tone equal temperament with A4 at 440 Hz.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(default)]
+pub struct Tuning {
+    /// Scale description, e.g. the `.scl` header line.
+    pub name: String,
+    /// Key that sounds at `reference_hz`.
+    pub reference_note: u8,
+    pub reference_hz: f64,
+    /// Scale degrees in cents above the 1/1. The last degree is the period,
+    /// usually 1200 for an octave. Empty means 12-tone equal temperament.
+    pub scale_cents: Vec<f64>,
+    /// Key the scale's 1/1 sits on.
+    pub root_note: u8,
+    /// Keyboard mapping from a `.kbm` file. Entry `i` gives the scale degree of
+    /// key `root_note + i`, repeating every `key_map.len()` keys; `None` leaves the
+    /// key silent. Empty maps keys to consecutive degrees.
+    pub key_map: Vec<Option<u16>>,
+    /// Degrees one repetition of `key_map` advances by; 0 uses the scale size.
+    pub map_octave_degree: u16,
+    /// Keys outside `first_note..=last_note` are silent.
+    pub first_note: u8,
+    pub last_note: u8,
+}
+
+impl Default for Tuning {
+    fn default() -> Self {
+        Self {
+            name: String::new(),
+            reference_note: DEFAULT_REFERENCE_NOTE,
+            reference_hz: DEFAULT_REFERENCE_PITCH_HZ,
+            scale_cents: Vec::new(),
+            root_note: 60,
+            key_map: Vec::new(),
+            map_octave_degree: 0,
+            first_note: 0,
+            last_note: 127,
+        }
+    }
+}
+
+impl Tuning {
+    #[must_use]
+    pub fn is_standard(&self) -> bool {
+        *self == Self::default()
+    }
+
+    /// Frequency of a key, or `None` for keys the mapping leaves silent.
+    #[must_use]
+    pub fn frequency_hz(&self, key: u8) -> Option<f64> {
+        if key < self.first_note || key > self.last_note {
+            return None;
+        }
+        let cents = self.degree_cents(self.key_degree(key)?);
+        let reference_degree = self
+            .key_degree(self.reference_note)
+            .unwrap_or_else(|| i64::from(self.reference_note) - i64::from(self.root_note));
+        let reference_cents = self.degree_cents(reference_degree);
+        Some(self.reference_hz * 2_f64.powf((cents - reference_cents) / 1_200.0))
+    }
+
+    fn key_degree(&self, key: u8) -> Option<i64> {
+        let offset = i64::from(key) - i64::from(self.root_note);
+        if self.key_map.is_empty() {
+            return Some(offset);
+        }
+        let map_size = self.key_map.len() as i64;
+        let octave_degree = match self.map_octave_degree {
+            0 if self.scale_cents.is_empty() => 12,
+            0 => self.scale_cents.len() as i64,
+            degree => i64::from(degree),
+        };
+        let degree = self.key_map[offset.rem_euclid(map_size) as usize]?;
+        Some(offset.div_euclid(map_size) * octave_degree + i64::from(degree))
+    }
+
+    fn degree_cents(&self, degree: i64) -> f64 {
+        let Some(period) = self.scale_cents.last() else {
+            return degree as f64 * 100.0;
+        };
+        let size = self.scale_cents.len() as i64;
+        let step = degree.rem_euclid(size);
+        let within = if step == 0 {
+            0.0
+        } else {
+            self.scale_cents[step as usize - 1]
+        };
+        degree.div_euclid(size) as f64 * period + within
+    }
+}
+
+/// Frequency of a key in 12-tone equal temperament at A4 = 440 Hz, the tuning
+/// sampler root notes are recorded in.
+#[must_use]
+pub fn equal_tempered_hz(key: u8) -> f64 {
+    DEFAULT_REFERENCE_PITCH_HZ * 2_f64.powf((f64::from(key) - 69.0) / 12.0)
+}
+
+/// Tracker-style song arrangement. Each row plays one pooled pattern per channel,
+/// and rows are laid out back to back on the timeline.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(default)]
+pub struct OrderList {
+    pub row_length_ticks: u64,
+    /// Track ids, one per order column.
+    pub channels: Vec<Uuid>,
+    pub rows: Vec<OrderRow>,
+    /// Row the song jumps back to after the last row, as used by game music.
+    pub loop_row: Option<usize>,
+    /// Timeline clips placed by this order list. Applying a new order list removes
+    /// only these, so hand-placed clips on the channel tracks survive.
+    pub placement_ids: Vec<Uuid>,
+}
+
+impl Default for OrderList {
+    fn default() -> Self {
+        Self {
+            row_length_ticks: u64::from(DEFAULT_PPQ) * 16,
+            channels: Vec::new(),
+            rows: Vec::new(),
+            loop_row: None,
+            placement_ids: Vec::new(),
+        }
+    }
+}
+
+impl OrderList {
+    #[must_use]
+    pub fn length_ticks(&self) -> u64 {
+        self.row_length_ticks.saturating_mul(self.rows.len() as u64)
+    }
+
+    #[must_use]
+    pub fn row_start_tick(&self, row: usize) -> u64 {
+        self.row_length_ticks.saturating_mul(row as u64)
+    }
+
+    #[must_use]
+    pub fn loop_ticks(&self) -> Option<(u64, u64)> {
+        self.loop_row
+            .filter(|row| *row < self.rows.len())
+            .map(|row| (self.row_start_tick(row), self.length_ticks()))
+    }
+}
+
+/// One order row: pool content ids per channel, `None` leaving the channel empty.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Default)]
+#[serde(default)]
+pub struct OrderRow {
+    pub patterns: Vec<Option<Uuid>>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
@@ -119,6 +409,10 @@ pub struct Track {
     #[serde(default, skip_serializing_if = "Vec::is_empty")]
     pub sends: Vec<TrackSend>,
     pub effects: Vec<EffectSpec>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub synth_patches: Vec<SynthPatch>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub samplers: Vec<SamplerInstrument>,
     pub clips: Vec<Clip>,
 }
 
@@ -139,9 +433,29 @@ impl Track {
             output_bus: None,
             sends: Vec::new(),
             effects: Vec::new(),
+            synth_patches: Vec::new(),
+            samplers: Vec::new(),
             clips: Vec::new(),
         }
     }
+
+    /// Resolves the synth patch selected by a clip instrument name, if the track defines one.
+    #[must_use]
+    pub fn synth_patch(&self, instrument: Option<&str>) -> Option<&SynthPatch> {
+        let instrument = instrument?.trim();
+        self.synth_patches
+            .iter()
+            .find(|patch| patch.name.eq_ignore_ascii_case(instrument))
+    }
+
+    /// Resolves the sampler selected by a clip instrument name, if the track defines one.
+    #[must_use]
+    pub fn sampler(&self, instrument: Option<&str>) -> Option<&SamplerInstrument> {
+        let instrument = instrument?.trim();
+        self.samplers
+            .iter()
+            .find(|sampler| sampler.name.eq_ignore_ascii_case(instrument))
+    }
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
@@ -208,6 +522,239 @@ impl EffectSpec {
     }
 }
 
+/// Subtractive synth patch owned by a track and selected by name through
+/// `MidiClip.instrument`.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(default)]
+pub struct SynthPatch {
+    pub name: String,
+    pub oscillators: Vec<SynthOscillator>,
+    pub unison_voices: u8,
+    pub unison_detune_cents: f32,
+    pub amp_envelope: AdsrEnvelope,
+    pub filter: SynthFilter,
+    pub filter_envelope: AdsrEnvelope,
+    pub lfo: SynthLfo,
+    pub glide_seconds: f32,
+    pub gain_db: f32,
+}
+
+impl Default for SynthPatch {
+    fn default() -> Self {
+        Self {
+            name: "Init".to_string(),
+            oscillators: vec![SynthOscillator::default()],
+            unison_voices: 1,
+            unison_detune_cents: 0.0,
+            amp_envelope: AdsrEnvelope::default(),
+            filter: SynthFilter::default(),
+            filter_envelope: AdsrEnvelope {
+                attack_seconds: 0.0,
+                decay_seconds: 0.0,
+                sustain_level: 1.0,
+                release_seconds: 0.0,
+            },
+            lfo: SynthLfo::default(),
+            glide_seconds: 0.0,
+            gain_db: 0.0,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(default)]
+pub struct SynthOscillator {
+    pub waveform: SynthWaveform,
+    pub level: f32,
+    pub octave: i8,
+    pub detune_cents: f32,
+    pub pulse_width: f32,
+}
+
+impl Default for SynthOscillator {
+    fn default() -> Self {
+        Self {
+            waveform: SynthWaveform::Saw,
+            level: 1.0,
+            octave: 0,
+            detune_cents: 0.0,
+            pulse_width: 0.5,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum SynthWaveform {
+    Sine,
+    Triangle,
+    Saw,
+    Square,
+    Noise,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(default)]
+pub struct AdsrEnvelope {
+    pub attack_seconds: f32,
+    pub decay_seconds: f32,
+    pub sustain_level: f32,
+    pub release_seconds: f32,
+}
+
+impl Default for AdsrEnvelope {
+    fn default() -> Self {
+        Self {
+            attack_seconds: 0.005,
+            decay_seconds: 0.12,
+            sustain_level: 0.8,
+            release_seconds: 0.15,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(default)]
+pub struct SynthFilter {
+    pub mode: SynthFilterMode,
+    pub cutoff_hz: f32,
+    pub resonance: f32,
+    /// Filter envelope depth in octaves above `cutoff_hz`.
+    pub envelope_octaves: f32,
+    /// 0.0 keeps the cutoff fixed, 1.0 tracks the played pitch exactly.
+    pub key_tracking: f32,
+}
+
+impl Default for SynthFilter {
+    fn default() -> Self {
+        Self {
+            mode: SynthFilterMode::LowPass,
+            cutoff_hz: 18_000.0,
+            resonance: 0.0,
+            envelope_octaves: 0.0,
+            key_tracking: 0.0,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum SynthFilterMode {
+    Off,
+    LowPass,
+    HighPass,
+    BandPass,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(default)]
+pub struct SynthLfo {
+    pub waveform: SynthWaveform,
+    pub rate_hz: f32,
+    pub target: SynthLfoTarget,
+    /// Semitones for pitch, octaves for filter cutoff, 0..1 for amplitude.
+    pub depth: f32,
+}
+
+impl Default for SynthLfo {
+    fn default() -> Self {
+        Self {
+            waveform: SynthWaveform::Sine,
+            rate_hz: 5.0,
+            target: SynthLfoTarget::Pitch,
+            depth: 0.0,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum SynthLfoTarget {
+    Pitch,
+    FilterCutoff,
+    Amplitude,
+}
+
+/// Sample-based instrument owned by a track and selected by name through
+/// `MidiClip.instrument` or `PatternClip.instrument`.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(default)]
+pub struct SamplerInstrument {
+    pub name: String,
+    pub zones: Vec<SamplerZone>,
+    pub amp_envelope: AdsrEnvelope,
+    pub gain_db: f32,
+}
+
+impl Default for SamplerInstrument {
+    fn default() -> Self {
+        Self {
+            name: "Sampler".to_string(),
+            zones: Vec::new(),
+            amp_envelope: AdsrEnvelope {
+                attack_seconds: 0.0,
+                decay_seconds: 0.0,
+                sustain_level: 1.0,
+                release_seconds: 0.05,
+            },
+            gain_db: 0.0,
+        }
+    }
+}
+
+impl SamplerInstrument {
+    /// Zones that should sound for a key/velocity pair; overlapping zones layer.
+    pub fn zones_for(&self, pitch: u8, velocity: u8) -> impl Iterator<Item = &SamplerZone> {
+        self.zones.iter().filter(move |zone| {
+            (zone.low_note..=zone.high_note).contains(&pitch)
+                && (zone.low_velocity..=zone.high_velocity).contains(&velocity)
+        })
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(default)]
+pub struct SamplerZone {
+    pub source_path: String,
+    pub root_note: u8,
+    pub low_note: u8,
+    pub high_note: u8,
+    pub low_velocity: u8,
+    pub high_velocity: u8,
+    pub tune_cents: f32,
+    pub gain_db: f32,
+    /// Plays the whole sample regardless of note length (drum hits).
+    pub one_shot: bool,
+    pub loop_enabled: bool,
+    pub loop_start_seconds: f64,
+    pub loop_end_seconds: f64,
+    /// Part of the source the zone plays, for slices of a longer file. An end
+    /// of `0.0` plays to the end of the file.
+    pub sample_start_seconds: f64,
+    pub sample_end_seconds: f64,
+}
+
+impl Default for SamplerZone {
+    fn default() -> Self {
+        Self {
+            source_path: String::new(),
+            root_note: 60,
+            low_note: 0,
+            high_note: 127,
+            low_velocity: 0,
+            high_velocity: 127,
+            tune_cents: 0.0,
+            gain_db: 0.0,
+            one_shot: false,
+            loop_enabled: false,
+            loop_start_seconds: 0.0,
+            loop_end_seconds: 0.0,
+            sample_start_seconds: 0.0,
+            sample_end_seconds: 0.0,
+        }
+    }
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
 pub struct Clip {
     pub id: Uuid,
@@ -215,23 +762,146 @@ pub struct Clip {
     pub start_tick: u64,
     pub length_ticks: u64,
     pub disabled: bool,
+    /// Content tick heard at the clip's left edge (slip editing). Offset, loop,
+    /// transpose and velocity scale shape note, controller and automation
+    /// content; audio clips keep using their own trim range.
+    #[serde(default, skip_serializing_if = "is_zero_ticks")]
+    pub content_offset_ticks: u64,
+    /// When set, note content repeats every this many ticks across the clip.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub loop_length_ticks: Option<u64>,
+    #[serde(default, skip_serializing_if = "is_zero_semitones")]
+    pub transpose_semitones: i8,
+    #[serde(
+        default = "default_velocity_scale",
+        skip_serializing_if = "is_default_velocity_scale"
+    )]
+    pub velocity_scale: f32,
+    /// Overrides the project key for scale snapping and highlighting.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub key: Option<Key>,
     pub payload: ClipPayload,
 }
 
 impl Clip {
+    /// New enabled clip with neutral playback settings.
+    #[must_use]
+    pub fn new(
+        name: impl Into<String>,
+        start_tick: u64,
+        length_ticks: u64,
+        payload: ClipPayload,
+    ) -> Self {
+        Self {
+            id: Uuid::new_v4(),
+            name: name.into(),
+            start_tick,
+            length_ticks,
+            disabled: false,
+            content_offset_ticks: 0,
+            loop_length_ticks: None,
+            transpose_semitones: 0,
+            velocity_scale: DEFAULT_CLIP_VELOCITY_SCALE,
+            key: None,
+            payload,
+        }
+    }
+
     #[must_use]
     pub fn end_tick(&self) -> u64 {
         self.start_tick.saturating_add(self.length_ticks)
     }
 
+    /// Loop length when the clip loops its content, ignoring a zero length.
     #[must_use]
-    pub fn note_count(&self) -> usize {
-        match &self.payload {
-            ClipPayload::Midi(midi) => midi.notes.len(),
-            ClipPayload::Pattern(pattern) => pattern.notes.len(),
-            ClipPayload::Audio(_) | ClipPayload::Automation(_) => 0,
+    pub fn content_loop_ticks(&self) -> Option<u64> {
+        self.loop_length_ticks.filter(|length| *length > 0)
+    }
+
+    /// Ticks of content the clip can reveal: one loop, or everything up to the
+    /// clip's right edge.
+    #[must_use]
+    pub fn content_length_ticks(&self) -> u64 {
+        self.content_loop_ticks()
+            .unwrap_or_else(|| self.content_offset_ticks.saturating_add(self.length_ticks))
+    }
+
+    /// Maps a clip-relative tick onto the content tick that sounds there.
+    #[must_use]
+    pub fn content_tick(&self, clip_tick: u64) -> u64 {
+        let tick = self.content_offset_ticks.saturating_add(clip_tick);
+        self.content_loop_ticks()
+            .map_or(tick, |length| tick % length)
+    }
+
+    /// Clip-relative ticks, within the clip, at which a content tick sounds. A
+    /// looping clip can play the same content tick several times.
+    #[must_use]
+    pub fn clip_ticks_of(&self, content_tick: u64) -> Vec<u64> {
+        let offset = self.content_offset_ticks;
+        let visible_end = offset.saturating_add(self.length_ticks);
+        let Some(length) = self.content_loop_ticks() else {
+            return (offset..=visible_end)
+                .contains(&content_tick)
+                .then(|| content_tick - offset)
+                .into_iter()
+                .collect();
+        };
+        if content_tick >= length {
+            return Vec::new();
+        }
+        (offset / length..=visible_end / length)
+            .map(|repeat| repeat.saturating_mul(length).saturating_add(content_tick))
+            .filter(|tick| (offset..=visible_end).contains(tick))
+            .map(|tick| tick - offset)
+            .collect()
+    }
+
+    /// Clip-relative notes as they sound: content shifted by the offset and
+    /// repeated when looping, cut at the clip's edges, then transposed and
+    /// velocity scaled.
+    #[must_use]
+    pub fn arrange_notes(&self, notes: &[MidiNote]) -> Vec<MidiNote> {
+        let content_end = self.content_loop_ticks().unwrap_or(u64::MAX);
+        let mut arranged = Vec::new();
+        for note in notes {
+            let note_end = note.end_tick().min(content_end);
+            for start_tick in self.clip_ticks_of(note.start_tick) {
+                if start_tick >= self.length_ticks {
+                    continue;
+                }
+                let end_tick = start_tick
+                    .saturating_add(note_end - note.start_tick)
+                    .min(self.length_ticks);
+                arranged.push(self.play_note(note, start_tick, end_tick - start_tick));
+            }
+        }
+        arranged.sort_by_key(|note| (note.start_tick, note.pitch));
+        arranged
+    }
+
+    fn play_note(&self, note: &MidiNote, start_tick: u64, length_ticks: u64) -> MidiNote {
+        let pitch = (i16::from(note.pitch) + i16::from(self.transpose_semitones)).clamp(0, 127);
+        let velocity = if is_default_velocity_scale(&self.velocity_scale) {
+            note.velocity
+        } else {
+            (f32::from(note.velocity) * self.velocity_scale)
+                .round()
+                .clamp(1.0, 127.0) as u8
+        };
+        MidiNote {
+            pitch: pitch as u8,
+            velocity,
+            start_tick,
+            length_ticks,
+            ..note.clone()
         }
     }
+
+    #[must_use]
+    pub fn note_count(&self) -> usize {
+        self.payload.note_count()
+    }
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
@@ -239,19 +909,126 @@ impl Clip {
 pub enum ClipPayload {
     Midi(MidiClip),
     Pattern(PatternClip),
+    Steps(StepSequencerClip),
     Audio(AudioClip),
     Automation(AutomationClip),
+    /// Placement of shared content from `Project.clip_pool`.
+    Linked(LinkedClip),
+}
+
+impl ClipPayload {
+    /// Notes owned directly by this payload; linked placements report zero.
+    #[must_use]
+    pub fn note_count(&self) -> usize {
+        match self {
+            Self::Midi(midi) => midi.notes.len(),
+            Self::Pattern(pattern) => pattern.notes.len(),
+            Self::Steps(steps) => steps.active_step_count(),
+            Self::Audio(_) | Self::Automation(_) | Self::Linked(_) => 0,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct LinkedClip {
+    pub content_id: Uuid,
+}
+
+/// Clip content shared by every placement that links to it.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct ClipPoolEntry {
+    pub id: Uuid,
+    pub name: String,
+    pub payload: ClipPayload,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
 pub struct MidiClip {
     pub instrument: Option<String>,
     pub notes: Vec<MidiNote>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub controllers: Vec<ControllerLane>,
+}
+
+impl MidiClip {
+    #[must_use]
+    pub fn controller(&self, target: ControllerTarget) -> Option<&ControllerLane> {
+        self.controllers.iter().find(|lane| lane.target == target)
+    }
+}
+
+/// Performance controller a [`ControllerLane`] drives.
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq, PartialOrd, Ord, Hash)]
+#[serde(rename_all = "snake_case")]
+pub enum ControllerTarget {
+    ModWheel,
+    Expression,
+    Sustain,
+    PitchBend,
+}
+
+impl ControllerTarget {
+    /// Value the controller rests at before its first point.
+    #[must_use]
+    pub fn default_value(self) -> f32 {
+        match self {
+            Self::Expression => 1.0,
+            Self::ModWheel | Self::Sustain | Self::PitchBend => 0.0,
+        }
+    }
+
+    #[must_use]
+    pub fn value_range(self) -> (f32, f32) {
+        match self {
+            Self::PitchBend => (-1.0, 1.0),
+            Self::ModWheel | Self::Expression | Self::Sustain => (0.0, 1.0),
+        }
+    }
+}
+
+/// Continuous controller curve inside a MIDI clip. Point ticks are relative to
+/// the clip start and values are normalized: `0..=1` for mod wheel, expression
+/// and sustain (pedal down from 0.5), `-1..=1` for pitch bend where the extremes
+/// reach [`PITCH_BEND_RANGE_SEMITONES`].
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct ControllerLane {
+    pub target: ControllerTarget,
+    pub points: Vec<AutomationPoint>,
+}
+
+impl ControllerLane {
+    /// Value at a clip-relative tick. Sustain holds each point until the next one;
+    /// the other controllers ramp linearly between points.
+    #[must_use]
+    pub fn value_at(&self, tick: u64) -> f32 {
+        let next_index = self.points.partition_point(|point| point.tick <= tick);
+        let Some(previous) = next_index
+            .checked_sub(1)
+            .and_then(|index| self.points.get(index))
+        else {
+            return self.target.default_value();
+        };
+        match self.points.get(next_index) {
+            Some(next) if self.target != ControllerTarget::Sustain => {
+                let span = next.tick.saturating_sub(previous.tick).max(1) as f32;
+                let progress = tick.saturating_sub(previous.tick) as f32 / span;
+                previous.value + (next.value - previous.value) * progress
+            }
+            _ => previous.value,
+        }
+    }
+
+    #[must_use]
+    pub fn pedal_down_at(&self, tick: u64) -> bool {
+        self.value_at(tick) >= 0.5
+    }
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
 pub struct PatternClip {
     pub source_chip: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub instrument: Option<String>,
     #[serde(default)]
     pub notes: Vec<MidiNote>,
     #[serde(default, skip_serializing_if = "Vec::is_empty")]
@@ -269,6 +1046,7 @@ impl Default for PatternClip {
     fn default() -> Self {
         Self {
             source_chip: String::new(),
+            instrument: None,
             notes: Vec::new(),
             rows: Vec::new(),
             macros: Vec::new(),
@@ -277,6 +1055,94 @@ impl Default for PatternClip {
     }
 }
 
+/// Channel-rack style drum programming: rows of instruments by a fixed number of
+/// steps, looped across the clip length.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(default)]
+pub struct StepSequencerClip {
+    /// Chip backend used for rows without a sampler instrument (noise voice).
+    pub source_chip: String,
+    pub steps_per_beat: u16,
+    pub step_count: u16,
+    /// Seed for per-step probability so renders and exports stay deterministic.
+    pub seed: u64,
+    pub rows: Vec<StepRow>,
+}
+
+impl Default for StepSequencerClip {
+    fn default() -> Self {
+        Self {
+            source_chip: String::new(),
+            steps_per_beat: DEFAULT_STEPS_PER_BEAT,
+            step_count: DEFAULT_STEP_COUNT,
+            seed: 0,
+            rows: Vec::new(),
+        }
+    }
+}
+
+impl StepSequencerClip {
+    #[must_use]
+    pub fn active_step_count(&self) -> usize {
+        self.rows
+            .iter()
+            .filter(|row| !row.mute)
+            .map(|row| row.steps.iter().filter(|step| step.active).count())
+            .sum()
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(default)]
+pub struct StepRow {
+    pub name: String,
+    /// Sampler on the owning track; rows without one use the chip noise voice.
+    pub instrument: Option<String>,
+    pub pitch: u8,
+    pub mute: bool,
+    pub steps: Vec<Step>,
+}
+
+impl Default for StepRow {
+    fn default() -> Self {
+        Self {
+            name: String::new(),
+            instrument: None,
+            pitch: 36,
+            mute: false,
+            steps: Vec::new(),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(default)]
+pub struct Step {
+    pub active: bool,
+    pub velocity: u8,
+    /// Chance in 0..=1 that the step fires on a given pass through the pattern.
+    pub probability: f32,
+    /// Signed timing offset in ticks, limited to half a step.
+    pub nudge_ticks: i32,
+    /// Number of evenly spaced retriggers within the step (1 = single hit).
+    pub ratchet: u8,
+    /// Adds a softer grace hit just ahead of the main hit.
+    pub flam: bool,
+}
+
+impl Default for Step {
+    fn default() -> Self {
+        Self {
+            active: false,
+            velocity: 100,
+            probability: 1.0,
+            nudge_ticks: 0,
+            ratchet: 1,
+            flam: false,
+        }
+    }
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
 #[serde(default)]
 pub struct ChipMacroLane {
@@ -338,11 +1204,34 @@ pub struct AudioClip {
     pub fade_out_seconds: f64,
     pub reverse: bool,
     pub stretch_ratio: f32,
+    #[serde(default, skip_serializing_if = "is_resample_stretch")]
+    pub stretch_mode: AudioStretchMode,
+    /// Pitch shift applied on top of the stretch, independent of it.
+    #[serde(default, skip_serializing_if = "is_zero_pitch")]
+    pub pitch_semitones: f32,
+    #[serde(default, skip_serializing_if = "is_zero_pitch")]
+    pub pitch_cents: f32,
+    /// With two or more markers the clip follows them instead of
+    /// `stretch_ratio`, stretching each stretch of source between neighbours.
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub warp_markers: Vec<WarpMarker>,
+    /// Tempo the source was recorded at, when it was auto-warped.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub source_bpm: Option<f64>,
     pub waveform_bucket_size: usize,
     pub waveform_peaks: Vec<f32>,
     pub waveform_cache_path: Option<String>,
 }
 
+/// Pins a source position to a tick on the clip's warped timeline. Ticks count
+/// from where the source start would play, so trimming does not move markers,
+/// and being in ticks they follow project tempo changes.
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq)]
+pub struct WarpMarker {
+    pub source_seconds: f64,
+    pub tick: u64,
+}
+
 impl Default for AudioClip {
     fn default() -> Self {
         Self {
@@ -358,6 +1247,11 @@ impl Default for AudioClip {
             fade_out_seconds: 0.0,
             reverse: false,
             stretch_ratio: 1.0,
+            stretch_mode: AudioStretchMode::Resample,
+            pitch_semitones: 0.0,
+            pitch_cents: 0.0,
+            warp_markers: Vec::new(),
+            source_bpm: None,
             waveform_bucket_size: 1024,
             waveform_peaks: Vec::new(),
             waveform_cache_path: None,
@@ -374,12 +1268,110 @@ impl AudioClip {
         (trim_start, trim_end)
     }
 
+    /// Unwarped playback length: the trimmed source times `stretch_ratio`.
     #[must_use]
     pub fn effective_duration_seconds(&self) -> f64 {
         let (trim_start, trim_end) = self.normalized_trim_range();
         let trimmed_duration = (trim_end - trim_start).max(0.0);
         trimmed_duration * f64::from(self.stretch_ratio.max(0.01))
     }
+
+    #[must_use]
+    pub fn is_warped(&self) -> bool {
+        self.warp_markers.len() >= 2
+    }
+
+    /// Clip length in ticks, following the warp markers when there are any.
+    #[must_use]
+    pub fn timeline_length_ticks(&self, bpm: f64, ppq: u16) -> u64 {
+        if !self.is_warped() {
+            return seconds_to_ticks(self.effective_duration_seconds(), bpm, ppq);
+        }
+        let (trim_start, trim_end) = self.normalized_trim_range();
+        let ticks = self.warp_tick_at(trim_end, bpm, ppq) - self.warp_tick_at(trim_start, bpm, ppq);
+        ticks.round().max(0.0) as u64
+    }
+
+    /// Source position, in seconds, heard `clip_tick` ticks into the clip.
+    #[must_use]
+    pub fn source_seconds_at(&self, clip_tick: f64, bpm: f64, ppq: u16) -> f64 {
+        let (trim_start, trim_end) = self.normalized_trim_range();
+        let origin = self.warp_tick_at(trim_start, bpm, ppq);
+        let forward = self
+            .warp_source_at(origin + clip_tick, bpm, ppq)
+            .clamp(trim_start, trim_end);
+        if self.reverse {
+            trim_start + trim_end - forward
+        } else {
+            forward
+        }
+    }
+
+    /// Clip tick at which `source_seconds` is heard; the inverse of
+    /// [`AudioClip::source_seconds_at`].
+    #[must_use]
+    pub fn clip_tick_at(&self, source_seconds: f64, bpm: f64, ppq: u16) -> f64 {
+        let (trim_start, trim_end) = self.normalized_trim_range();
+        let forward = if self.reverse {
+            trim_start + trim_end - source_seconds
+        } else {
+            source_seconds
+        };
+        self.warp_tick_at(forward, bpm, ppq) - self.warp_tick_at(trim_start, bpm, ppq)
+    }
+
+    /// Warped-timeline tick of a source position. Positions beyond the outer
+    /// markers continue at the rate of the nearest marker pair.
+    fn warp_tick_at(&self, source_seconds: f64, bpm: f64, ppq: u16) -> f64 {
+        if !self.is_warped() {
+            return source_seconds
+                * f64::from(self.stretch_ratio.max(0.01))
+                * ticks_per_second(bpm, ppq);
+        }
+        let markers = &self.warp_markers;
+        let index = markers
+            .partition_point(|marker| marker.source_seconds <= source_seconds)
+            .clamp(1, markers.len() - 1);
+        let (from, to) = (markers[index - 1], markers[index]);
+        let rate = (to.tick as f64 - from.tick as f64) / (to.source_seconds - from.source_seconds);
+        from.tick as f64 + (source_seconds - from.source_seconds) * rate
+    }
+
+    fn warp_source_at(&self, tick: f64, bpm: f64, ppq: u16) -> f64 {
+        if !self.is_warped() {
+            let rate = f64::from(self.stretch_ratio.max(0.01)) * ticks_per_second(bpm, ppq);
+            return if rate > 0.0 { tick / rate } else { 0.0 };
+        }
+        let markers = &self.warp_markers;
+        let index = markers
+            .partition_point(|marker| marker.tick as f64 <= tick)
+            .clamp(1, markers.len() - 1);
+        let (from, to) = (markers[index - 1], markers[index]);
+        let rate = (to.source_seconds - from.source_seconds) / (to.tick as f64 - from.tick as f64);
+        from.source_seconds + (tick - from.tick as f64) * rate
+    }
+
+    /// Frequency ratio of the pitch shift, `1.0` when unshifted.
+    #[must_use]
+    pub fn pitch_ratio(&self) -> f64 {
+        let semitones = f64::from(self.pitch_semitones) + f64::from(self.pitch_cents) / 100.0;
+        2.0_f64.powf(semitones / 12.0)
+    }
+}
+
+fn ticks_per_second(bpm: f64, ppq: u16) -> f64 {
+    bpm.max(0.0) / 60.0 * f64::from(ppq)
+}
+
+/// How an audio clip fits its source to the clip length.
+#[derive(Debug, Clone, Copy, Default, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum AudioStretchMode {
+    /// Plays the source faster or slower, so pitch follows the stretch.
+    #[default]
+    Resample,
+    /// Keeps the source pitch while changing its length.
+    PreservePitch,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
@@ -401,6 +1393,9 @@ pub struct MidiNote {
     pub start_tick: u64,
     pub length_ticks: u64,
     pub channel: u8,
+    /// Note-off velocity; faster releases shorten the voice release stage.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub release_velocity: Option<u8>,
 }
 
 impl MidiNote {
@@ -422,6 +1417,30 @@ const fn default_track_pan() -> f32 {
     DEFAULT_TRACK_PAN
 }
 
+const fn default_velocity_scale() -> f32 {
+    DEFAULT_CLIP_VELOCITY_SCALE
+}
+
+const fn is_zero_ticks(value: &u64) -> bool {
+    *value == 0
+}
+
+const fn is_zero_semitones(value: &i8) -> bool {
+    *value == 0
+}
+
+fn is_resample_stretch(value: &AudioStretchMode) -> bool {
+    *value == AudioStretchMode::Resample
+}
+
+fn is_zero_pitch(value: &f32) -> bool {
+    *value == 0.0
+}
+
+fn is_default_velocity_scale(value: &f32) -> bool {
+    (*value - DEFAULT_CLIP_VELOCITY_SCALE).abs() <= f32::EPSILON
+}
+
 fn is_default_track_gain_db(value: &f32) -> bool {
     (*value - DEFAULT_TRACK_GAIN_DB).abs() <= f32::EPSILON
 }
@@ -0,0 +1,1087 @@
+use std::{
+    collections::{BTreeMap, BTreeSet},
+    fs,
+    path::{Path, PathBuf},
+};
+
+use anyhow::{Context, Result, bail};
+use tracing::{debug, info, instrument, warn};
+use uuid::Uuid;
+
+use crate::{
+    engine::{Engine, normalize_pattern_clip},
+    model::{
+        Clip, ClipPayload, ClipPoolEntry, DEFAULT_SAMPLE_RATE, DEFAULT_TRACKER_LINES_PER_BEAT,
+        LinkedClip, OrderList, OrderRow, PatternClip, Project, SamplerInstrument, SamplerZone,
+        Track, TrackKind, TrackerRow,
+    },
+    time::tracker_rows_to_ticks,
+};
+
+/// Sample rate at which MOD and XM samples play their root note.
+const AMIGA_C_RATE: u32 = 8_363;
+const MOD_ROWS: u32 = 64;
+const TRACK_COLORS: [&str; 6] = [
+    "#4fb8ff", "#ffb347", "#9be564", "#ff5f7e", "#8f6bff", "#52e1c4",
+];
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ModuleFormat {
+    ProTracker,
+    FastTracker,
+    ImpulseTracker,
+}
+
+impl ModuleFormat {
+    fn source_chip(self) -> &'static str {
+        match self {
+            Self::ProTracker => "mod",
+            Self::FastTracker => "xm",
+            Self::ImpulseTracker => "it",
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum ModuleNote {
+    Note(u8),
+    Off,
+}
+
+#[derive(Debug, Clone, Default)]
+struct ModuleCell {
+    note: Option<ModuleNote>,
+    /// 1-based instrument number, as stored in the module.
+    instrument: Option<u16>,
+    /// Volume column value in the tracker 0..=64 range.
+    volume: Option<u8>,
+    effect: Option<(String, u16)>,
+}
+
+#[derive(Debug, Clone, Default)]
+struct ModulePattern {
+    rows: u32,
+    cells: Vec<(u32, usize, ModuleCell)>,
+}
+
+#[derive(Debug, Clone, Default)]
+struct ModuleSample {
+    name: String,
+    frames: Vec<f32>,
+    sample_rate: u32,
+    root_note: u8,
+    tune_cents: f32,
+    default_volume: u8,
+    loop_frames: Option<(usize, usize)>,
+}
+
+#[derive(Debug, Clone, Default)]
+struct ModuleInstrument {
+    name: String,
+    /// `(sample index, low note, high note)` key ranges.
+    zones: Vec<(usize, u8, u8)>,
+}
+
+/// Format-neutral view of a tracker module before it becomes a [`Project`].
+#[derive(Debug)]
+struct ModuleSong {
+    format: ModuleFormat,
+    title: String,
+    bpm: f64,
+    orders: Vec<u16>,
+    patterns: BTreeMap<u16, ModulePattern>,
+    instruments: Vec<ModuleInstrument>,
+    samples: Vec<ModuleSample>,
+}
+
+#[derive(Debug, Default)]
+struct PatternRows {
+    rows: Vec<TrackerRow>,
+    /// Rows each note sounds for, keyed by its start row.
+    sustain: BTreeMap<u32, u32>,
+}
+
+#[must_use]
+pub fn detect_module_format(bytes: &[u8]) -> Option<ModuleFormat> {
+    if bytes.starts_with(b"Extended Module: ") {
+        Some(ModuleFormat::FastTracker)
+    } else if bytes.starts_with(b"IMPM") {
+        Some(ModuleFormat::ImpulseTracker)
+    } else if bytes
+        .get(1_080..1_084)
+        .and_then(mod_channel_count)
+        .is_some()
+    {
+        Some(ModuleFormat::ProTracker)
+    } else {
+        None
+    }
+}
+
+/// Builds a project from a MOD, XM or IT module. Embedded samples are written as
+/// WAV assets into `sample_dir`, every channel/instrument pair becomes a
+/// sampler-backed track, patterns become pooled [`PatternClip`]s and the order
+/// table is laid out on the timeline.
+///
+/// Only sample data is imported: instrument envelopes, IT compressed samples and
+/// per-row panning are ignored, and only the first effect column is kept.
+#[instrument(fields(path = %path.display(), sample_dir = %sample_dir.display()))]
+pub fn import_module(path: &Path, sample_dir: &Path) -> Result<Project> {
+    let bytes =
+        fs::read(path).with_context(|| format!("failed to read module: {}", path.display()))?;
+    let song = match detect_module_format(&bytes) {
+        Some(ModuleFormat::ProTracker) => parse_mod(&bytes)?,
+        Some(ModuleFormat::FastTracker) => parse_xm(&bytes)?,
+        Some(ModuleFormat::ImpulseTracker) => parse_it(&bytes)?,
+        None => bail!("unrecognized module format: {}", path.display()),
+    };
+    let format = song.format;
+    let project = build_project(song, sample_dir)?;
+    info!(
+        ?format,
+        tracks = project.tracks.len(),
+        clips = project.clip_count(),
+        "tracker module imported"
+    );
+    Ok(project)
+}
+
+fn parse_mod(bytes: &[u8]) -> Result<ModuleSong> {
+    let channel_count =
+        mod_channel_count(slice_at(bytes, 1_080, 4)?).context("unsupported MOD signature")?;
+    let mut headers = Vec::with_capacity(31);
+    for index in 0..31 {
+        let offset = 20 + index * 30;
+        let finetune = (u8_at(bytes, offset + 24)? & 0x0F) as i8;
+        headers.push((
+            text_at(bytes, offset, 22)?,
+            usize::from(u16_be_at(bytes, offset + 22)?) * 2,
+            if finetune > 7 {
+                finetune - 16
+            } else {
+                finetune
+            },
+            u8_at(bytes, offset + 25)?.min(64),
+            usize::from(u16_be_at(bytes, offset + 26)?) * 2,
+            usize::from(u16_be_at(bytes, offset + 28)?) * 2,
+        ));
+    }
+
+    let song_length = usize::from(u8_at(bytes, 950)?).clamp(1, 128);
+    let order_table = slice_at(bytes, 952, 128)?;
+    let orders: Vec<u16> = order_table[..song_length]
+        .iter()
+        .map(|pattern| u16::from(*pattern))
+        .collect();
+    let pattern_count = usize::from(order_table.iter().copied().max().unwrap_or_default()) + 1;
+
+    let pattern_size = MOD_ROWS as usize * channel_count * 4;
+    let mut patterns = BTreeMap::new();
+    for index in 0..pattern_count {
+        let data = slice_at(bytes, 1_084 + index * pattern_size, pattern_size)?;
+        let mut pattern = ModulePattern {
+            rows: MOD_ROWS,
+            cells: Vec::new(),
+        };
+        for (cell_index, cell) in data.chunks_exact(4).enumerate() {
+            let instrument = u16::from((cell[0] & 0xF0) | (cell[2] >> 4));
+            let period = (u16::from(cell[0] & 0x0F) << 8) | u16::from(cell[1]);
+            let (effect, param) = (cell[2] & 0x0F, cell[3]);
+            let module_cell = ModuleCell {
+                note: mod_period_to_note(period).map(ModuleNote::Note),
+                instrument: (instrument > 0).then_some(instrument),
+                volume: (effect == 0x0C).then_some(param.min(64)),
+                effect: hex_effect(effect, param),
+            };
+            pattern.cells.push((
+                (cell_index / channel_count) as u32,
+                cell_index % channel_count,
+                module_cell,
+            ));
+        }
+        patterns.insert(index as u16, pattern);
+    }
+
+    let mut sample_offset = 1_084 + pattern_count * pattern_size;
+    let mut samples = Vec::with_capacity(31);
+    for (name, length, finetune, volume, loop_start, loop_length) in headers {
+        let available = bytes.len().saturating_sub(sample_offset).min(length);
+        let frames: Vec<f32> = bytes[sample_offset..sample_offset + available]
+            .iter()
+            .map(|byte| f32::from(*byte as i8) / 128.0)
+            .collect();
+        sample_offset += available;
+        samples.push(ModuleSample {
+            name,
+            loop_frames: loop_range(loop_start, loop_start + loop_length, frames.len()),
+            frames,
+            sample_rate: AMIGA_C_RATE,
+            root_note: 60,
+            tune_cents: f32::from(finetune) * 12.5,
+            default_volume: volume,
+        });
+    }
+
+    let (speed, tempo) = initial_speed_tempo(&patterns, orders.first().copied(), 6, 125);
+    Ok(ModuleSong {
+        format: ModuleFormat::ProTracker,
+        title: text_at(bytes, 0, 20)?,
+        bpm: tracker_bpm(speed, tempo),
+        orders,
+        instruments: single_sample_instruments(&samples),
+        patterns,
+        samples,
+    })
+}
+
+fn parse_xm(bytes: &[u8]) -> Result<ModuleSong> {
+    let header_size = u32_le_at(bytes, 60)? as usize;
+    let song_length = usize::from(u16_le_at(bytes, 64)?).min(256);
+    let channel_count = usize::from(u16_le_at(bytes, 68)?).clamp(1, 64);
+    let pattern_count = usize::from(u16_le_at(bytes, 70)?);
+    let instrument_count = usize::from(u16_le_at(bytes, 72)?);
+    let speed = u16_le_at(bytes, 76)?;
+    let tempo = u16_le_at(bytes, 78)?;
+    let orders: Vec<u16> = slice_at(bytes, 80, song_length)?
+        .iter()
+        .map(|pattern| u16::from(*pattern))
+        .collect();
+
+    let mut offset = 60 + header_size;
+    let mut patterns = BTreeMap::new();
+    for index in 0..pattern_count {
+        let pattern_header = u32_le_at(bytes, offset)? as usize;
+        let rows = u32::from(u16_le_at(bytes, offset + 5)?).clamp(1, 256);
+        let packed_size = usize::from(u16_le_at(bytes, offset + 7)?);
+        let data = slice_at(bytes, offset + pattern_header, packed_size)?;
+        offset += pattern_header + packed_size;
+
+        let mut pattern = ModulePattern {
+            rows,
+            cells: Vec::new(),
+        };
+        let mut cursor = 0;
+        'rows: for row in 0..rows {
+            for channel in 0..channel_count {
+                if packed_size == 0 || cursor >= data.len() {
+                    break 'rows;
+                }
+                let mut fields = [0_u8; 5];
+                let lead = data[cursor];
+                cursor += 1;
+                if lead & 0x80 == 0 {
+                    fields[0] = lead;
+                    for field in &mut fields[1..] {
+                        *field = data.get(cursor).copied().unwrap_or_default();
+                        cursor += 1;
+                    }
+                } else {
+                    for (bit, field) in fields.iter_mut().enumerate() {
+                        if lead & (1 << bit) != 0 {
+                            *field = data.get(cursor).copied().unwrap_or_default();
+                            cursor += 1;
+                        }
+                    }
+                }
+
+                let [note, instrument, volume, effect, param] = fields;
+                let cell = ModuleCell {
+                    note: match note {
+                        1..=96 => Some(ModuleNote::Note(note + 11)),
+                        97 => Some(ModuleNote::Off),
+                        _ => None,
+                    },
+                    instrument: (instrument > 0).then_some(u16::from(instrument)),
+                    volume: if (0x10..=0x50).contains(&volume) {
+                        Some(volume - 0x10)
+                    } else if effect == 0x0C {
+                        Some(param.min(64))
+                    } else {
+                        None
+                    },
+                    effect: hex_effect(effect, param),
+                };
+                pattern.cells.push((row, channel, cell));
+            }
+        }
+        patterns.insert(index as u16, pattern);
+    }
+
+    let mut instruments = Vec::with_capacity(instrument_count);
+    let mut samples = Vec::new();
+    for _ in 0..instrument_count {
+        let instrument_size = u32_le_at(bytes, offset)? as usize;
+        let name = text_at(bytes, offset + 4, 22)?;
+        let sample_count = usize::from(u16_le_at(bytes, offset + 27)?);
+        if sample_count == 0 {
+            offset += instrument_size.max(29);
+            instruments.push(ModuleInstrument {
+                name,
+                zones: Vec::new(),
+            });
+            continue;
+        }
+        let sample_header_size = u32_le_at(bytes, offset + 29)? as usize;
+        let keymap = slice_at(bytes, offset + 33, 96)?.to_vec();
+        offset += instrument_size;
+
+        let first_sample = samples.len();
+        let mut headers = Vec::with_capacity(sample_count);
+        for sample in 0..sample_count {
+            let header = offset + sample * sample_header_size;
+            headers.push((
+                u32_le_at(bytes, header)? as usize,
+                u32_le_at(bytes, header + 4)? as usize,
+                u32_le_at(bytes, header + 8)? as usize,
+                u8_at(bytes, header + 12)?.min(64),
+                u8_at(bytes, header + 13)? as i8,
+                u8_at(bytes, header + 14)?,
+                u8_at(bytes, header + 16)? as i8,
+                text_at(bytes, header + 18, 22)?,
+            ));
+        }
+        offset += sample_count * sample_header_size;
+
+        for (length, loop_start, loop_length, volume, finetune, kind, relative_note, sample_name) in
+            headers
+        {
+            let data = slice_at(bytes, offset, length)?;
+            offset += length;
+            let sixteen_bit = kind & 0x10 != 0;
+            let frames = if sixteen_bit {
+                delta_decode_16(data)
+            } else {
+                delta_decode_8(data)
+            };
+            let width = if sixteen_bit { 2 } else { 1 };
+            let looped = kind & 0x03 != 0 && loop_length > 0;
+            samples.push(ModuleSample {
+                name: sample_name,
+                loop_frames: if looped {
+                    loop_range(
+                        loop_start / width,
+                        (loop_start + loop_length) / width,
+                        frames.len(),
+                    )
+                } else {
+                    None
+                },
+                frames,
+                sample_rate: AMIGA_C_RATE,
+                root_note: (60 - i16::from(relative_note)).clamp(0, 127) as u8,
+                tune_cents: f32::from(finetune) * 100.0 / 128.0,
+                default_volume: volume,
+            });
+        }
+
+        let zones = keymap_zones(&keymap, 12, |sample| {
+            let sample = usize::from(sample);
+            (sample < sample_count).then_some(first_sample + sample)
+        });
+        instruments.push(ModuleInstrument { name, zones });
+    }
+
+    Ok(ModuleSong {
+        format: ModuleFormat::FastTracker,
+        title: text_at(bytes, 17, 20)?,
+        bpm: tracker_bpm(speed, tempo),
+        orders,
+        patterns,
+        instruments,
+        samples,
+    })
+}
+
+fn parse_it(bytes: &[u8]) -> Result<ModuleSong> {
+    let order_count = usize::from(u16_le_at(bytes, 0x20)?);
+    let instrument_count = usize::from(u16_le_at(bytes, 0x22)?);
+    let sample_count = usize::from(u16_le_at(bytes, 0x24)?);
+    let pattern_count = usize::from(u16_le_at(bytes, 0x26)?);
+    let instrument_mode = u16_le_at(bytes, 0x2C)? & 0x04 != 0;
+    let speed = u16::from(u8_at(bytes, 0x32)?);
+    let tempo = u16::from(u8_at(bytes, 0x33)?);
+
+    let orders: Vec<u16> = slice_at(bytes, 0xC0, order_count)?
+        .iter()
+        .take_while(|pattern| **pattern != 255)
+        .filter(|pattern| **pattern != 254)
+        .map(|pattern| u16::from(*pattern))
+        .collect();
+    let pointer_base = 0xC0 + order_count;
+    let pointer = |index: usize| u32_le_at(bytes, pointer_base + index * 4).map(|ptr| ptr as usize);
+
+    let mut samples = Vec::with_capacity(sample_count);
+    for index in 0..sample_count {
+        let header = pointer(instrument_count + index)?;
+        if slice_at(bytes, header, 4)? != b"IMPS" {
+            bail!("IT sample {index} has an invalid header");
+        }
+        let flags = u8_at(bytes, header + 0x12)?;
+        let name = text_at(bytes, header + 0x14, 26)?;
+        let mut sample = ModuleSample {
+            name,
+            sample_rate: u32_le_at(bytes, header + 0x3C)?.max(1),
+            root_note: 60,
+            default_volume: u8_at(bytes, header + 0x13)?.min(64),
+            ..ModuleSample::default()
+        };
+
+        let length = u32_le_at(bytes, header + 0x30)? as usize;
+        if flags & 0x01 == 0 || length == 0 {
+            samples.push(sample);
+            continue;
+        }
+        if flags & 0x08 != 0 {
+            warn!(
+                sample = index,
+                "compressed IT samples are not supported, skipping"
+            );
+            samples.push(sample);
+            continue;
+        }
+
+        let signed = u8_at(bytes, header + 0x2E)? & 0x01 != 0;
+        let data_pointer = u32_le_at(bytes, header + 0x48)? as usize;
+        sample.frames = if flags & 0x02 != 0 {
+            let available = bytes.len().saturating_sub(data_pointer) / 2;
+            slice_at(bytes, data_pointer, length.min(available) * 2)?
+                .chunks_exact(2)
+                .map(|pair| {
+                    let raw = u16::from_le_bytes([pair[0], pair[1]]);
+                    let value = if signed {
+                        raw as i16
+                    } else {
+                        raw.wrapping_sub(0x8000) as i16
+                    };
+                    f32::from(value) / 32_768.0
+                })
+                .collect()
+        } else {
+            let available = bytes.len().saturating_sub(data_pointer);
+            slice_at(bytes, data_pointer, length.min(available))?
+                .iter()
+                .map(|byte| {
+                    let value = if signed {
+                        *byte as i8
+                    } else {
+                        byte.wrapping_sub(0x80) as i8
+                    };
+                    f32::from(value) / 128.0
+                })
+                .collect()
+        };
+        if flags & 0x10 != 0 {
+            sample.loop_frames = loop_range(
+                u32_le_at(bytes, header + 0x34)? as usize,
+                u32_le_at(bytes, header + 0x38)? as usize,
+                sample.frames.len(),
+            );
+        }
+        samples.push(sample);
+    }
+
+    let instruments = if instrument_mode {
+        let mut instruments = Vec::with_capacity(instrument_count);
+        for index in 0..instrument_count {
+            let header = pointer(index)?;
+            if slice_at(bytes, header, 4)? != b"IMPI" {
+                bail!("IT instrument {index} has an invalid header");
+            }
+            let keyboard = slice_at(bytes, header + 0x40, 240)?;
+            let sample_map: Vec<u8> = keyboard.chunks_exact(2).map(|pair| pair[1]).collect();
+            instruments.push(ModuleInstrument {
+                name: text_at(bytes, header + 0x20, 26)?,
+                zones: keymap_zones(&sample_map, 0, |sample| {
+                    let sample = usize::from(sample);
+                    (sample > 0 && sample <= sample_count).then_some(sample - 1)
+                }),
+            });
+        }
+        instruments
+    } else {
+        single_sample_instruments(&samples)
+    };
+
+    let mut patterns = BTreeMap::new();
+    for index in 0..pattern_count {
+        let header = pointer(instrument_count + sample_count + index)?;
+        if header == 0 {
+            patterns.insert(
+                index as u16,
+                ModulePattern {
+                    rows: 64,
+                    cells: Vec::new(),
+                },
+            );
+            continue;
+        }
+        let length = usize::from(u16_le_at(bytes, header)?);
+        let rows = u32::from(u16_le_at(bytes, header + 2)?).clamp(1, 256);
+        let data = slice_at(bytes, header + 8, length)?;
+        patterns.insert(index as u16, decode_it_pattern(data, rows));
+    }
+
+    Ok(ModuleSong {
+        format: ModuleFormat::ImpulseTracker,
+        title: text_at(bytes, 4, 26)?,
+        bpm: tracker_bpm(speed, tempo),
+        orders,
+        patterns,
+        instruments,
+        samples,
+    })
+}
+
+fn decode_it_pattern(data: &[u8], rows: u32) -> ModulePattern {
+    let mut pattern = ModulePattern {
+        rows,
+        cells: Vec::new(),
+    };
+    let mut last_mask = [0_u8; 64];
+    let mut last_cell: Vec<ModuleCell> = vec![ModuleCell::default(); 64];
+    let mut cursor = 0;
+    let mut next = || {
+        let value = data.get(cursor).copied().unwrap_or_default();
+        cursor += 1;
+        value
+    };
+
+    for row in 0..rows {
+        loop {
+            let channel_variable = next();
+            if channel_variable == 0 {
+                break;
+            }
+            let channel = usize::from((channel_variable - 1) & 63);
+            if channel_variable & 0x80 != 0 {
+                last_mask[channel] = next();
+            }
+            let mask = last_mask[channel];
+            let previous = last_cell[channel].clone();
+            let mut cell = ModuleCell::default();
+
+            if mask & 0x01 != 0 {
+                cell.note = match next() {
+                    note @ 0..=119 => Some(ModuleNote::Note(note)),
+                    254 | 255 => Some(ModuleNote::Off),
+                    _ => None,
+                };
+                last_cell[channel].note = cell.note;
+            }
+            if mask & 0x02 != 0 {
+                let instrument = next();
+                cell.instrument = (instrument > 0).then_some(u16::from(instrument));
+                last_cell[channel].instrument = cell.instrument;
+            }
+            if mask & 0x04 != 0 {
+                let volume = next();
+                cell.volume = (volume <= 64).then_some(volume);
+                last_cell[channel].volume = cell.volume;
+            }
+            if mask & 0x08 != 0 {
+                let (command, param) = (next(), next());
+                cell.effect = (1..=26)
+                    .contains(&command)
+                    .then(|| (char::from(b'A' + command - 1).to_string(), u16::from(param)));
+                last_cell[channel].effect.clone_from(&cell.effect);
+            }
+            if mask & 0x10 != 0 {
+                cell.note = previous.note;
+            }
+            if mask & 0x20 != 0 {
+                cell.instrument = previous.instrument;
+            }
+            if mask & 0x40 != 0 {
+                cell.volume = previous.volume;
+            }
+            if mask & 0x80 != 0 {
+                cell.effect = previous.effect;
+            }
+            pattern.cells.push((row, channel, cell));
+        }
+    }
+    pattern
+}
+
+fn build_project(song: ModuleSong, sample_dir: &Path) -> Result<Project> {
+    fs::create_dir_all(sample_dir).with_context(|| {
+        format!(
+            "failed to create module sample directory: {}",
+            sample_dir.display()
+        )
+    })?;
+    let sample_paths = song
+        .samples
+        .iter()
+        .enumerate()
+        .map(|(index, sample)| write_sample_asset(sample_dir, index, sample))
+        .collect::<Result<Vec<_>>>()?;
+
+    let samplers: BTreeMap<u16, SamplerInstrument> = song
+        .instruments
+        .iter()
+        .enumerate()
+        .filter_map(|(index, instrument)| {
+            let zones: Vec<SamplerZone> = instrument
+                .zones
+                .iter()
+                .filter_map(|(sample_index, low_note, high_note)| {
+                    let path = sample_paths.get(*sample_index)?.as_ref()?;
+                    let sample = &song.samples[*sample_index];
+                    let rate = f64::from(sample.sample_rate.max(1));
+                    Some(SamplerZone {
+                        source_path: path.to_string_lossy().to_string(),
+                        root_note: sample.root_note,
+                        low_note: *low_note,
+                        high_note: *high_note,
+                        tune_cents: sample.tune_cents,
+                        one_shot: sample.loop_frames.is_none(),
+                        loop_enabled: sample.loop_frames.is_some(),
+                        loop_start_seconds: sample
+                            .loop_frames
+                            .map_or(0.0, |(start, _)| start as f64 / rate),
+                        loop_end_seconds: sample
+                            .loop_frames
+                            .map_or(0.0, |(_, end)| end as f64 / rate),
+                        ..SamplerZone::default()
+                    })
+                })
+                .collect();
+            if zones.is_empty() {
+                return None;
+            }
+            let number = index as u16 + 1;
+            let name = if instrument.name.trim().is_empty() {
+                format!("{number:02} Instrument")
+            } else {
+                format!("{number:02} {}", instrument.name.trim())
+            };
+            Some((
+                number,
+                SamplerInstrument {
+                    name,
+                    zones,
+                    ..SamplerInstrument::default()
+                },
+            ))
+        })
+        .collect();
+
+    // Patterns are walked in order-table sequence so running instruments carry over
+    // the way a tracker would play them.
+    let mut seen = BTreeSet::new();
+    let play_order: Vec<u16> = song
+        .orders
+        .iter()
+        .copied()
+        .filter(|pattern| song.patterns.contains_key(pattern) && seen.insert(*pattern))
+        .collect();
+    let mut running_instrument: BTreeMap<usize, u16> = BTreeMap::new();
+    let mut lanes: BTreeMap<(usize, u16), BTreeMap<u16, PatternRows>> = BTreeMap::new();
+    for pattern_index in &play_order {
+        let pattern = &song.patterns[pattern_index];
+        let mut by_channel: BTreeMap<usize, Vec<(u32, &ModuleCell)>> = BTreeMap::new();
+        for (row, channel, cell) in &pattern.cells {
+            by_channel.entry(*channel).or_default().push((*row, cell));
+        }
+
+        for (channel, mut cells) in by_channel {
+            cells.sort_by_key(|(row, _)| *row);
+            let event_rows: Vec<u32> = cells
+                .iter()
+                .filter(|(_, cell)| cell.note.is_some())
+                .map(|(row, _)| *row)
+                .collect();
+            for (row, cell) in cells {
+                if let Some(instrument) = cell.instrument {
+                    running_instrument.insert(channel, instrument);
+                }
+                let Some(instrument) = running_instrument.get(&channel).copied() else {
+                    continue;
+                };
+                let Some(sampler) = samplers.get(&instrument) else {
+                    continue;
+                };
+                let (effect, effect_value) = cell
+                    .effect
+                    .clone()
+                    .map_or((None, None), |(name, value)| (Some(name), Some(value)));
+
+                let tracker_row = match cell.note {
+                    Some(ModuleNote::Note(pitch)) => {
+                        let default_volume = sampler
+                            .zones_for(pitch, 127)
+                            .next()
+                            .and_then(|zone| {
+                                sample_paths.iter().position(|path| {
+                                    path.as_ref().is_some_and(|path| {
+                                        path.to_string_lossy() == zone.source_path
+                                    })
+                                })
+                            })
+                            .map_or(64, |sample| song.samples[sample].default_volume);
+                        let volume = cell.volume.unwrap_or(default_volume).min(64);
+                        TrackerRow {
+                            row,
+                            note: Some(pitch.min(127)),
+                            velocity: ((u16::from(volume) * 127 + 32) / 64).max(1) as u8,
+                            gate: true,
+                            effect,
+                            effect_value,
+                        }
+                    }
+                    Some(ModuleNote::Off) => TrackerRow {
+                        row,
+                        note: None,
+                        velocity: 0,
+                        gate: false,
+                        effect: Some("OFF".to_string()),
+                        effect_value: None,
+                    },
+                    None if effect.is_some() => TrackerRow {
+                        row,
+                        note: None,
+                        velocity: 0,
+                        gate: false,
+                        effect,
+                        effect_value,
+                    },
+                    None => continue,
+                };
+
+                let lane = lanes
+                    .entry((channel, instrument))
+                    .or_default()
+                    .entry(*pattern_index)
+                    .or_default();
+                if tracker_row.gate {
+                    let next_event = event_rows
+                        .iter()
+                        .copied()
+                        .find(|candidate| *candidate > row)
+                        .unwrap_or(pattern.rows);
+                    lane.sustain
+                        .insert(row, next_event.saturating_sub(row).max(1));
+                }
+                lane.rows.push(tracker_row);
+            }
+        }
+    }
+
+    let title = if song.title.trim().is_empty() {
+        "Imported Module".to_string()
+    } else {
+        song.title.trim().to_string()
+    };
+    let mut project = Project::new(title, song.bpm.clamp(20.0, 999.0), DEFAULT_SAMPLE_RATE);
+    let lines_per_beat = DEFAULT_TRACKER_LINES_PER_BEAT;
+    let ticks_per_row = tracker_rows_to_ticks(1, lines_per_beat, project.ppq).max(1);
+
+    let mut lane_tracks = Vec::new();
+    let mut content_ids: BTreeMap<((usize, u16), u16), Uuid> = BTreeMap::new();
+    for (lane_index, (key, patterns)) in lanes.into_iter().enumerate() {
+        let (channel, instrument) = key;
+        let sampler = &samplers[&instrument];
+        let mut track = Track::new(
+            format!("Ch {:02} {}", channel + 1, sampler.name),
+            TRACK_COLORS[lane_index % TRACK_COLORS.len()],
+            TrackKind::Midi,
+        );
+        track.samplers.push(sampler.clone());
+
+        for (pattern_index, lane) in patterns {
+            let mut pattern = PatternClip {
+                source_chip: song.format.source_chip().to_string(),
+                instrument: Some(sampler.name.clone()),
+                rows: lane.rows,
+                lines_per_beat,
+                ..PatternClip::default()
+            };
+            normalize_pattern_clip(&mut pattern, project.ppq)?;
+            // Tracker notes ring until the next event in their channel.
+            for note in &mut pattern.notes {
+                let row = (note.start_tick / ticks_per_row) as u32;
+                if let Some(rows) = lane.sustain.get(&row) {
+                    note.length_ticks = u64::from(*rows) * ticks_per_row;
+                }
+            }
+
+            let id = Uuid::new_v4();
+            project.clip_pool.push(ClipPoolEntry {
+                id,
+                name: format!("{} {pattern_index:02X}", track.name),
+                payload: ClipPayload::Pattern(pattern),
+            });
+            content_ids.insert((key, pattern_index), id);
+        }
+        lane_tracks.push((key, track.id));
+        project.tracks.push(track);
+    }
+
+    let pattern_rows = |pattern: u16| song.patterns.get(&pattern).map_or(64, |p| p.rows);
+    let uniform_rows = song
+        .orders
+        .iter()
+        .map(|pattern| pattern_rows(*pattern))
+        .collect::<BTreeSet<_>>();
+    if uniform_rows.len() <= 1 {
+        let rows_per_pattern = uniform_rows.into_iter().next().unwrap_or(64);
+        let rows = song
+            .orders
+            .iter()
+            .map(|pattern| OrderRow {
+                patterns: lane_tracks
+                    .iter()
+                    .map(|(key, _)| content_ids.get(&(*key, *pattern)).copied())
+                    .collect(),
+            })
+            .collect::<Vec<_>>();
+        let loop_row = song
+            .orders
+            .last()
+            .and_then(|pattern| song.patterns.get(pattern))
+            .and_then(|pattern| {
+                pattern
+                    .cells
+                    .iter()
+                    .find_map(|(_, _, cell)| match &cell.effect {
+                        Some((name, target)) if name == "B" => Some(usize::from(*target)),
+                        _ => None,
+                    })
+            })
+            .filter(|target| *target < song.orders.len());
+
+        let mut engine = Engine::new(project);
+        engine.set_order_list(OrderList {
+            row_length_ticks: u64::from(rows_per_pattern) * ticks_per_row,
+            channels: lane_tracks.iter().map(|(_, track_id)| *track_id).collect(),
+            rows,
+            loop_row,
+            ..OrderList::default()
+        })?;
+        return Ok(engine.project().clone());
+    }
+
+    // Mixed pattern lengths cannot share an order list row length, so placements
+    // are laid out back to back instead.
+    debug!("module patterns differ in length, placing clips without an order list");
+    let mut start_tick = 0_u64;
+    for pattern in &song.orders {
+        let length_ticks = u64::from(pattern_rows(*pattern)) * ticks_per_row;
+        for (key, track_id) in &lane_tracks {
+            let Some(content_id) = content_ids.get(&(*key, *pattern)).copied() else {
+                continue;
+            };
+            let name = project
+                .pool_entry(content_id)
+                .map(|entry| entry.name.clone())
+                .unwrap_or_default();
+            if let Some(track) = project
+                .tracks
+                .iter_mut()
+                .find(|track| track.id == *track_id)
+            {
+                track.clips.push(Clip::new(
+                    name,
+                    start_tick,
+                    length_ticks,
+                    ClipPayload::Linked(LinkedClip { content_id }),
+                ));
+            }
+        }
+        start_tick += length_ticks;
+    }
+    Ok(project)
+}
+
+fn write_sample_asset(
+    sample_dir: &Path,
+    index: usize,
+    sample: &ModuleSample,
+) -> Result<Option<PathBuf>> {
+    if sample.frames.is_empty() {
+        return Ok(None);
+    }
+
+    let stem: String = sample
+        .name
+        .trim()
+        .chars()
+        .map(|ch| if ch.is_ascii_alphanumeric() { ch } else { '_' })
+        .collect();
+    let path = sample_dir.join(format!("{:02}_{}.wav", index + 1, stem.trim_matches('_')));
+    let spec = hound::WavSpec {
+        channels: 1,
+        sample_rate: sample.sample_rate.max(1),
+        bits_per_sample: 16,
+        sample_format: hound::SampleFormat::Int,
+    };
+    let mut writer = hound::WavWriter::create(&path, spec)
+        .with_context(|| format!("failed to create sample asset: {}", path.display()))?;
+    for frame in &sample.frames {
+        let quantized = (frame.clamp(-1.0, 1.0) * f32::from(i16::MAX)).round() as i16;
+        writer
+            .write_sample(quantized)
+            .context("failed to write module sample frame")?;
+    }
+    writer
+        .finalize()
+        .context("failed to finalize module sample asset")?;
+    debug!(path = %path.display(), frames = sample.frames.len(), "module sample written");
+    Ok(Some(path))
+}
+
+fn single_sample_instruments(samples: &[ModuleSample]) -> Vec<ModuleInstrument> {
+    samples
+        .iter()
+        .enumerate()
+        .map(|(index, sample)| ModuleInstrument {
+            name: sample.name.clone(),
+            zones: vec![(index, 0, 127)],
+        })
+        .collect()
+}
+
+/// Groups a per-note sample map into contiguous key ranges. `first_note` is the
+/// MIDI pitch of the first map entry.
+fn keymap_zones(
+    keymap: &[u8],
+    first_note: u8,
+    resolve: impl Fn(u8) -> Option<usize>,
+) -> Vec<(usize, u8, u8)> {
+    let mut zones: Vec<(usize, u8, u8)> = Vec::new();
+    for (offset, entry) in keymap.iter().enumerate() {
+        let note = (usize::from(first_note) + offset).min(127) as u8;
+        let Some(sample) = resolve(*entry) else {
+            continue;
+        };
+        match zones.last_mut() {
+            Some((last_sample, _, high)) if *last_sample == sample && *high + 1 == note => {
+                *high = note;
+            }
+            _ => zones.push((sample, note, note)),
+        }
+    }
+    zones
+}
+
+fn mod_channel_count(signature: &[u8]) -> Option<usize> {
+    match signature {
+        b"M.K." | b"M!K!" | b"FLT4" | b"4CHN" => Some(4),
+        b"6CHN" => Some(6),
+        b"8CHN" | b"CD81" | b"OKTA" | b"FLT8" => Some(8),
+        [tens @ b'1'..=b'3', ones @ b'0'..=b'9', b'C', b'H'] => {
+            Some(usize::from(tens - b'0') * 10 + usize::from(ones - b'0'))
+        }
+        [count @ b'1'..=b'9', b'C', b'H', b'N'] => Some(usize::from(count - b'0')),
+        _ => None,
+    }
+}
+
+/// Maps an Amiga period to MIDI, with ProTracker C-2 (period 428) at middle C.
+fn mod_period_to_note(period: u16) -> Option<u8> {
+    if period == 0 {
+        return None;
+    }
+    let semitones_above_c1 = 12.0 * (856.0 / f64::from(period)).log2();
+    Some((48.0 + semitones_above_c1.round()).clamp(0.0, 127.0) as u8)
+}
+
+fn hex_effect(effect: u8, param: u8) -> Option<(String, u16)> {
+    if effect == 0 && param == 0 {
+        return None;
+    }
+    let name = match effect {
+        0..=15 => format!("{effect:X}"),
+        16..=35 => char::from(b'G' + effect - 16).to_string(),
+        _ => return None,
+    };
+    Some((name, u16::from(param)))
+}
+
+/// Reads speed/tempo from `Fxx` effects on the first row of the first pattern.
+fn initial_speed_tempo(
+    patterns: &BTreeMap<u16, ModulePattern>,
+    first_pattern: Option<u16>,
+    mut speed: u16,
+    mut tempo: u16,
+) -> (u16, u16) {
+    let cells = first_pattern
+        .and_then(|pattern| patterns.get(&pattern))
+        .map(|pattern| pattern.cells.as_slice())
+        .unwrap_or_default();
+    for (_, _, cell) in cells.iter().filter(|(row, _, _)| *row == 0) {
+        if let Some((name, value)) = &cell.effect
+            && name == "F"
+            && *value > 0
+        {
+            if *value < 32 {
+                speed = *value;
+            } else {
+                tempo = *value;
+            }
+        }
+    }
+    (speed, tempo)
+}
+
+/// Trackers play four rows per beat at speed 6, so BPM scales with 6 / speed.
+fn tracker_bpm(speed: u16, tempo: u16) -> f64 {
+    f64::from(tempo.max(1)) * 6.0 / f64::from(speed.clamp(1, 31))
+}
+
+fn loop_range(start: usize, end: usize, frame_count: usize) -> Option<(usize, usize)> {
+    let end = end.min(frame_count);
+    (end > start + 2).then_some((start, end))
+}
+
+fn delta_decode_8(data: &[u8]) -> Vec<f32> {
+    let mut value = 0_i8;
+    data.iter()
+        .map(|delta| {
+            value = value.wrapping_add(*delta as i8);
+            f32::from(value) / 128.0
+        })
+        .collect()
+}
+
+fn delta_decode_16(data: &[u8]) -> Vec<f32> {
+    let mut value = 0_i16;
+    data.chunks_exact(2)
+        .map(|pair| {
+            value = value.wrapping_add(i16::from_le_bytes([pair[0], pair[1]]));
+            f32::from(value) / 32_768.0
+        })
+        .collect()
+}
+
+fn slice_at(bytes: &[u8], offset: usize, length: usize) -> Result<&[u8]> {
+    bytes
+        .get(offset..offset.saturating_add(length))
+        .with_context(|| format!("module truncated at offset {offset:#x} (+{length})"))
+}
+
+fn u8_at(bytes: &[u8], offset: usize) -> Result<u8> {
+    Ok(slice_at(bytes, offset, 1)?[0])
+}
+
+fn u16_be_at(bytes: &[u8], offset: usize) -> Result<u16> {
+    let slice = slice_at(bytes, offset, 2)?;
+    Ok(u16::from_be_bytes([slice[0], slice[1]]))
+}
+
+fn u16_le_at(bytes: &[u8], offset: usize) -> Result<u16> {
+    let slice = slice_at(bytes, offset, 2)?;
+    Ok(u16::from_le_bytes([slice[0], slice[1]]))
+}
+
+fn u32_le_at(bytes: &[u8], offset: usize) -> Result<u32> {
+    let slice = slice_at(bytes, offset, 4)?;
+    Ok(u32::from_le_bytes([slice[0], slice[1], slice[2], slice[3]]))
+}
+
+fn text_at(bytes: &[u8], offset: usize, length: usize) -> Result<String> {
+    let raw = slice_at(bytes, offset, length)?;
+    let end = raw.iter().position(|byte| *byte == 0).unwrap_or(raw.len());
+    Ok(String::from_utf8_lossy(&raw[..end]).trim().to_string())
+}
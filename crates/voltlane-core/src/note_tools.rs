@@ -0,0 +1,261 @@
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    model::MidiNote,
+    theory::{ChordKind, Key, diatonic_chord},
+};
+
+/// Random spread applied by [`humanize_notes`]. The same seed and notes always
+/// give the same result.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct HumanizeOptions {
+    pub seed: u64,
+    /// Largest start shift either way.
+    pub timing_ticks: u64,
+    /// Largest velocity change either way.
+    pub velocity: u8,
+    /// Largest length change either way, as a percentage of each note's length.
+    pub length_percent: f32,
+}
+
+#[derive(Debug, Clone, Copy, Default, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum StrumDirection {
+    /// Lowest note first, like a downstroke on a guitar.
+    #[default]
+    Up,
+    Down,
+}
+
+/// Velocity ramp across notes in time order. `curve` bends the ramp: `1.0` is
+/// linear, larger values hold back and rise late, smaller values rise early.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct VelocityShape {
+    pub from: u8,
+    pub to: u8,
+    pub curve: f32,
+}
+
+pub fn humanize_notes(notes: &mut [MidiNote], options: &HumanizeOptions) {
+    let length_amount = f64::from(options.length_percent.clamp(0.0, 100.0)) / 100.0;
+    for (index, note) in notes.iter_mut().enumerate() {
+        let timing = roll(options.seed, index, 0) * options.timing_ticks as f64;
+        let velocity = roll(options.seed, index, 1) * f64::from(options.velocity);
+        let length = 1.0 + roll(options.seed, index, 2) * length_amount;
+
+        note.start_tick = (note.start_tick as f64 + timing).round().max(0.0) as u64;
+        note.velocity = (f64::from(note.velocity) + velocity)
+            .round()
+            .clamp(1.0, 127.0) as u8;
+        note.length_ticks = (note.length_ticks as f64 * length).round().max(1.0) as u64;
+    }
+}
+
+/// Staggers notes that start together so the chord rolls over `spread_ticks`.
+/// Ends stay put, so strummed notes still release with the chord.
+pub fn strum_notes(notes: &mut [MidiNote], direction: StrumDirection, spread_ticks: u64) {
+    let mut order: Vec<(u64, u8, usize)> = notes
+        .iter()
+        .enumerate()
+        .map(|(index, note)| (note.start_tick, note.pitch, index))
+        .collect();
+    order.sort_unstable();
+    for chord in order.chunk_by(|left, right| left.0 == right.0) {
+        if chord.len() < 2 {
+            continue;
+        }
+        let last = chord.len() as u64 - 1;
+        for (position, (_, _, index)) in chord.iter().enumerate() {
+            let step = match direction {
+                StrumDirection::Up => position as u64,
+                StrumDirection::Down => last - position as u64,
+            };
+            let note = &mut notes[*index];
+            let offset = (spread_ticks * step / last).min(note.length_ticks.saturating_sub(1));
+            note.start_tick += offset;
+            note.length_ticks -= offset;
+        }
+    }
+}
+
+pub fn shape_note_velocities(notes: &mut [MidiNote], shape: &VelocityShape) {
+    let mut order: Vec<usize> = (0..notes.len()).collect();
+    order.sort_by_key(|index| (notes[*index].start_tick, notes[*index].pitch));
+    let (Some(first), Some(last)) = (order.first(), order.last()) else {
+        return;
+    };
+    let first_tick = notes[*first].start_tick;
+    let span = notes[*last].start_tick.saturating_sub(first_tick);
+    let (from, to) = (f64::from(shape.from), f64::from(shape.to));
+    for (position, index) in order.iter().enumerate() {
+        // Chords share a position in time; a single column of notes is spread
+        // by order instead.
+        let progress = if span > 0 {
+            (notes[*index].start_tick - first_tick) as f64 / span as f64
+        } else if order.len() > 1 {
+            position as f64 / (order.len() - 1) as f64
+        } else {
+            0.0
+        };
+        let bent = progress.powf(f64::from(shape.curve));
+        notes[*index].velocity = (from + (to - from) * bent).round().clamp(1.0, 127.0) as u8;
+    }
+}
+
+/// Extends each note to the start of the next later note. Notes with nothing
+/// after them keep their length.
+pub fn legato_notes(notes: &mut [MidiNote]) {
+    let mut starts: Vec<u64> = notes.iter().map(|note| note.start_tick).collect();
+    starts.sort_unstable();
+    starts.dedup();
+    for note in notes.iter_mut() {
+        let next = starts.partition_point(|start| *start <= note.start_tick);
+        if let Some(next_start) = starts.get(next) {
+            note.length_ticks = next_start - note.start_tick;
+        }
+    }
+}
+
+pub fn set_note_lengths(notes: &mut [MidiNote], length_ticks: u64) {
+    for note in notes.iter_mut() {
+        note.length_ticks = length_ticks.max(1);
+    }
+}
+
+#[derive(Debug, Clone, Copy, Default, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum ArpeggioMode {
+    #[default]
+    Up,
+    Down,
+    UpDown,
+    /// Seeded, so the same options always pick the same notes.
+    Random,
+    /// The order the held notes were played in.
+    AsPlayed,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct ArpeggiatorOptions {
+    pub mode: ArpeggioMode,
+    /// Ticks between arpeggio steps.
+    pub rate_ticks: u64,
+    /// Octaves the held notes are repeated over, starting at the played octave.
+    pub octaves: u8,
+    /// Step length as a percentage of the rate.
+    pub gate_percent: f32,
+    pub seed: u64,
+}
+
+/// Replays held notes one at a time on a `rate_ticks` grid starting at the first
+/// note. The pattern restarts whenever the set of held notes changes, and each
+/// step takes its velocity and channel from the note it plays.
+#[must_use]
+pub fn arpeggiate_notes(notes: &[MidiNote], options: &ArpeggiatorOptions) -> Vec<MidiNote> {
+    let (Some(first), Some(last)) = (
+        notes.iter().map(|note| note.start_tick).min(),
+        notes.iter().map(MidiNote::end_tick).max(),
+    ) else {
+        return Vec::new();
+    };
+    let rate = options.rate_ticks.max(1);
+    let octaves = options.octaves.max(1);
+    let length_ticks = (rate as f64 * f64::from(options.gate_percent.max(0.0)) / 100.0)
+        .round()
+        .max(1.0) as u64;
+
+    let mut arpeggio = Vec::new();
+    let mut previous_held: Vec<usize> = Vec::new();
+    let mut counter = 0_usize;
+    for (step, tick) in (first..last).step_by(rate as usize).enumerate() {
+        let held: Vec<usize> = (0..notes.len())
+            .filter(|index| notes[*index].start_tick <= tick && tick < notes[*index].end_tick())
+            .collect();
+        if held != previous_held {
+            counter = 0;
+            previous_held.clone_from(&held);
+        }
+        if held.is_empty() {
+            continue;
+        }
+
+        let mut played: Vec<&MidiNote> = held.iter().map(|index| &notes[*index]).collect();
+        if options.mode == ArpeggioMode::AsPlayed {
+            played.sort_by_key(|note| (note.start_tick, note.pitch));
+        } else {
+            played.sort_by_key(|note| note.pitch);
+        }
+        played.dedup_by_key(|note| note.pitch);
+        let pool: Vec<(u8, &MidiNote)> = (0..octaves)
+            .flat_map(|octave| {
+                played.iter().filter_map(move |note| {
+                    let pitch = u16::from(note.pitch) + u16::from(octave) * 12;
+                    (pitch <= 127).then_some((pitch as u8, *note))
+                })
+            })
+            .collect();
+        if pool.is_empty() {
+            continue;
+        }
+
+        let size = pool.len();
+        let index = match options.mode {
+            ArpeggioMode::Up | ArpeggioMode::AsPlayed => counter % size,
+            ArpeggioMode::Down => size - 1 - counter % size,
+            ArpeggioMode::UpDown if size == 1 => 0,
+            ArpeggioMode::UpDown => {
+                let position = counter % (2 * size - 2);
+                if position < size {
+                    position
+                } else {
+                    2 * size - 2 - position
+                }
+            }
+            ArpeggioMode::Random => {
+                let unit = (roll(options.seed, step, 3) + 1.0) / 2.0;
+                ((unit * size as f64) as usize).min(size - 1)
+            }
+        };
+        counter += 1;
+
+        let (pitch, source) = pool[index];
+        arpeggio.push(MidiNote {
+            pitch,
+            start_tick: tick,
+            length_ticks,
+            ..source.clone()
+        });
+    }
+    arpeggio
+}
+
+/// Turns each note into a diatonic chord in `key` rooted on it. Out-of-key notes
+/// are lowered onto the scale first.
+#[must_use]
+pub fn chord_notes(notes: &[MidiNote], key: &Key, kind: ChordKind) -> Vec<MidiNote> {
+    let mut chords: Vec<MidiNote> = notes
+        .iter()
+        .flat_map(|note| {
+            diatonic_chord(key, note.pitch, kind)
+                .into_iter()
+                .map(|pitch| MidiNote {
+                    pitch,
+                    ..note.clone()
+                })
+        })
+        .collect();
+    chords.sort_by_key(|note| (note.start_tick, note.pitch));
+    chords.dedup_by_key(|note| (note.start_tick, note.pitch));
+    chords
+}
+
+/// Deterministic value in `-1.0..1.0` for one note and one property.
+pub(crate) fn roll(seed: u64, index: usize, salt: u64) -> f64 {
+    let mut value = seed
+        ^ (index as u64).wrapping_mul(0x9E37_79B9_7F4A_7C15)
+        ^ salt.wrapping_mul(0xC2B2_AE3D_27D4_EB4F);
+    value = (value ^ (value >> 30)).wrapping_mul(0xBF58_476D_1CE4_E5B9);
+    value = (value ^ (value >> 27)).wrapping_mul(0x94D0_49BB_1331_11EB);
+    value ^= value >> 31;
+    (value >> 11) as f64 / (1_u64 << 52) as f64 - 1.0
+}
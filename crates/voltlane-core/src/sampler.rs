@@ -0,0 +1,111 @@
+use crate::{
+    assets::DecodedAudio,
+    export::sample_linear,
+    model::{SamplerInstrument, SamplerZone, equal_tempered_hz},
+    synth::{NoteExpression, adsr_level, db_to_gain},
+};
+
+/// A single note-on/note-off pair to be played back through a [`SamplerZone`].
+#[derive(Debug, Clone, PartialEq)]
+pub struct SamplerNote {
+    pub pitch: u8,
+    pub velocity: u8,
+    /// Target pitch under the project tuning; zones play at their root note's
+    /// equal-tempered pitch.
+    pub frequency_hz: f64,
+    pub start_frame: usize,
+    pub gate_frames: usize,
+    pub expression: NoteExpression,
+}
+
+/// Plays one zone of a sampler for a note, resampling from the zone root key to
+/// the note frequency.
+/// Returns the number of frames written.
+pub fn render_sampler_note(
+    sampler: &SamplerInstrument,
+    zone: &SamplerZone,
+    decoded: &DecodedAudio,
+    note: &SamplerNote,
+    sample_rate: u32,
+    buffer: &mut [f32],
+) -> usize {
+    let samples = &decoded.samples;
+    if samples.is_empty() || decoded.sample_rate == 0 || sample_rate == 0 {
+        return 0;
+    }
+
+    let output_rate = f64::from(sample_rate);
+    let source_rate = f64::from(decoded.sample_rate);
+    let step = note.frequency_hz / equal_tempered_hz(zone.root_note)
+        * 2_f64.powf(f64::from(zone.tune_cents) / 1_200.0)
+        * source_rate
+        / output_rate;
+    let source_len = samples.len() as f64;
+    let region_start = (zone.sample_start_seconds.max(0.0) * source_rate).min(source_len);
+    let region_end = if zone.sample_end_seconds > zone.sample_start_seconds {
+        (zone.sample_end_seconds * source_rate).min(source_len)
+    } else {
+        source_len
+    };
+
+    let loop_range = if zone.loop_enabled && !zone.one_shot {
+        let loop_start = (zone.loop_start_seconds.max(0.0) * source_rate).min(source_len);
+        let loop_end = (zone.loop_end_seconds.max(0.0) * source_rate).min(source_len);
+        (loop_end - loop_start > 1.0).then_some((loop_start, loop_end))
+    } else {
+        None
+    };
+
+    let gate_frames = note.gate_frames.max(1);
+    let mut amp_envelope = sampler.amp_envelope.clone();
+    amp_envelope.release_seconds = note
+        .expression
+        .release_seconds(amp_envelope.release_seconds);
+    let release_frames =
+        (f64::from(amp_envelope.release_seconds.max(0.0)) * output_rate).round() as usize;
+    let voice_frames = if zone.one_shot {
+        ((region_end - region_start) / step).ceil() as usize
+    } else {
+        gate_frames.saturating_add(release_frames)
+    };
+    let start = note.start_frame.min(buffer.len());
+    let end = note
+        .start_frame
+        .saturating_add(voice_frames)
+        .min(buffer.len());
+    if end <= start {
+        return 0;
+    }
+
+    let gate_seconds = if zone.one_shot {
+        f64::INFINITY
+    } else {
+        gate_frames as f64 / output_rate
+    };
+    let amplitude = (f32::from(note.velocity.min(127)) / 127.0)
+        * db_to_gain(sampler.gain_db)
+        * db_to_gain(zone.gain_db);
+
+    let mut position = region_start;
+    let mut written = 0_usize;
+    for (index, frame) in buffer[start..end].iter_mut().enumerate() {
+        if position >= region_end {
+            break;
+        }
+        let envelope = adsr_level(&amp_envelope, index as f64 / output_rate, gate_seconds);
+        *frame += sample_linear(samples, position)
+            * amplitude
+            * note.expression.gain(index)
+            * envelope as f32;
+        written += 1;
+
+        position += step * note.expression.pitch_ratio(index);
+        if let Some((loop_start, loop_end)) = loop_range {
+            while position >= loop_end {
+                position -= loop_end - loop_start;
+            }
+        }
+    }
+
+    written
+}
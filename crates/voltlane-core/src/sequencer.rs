@@ -0,0 +1,125 @@
+use crate::{
+    model::{MidiNote, StepSequencerClip},
+    time::tracker_rows_to_ticks,
+};
+
+/// General MIDI percussion channel used when steps are flattened to notes.
+pub const STEP_DRUM_CHANNEL: u8 = 9;
+
+const FLAM_VELOCITY_SCALE: f32 = 0.6;
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct StepNote {
+    pub row_index: usize,
+    pub note: MidiNote,
+}
+
+/// Flattens a step clip into clip-relative notes, looping the pattern across
+/// `length_ticks` and resolving probability, nudge, ratchet and flam.
+#[must_use]
+pub fn step_notes(clip: &StepSequencerClip, length_ticks: u64, ppq: u16) -> Vec<StepNote> {
+    let steps_per_beat = clip.steps_per_beat.max(1);
+    let step_count = u64::from(clip.step_count.max(1));
+    let step_ticks = tracker_rows_to_ticks(1, steps_per_beat, ppq).max(1);
+    let pattern_ticks = step_ticks.saturating_mul(step_count).max(1);
+    let repetitions = length_ticks.max(1).div_ceil(pattern_ticks);
+    let max_nudge = (step_ticks / 2) as i64;
+    let flam_offset = (step_ticks / 4).min(u64::from(ppq / 32)).max(1);
+
+    let mut notes = Vec::new();
+    for repetition in 0..repetitions {
+        for (row_index, row) in clip.rows.iter().enumerate() {
+            if row.mute {
+                continue;
+            }
+            for (step_index, step) in row.steps.iter().enumerate().take(step_count as usize) {
+                if !step.active || step.velocity == 0 {
+                    continue;
+                }
+                if step.probability < 1.0
+                    && step_roll(clip.seed, row_index, step_index, repetition)
+                        >= f64::from(step.probability.max(0.0))
+                {
+                    continue;
+                }
+
+                let global_step = repetition * step_count + step_index as u64;
+                let step_start = tracker_rows_to_ticks(global_step as u32, steps_per_beat, ppq);
+                if step_start >= length_ticks {
+                    continue;
+                }
+                let nudge = i64::from(step.nudge_ticks).clamp(-max_nudge, max_nudge);
+                let start = step_start.saturating_add_signed(nudge);
+
+                let velocity = step.velocity.min(127);
+                if step.flam {
+                    let grace_velocity =
+                        ((f32::from(velocity) * FLAM_VELOCITY_SCALE).round() as u8).max(1);
+                    let grace_start = start.saturating_sub(flam_offset);
+                    push_step_note(
+                        &mut notes,
+                        row_index,
+                        row.pitch,
+                        grace_velocity,
+                        grace_start,
+                        (start - grace_start).max(1),
+                        length_ticks,
+                    );
+                }
+
+                let ratchet = u64::from(step.ratchet.max(1));
+                let hit_ticks = (step_ticks / ratchet).max(1);
+                for hit in 0..ratchet {
+                    push_step_note(
+                        &mut notes,
+                        row_index,
+                        row.pitch,
+                        velocity,
+                        start.saturating_add(hit * hit_ticks),
+                        hit_ticks,
+                        length_ticks,
+                    );
+                }
+            }
+        }
+    }
+
+    notes.sort_by_key(|step_note| (step_note.note.start_tick, step_note.row_index));
+    notes
+}
+
+fn push_step_note(
+    notes: &mut Vec<StepNote>,
+    row_index: usize,
+    pitch: u8,
+    velocity: u8,
+    start_tick: u64,
+    length_ticks: u64,
+    clip_length_ticks: u64,
+) {
+    if start_tick >= clip_length_ticks {
+        return;
+    }
+    notes.push(StepNote {
+        row_index,
+        note: MidiNote {
+            pitch: pitch.min(127),
+            velocity,
+            start_tick,
+            length_ticks: length_ticks.min(clip_length_ticks - start_tick).max(1),
+            channel: STEP_DRUM_CHANNEL,
+            release_velocity: None,
+        },
+    });
+}
+
+fn step_roll(seed: u64, row_index: usize, step_index: usize, repetition: u64) -> f64 {
+    let mut value = seed
+        ^ (row_index as u64).wrapping_mul(0x9E37_79B9_7F4A_7C15)
+        ^ (step_index as u64).wrapping_mul(0xC2B2_AE3D_27D4_EB4F)
+        ^ repetition.wrapping_mul(0x1656_67B1_9E37_79F9);
+    value = (value ^ (value >> 30)).wrapping_mul(0xBF58_476D_1CE4_E5B9);
+    value = (value ^ (value >> 27)).wrapping_mul(0x94D0_49BB_1331_11EB);
+    value ^= value >> 31;
+    (value >> 11) as f64 / (1_u64 << 53) as f64
+}
@@ -0,0 +1,102 @@
+use std::f32::consts::TAU;
+
+use crate::export::sample_linear;
+
+/// Grain length of the WSOLA stretch. Long enough to hold a couple of periods
+/// of a low voice, short enough to keep transients tight.
+const GRAIN_SECONDS: f64 = 0.04;
+const MIN_GRAIN_FRAMES: usize = 64;
+/// Coarse search stride, in frames, before the neighbourhood of the best
+/// coarse match is searched frame by frame.
+const SEARCH_STRIDE: usize = 4;
+
+/// Stretches `samples` to `output_len` frames without changing their pitch,
+/// using waveform-similarity overlap-add (WSOLA). Each Hann-windowed grain is
+/// taken from near its nominal position in the input, at the offset whose
+/// waveform best continues the previous grain, so overlapping grains add up in
+/// phase. Inputs shorter than two grains are resampled instead.
+#[must_use]
+pub fn time_stretch(samples: &[f32], output_len: usize, sample_rate: u32) -> Vec<f32> {
+    let grain = ((f64::from(sample_rate) * GRAIN_SECONDS) as usize).max(MIN_GRAIN_FRAMES) & !1;
+    if output_len == 0 || samples.len() < grain * 2 {
+        return resample(samples, output_len);
+    }
+    let hop = grain / 2;
+    let tolerance = hop / 2;
+    let window: Vec<f32> = (0..grain)
+        .map(|index| 0.5 - 0.5 * (TAU * index as f32 / grain as f32).cos())
+        .collect();
+    let input_per_output = samples.len() as f64 / output_len as f64;
+    let last_start = samples.len() - grain;
+
+    let mut output = vec![0.0_f32; output_len + grain];
+    let mut weights = vec![0.0_f32; output_len + grain];
+    let mut previous: Option<usize> = None;
+    for output_start in (0..output_len).step_by(hop) {
+        let nominal = ((output_start as f64 * input_per_output).round() as usize).min(last_start);
+        let start = previous.map_or(nominal, |previous| {
+            let natural = (previous + hop).min(last_start);
+            let low = nominal.saturating_sub(tolerance);
+            let high = (nominal + tolerance).min(last_start);
+            best_match(samples, natural, low, high, hop)
+        });
+
+        for (index, gain) in window.iter().enumerate() {
+            output[output_start + index] += samples[start + index] * gain;
+            weights[output_start + index] += gain;
+        }
+        previous = Some(start);
+    }
+
+    output.truncate(output_len);
+    for (sample, weight) in output.iter_mut().zip(weights) {
+        if weight > 0.0 {
+            *sample /= weight;
+        }
+    }
+    output
+}
+
+/// Start in `low..=high` whose next `length` frames best correlate with the
+/// frames at `target`.
+fn best_match(samples: &[f32], target: usize, low: usize, high: usize, length: usize) -> usize {
+    let reference = &samples[target..target + length];
+    let score = |start: usize, stride: usize| -> f32 {
+        samples[start..start + length]
+            .iter()
+            .zip(reference)
+            .step_by(stride)
+            .map(|(candidate, reference)| candidate * reference)
+            .sum()
+    };
+
+    let pick = |candidates: &mut dyn Iterator<Item = usize>, stride: usize| {
+        candidates
+            .map(|start| (start, score(start, stride)))
+            .fold((low, f32::NEG_INFINITY), |best, current| {
+                if current.1 > best.1 { current } else { best }
+            })
+            .0
+    };
+    let coarse = pick(&mut (low..=high).step_by(SEARCH_STRIDE), SEARCH_STRIDE);
+    let fine_low = coarse.saturating_sub(SEARCH_STRIDE - 1).max(low);
+    let fine_high = (coarse + SEARCH_STRIDE - 1).min(high);
+    pick(&mut (fine_low..=fine_high), 1)
+}
+
+fn resample(samples: &[f32], output_len: usize) -> Vec<f32> {
+    if samples.is_empty() {
+        return vec![0.0; output_len];
+    }
+    let span = samples.len().saturating_sub(1) as f64;
+    (0..output_len)
+        .map(|index| {
+            let position = if output_len > 1 {
+                index as f64 / (output_len - 1) as f64 * span
+            } else {
+                0.0
+            };
+            sample_linear(samples, position)
+        })
+        .collect()
+}
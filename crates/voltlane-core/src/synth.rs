@@ -0,0 +1,382 @@
+use std::f64::consts::PI;
+
+use crate::model::{
+    AdsrEnvelope, SynthFilter, SynthFilterMode, SynthLfoTarget, SynthPatch, SynthWaveform,
+};
+
+const OUTPUT_LEVEL: f32 = 0.18;
+const MAX_UNISON_VOICES: u8 = 8;
+/// Frames between successive samples of a [`NoteExpression`] curve.
+pub const EXPRESSION_BLOCK_FRAMES: usize = 64;
+
+/// A single note-on/note-off pair to be rendered through a [`SynthPatch`].
+#[derive(Debug, Clone, PartialEq)]
+pub struct PatchNote {
+    pub pitch: u8,
+    pub velocity: u8,
+    pub frequency_hz: f64,
+    /// Frequency of the previously sounding note when glide should be applied.
+    pub glide_from_hz: Option<f64>,
+    pub start_frame: usize,
+    pub gate_frames: usize,
+    pub expression: NoteExpression,
+}
+
+/// Per-voice controller curves, sampled once every [`EXPRESSION_BLOCK_FRAMES`]
+/// from the note start. Curves hold their last value once they run out, and an
+/// empty curve leaves the voice untouched.
+#[derive(Debug, Clone, PartialEq)]
+pub struct NoteExpression {
+    /// Pitch offset in semitones (bend plus vibrato).
+    pub pitch_semitones: Vec<f32>,
+    /// Linear gain multiplier.
+    pub gain: Vec<f32>,
+    /// Multiplier on the release stage length.
+    pub release_scale: f32,
+}
+
+impl Default for NoteExpression {
+    fn default() -> Self {
+        Self {
+            pitch_semitones: Vec::new(),
+            gain: Vec::new(),
+            release_scale: 1.0,
+        }
+    }
+}
+
+impl NoteExpression {
+    /// Maps note-off velocity onto a release multiplier: 64 keeps the patch
+    /// release, 127 halves it and 0 stretches it by half again.
+    #[must_use]
+    pub fn release_scale_for_velocity(release_velocity: Option<u8>) -> f32 {
+        release_velocity.map_or(1.0, |velocity| 1.5 - f32::from(velocity.min(127)) / 127.0)
+    }
+
+    #[must_use]
+    pub fn pitch_ratio(&self, frame: usize) -> f64 {
+        curve_value(&self.pitch_semitones, frame)
+            .map_or(1.0, |semitones| 2_f64.powf(f64::from(semitones) / 12.0))
+    }
+
+    #[must_use]
+    pub fn gain(&self, frame: usize) -> f32 {
+        curve_value(&self.gain, frame).unwrap_or(1.0)
+    }
+
+    #[must_use]
+    pub fn release_seconds(&self, release_seconds: f32) -> f32 {
+        release_seconds * self.release_scale.max(0.0)
+    }
+}
+
+fn curve_value(curve: &[f32], frame: usize) -> Option<f32> {
+    let last = curve.len().checked_sub(1)?;
+    Some(curve[(frame / EXPRESSION_BLOCK_FRAMES).min(last)])
+}
+
+#[derive(Debug, Clone, Copy)]
+struct VoiceOscillator {
+    waveform: SynthWaveform,
+    level: f32,
+    pulse_width: f64,
+    ratio: f64,
+    phase: f64,
+    noise_state: u32,
+}
+
+#[derive(Debug, Default, Clone, Copy)]
+struct SvfState {
+    ic1: f64,
+    ic2: f64,
+}
+
+/// Renders one note of a patch into `buffer`, including its release tail.
+/// Returns the number of frames written.
+pub fn render_patch_note(
+    patch: &SynthPatch,
+    note: &PatchNote,
+    sample_rate: u32,
+    buffer: &mut [f32],
+) -> usize {
+    let sample_rate_f = f64::from(sample_rate.max(1));
+    let mut amp_envelope = patch.amp_envelope.clone();
+    amp_envelope.release_seconds = note
+        .expression
+        .release_seconds(amp_envelope.release_seconds);
+    let release_frames = seconds_to_frames(amp_envelope.release_seconds, sample_rate);
+    let gate_frames = note.gate_frames.max(1);
+    let start = note.start_frame.min(buffer.len());
+    let end = note
+        .start_frame
+        .saturating_add(gate_frames)
+        .saturating_add(release_frames)
+        .min(buffer.len());
+    if end <= start {
+        return 0;
+    }
+
+    let mut oscillators = build_voice_oscillators(patch, note);
+    if oscillators.is_empty() {
+        return 0;
+    }
+    let level_sum: f32 = oscillators
+        .iter()
+        .map(|osc| osc.level)
+        .sum::<f32>()
+        .max(1e-3);
+    let unison = f32::from(patch.unison_voices.clamp(1, MAX_UNISON_VOICES));
+    let amplitude =
+        (f32::from(note.velocity.min(127)) / 127.0) * OUTPUT_LEVEL * db_to_gain(patch.gain_db)
+            / (level_sum / unison.sqrt());
+
+    let gate_seconds = gate_frames as f64 / sample_rate_f;
+    let glide_seconds = f64::from(patch.glide_seconds.max(0.0));
+    let mut filter_state = SvfState::default();
+    let mut lfo_phase = 0.0_f64;
+    let mut lfo_hold = 0.0_f64;
+    let mut lfo_noise = 0x2545_F491_u32 ^ u32::from(note.pitch);
+    let lfo_increment = f64::from(patch.lfo.rate_hz.max(0.0)) / sample_rate_f;
+
+    for (index, frame) in buffer[start..end].iter_mut().enumerate() {
+        let time = index as f64 / sample_rate_f;
+        let amp_env = adsr_level(&amp_envelope, time, gate_seconds);
+        let filter_env = adsr_level(&patch.filter_envelope, time, gate_seconds);
+
+        if lfo_phase + lfo_increment >= 1.0 || index == 0 {
+            lfo_noise = xorshift(lfo_noise);
+            lfo_hold = noise_value(lfo_noise);
+        }
+        let lfo = lfo_value(patch.lfo.waveform, lfo_phase, lfo_hold);
+        lfo_phase = (lfo_phase + lfo_increment).fract();
+        let lfo_depth = f64::from(patch.lfo.depth);
+
+        let mut frequency =
+            glide_frequency(note, time, glide_seconds) * note.expression.pitch_ratio(index);
+        if patch.lfo.target == SynthLfoTarget::Pitch {
+            frequency *= 2_f64.powf((lfo * lfo_depth) / 12.0);
+        }
+
+        let mut raw = 0.0_f64;
+        for oscillator in &mut oscillators {
+            let increment = (frequency * oscillator.ratio / sample_rate_f).min(0.5);
+            raw += oscillator_sample(oscillator, increment) * f64::from(oscillator.level);
+            oscillator.phase = (oscillator.phase + increment).fract();
+        }
+
+        let cutoff_octaves = f64::from(patch.filter.envelope_octaves) * filter_env
+            + f64::from(patch.filter.key_tracking) * (f64::from(note.pitch) - 60.0) / 12.0
+            + if patch.lfo.target == SynthLfoTarget::FilterCutoff {
+                lfo * lfo_depth
+            } else {
+                0.0
+            };
+        let filtered = filter_sample(
+            &patch.filter,
+            &mut filter_state,
+            raw,
+            cutoff_octaves,
+            sample_rate_f,
+        );
+
+        let tremolo = if patch.lfo.target == SynthLfoTarget::Amplitude {
+            1.0 - lfo_depth.clamp(0.0, 1.0) * (0.5 - 0.5 * lfo)
+        } else {
+            1.0
+        };
+        *frame += (filtered * amp_env * tremolo) as f32 * amplitude * note.expression.gain(index);
+    }
+
+    end - start
+}
+
+fn build_voice_oscillators(patch: &SynthPatch, note: &PatchNote) -> Vec<VoiceOscillator> {
+    let unison = patch.unison_voices.clamp(1, MAX_UNISON_VOICES);
+    let mut voices = Vec::with_capacity(patch.oscillators.len() * usize::from(unison));
+    for (osc_index, oscillator) in patch.oscillators.iter().enumerate() {
+        if oscillator.level <= 0.0 {
+            continue;
+        }
+        for voice in 0..unison {
+            let spread = if unison > 1 {
+                (f64::from(voice) / f64::from(unison - 1)) * 2.0 - 1.0
+            } else {
+                0.0
+            };
+            let cents =
+                f64::from(oscillator.detune_cents) + spread * f64::from(patch.unison_detune_cents);
+            let ratio = 2_f64.powf(f64::from(oscillator.octave) + cents / 1_200.0);
+            let seed = (osc_index as u32)
+                .wrapping_mul(0x9E37_79B9)
+                .wrapping_add(u32::from(voice).wrapping_mul(0x85EB_CA6B))
+                .wrapping_add(u32::from(note.pitch))
+                | 1;
+            voices.push(VoiceOscillator {
+                waveform: oscillator.waveform,
+                level: oscillator.level,
+                pulse_width: f64::from(oscillator.pulse_width.clamp(0.05, 0.95)),
+                ratio,
+                phase: if unison > 1 {
+                    (f64::from(voice) * 0.618_034).fract()
+                } else {
+                    0.0
+                },
+                noise_state: seed,
+            });
+        }
+    }
+    voices
+}
+
+fn glide_frequency(note: &PatchNote, time: f64, glide_seconds: f64) -> f64 {
+    match note.glide_from_hz {
+        Some(from) if glide_seconds > 0.0 && time < glide_seconds && from > 0.0 => {
+            let progress = time / glide_seconds;
+            from * (note.frequency_hz / from).powf(progress)
+        }
+        _ => note.frequency_hz,
+    }
+}
+
+fn oscillator_sample(oscillator: &mut VoiceOscillator, increment: f64) -> f64 {
+    let phase = oscillator.phase;
+    match oscillator.waveform {
+        SynthWaveform::Sine => (phase * 2.0 * PI).sin(),
+        SynthWaveform::Triangle => {
+            if phase < 0.5 {
+                phase * 4.0 - 1.0
+            } else {
+                3.0 - phase * 4.0
+            }
+        }
+        SynthWaveform::Saw => (2.0 * phase - 1.0) - poly_blep(phase, increment),
+        SynthWaveform::Square => {
+            let width = oscillator.pulse_width;
+            let naive = if phase < width { 1.0 } else { -1.0 };
+            naive + poly_blep(phase, increment)
+                - poly_blep((phase + 1.0 - width).fract(), increment)
+        }
+        SynthWaveform::Noise => {
+            oscillator.noise_state = xorshift(oscillator.noise_state);
+            noise_value(oscillator.noise_state)
+        }
+    }
+}
+
+fn poly_blep(phase: f64, increment: f64) -> f64 {
+    if increment <= 0.0 {
+        return 0.0;
+    }
+    if phase < increment {
+        let t = phase / increment;
+        t + t - t * t - 1.0
+    } else if phase > 1.0 - increment {
+        let t = (phase - 1.0) / increment;
+        t * t + t + t + 1.0
+    } else {
+        0.0
+    }
+}
+
+fn lfo_value(waveform: SynthWaveform, phase: f64, hold: f64) -> f64 {
+    match waveform {
+        SynthWaveform::Sine => (phase * 2.0 * PI).sin(),
+        SynthWaveform::Triangle => {
+            if phase < 0.5 {
+                phase * 4.0 - 1.0
+            } else {
+                3.0 - phase * 4.0
+            }
+        }
+        SynthWaveform::Saw => 2.0 * phase - 1.0,
+        SynthWaveform::Square => {
+            if phase < 0.5 {
+                1.0
+            } else {
+                -1.0
+            }
+        }
+        SynthWaveform::Noise => hold,
+    }
+}
+
+fn filter_sample(
+    filter: &SynthFilter,
+    state: &mut SvfState,
+    input: f64,
+    cutoff_octaves: f64,
+    sample_rate: f64,
+) -> f64 {
+    if filter.mode == SynthFilterMode::Off {
+        return input;
+    }
+
+    let cutoff =
+        (f64::from(filter.cutoff_hz) * 2_f64.powf(cutoff_octaves)).clamp(20.0, sample_rate * 0.45);
+    let g = (PI * cutoff / sample_rate).tan();
+    let k = 2.0 - 1.96 * f64::from(filter.resonance.clamp(0.0, 1.0));
+    let a1 = 1.0 / (1.0 + g * (g + k));
+    let a2 = g * a1;
+    let a3 = g * a2;
+
+    let v3 = input - state.ic2;
+    let v1 = a1 * state.ic1 + a2 * v3;
+    let v2 = state.ic2 + a2 * state.ic1 + a3 * v3;
+    state.ic1 = 2.0 * v1 - state.ic1;
+    state.ic2 = 2.0 * v2 - state.ic2;
+
+    match filter.mode {
+        SynthFilterMode::LowPass => v2,
+        SynthFilterMode::BandPass => v1,
+        SynthFilterMode::HighPass => input - k * v1 - v2,
+        SynthFilterMode::Off => input,
+    }
+}
+
+pub(crate) fn adsr_level(envelope: &AdsrEnvelope, time: f64, gate_seconds: f64) -> f64 {
+    if time < gate_seconds {
+        return held_level(envelope, time);
+    }
+
+    let released_from = held_level(envelope, gate_seconds);
+    let release = f64::from(envelope.release_seconds.max(0.0));
+    if release <= 0.0 {
+        return 0.0;
+    }
+    (released_from * (1.0 - (time - gate_seconds) / release)).max(0.0)
+}
+
+fn held_level(envelope: &AdsrEnvelope, time: f64) -> f64 {
+    let attack = f64::from(envelope.attack_seconds.max(0.0));
+    let decay = f64::from(envelope.decay_seconds.max(0.0));
+    let sustain = f64::from(envelope.sustain_level.clamp(0.0, 1.0));
+
+    if time < attack {
+        return time / attack;
+    }
+    let decay_time = time - attack;
+    if decay_time < decay {
+        return 1.0 - (1.0 - sustain) * (decay_time / decay);
+    }
+    sustain
+}
+
+fn seconds_to_frames(seconds: f32, sample_rate: u32) -> usize {
+    (f64::from(seconds.max(0.0)) * f64::from(sample_rate)).round() as usize
+}
+
+fn xorshift(state: u32) -> u32 {
+    let mut value = state.max(1);
+    value ^= value << 13;
+    value ^= value >> 17;
+    value ^= value << 5;
+    value
+}
+
+fn noise_value(state: u32) -> f64 {
+    (f64::from(state) / f64::from(u32::MAX)) * 2.0 - 1.0
+}
+
+pub(crate) fn db_to_gain(gain_db: f32) -> f32 {
+    10.0_f32.powf(gain_db / 20.0)
+}
@@ -0,0 +1,247 @@
+use serde::{Deserialize, Serialize};
+
+use crate::model::MidiNote;
+
+const CHROMATIC: [u8; 12] = [0, 1, 2, 3, 4, 5, 6, 7, 8, 9, 10, 11];
+
+/// Krumhansl-Kessler key profiles, starting at the tonic.
+const MAJOR_PROFILE: [f64; 12] = [
+    6.35, 2.23, 3.48, 2.33, 4.38, 4.09, 2.52, 5.19, 2.39, 3.66, 2.29, 2.88,
+];
+const MINOR_PROFILE: [f64; 12] = [
+    6.33, 2.68, 3.52, 5.38, 2.60, 3.53, 2.54, 4.75, 3.98, 2.69, 3.34, 3.17,
+];
+
+/// Diatonic and common modal scales, or a custom set of pitch classes.
+#[derive(Debug, Clone, Default, Serialize, Deserialize, PartialEq, Eq, Hash)]
+#[serde(rename_all = "snake_case")]
+pub enum ScaleMode {
+    #[default]
+    Major,
+    Minor,
+    HarmonicMinor,
+    MelodicMinor,
+    Dorian,
+    Phrygian,
+    Lydian,
+    Mixolydian,
+    Locrian,
+    /// Semitones above the root, ascending from `0` and below `12`.
+    Custom(Vec<u8>),
+}
+
+impl ScaleMode {
+    /// Semitones above the root for each degree, within one octave. An empty
+    /// custom scale reads as chromatic.
+    #[must_use]
+    pub fn intervals(&self) -> &[u8] {
+        match self {
+            Self::Major => &[0, 2, 4, 5, 7, 9, 11],
+            Self::Minor => &[0, 2, 3, 5, 7, 8, 10],
+            Self::HarmonicMinor => &[0, 2, 3, 5, 7, 8, 11],
+            Self::MelodicMinor => &[0, 2, 3, 5, 7, 9, 11],
+            Self::Dorian => &[0, 2, 3, 5, 7, 9, 10],
+            Self::Phrygian => &[0, 1, 3, 5, 7, 8, 10],
+            Self::Lydian => &[0, 2, 4, 6, 7, 9, 11],
+            Self::Mixolydian => &[0, 2, 4, 5, 7, 9, 10],
+            Self::Locrian => &[0, 1, 3, 5, 6, 8, 10],
+            Self::Custom(intervals) if intervals.is_empty() => &CHROMATIC,
+            Self::Custom(intervals) => intervals,
+        }
+    }
+}
+
+/// A tonic pitch class (`0` = C … `11` = B) and a scale. Keys read from
+/// project files are not validated, so the methods tolerate any root and
+/// custom intervals rather than assuming the invariants the engine enforces.
+#[derive(Debug, Clone, Default, Serialize, Deserialize, PartialEq, Eq, Hash)]
+pub struct Key {
+    pub root: u8,
+    pub mode: ScaleMode,
+}
+
+impl Key {
+    /// Which of the twelve pitch classes, from C, belong to the key.
+    #[must_use]
+    pub fn pitch_classes(&self) -> [bool; 12] {
+        let mut classes = [false; 12];
+        for interval in self.mode.intervals() {
+            classes[usize::from((interval % 12 + self.root % 12) % 12)] = true;
+        }
+        classes
+    }
+
+    #[must_use]
+    pub fn contains(&self, pitch: u8) -> bool {
+        self.degree_of(pitch).is_some()
+    }
+
+    /// Scale degree (0-based) of `pitch`, if it belongs to the key.
+    #[must_use]
+    pub fn degree_of(&self, pitch: u8) -> Option<usize> {
+        let class = ((u16::from(pitch) + 12 - u16::from(self.root % 12)) % 12) as u8;
+        self.mode
+            .intervals()
+            .iter()
+            .position(|interval| interval % 12 == class)
+    }
+
+    /// Scale step count from the root of octave 0 up to the highest scale pitch at
+    /// or below `pitch`.
+    #[must_use]
+    pub fn step_below(&self, pitch: u8) -> i64 {
+        let intervals = self.mode.intervals();
+        let relative = i64::from(pitch) - i64::from(self.root % 12);
+        let octave = relative.div_euclid(12);
+        let class = relative.rem_euclid(12) as u8;
+        let degrees = intervals.len() as i64;
+        // A scale without the root puts pitches under its first degree in the
+        // octave below.
+        intervals
+            .iter()
+            .rposition(|interval| interval % 12 <= class)
+            .map_or(octave * degrees - 1, |degree| {
+                octave * degrees + degree as i64
+            })
+    }
+
+    /// MIDI pitch of an absolute scale step counted by [`Key::step_below`], or
+    /// `None` outside `0..=127`.
+    #[must_use]
+    pub fn pitch_at_step(&self, step: i64) -> Option<u8> {
+        u8::try_from(self.step_pitch(step))
+            .ok()
+            .filter(|pitch| *pitch <= 127)
+    }
+
+    /// Nearest in-key pitch to `pitch`, preferring the lower one on a tie.
+    #[must_use]
+    pub fn snap(&self, pitch: u8) -> u8 {
+        let below = self.step_below(pitch);
+        let lower = self.pitch_at_step(below);
+        let upper = self.pitch_at_step(below + 1);
+        let distance = |other: u8| (i16::from(other) - i16::from(pitch)).abs();
+        match (lower, upper) {
+            (Some(lower), Some(upper)) if distance(upper) < distance(lower) => upper,
+            (Some(lower), _) => lower,
+            (None, Some(upper)) => upper,
+            (None, None) => pitch,
+        }
+    }
+
+    /// Moves `pitch` by `steps` scale degrees. Out-of-key pitches keep their
+    /// distance above the scale degree below them. Results clamp to `0..=127`.
+    #[must_use]
+    pub fn transpose(&self, pitch: u8, steps: i64) -> u8 {
+        let below = self.step_below(pitch);
+        let chromatic = i64::from(pitch) - self.step_pitch(below);
+        (self.step_pitch(below + steps) + chromatic).clamp(0, 127) as u8
+    }
+
+    fn step_pitch(&self, step: i64) -> i64 {
+        let intervals = self.mode.intervals();
+        let octave = step.div_euclid(intervals.len() as i64);
+        let degree = step.rem_euclid(intervals.len() as i64) as usize;
+        i64::from(self.root % 12) + octave * 12 + i64::from(intervals[degree] % 12)
+    }
+}
+
+#[derive(Debug, Clone, Copy, Default, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum ChordKind {
+    #[default]
+    Triad,
+    Seventh,
+}
+
+impl ChordKind {
+    fn stacked_steps(self) -> &'static [i64] {
+        match self {
+            Self::Triad => &[0, 2, 4],
+            Self::Seventh => &[0, 2, 4, 6],
+        }
+    }
+}
+
+/// Result of [`detect_key`].
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct KeyEstimate {
+    pub key: Key,
+    /// Correlation of the note profile with the key profile, from -1 to 1.
+    pub confidence: f32,
+}
+
+/// Estimates the major or minor key of `notes` by correlating their
+/// duration-weighted pitch classes with the Krumhansl-Kessler profiles.
+#[must_use]
+pub fn detect_key(notes: &[MidiNote]) -> Option<KeyEstimate> {
+    let mut weights = [0.0_f64; 12];
+    for note in notes {
+        weights[usize::from(note.pitch % 12)] += note.length_ticks.max(1) as f64;
+    }
+    detect_key_from_pitch_classes(&weights)
+}
+
+/// Estimates the major or minor key of a pitch class profile (index `0` is C),
+/// such as a chromagram of audio.
+#[must_use]
+pub fn detect_key_from_pitch_classes(weights: &[f64; 12]) -> Option<KeyEstimate> {
+    if weights.iter().all(|weight| *weight == 0.0) {
+        return None;
+    }
+
+    let mut best: Option<KeyEstimate> = None;
+    for (mode, profile) in [
+        (ScaleMode::Major, &MAJOR_PROFILE),
+        (ScaleMode::Minor, &MINOR_PROFILE),
+    ] {
+        for root in 0..12_u8 {
+            let rotated: Vec<f64> = (0..12)
+                .map(|class| profile[(class + 12 - usize::from(root)) % 12])
+                .collect();
+            let confidence = correlation(weights, &rotated) as f32;
+            if best
+                .as_ref()
+                .is_none_or(|estimate| confidence > estimate.confidence)
+            {
+                best = Some(KeyEstimate {
+                    key: Key {
+                        root,
+                        mode: mode.clone(),
+                    },
+                    confidence,
+                });
+            }
+        }
+    }
+    best
+}
+
+fn correlation(left: &[f64], right: &[f64]) -> f64 {
+    let count = left.len() as f64;
+    let left_mean = left.iter().sum::<f64>() / count;
+    let right_mean = right.iter().sum::<f64>() / count;
+    let mut covariance = 0.0;
+    let mut left_variance = 0.0;
+    let mut right_variance = 0.0;
+    for (left, right) in left.iter().zip(right) {
+        covariance += (left - left_mean) * (right - right_mean);
+        left_variance += (left - left_mean).powi(2);
+        right_variance += (right - right_mean).powi(2);
+    }
+    if left_variance == 0.0 || right_variance == 0.0 {
+        return 0.0;
+    }
+    covariance / (left_variance * right_variance).sqrt()
+}
+
+/// Diatonic chord in `key` built on `pitch`, which is first lowered onto the
+/// scale if it falls between degrees. Chord tones above 127 are dropped.
+#[must_use]
+pub fn diatonic_chord(key: &Key, pitch: u8, kind: ChordKind) -> Vec<u8> {
+    let root_step = key.step_below(pitch);
+    kind.stacked_steps()
+        .iter()
+        .filter_map(|step| key.pitch_at_step(root_step + step))
+        .collect()
+}
@@ -0,0 +1,722 @@
+use std::{collections::BTreeMap, fs, path::Path};
+
+use anyhow::{Context, Result, bail};
+use tracing::{debug, info, instrument, warn};
+use uuid::Uuid;
+
+use crate::{
+    engine::{Engine, normalize_pattern_clip},
+    model::{
+        ChipMacroLane, ClipPayload, ClipPoolEntry, DEFAULT_SAMPLE_RATE,
+        DEFAULT_TRACKER_LINES_PER_BEAT, OrderList, OrderRow, PatternClip, Project, Track,
+        TrackKind, TrackerRow,
+    },
+    time::tracker_rows_to_ticks,
+};
+
+const TRACK_COLORS: [&str; 6] = [
+    "#52e1c4", "#ffd166", "#4fb8ff", "#ff5f7e", "#9be564", "#8f6bff",
+];
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TrackerTextFormat {
+    FamiTracker,
+    Furnace,
+}
+
+#[derive(Debug, Clone)]
+struct TrackerChannel {
+    name: String,
+    source_chip: String,
+    max_volume: u8,
+    supported: bool,
+}
+
+#[derive(Debug, Clone, Default)]
+struct TrackerInstrument {
+    name: String,
+    macros: Vec<ChipMacroLane>,
+}
+
+/// Format-neutral view of a tracker module before it becomes a [`Project`].
+#[derive(Debug, Default)]
+struct TrackerSong {
+    title: String,
+    bpm: f64,
+    rows_per_pattern: u32,
+    channels: Vec<TrackerChannel>,
+    orders: Vec<Vec<u16>>,
+    patterns: BTreeMap<(usize, u16), Vec<TrackerRow>>,
+    pattern_instruments: BTreeMap<(usize, u16), u16>,
+    /// Last volume column value seen per pattern, carried to following notes.
+    running_velocity: BTreeMap<(usize, u16), u8>,
+    jumps: BTreeMap<(usize, u16), u16>,
+    instruments: BTreeMap<u16, TrackerInstrument>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum NoteCell {
+    Empty,
+    Note(u8),
+    Off,
+}
+
+#[must_use]
+pub fn detect_tracker_text_format(text: &str) -> Option<TrackerTextFormat> {
+    let header = text
+        .lines()
+        .map(str::trim)
+        .find(|line| !line.is_empty())?
+        .to_ascii_lowercase();
+    if header.contains("famitracker text export") {
+        Some(TrackerTextFormat::FamiTracker)
+    } else if header.contains("furnace text export") {
+        Some(TrackerTextFormat::Furnace)
+    } else {
+        None
+    }
+}
+
+#[instrument(fields(path = %path.display()))]
+pub fn import_tracker_text(path: &Path) -> Result<Project> {
+    let text = fs::read_to_string(path)
+        .with_context(|| format!("failed to read tracker text export: {}", path.display()))?;
+    let project = parse_tracker_text(&text)?;
+    info!(
+        tracks = project.tracks.len(),
+        clips = project.clip_count(),
+        "tracker text export imported"
+    );
+    Ok(project)
+}
+
+/// Builds a project from a FamiTracker or Furnace text export. Each channel becomes a
+/// chip track, each pattern a pooled [`PatternClip`], and the frame order an
+/// [`OrderList`] whose loop row comes from a jump effect in the last frame.
+///
+/// Only the first song/subsong is imported, only the first effect column of a row
+/// is kept, and DPCM channels are skipped.
+pub fn parse_tracker_text(text: &str) -> Result<Project> {
+    let song = match detect_tracker_text_format(text) {
+        Some(TrackerTextFormat::FamiTracker) => parse_famitracker(text)?,
+        Some(TrackerTextFormat::Furnace) => parse_furnace(text)?,
+        None => bail!("unrecognized tracker text export header"),
+    };
+    build_project(song)
+}
+
+fn parse_famitracker(text: &str) -> Result<TrackerSong> {
+    let mut song = TrackerSong {
+        bpm: 150.0,
+        rows_per_pattern: 64,
+        ..TrackerSong::default()
+    };
+    let mut macros: BTreeMap<(u8, i32), ChipMacroLane> = BTreeMap::new();
+    let mut instrument_macros: Vec<(u16, String, [i32; 5])> = Vec::new();
+    let mut seen_track = false;
+    let mut current_pattern = None;
+
+    for (line_index, raw_line) in text.lines().enumerate() {
+        let line = raw_line.trim();
+        if line.is_empty() || line.starts_with('#') {
+            continue;
+        }
+        let keyword = line.split_whitespace().next().unwrap_or_default();
+        match keyword {
+            "TITLE" => song.title = quoted(line).unwrap_or_default(),
+            "MACRO" => {
+                let (head, values) = split_colon(line);
+                let fields = parse_numbers(head);
+                if fields.len() < 3 {
+                    bail!("malformed MACRO on line {}", line_index + 1);
+                }
+                let lane = ChipMacroLane {
+                    values: values
+                        .split_whitespace()
+                        .filter_map(|value| value.parse::<i16>().ok())
+                        .collect(),
+                    loop_start: usize::try_from(fields[2]).ok(),
+                    ..ChipMacroLane::default()
+                };
+                macros.insert((fields[0] as u8, fields[1]), lane);
+            }
+            "INST2A03" => {
+                let fields = parse_numbers(line.split('"').next().unwrap_or_default());
+                if fields.len() < 6 {
+                    bail!("malformed INST2A03 on line {}", line_index + 1);
+                }
+                instrument_macros.push((
+                    fields[0] as u16,
+                    quoted(line).unwrap_or_default(),
+                    [fields[1], fields[2], fields[3], fields[4], fields[5]],
+                ));
+            }
+            "TRACK" => {
+                if seen_track {
+                    warn!(
+                        line = line_index + 1,
+                        "additional famitracker songs ignored"
+                    );
+                    break;
+                }
+                seen_track = true;
+                let fields = parse_numbers(line.split('"').next().unwrap_or_default());
+                if let [rows, speed, tempo, ..] = fields[..] {
+                    song.rows_per_pattern = rows.clamp(1, 256) as u32;
+                    song.bpm = f64::from(tempo.max(1)) * 6.0 / f64::from(speed.clamp(1, 31));
+                }
+            }
+            "COLUMNS" => {
+                let (_, columns) = split_colon(line);
+                let count = columns.split_whitespace().count();
+                song.channels = (0..count).map(famitracker_channel).collect();
+            }
+            "ORDER" => {
+                let (_, patterns) = split_colon(line);
+                song.orders.push(parse_hex_list(patterns));
+            }
+            "PATTERN" => {
+                current_pattern = line
+                    .split_whitespace()
+                    .nth(1)
+                    .and_then(|index| u16::from_str_radix(index, 16).ok());
+            }
+            "ROW" => {
+                let Some(pattern) = current_pattern else {
+                    bail!("ROW outside of PATTERN on line {}", line_index + 1);
+                };
+                let mut cells = line.split(" : ");
+                let row = cells
+                    .next()
+                    .and_then(|head| head.split_whitespace().nth(1))
+                    .and_then(|index| u32::from_str_radix(index, 16).ok())
+                    .with_context(|| format!("malformed ROW on line {}", line_index + 1))?;
+                for (channel, cell) in cells.enumerate() {
+                    push_cell(&mut song, channel, pattern, row, cell, "B");
+                }
+            }
+            _ => debug!(keyword, "famitracker keyword ignored"),
+        }
+    }
+
+    if song.channels.is_empty() {
+        bail!("famitracker export has no COLUMNS definition");
+    }
+    for (index, name, [volume, arpeggio, pitch, _hi_pitch, duty]) in instrument_macros {
+        let mut lanes = Vec::new();
+        for (macro_type, macro_index, target) in [
+            (0_u8, volume, "env"),
+            (1, arpeggio, "arpeggio"),
+            (2, pitch, "pitch"),
+            (4, duty, "duty"),
+        ] {
+            if let Some(lane) = macros.get(&(macro_type, macro_index)) {
+                lanes.push(macro_lane(target, lane.values.clone(), lane.loop_start));
+            }
+        }
+        song.instruments.insert(
+            index,
+            TrackerInstrument {
+                name,
+                macros: lanes,
+            },
+        );
+    }
+    Ok(song)
+}
+
+fn parse_furnace(text: &str) -> Result<TrackerSong> {
+    let mut song = TrackerSong {
+        bpm: 150.0,
+        rows_per_pattern: 64,
+        ..TrackerSong::default()
+    };
+    let mut section = String::new();
+    let mut system = String::new();
+    let mut tick_rate = 60.0_f64;
+    let mut speed = 6.0_f64;
+    let mut virtual_tempo = 1.0_f64;
+    let mut orders_pending = false;
+    let mut in_orders = false;
+    let mut current_instrument = None;
+    let mut current_order = None;
+    let mut subsong_seen = false;
+
+    for raw_line in text.lines() {
+        let line = raw_line.trim();
+        if let Some(title) = line.strip_prefix("# ") {
+            section = title.trim().to_ascii_lowercase();
+            continue;
+        }
+        if let Some(heading) = line.strip_prefix("## ") {
+            if heading.trim().eq_ignore_ascii_case("patterns") {
+                section = "patterns".to_string();
+            } else if section == "instruments" {
+                let (index, name) = heading.split_once(':').unwrap_or((heading, ""));
+                current_instrument = u16::from_str_radix(index.trim(), 16).ok();
+                if let Some(index) = current_instrument {
+                    song.instruments.insert(
+                        index,
+                        TrackerInstrument {
+                            name: name.trim().to_string(),
+                            macros: Vec::new(),
+                        },
+                    );
+                }
+            } else if section == "subsongs" {
+                if subsong_seen {
+                    warn!("additional furnace subsongs ignored");
+                    break;
+                }
+                subsong_seen = true;
+            }
+            continue;
+        }
+        if line.starts_with("```") {
+            // The orders table is the fenced block that follows `orders:`.
+            in_orders = orders_pending;
+            orders_pending = false;
+            continue;
+        }
+        if line == "orders:" {
+            orders_pending = true;
+            continue;
+        }
+
+        if let Some(entry) = line.strip_prefix("- ") {
+            let (key, value) = entry.split_once(':').unwrap_or((entry, ""));
+            let (key, value) = (key.trim(), value.trim());
+            match (section.as_str(), key) {
+                ("song information", "name") => song.title = value.to_string(),
+                ("song information", "system") => system = value.to_string(),
+                ("sound chips", _) if system.is_empty() && value.is_empty() => {
+                    system = key.to_string();
+                }
+                ("instruments", "vol" | "arp" | "duty" | "pitch") => {
+                    if let Some(instrument) =
+                        current_instrument.and_then(|index| song.instruments.get_mut(&index))
+                    {
+                        let target = match key {
+                            "vol" => "env",
+                            "arp" => "arpeggio",
+                            other => other,
+                        };
+                        let loop_start = value.split_whitespace().position(|token| token == "|");
+                        let values = value
+                            .split_whitespace()
+                            .filter_map(|token| token.parse::<i16>().ok())
+                            .collect();
+                        instrument
+                            .macros
+                            .push(macro_lane(target, values, loop_start));
+                    }
+                }
+                ("subsongs", "tick rate") => tick_rate = value.parse().unwrap_or(60.0),
+                ("subsongs", "speeds") => {
+                    speed = value
+                        .split_whitespace()
+                        .next()
+                        .and_then(|first| first.parse().ok())
+                        .unwrap_or(6.0);
+                }
+                ("subsongs", "virtual tempo") => {
+                    if let Some((numerator, denominator)) = value.split_once('/')
+                        && let (Ok(numerator), Ok(denominator)) = (
+                            numerator.trim().parse::<f64>(),
+                            denominator.trim().parse::<f64>(),
+                        )
+                        && denominator > 0.0
+                    {
+                        virtual_tempo = numerator / denominator;
+                    }
+                }
+                ("subsongs", "pattern length") => {
+                    song.rows_per_pattern = value.parse::<u32>().unwrap_or(64).clamp(1, 256);
+                }
+                _ => {}
+            }
+            continue;
+        }
+
+        if in_orders {
+            if let Some((_, patterns)) = line.split_once('|') {
+                song.orders.push(parse_hex_list(patterns));
+            }
+            continue;
+        }
+        if let Some(order) = line.strip_prefix("----- ORDER") {
+            current_order = usize::from_str_radix(order.trim(), 16).ok();
+            continue;
+        }
+        if section == "patterns"
+            && let Some(order) = current_order
+            && let Some((row, cells)) = line.split_once('|')
+        {
+            let Ok(row) = u32::from_str_radix(row.trim(), 16) else {
+                continue;
+            };
+            if song.channels.is_empty() {
+                song.channels = furnace_channels(&system, cells.split('|').count() - 1);
+            }
+            for (channel, cell) in cells.split('|').enumerate() {
+                let Some(pattern) = song
+                    .orders
+                    .get(order)
+                    .and_then(|patterns| patterns.get(channel))
+                    .copied()
+                else {
+                    continue;
+                };
+                // Orders reuse patterns; only the first occurrence carries new data.
+                let first_use = song.orders[..order]
+                    .iter()
+                    .all(|previous| previous.get(channel) != Some(&pattern));
+                if first_use {
+                    push_cell(&mut song, channel, pattern, row, cell, "0B");
+                }
+            }
+        }
+    }
+
+    if song.orders.is_empty() {
+        bail!("furnace export has no orders table");
+    }
+    song.bpm = tick_rate * 60.0 / (speed.max(1.0) * f64::from(DEFAULT_TRACKER_LINES_PER_BEAT))
+        * virtual_tempo;
+    Ok(song)
+}
+
+/// Decodes one channel cell (`note instrument volume effects...`) into a tracker row.
+fn push_cell(
+    song: &mut TrackerSong,
+    channel: usize,
+    pattern: u16,
+    row: u32,
+    cell: &str,
+    jump_effect: &str,
+) {
+    let Some(info) = song.channels.get(channel) else {
+        return;
+    };
+    let mut tokens = cell.split_whitespace();
+    let note = tokens.next().map_or(NoteCell::Empty, parse_note);
+    let instrument = tokens.next().and_then(parse_hex);
+    let volume = tokens.next().and_then(parse_hex);
+    let effect = tokens
+        .filter(|token| token.len() > 2 && !token.chars().all(|ch| ch == '.'))
+        .find_map(|token| {
+            let split = token.len() - 2;
+            let (name, value) = (token.get(..split)?, token.get(split..)?);
+            Some((
+                name.to_ascii_uppercase(),
+                u16::from_str_radix(value, 16).ok()?,
+            ))
+        });
+
+    let key = (channel, pattern);
+    if let Some(instrument) = instrument {
+        song.pattern_instruments.entry(key).or_insert(instrument);
+    }
+    if let Some((name, value)) = &effect
+        && name == jump_effect
+    {
+        song.jumps.insert(key, *value);
+    }
+
+    let max_volume = info.max_volume;
+    let velocity = volume.map_or_else(
+        || song.running_velocity.get(&key).copied().unwrap_or(127),
+        |volume| {
+            ((f64::from(volume.min(u16::from(max_volume))) * 127.0 / f64::from(max_volume.max(1)))
+                .round() as u8)
+                .max(1)
+        },
+    );
+    song.running_velocity.insert(key, velocity);
+    let (effect, effect_value) =
+        effect.map_or((None, None), |(name, value)| (Some(name), Some(value)));
+
+    let tracker_row = match note {
+        NoteCell::Note(pitch) => TrackerRow {
+            row,
+            note: Some(pitch),
+            velocity,
+            gate: true,
+            effect,
+            effect_value,
+        },
+        NoteCell::Off => TrackerRow {
+            row,
+            note: None,
+            velocity,
+            gate: false,
+            effect: Some("OFF".to_string()),
+            effect_value: None,
+        },
+        NoteCell::Empty if effect.is_some() => TrackerRow {
+            row,
+            note: None,
+            velocity,
+            gate: false,
+            effect,
+            effect_value,
+        },
+        NoteCell::Empty => return,
+    };
+    let rows = song.patterns.entry(key).or_default();
+    rows.push(tracker_row);
+}
+
+fn build_project(song: TrackerSong) -> Result<Project> {
+    let title = if song.title.trim().is_empty() {
+        "Imported Module".to_string()
+    } else {
+        song.title.trim().to_string()
+    };
+    let mut project = Project::new(title, song.bpm.clamp(20.0, 999.0), DEFAULT_SAMPLE_RATE);
+    let lines_per_beat = DEFAULT_TRACKER_LINES_PER_BEAT;
+    let row_length_ticks =
+        tracker_rows_to_ticks(song.rows_per_pattern, lines_per_beat, project.ppq).max(1);
+
+    let mut channel_tracks = Vec::new();
+    for (channel, info) in song.channels.iter().enumerate() {
+        if !info.supported {
+            warn!(channel = %info.name, "tracker channel has no voltlane backend, skipping");
+            continue;
+        }
+        let track = Track::new(
+            info.name.clone(),
+            TRACK_COLORS[channel % TRACK_COLORS.len()],
+            TrackKind::Chip,
+        );
+        channel_tracks.push((channel, track.id));
+        project.tracks.push(track);
+    }
+
+    let mut content_ids = BTreeMap::new();
+    for ((channel, index), rows) in song.patterns {
+        let Some(info) = song.channels.get(channel).filter(|info| info.supported) else {
+            continue;
+        };
+        let instrument = song
+            .pattern_instruments
+            .get(&(channel, index))
+            .and_then(|instrument| song.instruments.get(instrument));
+        let mut pattern = PatternClip {
+            source_chip: info.source_chip.clone(),
+            instrument: instrument.map(|instrument| instrument.name.clone()),
+            rows,
+            macros: instrument
+                .map(|instrument| instrument.macros.clone())
+                .unwrap_or_default(),
+            lines_per_beat,
+            ..PatternClip::default()
+        };
+        normalize_pattern_clip(&mut pattern, project.ppq)?;
+
+        let id = Uuid::new_v4();
+        project.clip_pool.push(ClipPoolEntry {
+            id,
+            name: format!("{} {index:02X}", info.name),
+            payload: ClipPayload::Pattern(pattern),
+        });
+        content_ids.insert((channel, index), id);
+    }
+
+    let rows = song
+        .orders
+        .iter()
+        .map(|order| OrderRow {
+            patterns: channel_tracks
+                .iter()
+                .map(|(channel, _)| {
+                    order
+                        .get(*channel)
+                        .and_then(|index| content_ids.get(&(*channel, *index)))
+                        .copied()
+                })
+                .collect(),
+        })
+        .collect::<Vec<_>>();
+    let loop_row = song.orders.last().and_then(|order| {
+        order
+            .iter()
+            .enumerate()
+            .find_map(|(channel, index)| song.jumps.get(&(channel, *index)))
+            .map(|target| usize::from(*target))
+            .filter(|target| *target < song.orders.len())
+    });
+
+    let mut engine = Engine::new(project);
+    engine.set_order_list(OrderList {
+        row_length_ticks,
+        channels: channel_tracks
+            .iter()
+            .map(|(_, track_id)| *track_id)
+            .collect(),
+        rows,
+        loop_row,
+        ..OrderList::default()
+    })?;
+    Ok(engine.project().clone())
+}
+
+fn famitracker_channel(index: usize) -> TrackerChannel {
+    let (name, source_chip, supported) = match index {
+        0 => ("Pulse 1".to_string(), "nes_2a03_pulse", true),
+        1 => ("Pulse 2".to_string(), "nes_2a03_pulse", true),
+        2 => ("Triangle".to_string(), "nes_2a03_triangle", true),
+        3 => ("Noise".to_string(), "nes_2a03_noise", true),
+        4 => ("DPCM".to_string(), "nes_2a03_dpcm", false),
+        other => (format!("Expansion {}", other - 4), "nes_expansion", true),
+    };
+    TrackerChannel {
+        name,
+        source_chip: source_chip.to_string(),
+        max_volume: 15,
+        supported,
+    }
+}
+
+fn furnace_channels(system: &str, count: usize) -> Vec<TrackerChannel> {
+    let normalized = system.to_ascii_lowercase();
+    let layout: Vec<(&str, &str, u8)> = if normalized.contains("nes")
+        || normalized.contains("famicom")
+    {
+        return (0..count).map(famitracker_channel).collect();
+    } else if normalized.contains("game boy") || normalized.contains("gameboy") {
+        vec![
+            ("Pulse 1", "gameboy_apu_pulse", 15),
+            ("Pulse 2", "gameboy_apu_pulse", 15),
+            ("Wave", "gameboy_apu_wave", 15),
+            ("Noise", "gameboy_apu_noise", 15),
+        ]
+    } else if normalized.contains("genesis") || normalized.contains("mega drive") {
+        let mut layout: Vec<(&str, &str, u8)> = ["FM 1", "FM 2", "FM 3", "FM 4", "FM 5", "FM 6"]
+            .into_iter()
+            .map(|name| (name, "ym2612", 127))
+            .collect();
+        layout.extend([
+            ("Square 1", "sn76489", 15),
+            ("Square 2", "sn76489", 15),
+            ("Square 3", "sn76489", 15),
+            ("Noise", "sn76489_noise", 15),
+        ]);
+        layout
+    } else if normalized.contains("master system") || normalized.contains("sn76489") {
+        vec![
+            ("Square 1", "sn76489", 15),
+            ("Square 2", "sn76489", 15),
+            ("Square 3", "sn76489", 15),
+            ("Noise", "sn76489_noise", 15),
+        ]
+    } else if normalized.contains("ay-3-8910") || normalized.contains("ay8910") {
+        vec![
+            ("Square A", "ay_3_8910", 15),
+            ("Square B", "ay_3_8910", 15),
+            ("Square C", "ay_3_8910", 15),
+        ]
+    } else {
+        Vec::new()
+    };
+
+    (0..count)
+        .map(|index| match layout.get(index) {
+            Some((name, source_chip, max_volume)) => TrackerChannel {
+                name: (*name).to_string(),
+                source_chip: (*source_chip).to_string(),
+                max_volume: *max_volume,
+                supported: true,
+            },
+            None => TrackerChannel {
+                name: format!("Channel {}", index + 1),
+                source_chip: normalized.clone(),
+                max_volume: 15,
+                supported: true,
+            },
+        })
+        .collect()
+}
+
+fn parse_note(token: &str) -> NoteCell {
+    match token {
+        "---" | "===" | "OFF" | "REL" | "MREL" => return NoteCell::Off,
+        _ if token.chars().all(|ch| ch == '.') => return NoteCell::Empty,
+        _ => {}
+    }
+
+    // FamiTracker noise notes are a hex period followed by "-#".
+    if let Some(period) = token.strip_suffix("-#")
+        && let Ok(period) = u8::from_str_radix(period, 16)
+    {
+        return NoteCell::Note(18 + period.min(15) * 6);
+    }
+
+    let bytes = token.as_bytes();
+    if bytes.len() != 3 {
+        return NoteCell::Empty;
+    }
+    let semitone: i16 = match bytes[0].to_ascii_uppercase() {
+        b'C' => 0,
+        b'D' => 2,
+        b'E' => 4,
+        b'F' => 5,
+        b'G' => 7,
+        b'A' => 9,
+        b'B' => 11,
+        _ => return NoteCell::Empty,
+    };
+    let accidental = i16::from(bytes[1] == b'#');
+    let octave = match bytes[2] {
+        b'0'..=b'9' => i16::from(bytes[2] - b'0'),
+        _ => return NoteCell::Empty,
+    };
+    let pitch = (octave + 1) * 12 + semitone + accidental;
+    NoteCell::Note(pitch.clamp(0, 127) as u8)
+}
+
+fn macro_lane(target: &str, values: Vec<i16>, loop_start: Option<usize>) -> ChipMacroLane {
+    // Tracker volume macros are absolute levels; the env lane is a velocity offset.
+    let values: Vec<i16> = if target == "env" {
+        values
+            .into_iter()
+            .map(|level| ((f64::from(level.clamp(0, 15)) - 15.0) * 127.0 / 15.0).round() as i16)
+            .collect()
+    } else {
+        values
+    };
+    let loop_start = loop_start.filter(|start| *start < values.len());
+    ChipMacroLane {
+        target: target.to_string(),
+        enabled: !values.is_empty(),
+        loop_start,
+        loop_end: loop_start.and(values.len().checked_sub(1)),
+        values,
+    }
+}
+
+fn quoted(line: &str) -> Option<String> {
+    let start = line.find('"')?;
+    let end = line.rfind('"')?;
+    (end > start).then(|| line[start + 1..end].to_string())
+}
+
+fn split_colon(line: &str) -> (&str, &str) {
+    line.split_once(':').unwrap_or((line, ""))
+}
+
+/// Numeric fields after the keyword, e.g. `MACRO 0 0 -1 -1 0` -> `[0, 0, -1, -1, 0]`.
+fn parse_numbers(text: &str) -> Vec<i32> {
+    text.split_whitespace()
+        .skip(1)
+        .filter_map(|field| field.parse::<i32>().ok())
+        .collect()
+}
+
+fn parse_hex_list(text: &str) -> Vec<u16> {
+    text.split_whitespace().filter_map(parse_hex).collect()
+}
+
+fn parse_hex(token: &str) -> Option<u16> {
+    u16::from_str_radix(token, 16).ok()
+}
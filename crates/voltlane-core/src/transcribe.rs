@@ -0,0 +1,219 @@
+use serde::{Deserialize, Serialize};
+
+use crate::{assets::DecodedAudio, groove::QuantizeOptions};
+
+/// Rate the pitch tracker works at; voices and chip leads sit well below its
+/// Nyquist frequency and the YIN difference function is quadratic in it.
+const ANALYSIS_RATE: u32 = 16_000;
+const FRAME_SECONDS: f64 = 0.025;
+const HOP_SECONDS: f64 = 0.01;
+const MIN_PITCH_HZ: f64 = 60.0;
+const MAX_PITCH_HZ: f64 = 1_500.0;
+/// Frames quieter than this are unvoiced whatever their periodicity.
+const VOICING_FLOOR_DB: f32 = -50.0;
+/// Frames each side of a frame in the pitch median filter.
+const MEDIAN_RADIUS: usize = 2;
+/// Level range, below the loudest note, mapped onto velocities 1 to 127.
+const VELOCITY_RANGE_DB: f32 = 40.0;
+
+/// One analysis frame of [`track_pitch`].
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq)]
+pub struct PitchFrame {
+    pub start_seconds: f64,
+    /// `None` for unvoiced or silent frames.
+    pub frequency_hz: Option<f64>,
+    pub rms: f32,
+}
+
+/// A note found by [`segment_notes`], in source seconds.
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq)]
+pub struct DetectedNote {
+    /// Fractional MIDI pitch, the median of the note's frames.
+    pub pitch: f64,
+    pub start_seconds: f64,
+    pub end_seconds: f64,
+    /// Loudest frame RMS within the note.
+    pub level: f32,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct TranscriptionOptions {
+    /// Chip to write a pattern clip for; `None` writes a MIDI clip.
+    pub source_chip: Option<String>,
+    /// YIN aperiodicity threshold, lower is stricter about what counts as
+    /// pitched.
+    pub threshold: f32,
+    /// Shorter pitch runs are dropped as glitches.
+    pub min_note_seconds: f64,
+    pub quantize: Option<QuantizeOptions>,
+}
+
+impl Default for TranscriptionOptions {
+    fn default() -> Self {
+        Self {
+            source_chip: None,
+            threshold: 0.15,
+            min_note_seconds: 0.06,
+            quantize: None,
+        }
+    }
+}
+
+/// Monophonic pitch track with the YIN estimator: the cumulative mean
+/// normalized difference function is searched for its first dip under
+/// `threshold`, refined by parabolic interpolation.
+#[must_use]
+pub fn track_pitch(audio: &DecodedAudio, threshold: f32) -> Vec<PitchFrame> {
+    let factor = (audio.sample_rate / ANALYSIS_RATE).max(1) as usize;
+    let rate = f64::from(audio.sample_rate.max(1)) / factor as f64;
+    let samples: Vec<f32> = audio
+        .samples
+        .chunks(factor)
+        .map(|chunk| chunk.iter().sum::<f32>() / chunk.len() as f32)
+        .collect();
+
+    let window = (rate * FRAME_SECONDS) as usize;
+    let hop = ((rate * HOP_SECONDS) as usize).max(1);
+    let min_lag = ((rate / MAX_PITCH_HZ) as usize).max(2);
+    let max_lag = (rate / MIN_PITCH_HZ).ceil() as usize;
+    if window == 0 || samples.len() < window + max_lag + 1 {
+        return Vec::new();
+    }
+    let floor = 10_f32.powf(VOICING_FLOOR_DB / 20.0);
+    let threshold = f64::from(threshold.clamp(0.01, 1.0));
+
+    let mut difference = vec![0.0_f64; max_lag + 2];
+    (0..=samples.len() - window - max_lag - 1)
+        .step_by(hop)
+        .map(|start| {
+            let frame = &samples[start..start + window];
+            let rms =
+                (frame.iter().map(|sample| sample * sample).sum::<f32>() / window as f32).sqrt();
+            let frequency_hz = (rms >= floor)
+                .then(|| {
+                    yin_lag(
+                        &samples[start..],
+                        window,
+                        min_lag,
+                        max_lag,
+                        threshold,
+                        &mut difference,
+                    )
+                })
+                .flatten()
+                .map(|lag| rate / lag);
+            PitchFrame {
+                start_seconds: start as f64 / rate,
+                frequency_hz,
+                rms,
+            }
+        })
+        .collect()
+}
+
+fn yin_lag(
+    samples: &[f32],
+    window: usize,
+    min_lag: usize,
+    max_lag: usize,
+    threshold: f64,
+    difference: &mut [f64],
+) -> Option<f64> {
+    difference[0] = 1.0;
+    let mut running = 0.0;
+    for lag in 1..=max_lag + 1 {
+        let raw: f64 = (0..window)
+            .map(|index| f64::from(samples[index] - samples[index + lag]).powi(2))
+            .sum();
+        running += raw;
+        difference[lag] = if running > 0.0 {
+            raw * lag as f64 / running
+        } else {
+            1.0
+        };
+    }
+
+    let mut lag = (min_lag..=max_lag).find(|lag| difference[*lag] < threshold)?;
+    while lag < max_lag && difference[lag + 1] < difference[lag] {
+        lag += 1;
+    }
+    let (before, at, after) = (difference[lag - 1], difference[lag], difference[lag + 1]);
+    let curvature = before - 2.0 * at + after;
+    let offset = if curvature > 0.0 {
+        (0.5 * (before - after) / curvature).clamp(-0.5, 0.5)
+    } else {
+        0.0
+    };
+    Some(lag as f64 + offset)
+}
+
+/// Groups frames into notes: runs of voiced frames whose median-filtered pitch
+/// rounds to the same semitone, at least `min_note_seconds` long.
+#[must_use]
+pub fn segment_notes(frames: &[PitchFrame], min_note_seconds: f64) -> Vec<DetectedNote> {
+    let pitches: Vec<Option<f64>> = frames
+        .iter()
+        .map(|frame| {
+            frame
+                .frequency_hz
+                .map(|hz| 69.0 + 12.0 * (hz / 440.0).log2())
+        })
+        .collect();
+    let smoothed: Vec<Option<f64>> = (0..pitches.len())
+        .map(|index| {
+            pitches[index]?;
+            let from = index.saturating_sub(MEDIAN_RADIUS);
+            let to = (index + MEDIAN_RADIUS + 1).min(pitches.len());
+            let mut voiced: Vec<f64> = pitches[from..to].iter().flatten().copied().collect();
+            voiced.sort_by(f64::total_cmp);
+            Some(voiced[voiced.len() / 2])
+        })
+        .collect();
+    let hop = match frames {
+        [first, second, ..] => second.start_seconds - first.start_seconds,
+        _ => HOP_SECONDS,
+    };
+
+    let mut notes = Vec::new();
+    let mut index = 0;
+    while index < frames.len() {
+        let Some(pitch) = smoothed[index] else {
+            index += 1;
+            continue;
+        };
+        let semitone = pitch.round();
+        let end = (index..frames.len())
+            .find(|candidate| smoothed[*candidate].is_none_or(|next| next.round() != semitone))
+            .unwrap_or(frames.len());
+        let mut run: Vec<f64> = smoothed[index..end].iter().flatten().copied().collect();
+        run.sort_by(f64::total_cmp);
+
+        let start_seconds = frames[index].start_seconds;
+        let end_seconds = frames[end - 1].start_seconds + hop;
+        if end_seconds - start_seconds >= min_note_seconds {
+            notes.push(DetectedNote {
+                pitch: run[run.len() / 2],
+                start_seconds,
+                end_seconds,
+                level: frames[index..end]
+                    .iter()
+                    .map(|frame| frame.rms)
+                    .fold(0.0, f32::max),
+            });
+        }
+        index = end;
+    }
+    notes
+}
+
+/// Velocity for a note at `level`, relative to the loudest note at `loudest`.
+#[must_use]
+pub fn level_velocity(level: f32, loudest: f32) -> u8 {
+    if level <= 0.0 || loudest <= 0.0 {
+        return 1;
+    }
+    let below_db = 20.0 * (level / loudest).log10();
+    (127.0 * (1.0 + below_db / VELOCITY_RANGE_DB))
+        .round()
+        .clamp(1.0, 127.0) as u8
+}
@@ -0,0 +1,196 @@
+use std::{fs, path::Path};
+
+use anyhow::{Context, Result, bail};
+use tracing::{info, instrument};
+
+use crate::model::Tuning;
+
+/// Far past any published scale, but small enough that a corrupt note count
+/// can't run an import out of memory.
+const MAX_SCALE_NOTES: usize = 4_096;
+/// Mapping entries are MIDI keys.
+const MAX_MAP_SIZE: usize = 128;
+
+/// Keyboard mapping parsed from a Scala `.kbm` file.
+#[derive(Debug, Clone, PartialEq)]
+struct KeyboardMapping {
+    first_note: u8,
+    last_note: u8,
+    middle_note: u8,
+    reference_note: u8,
+    reference_hz: f64,
+    octave_degree: u16,
+    keys: Vec<Option<u16>>,
+}
+
+/// Loads a Scala scale, optionally with a keyboard mapping. Without a mapping the
+/// 1/1 sits on middle C and A4 sounds at 440 Hz, as in Scala itself.
+#[instrument(fields(scl = %scl_path.display(), kbm = ?kbm_path.map(Path::display)))]
+pub fn load_scala_tuning(scl_path: &Path, kbm_path: Option<&Path>) -> Result<Tuning> {
+    let scl = fs::read_to_string(scl_path)
+        .with_context(|| format!("failed to read scala file: {}", scl_path.display()))?;
+    let kbm = kbm_path
+        .map(|path| {
+            fs::read_to_string(path)
+                .with_context(|| format!("failed to read keyboard mapping: {}", path.display()))
+        })
+        .transpose()?;
+    parse_scala_tuning(&scl, kbm.as_deref())
+}
+
+/// Builds a [`Tuning`] from `.scl` text and optional `.kbm` text.
+pub fn parse_scala_tuning(scl: &str, kbm: Option<&str>) -> Result<Tuning> {
+    let (name, scale_cents) = parse_scl(scl)?;
+    let mut tuning = Tuning {
+        name,
+        scale_cents,
+        ..Tuning::default()
+    };
+    if let Some(kbm) = kbm {
+        let mapping = parse_kbm(kbm)?;
+        tuning.first_note = mapping.first_note;
+        tuning.last_note = mapping.last_note;
+        tuning.root_note = mapping.middle_note;
+        tuning.reference_note = mapping.reference_note;
+        tuning.reference_hz = mapping.reference_hz;
+        tuning.map_octave_degree = mapping.octave_degree;
+        tuning.key_map = mapping.keys;
+    }
+
+    info!(
+        name = %tuning.name,
+        degrees = tuning.scale_cents.len(),
+        mapped_keys = tuning.key_map.len(),
+        "scala tuning parsed"
+    );
+    Ok(tuning)
+}
+
+/// Non-comment lines of a Scala file. Blank lines are kept because the `.scl`
+/// description may legitimately be empty.
+fn data_lines(text: &str) -> impl Iterator<Item = &str> {
+    text.lines()
+        .map(str::trim)
+        .filter(|line| !line.starts_with('!'))
+}
+
+fn parse_scl(text: &str) -> Result<(String, Vec<f64>)> {
+    let mut lines = data_lines(text);
+    let description = lines
+        .next()
+        .context("scala file is missing its description line")?
+        .to_string();
+    let count: usize = lines
+        .by_ref()
+        .find(|line| !line.is_empty())
+        .and_then(|line| line.split_whitespace().next())
+        .context("scala file is missing its note count")?
+        .parse()
+        .context("scala note count is not a number")?;
+    if count > MAX_SCALE_NOTES {
+        bail!("scala file declares {count} notes, more than {MAX_SCALE_NOTES}");
+    }
+
+    let mut cents = Vec::new();
+    for line in lines.filter(|line| !line.is_empty()).take(count) {
+        let token = line.split_whitespace().next().unwrap_or_default();
+        cents.push(
+            parse_pitch(token).with_context(|| format!("invalid scala pitch value: {token}"))?,
+        );
+    }
+    if cents.len() != count {
+        bail!(
+            "scala file declares {count} notes but lists {}",
+            cents.len()
+        );
+    }
+    if cents.last().is_some_and(|period| *period <= 0.0) {
+        bail!("scala period must be above the 1/1");
+    }
+    Ok((description, cents))
+}
+
+/// A Scala pitch is cents when it contains a period, otherwise a ratio such as
+/// `3/2` or a bare integer `2`.
+fn parse_pitch(token: &str) -> Result<f64> {
+    if token.contains('.') {
+        let cents: f64 = token.parse()?;
+        if !cents.is_finite() {
+            bail!("cents value is not finite");
+        }
+        return Ok(cents);
+    }
+    let (numerator, denominator) = token.split_once('/').unwrap_or((token, "1"));
+    let numerator: u64 = numerator.parse()?;
+    let denominator: u64 = denominator.parse()?;
+    if numerator == 0 || denominator == 0 {
+        bail!("ratio terms must be positive");
+    }
+    Ok(1_200.0 * (numerator as f64 / denominator as f64).log2())
+}
+
+fn parse_kbm(text: &str) -> Result<KeyboardMapping> {
+    let mut lines = data_lines(text).filter(|line| !line.is_empty());
+    let mut field = |name: &str| {
+        lines
+            .next()
+            .and_then(|line| line.split_whitespace().next())
+            .map(str::to_string)
+            .with_context(|| format!("keyboard mapping is missing its {name}"))
+    };
+    let map_size: usize = field("map size")?.parse().context("invalid map size")?;
+    if map_size > MAX_MAP_SIZE {
+        bail!("keyboard mapping size {map_size} is larger than {MAX_MAP_SIZE} keys");
+    }
+    let first_note = parse_key(&field("first note")?)?;
+    let last_note = parse_key(&field("last note")?)?;
+    let middle_note = parse_key(&field("middle note")?)?;
+    let reference_note = parse_key(&field("reference note")?)?;
+    let reference_hz: f64 = field("reference frequency")?
+        .parse()
+        .context("invalid reference frequency")?;
+    if !reference_hz.is_finite() || reference_hz <= 0.0 {
+        bail!("reference frequency must be positive");
+    }
+    let octave_degree: u16 = field("octave degree")?
+        .parse()
+        .context("invalid octave degree")?;
+
+    let mut keys = Vec::new();
+    for index in 0..map_size {
+        // Trailing entries may be omitted and are then unmapped.
+        let Ok(entry) = field("mapping entry") else {
+            keys.resize(map_size, None);
+            break;
+        };
+        keys.push(if entry.eq_ignore_ascii_case("x") {
+            None
+        } else {
+            Some(
+                entry
+                    .parse()
+                    .with_context(|| format!("invalid mapping entry {index}: {entry}"))?,
+            )
+        });
+    }
+
+    Ok(KeyboardMapping {
+        first_note,
+        last_note: last_note.max(first_note),
+        middle_note,
+        reference_note,
+        reference_hz,
+        octave_degree,
+        keys,
+    })
+}
+
+fn parse_key(value: &str) -> Result<u8> {
+    let key: u8 = value
+        .parse()
+        .with_context(|| format!("invalid midi key: {value}"))?;
+    if key > 127 {
+        bail!("midi key out of range: {key}");
+    }
+    Ok(key)
+}
@@ -0,0 +1,719 @@
+use std::{collections::BTreeMap, fs, ops::RangeInclusive, path::Path};
+
+use anyhow::{Context, Result};
+use tracing::{debug, info, instrument, warn};
+
+use crate::{
+    export::apply_pattern_macros,
+    model::{ClipPayload, MidiNote, Project, equal_tempered_hz},
+    sequencer::step_notes,
+    time::ticks_to_samples,
+};
+
+/// VGM timing is fixed at 44.1 kHz regardless of the project sample rate.
+pub const VGM_SAMPLE_RATE: u32 = 44_100;
+
+const VGM_VERSION: u32 = 0x171;
+const VGM_DATA_OFFSET: usize = 0x100;
+const GD3_VERSION: u32 = 0x100;
+
+pub(crate) const SN76489_CLOCK: u32 = 3_579_545;
+const YM2612_CLOCK: u32 = 7_670_453;
+const AY8910_CLOCK: u32 = 1_789_750;
+const GAMEBOY_DMG_CLOCK: u32 = 4_194_304;
+pub(crate) const NES_APU_CLOCK: u32 = 1_789_772;
+/// Game Boy pulse channels count down from 2048 at this rate per output cycle.
+pub(crate) const GAMEBOY_TONE_CLOCK: u32 = 131_072;
+
+const YM2612_OPERATOR_OFFSETS: [u8; 4] = [0x00, 0x04, 0x08, 0x0C];
+const YM2612_CARRIER_OFFSET: u8 = 0x0C;
+const GAMEBOY_WAVE_RAM: [u8; 16] = [
+    0x01, 0x23, 0x45, 0x67, 0x89, 0xAB, 0xCD, 0xEF, 0xFE, 0xDC, 0xBA, 0x98, 0x76, 0x54, 0x32, 0x10,
+];
+
+/// Sound chips the VGM exporter can drive, in header order.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum VgmChip {
+    Sn76489,
+    Ym2612,
+    Ay8910,
+    GameBoyDmg,
+    NesApu,
+}
+
+impl VgmChip {
+    /// Resolves a pattern clip `source_chip` to a VGM chip. Returns `None` for
+    /// backends that have no register-level VGM representation.
+    #[must_use]
+    pub fn for_source(source_chip: &str) -> Option<Self> {
+        let normalized = source_chip.trim().to_ascii_lowercase();
+        if normalized.contains("ym2612")
+            || normalized.contains("opn2")
+            || normalized.contains("genesis")
+            || normalized.contains("megadrive")
+        {
+            Some(Self::Ym2612)
+        } else if normalized.starts_with("ay")
+            || normalized.contains("ay-3-8910")
+            || normalized.contains("ay8910")
+            || normalized.contains("ym2149")
+        {
+            Some(Self::Ay8910)
+        } else if normalized.contains("sn76489") || normalized.contains("psg") {
+            Some(Self::Sn76489)
+        } else if normalized.contains("gameboy") || normalized.contains("gb_apu") {
+            Some(Self::GameBoyDmg)
+        } else if normalized.contains("nes") || normalized.contains("2a03") {
+            Some(Self::NesApu)
+        } else {
+            None
+        }
+    }
+
+    /// MIDI notes the chip's tone channels reach within the period and F-number
+    /// limits the exporter writes; anything outside is pinned to an edge pitch.
+    #[must_use]
+    pub fn note_range(self) -> RangeInclusive<u8> {
+        match self {
+            Self::Sn76489 => 45..=127,
+            Self::Ym2612 => 0..=116,
+            Self::Ay8910 => 21..=127,
+            Self::GameBoyDmg => 36..=127,
+            Self::NesApu => 33..=126,
+        }
+    }
+
+    fn clock(self) -> u32 {
+        match self {
+            Self::Sn76489 => SN76489_CLOCK,
+            Self::Ym2612 => YM2612_CLOCK,
+            Self::Ay8910 => AY8910_CLOCK,
+            Self::GameBoyDmg => GAMEBOY_DMG_CLOCK,
+            Self::NesApu => NES_APU_CLOCK,
+        }
+    }
+
+    fn header_clock_offset(self) -> usize {
+        match self {
+            Self::Sn76489 => 0x0C,
+            Self::Ym2612 => 0x2C,
+            Self::Ay8910 => 0x74,
+            Self::GameBoyDmg => 0x80,
+            Self::NesApu => 0x84,
+        }
+    }
+
+    fn display_name(self) -> &'static str {
+        match self {
+            Self::Sn76489 => "SN76489",
+            Self::Ym2612 => "YM2612",
+            Self::Ay8910 => "AY-3-8910",
+            Self::GameBoyDmg => "Game Boy DMG",
+            Self::NesApu => "NES APU",
+        }
+    }
+
+    fn voices(self) -> &'static [Voice] {
+        match self {
+            Self::Sn76489 => &[Voice::Tone(0), Voice::Tone(1), Voice::Tone(2), Voice::Noise],
+            Self::Ym2612 => &[
+                Voice::Fm(0),
+                Voice::Fm(1),
+                Voice::Fm(2),
+                Voice::Fm(3),
+                Voice::Fm(4),
+                Voice::Fm(5),
+            ],
+            Self::Ay8910 => &[Voice::Tone(0), Voice::Tone(1), Voice::Tone(2)],
+            Self::GameBoyDmg => &[Voice::Tone(0), Voice::Tone(1), Voice::Wave, Voice::Noise],
+            Self::NesApu => &[
+                Voice::Tone(0),
+                Voice::Tone(1),
+                Voice::Triangle,
+                Voice::Noise,
+            ],
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+enum Voice {
+    Tone(u8),
+    Triangle,
+    Wave,
+    Noise,
+    Fm(u8),
+}
+
+impl Voice {
+    fn matches_source(self, source_chip: &str) -> bool {
+        let normalized = source_chip.to_ascii_lowercase();
+        match self {
+            Self::Noise => normalized.contains("noise"),
+            Self::Triangle => normalized.contains("triangle"),
+            Self::Wave => normalized.contains("wave"),
+            Self::Tone(_) | Self::Fm(_) => !["noise", "triangle", "wave"]
+                .iter()
+                .any(|kind| normalized.contains(kind)),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy)]
+struct VgmNote {
+    start_sample: u64,
+    end_sample: u64,
+    pitch: u8,
+    velocity: u8,
+    frequency_hz: f64,
+}
+
+#[derive(Debug, Clone, Copy)]
+enum VgmEventKind {
+    NoteOff,
+    NoteOn {
+        pitch: u8,
+        velocity: u8,
+        frequency_hz: f64,
+    },
+}
+
+#[derive(Debug, Clone, Copy)]
+struct VgmEvent {
+    sample: u64,
+    chip: VgmChip,
+    voice: Voice,
+    kind: VgmEventKind,
+}
+
+impl VgmEvent {
+    fn sort_key(&self) -> (u64, u8, VgmChip, Voice) {
+        let order = match self.kind {
+            VgmEventKind::NoteOff => 0,
+            VgmEventKind::NoteOn { .. } => 1,
+        };
+        (self.sample, order, self.chip, self.voice)
+    }
+}
+
+#[derive(Debug, Default)]
+struct VgmWriter {
+    data: Vec<u8>,
+    current_sample: u64,
+    nes_channel_mask: u8,
+}
+
+#[instrument(skip(project), fields(project_id = %project.id, path = %path.display()))]
+pub fn export_vgm(project: &Project, path: &Path) -> Result<()> {
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent).with_context(|| {
+            format!(
+                "failed to create vgm output directory: {}",
+                parent.display()
+            )
+        })?;
+    }
+
+    let bytes = vgm_bytes(project)?;
+    fs::write(path, bytes)
+        .with_context(|| format!("failed to write vgm file: {}", path.display()))?;
+    info!("vgm export completed");
+    Ok(())
+}
+
+/// Encodes the project's chip pattern and step clips as a VGM 1.71 stream of timed
+/// register writes, with a GD3 tag and the order list loop point.
+#[instrument(skip(project), fields(project_id = %project.id))]
+pub fn vgm_bytes(project: &Project) -> Result<Vec<u8>> {
+    let channels = collect_channel_notes(project);
+    if channels.is_empty() {
+        anyhow::bail!("project has no chip pattern clips that map to a vgm chip");
+    }
+
+    let mut chips: Vec<VgmChip> = channels.keys().map(|(chip, _)| *chip).collect();
+    chips.dedup();
+
+    let mut events = Vec::new();
+    for ((chip, voice), mut notes) in channels {
+        notes.sort_by_key(|note| (note.start_sample, note.pitch));
+        for index in 0..notes.len() {
+            // Chip channels are monophonic: a new note cuts the previous one.
+            let end_sample = notes
+                .get(index + 1)
+                .map_or(notes[index].end_sample, |next| {
+                    notes[index].end_sample.min(next.start_sample)
+                });
+            let note = notes[index];
+            if end_sample <= note.start_sample {
+                continue;
+            }
+            events.push(VgmEvent {
+                sample: note.start_sample,
+                chip,
+                voice,
+                kind: VgmEventKind::NoteOn {
+                    pitch: note.pitch,
+                    velocity: note.velocity,
+                    frequency_hz: note.frequency_hz,
+                },
+            });
+            events.push(VgmEvent {
+                sample: end_sample,
+                chip,
+                voice,
+                kind: VgmEventKind::NoteOff,
+            });
+        }
+    }
+    events.sort_by_key(VgmEvent::sort_key);
+
+    let to_samples = |tick: u64| ticks_to_samples(tick, project.bpm, project.ppq, VGM_SAMPLE_RATE);
+    let loop_samples = project
+        .song_loop_ticks()
+        .map(|(start, end)| (to_samples(start), to_samples(end)));
+    let total_samples = events
+        .iter()
+        .map(|event| event.sample)
+        .chain(loop_samples.map(|(_, end)| end))
+        .chain(std::iter::once(to_samples(project.max_tick())))
+        .max()
+        .unwrap_or_default();
+
+    let mut writer = VgmWriter::default();
+    for chip in &chips {
+        writer.init_chip(*chip);
+    }
+
+    let mut loop_offset = None;
+    for event in &events {
+        if let Some((loop_start, _)) = loop_samples
+            && loop_offset.is_none()
+            && event.sample >= loop_start
+        {
+            writer.wait_until(loop_start);
+            loop_offset = Some(writer.data.len());
+        }
+        writer.wait_until(event.sample);
+        match event.kind {
+            VgmEventKind::NoteOn {
+                pitch,
+                velocity,
+                frequency_hz,
+            } => {
+                writer.note_on(event.chip, event.voice, pitch, velocity, frequency_hz);
+            }
+            VgmEventKind::NoteOff => writer.note_off(event.chip, event.voice),
+        }
+    }
+    if let Some((loop_start, _)) = loop_samples
+        && loop_offset.is_none()
+    {
+        writer.wait_until(loop_start);
+        loop_offset = Some(writer.data.len());
+    }
+    writer.wait_until(total_samples);
+    writer.data.push(0x66);
+
+    let mut bytes = vec![0_u8; VGM_DATA_OFFSET];
+    bytes[0..4].copy_from_slice(b"Vgm ");
+    put_u32(&mut bytes, 0x08, VGM_VERSION);
+    put_u32(
+        &mut bytes,
+        0x18,
+        total_samples.min(u64::from(u32::MAX)) as u32,
+    );
+    put_u32(&mut bytes, 0x34, (VGM_DATA_OFFSET - 0x34) as u32);
+    for chip in &chips {
+        put_u32(&mut bytes, chip.header_clock_offset(), chip.clock());
+        match chip {
+            VgmChip::Sn76489 => {
+                bytes[0x28..0x2A].copy_from_slice(&0x0009_u16.to_le_bytes());
+                bytes[0x2A] = 16;
+            }
+            VgmChip::Ay8910 => {
+                bytes[0x78] = 0x00;
+                bytes[0x79] = 0x01;
+            }
+            VgmChip::Ym2612 | VgmChip::GameBoyDmg | VgmChip::NesApu => {}
+        }
+    }
+    bytes.extend_from_slice(&writer.data);
+
+    if let (Some(offset), Some((loop_start, _))) = (loop_offset, loop_samples) {
+        let absolute = VGM_DATA_OFFSET + offset;
+        put_u32(&mut bytes, 0x1C, (absolute - 0x1C) as u32);
+        put_u32(
+            &mut bytes,
+            0x20,
+            total_samples
+                .saturating_sub(loop_start)
+                .min(u64::from(u32::MAX)) as u32,
+        );
+    }
+
+    let gd3_offset = bytes.len();
+    put_u32(&mut bytes, 0x14, (gd3_offset - 0x14) as u32);
+    let system = chips
+        .iter()
+        .map(|chip| chip.display_name())
+        .collect::<Vec<_>>()
+        .join(" + ");
+    bytes.extend_from_slice(&gd3_tag(&project.title, &system));
+    let eof_offset = (bytes.len() - 4) as u32;
+    put_u32(&mut bytes, 0x04, eof_offset);
+
+    debug!(
+        chips = chips.len(),
+        events = events.len(),
+        total_samples,
+        looped = loop_offset.is_some(),
+        "vgm stream encoded"
+    );
+    Ok(bytes)
+}
+
+/// Assigns each track a free voice on the chip named by its clips and gathers the
+/// notes it plays, in VGM sample time.
+fn collect_channel_notes(project: &Project) -> BTreeMap<(VgmChip, Voice), Vec<VgmNote>> {
+    let mut channels: BTreeMap<(VgmChip, Voice), Vec<VgmNote>> = BTreeMap::new();
+    let mut assigned: BTreeMap<(usize, VgmChip), Voice> = BTreeMap::new();
+    let to_samples = |tick: u64| ticks_to_samples(tick, project.bpm, project.ppq, VGM_SAMPLE_RATE);
+
+    for (track_index, track) in project.tracks.iter().enumerate() {
+        if !track.enabled || track.mute || track.hidden {
+            continue;
+        }
+
+        for clip in track.clips.iter().filter(|clip| !clip.disabled) {
+            let (source_chip, content): (&str, Vec<MidiNote>) = match project.resolved_payload(clip)
+            {
+                Some(ClipPayload::Pattern(pattern)) => (
+                    &pattern.source_chip,
+                    pattern
+                        .notes
+                        .iter()
+                        .map(|note| apply_pattern_macros(note, pattern, project.ppq))
+                        .collect(),
+                ),
+                Some(ClipPayload::Steps(steps)) => (
+                    &steps.source_chip,
+                    step_notes(steps, clip.content_length_ticks(), project.ppq)
+                        .into_iter()
+                        .map(|step_note| step_note.note)
+                        .collect(),
+                ),
+                _ => continue,
+            };
+            let Some(chip) = VgmChip::for_source(source_chip) else {
+                debug!(clip_id = %clip.id, source_chip, "clip chip has no vgm mapping, skipping");
+                continue;
+            };
+
+            let voice =
+                match assigned.get(&(track_index, chip)) {
+                    Some(voice) => *voice,
+                    None => {
+                        let taken: Vec<Voice> = assigned
+                            .iter()
+                            .filter(|((_, assigned_chip), _)| *assigned_chip == chip)
+                            .map(|(_, voice)| *voice)
+                            .collect();
+                        let Some(voice) = chip.voices().iter().copied().find(|voice| {
+                            voice.matches_source(source_chip) && !taken.contains(voice)
+                        }) else {
+                            warn!(
+                                track_id = %track.id,
+                                chip = chip.display_name(),
+                                "no free vgm chip voice for track, skipping"
+                            );
+                            continue;
+                        };
+                        assigned.insert((track_index, chip), voice);
+                        voice
+                    }
+                };
+
+            let target = channels.entry((chip, voice)).or_default();
+            for note in clip.arrange_notes(&content) {
+                let start_tick = clip.start_tick.saturating_add(note.start_tick);
+                let end_tick = clip.start_tick.saturating_add(note.end_tick());
+                let pitch = note.pitch.min(127);
+                // Noise voices pick a rate from the key, so tuning does not apply.
+                let frequency_hz = if voice == Voice::Noise {
+                    equal_tempered_hz(pitch)
+                } else {
+                    let Some(frequency_hz) = project.tuning.frequency_hz(pitch) else {
+                        continue;
+                    };
+                    frequency_hz
+                };
+                target.push(VgmNote {
+                    start_sample: to_samples(start_tick),
+                    end_sample: to_samples(end_tick),
+                    pitch,
+                    velocity: note.velocity.min(127),
+                    frequency_hz,
+                });
+            }
+        }
+    }
+
+    channels
+}
+
+impl VgmWriter {
+    fn wait_until(&mut self, sample: u64) {
+        while self.current_sample < sample {
+            let remaining = sample - self.current_sample;
+            let wait = remaining.min(u64::from(u16::MAX));
+            match wait {
+                1..=16 => self.data.push(0x70 + (wait - 1) as u8),
+                735 => self.data.push(0x62),
+                882 => self.data.push(0x63),
+                _ => {
+                    self.data.push(0x61);
+                    self.data.extend_from_slice(&(wait as u16).to_le_bytes());
+                }
+            }
+            self.current_sample += wait;
+        }
+    }
+
+    fn sn76489(&mut self, value: u8) {
+        self.data.extend_from_slice(&[0x50, value]);
+    }
+
+    fn ym2612(&mut self, port: u8, register: u8, value: u8) {
+        self.data
+            .extend_from_slice(&[0x52 + (port & 1), register, value]);
+    }
+
+    fn ay8910(&mut self, register: u8, value: u8) {
+        self.data.extend_from_slice(&[0xA0, register, value]);
+    }
+
+    fn gameboy(&mut self, address: u16, value: u8) {
+        self.data
+            .extend_from_slice(&[0xB3, (address - 0xFF10) as u8, value]);
+    }
+
+    fn nes(&mut self, address: u16, value: u8) {
+        self.data
+            .extend_from_slice(&[0xB4, (address - 0x4000) as u8, value]);
+    }
+
+    fn init_chip(&mut self, chip: VgmChip) {
+        match chip {
+            VgmChip::Sn76489 => {
+                for channel in 0..4 {
+                    self.sn76489(0x9F | (channel << 5));
+                }
+            }
+            VgmChip::Ym2612 => {
+                self.ym2612(0, 0x22, 0x00);
+                self.ym2612(0, 0x27, 0x00);
+                self.ym2612(0, 0x2B, 0x00);
+                for channel in 0..6_u8 {
+                    let (port, slot) = (channel / 3, channel % 3);
+                    self.ym2612(0, 0x28, slot + port * 4);
+                    for operator in YM2612_OPERATOR_OFFSETS {
+                        let total_level = if operator == YM2612_CARRIER_OFFSET {
+                            0x00
+                        } else {
+                            0x7F
+                        };
+                        self.ym2612(port, 0x30 + operator + slot, 0x01);
+                        self.ym2612(port, 0x40 + operator + slot, total_level);
+                        self.ym2612(port, 0x50 + operator + slot, 0x1F);
+                        self.ym2612(port, 0x60 + operator + slot, 0x00);
+                        self.ym2612(port, 0x70 + operator + slot, 0x00);
+                        self.ym2612(port, 0x80 + operator + slot, 0x08);
+                        self.ym2612(port, 0x90 + operator + slot, 0x00);
+                    }
+                    // Algorithm 7 with only operator 4 audible gives a clean sine voice.
+                    self.ym2612(port, 0xB0 + slot, 0x07);
+                    self.ym2612(port, 0xB4 + slot, 0xC0);
+                }
+            }
+            VgmChip::Ay8910 => {
+                self.ay8910(0x07, 0x38);
+                for register in 0x08..=0x0A {
+                    self.ay8910(register, 0x00);
+                }
+            }
+            VgmChip::GameBoyDmg => {
+                self.gameboy(0xFF26, 0x80);
+                self.gameboy(0xFF24, 0x77);
+                self.gameboy(0xFF25, 0xFF);
+                self.gameboy(0xFF1A, 0x00);
+                for (index, value) in GAMEBOY_WAVE_RAM.iter().enumerate() {
+                    self.gameboy(0xFF30 + index as u16, *value);
+                }
+                for address in [0xFF12, 0xFF17, 0xFF21] {
+                    self.gameboy(address, 0x00);
+                }
+            }
+            VgmChip::NesApu => {
+                self.nes(0x4015, 0x00);
+                self.nes(0x4017, 0x40);
+                self.nes_channel_mask = 0;
+            }
+        }
+    }
+
+    fn note_on(&mut self, chip: VgmChip, voice: Voice, pitch: u8, velocity: u8, frequency: f64) {
+        let volume = velocity_to_volume(velocity);
+        match (chip, voice) {
+            (VgmChip::Sn76489, Voice::Tone(channel)) => {
+                let period = period_for(SN76489_CLOCK, 32.0, frequency, 1, 1_023);
+                self.sn76489(0x80 | (channel << 5) | (period & 0x0F) as u8);
+                self.sn76489(((period >> 4) & 0x3F) as u8);
+                self.sn76489(0x90 | (channel << 5) | (15 - volume));
+            }
+            (VgmChip::Sn76489, Voice::Noise) => {
+                let rate = match pitch {
+                    60.. => 0,
+                    48..=59 => 1,
+                    _ => 2,
+                };
+                self.sn76489(0xE4 | rate);
+                self.sn76489(0xF0 | (15 - volume));
+            }
+            (VgmChip::Ym2612, Voice::Fm(channel)) => {
+                let (port, slot) = (channel / 3, channel % 3);
+                let (block, fnum) = ym2612_block_fnum(frequency);
+                let total_level = (127 - velocity.min(127)) / 2;
+                self.ym2612(0, 0x28, slot + port * 4);
+                self.ym2612(port, 0x40 + YM2612_CARRIER_OFFSET + slot, total_level);
+                self.ym2612(port, 0xA4 + slot, (block << 3) | (fnum >> 8) as u8);
+                self.ym2612(port, 0xA0 + slot, (fnum & 0xFF) as u8);
+                self.ym2612(0, 0x28, 0xF0 | (slot + port * 4));
+            }
+            (VgmChip::Ay8910, Voice::Tone(channel)) => {
+                let period = period_for(AY8910_CLOCK, 16.0, frequency, 1, 4_095);
+                self.ay8910(channel * 2, (period & 0xFF) as u8);
+                self.ay8910(channel * 2 + 1, ((period >> 8) & 0x0F) as u8);
+                self.ay8910(0x08 + channel, volume);
+            }
+            (VgmChip::GameBoyDmg, Voice::Tone(channel)) => {
+                let base = if channel == 0 { 0xFF10 } else { 0xFF15 };
+                let period = 2_048 - period_for(GAMEBOY_TONE_CLOCK, 1.0, frequency, 1, 2_047);
+                if channel == 0 {
+                    self.gameboy(0xFF10, 0x00);
+                }
+                self.gameboy(base + 1, 0x80);
+                self.gameboy(base + 2, volume << 4);
+                self.gameboy(base + 3, (period & 0xFF) as u8);
+                self.gameboy(base + 4, 0x80 | ((period >> 8) & 0x07) as u8);
+            }
+            (VgmChip::GameBoyDmg, Voice::Wave) => {
+                let period = 2_048 - period_for(65_536, 1.0, frequency, 1, 2_047);
+                self.gameboy(0xFF1A, 0x80);
+                self.gameboy(0xFF1C, 0x20);
+                self.gameboy(0xFF1D, (period & 0xFF) as u8);
+                self.gameboy(0xFF1E, 0x80 | ((period >> 8) & 0x07) as u8);
+            }
+            (VgmChip::GameBoyDmg, Voice::Noise) => {
+                let shift = ((84 - i16::from(pitch)) / 6).clamp(0, 13) as u8;
+                self.gameboy(0xFF21, volume << 4);
+                self.gameboy(0xFF22, (shift << 4) | 0x01);
+                self.gameboy(0xFF23, 0x80);
+            }
+            (VgmChip::NesApu, Voice::Tone(channel)) => {
+                let base = 0x4000 + u16::from(channel) * 4;
+                let timer = period_for(NES_APU_CLOCK, 16.0, frequency, 9, 2_048) - 1;
+                self.nes_enable(channel, true);
+                self.nes(base, 0xB0 | volume);
+                self.nes(base + 1, 0x08);
+                self.nes(base + 2, (timer & 0xFF) as u8);
+                self.nes(base + 3, 0xF8 | ((timer >> 8) & 0x07) as u8);
+            }
+            (VgmChip::NesApu, Voice::Triangle) => {
+                let timer = period_for(NES_APU_CLOCK, 32.0, frequency, 3, 2_048) - 1;
+                self.nes_enable(2, true);
+                self.nes(0x4008, 0xFF);
+                self.nes(0x400A, (timer & 0xFF) as u8);
+                self.nes(0x400B, 0xF8 | ((timer >> 8) & 0x07) as u8);
+            }
+            (VgmChip::NesApu, Voice::Noise) => {
+                let period_index = ((108 - i16::from(pitch)) / 6).clamp(0, 15) as u8;
+                self.nes_enable(3, true);
+                self.nes(0x400C, 0x30 | volume);
+                self.nes(0x400E, period_index);
+                self.nes(0x400F, 0xF8);
+            }
+            _ => warn!(?chip, ?voice, "unsupported vgm voice for note on"),
+        }
+    }
+
+    fn note_off(&mut self, chip: VgmChip, voice: Voice) {
+        match (chip, voice) {
+            (VgmChip::Sn76489, Voice::Tone(channel)) => self.sn76489(0x9F | (channel << 5)),
+            (VgmChip::Sn76489, Voice::Noise) => self.sn76489(0xFF),
+            (VgmChip::Ym2612, Voice::Fm(channel)) => {
+                self.ym2612(0, 0x28, channel % 3 + (channel / 3) * 4);
+            }
+            (VgmChip::Ay8910, Voice::Tone(channel)) => self.ay8910(0x08 + channel, 0x00),
+            (VgmChip::GameBoyDmg, Voice::Tone(0)) => self.gameboy(0xFF12, 0x00),
+            (VgmChip::GameBoyDmg, Voice::Tone(_)) => self.gameboy(0xFF17, 0x00),
+            (VgmChip::GameBoyDmg, Voice::Wave) => self.gameboy(0xFF1A, 0x00),
+            (VgmChip::GameBoyDmg, Voice::Noise) => self.gameboy(0xFF21, 0x00),
+            (VgmChip::NesApu, Voice::Tone(channel)) => self.nes_enable(channel, false),
+            (VgmChip::NesApu, Voice::Triangle) => self.nes_enable(2, false),
+            (VgmChip::NesApu, Voice::Noise) => self.nes_enable(3, false),
+            _ => warn!(?chip, ?voice, "unsupported vgm voice for note off"),
+        }
+    }
+
+    /// Gates an APU channel through $4015; disabling also silences it immediately.
+    fn nes_enable(&mut self, channel: u8, enabled: bool) {
+        let bit = 1 << channel;
+        if enabled {
+            self.nes_channel_mask |= bit;
+        } else {
+            self.nes_channel_mask &= !bit;
+        }
+        self.nes(0x4015, self.nes_channel_mask);
+    }
+}
+
+fn velocity_to_volume(velocity: u8) -> u8 {
+    ((u16::from(velocity.min(127)) * 15 + 63) / 127).max(1) as u8
+}
+
+/// Chip divider period for `frequency`, i.e. `clock / (divider * frequency)`.
+pub(crate) fn period_for(clock: u32, divider: f64, frequency: f64, min: u16, max: u16) -> u16 {
+    let period = f64::from(clock) / (divider * frequency.max(1.0));
+    (period.round() as u64).clamp(u64::from(min), u64::from(max)) as u16
+}
+
+fn ym2612_block_fnum(frequency: f64) -> (u8, u16) {
+    for block in 0..8_u8 {
+        let fnum = frequency * 144.0 * 2_f64.powi(21 - i32::from(block)) / f64::from(YM2612_CLOCK);
+        if fnum <= 2_047.0 {
+            return (block, fnum.round().clamp(1.0, 2_047.0) as u16);
+        }
+    }
+    (7, 2_047)
+}
+
+fn put_u32(bytes: &mut [u8], offset: usize, value: u32) {
+    bytes[offset..offset + 4].copy_from_slice(&value.to_le_bytes());
+}
+
+fn gd3_tag(title: &str, system: &str) -> Vec<u8> {
+    let fields = [title, "", "", "", system, "", "", "", "", "Voltlane", ""];
+    let mut payload = Vec::new();
+    for field in fields {
+        for unit in field.encode_utf16().chain(std::iter::once(0)) {
+            payload.extend_from_slice(&unit.to_le_bytes());
+        }
+    }
+
+    let mut tag = Vec::with_capacity(payload.len() + 12);
+    tag.extend_from_slice(b"Gd3 ");
+    tag.extend_from_slice(&GD3_VERSION.to_le_bytes());
+    tag.extend_from_slice(&(payload.len() as u32).to_le_bytes());
+    tag.extend_from_slice(&payload);
+    tag
+}
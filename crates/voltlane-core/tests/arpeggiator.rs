@@ -0,0 +1,233 @@
+mod common;
+
+use common::engine_with_clips;
+use uuid::Uuid;
+use voltlane_core::{
+    ArpeggiatorOptions, ArpeggioMode, ChordKind, Engine, EngineError, Key, ScaleMode,
+    model::{Clip, ClipPayload, DEFAULT_TRACKER_LINES_PER_BEAT, MidiNote, PatternClip, TrackKind},
+};
+
+fn held(pitch: u8, start_tick: u64, length_ticks: u64) -> MidiNote {
+    MidiNote {
+        pitch,
+        velocity: 100,
+        start_tick,
+        length_ticks,
+        channel: 0,
+        release_velocity: None,
+    }
+}
+
+/// A one-bar pattern clip on a chip track.
+fn engine_with(notes: Vec<MidiNote>) -> (Engine, Uuid, Uuid) {
+    let clip = Clip::new(
+        "chords",
+        0,
+        1_920,
+        ClipPayload::Pattern(PatternClip {
+            source_chip: "2a03".to_string(),
+            instrument: None,
+            notes,
+            rows: Vec::new(),
+            macros: Vec::new(),
+            lines_per_beat: DEFAULT_TRACKER_LINES_PER_BEAT,
+        }),
+    );
+    let clip_id = clip.id;
+    let (engine, track_id) = engine_with_clips(TrackKind::Chip, vec![clip]);
+    (engine, track_id, clip_id)
+}
+
+fn c_major_bar() -> Vec<MidiNote> {
+    vec![held(64, 0, 1_920), held(60, 0, 1_920), held(67, 0, 1_920)]
+}
+
+fn arpeggio(notes: Vec<MidiNote>, options: &ArpeggiatorOptions) -> Vec<u8> {
+    let (mut engine, track_id, clip_id) = engine_with(notes);
+    let clip = engine
+        .arpeggiate_clip(track_id, clip_id, options)
+        .expect("arpeggiate should succeed");
+    let ClipPayload::Pattern(pattern) = clip.payload else {
+        panic!("arpeggiated clip should stay a pattern");
+    };
+    pattern.notes.iter().map(|note| note.pitch).collect()
+}
+
+#[test]
+fn arpeggiator_modes_bake_held_chords_into_single_notes() {
+    let options = ArpeggiatorOptions {
+        mode: ArpeggioMode::Up,
+        rate_ticks: 240,
+        octaves: 1,
+        gate_percent: 50.0,
+        seed: 0,
+    };
+    assert_eq!(
+        arpeggio(c_major_bar(), &options),
+        vec![60, 64, 67, 60, 64, 67, 60, 64]
+    );
+    assert_eq!(
+        arpeggio(
+            c_major_bar(),
+            &ArpeggiatorOptions {
+                mode: ArpeggioMode::Down,
+                octaves: 2,
+                ..options.clone()
+            }
+        ),
+        vec![79, 76, 72, 67, 64, 60, 79, 76]
+    );
+    assert_eq!(
+        arpeggio(
+            c_major_bar(),
+            &ArpeggiatorOptions {
+                mode: ArpeggioMode::UpDown,
+                ..options.clone()
+            }
+        ),
+        vec![60, 64, 67, 64, 60, 64, 67, 64]
+    );
+    let random = ArpeggiatorOptions {
+        mode: ArpeggioMode::Random,
+        seed: 42,
+        ..options.clone()
+    };
+    let rolled = arpeggio(c_major_bar(), &random);
+    assert_eq!(rolled, arpeggio(c_major_bar(), &random));
+    assert!(rolled.iter().all(|pitch| [60, 64, 67].contains(pitch)));
+
+    // Played top-down, then a chord change half way restarts the pattern.
+    let changes = vec![
+        held(67, 0, 960),
+        held(64, 240, 720),
+        held(60, 480, 480),
+        held(65, 960, 960),
+        held(69, 960, 960),
+    ];
+    assert_eq!(
+        arpeggio(
+            changes,
+            &ArpeggiatorOptions {
+                mode: ArpeggioMode::AsPlayed,
+                ..options.clone()
+            }
+        ),
+        vec![67, 67, 67, 64, 65, 69, 65, 69]
+    );
+
+    let (mut engine, track_id, clip_id) = engine_with(c_major_bar());
+    let clip = engine
+        .arpeggiate_clip(track_id, clip_id, &options)
+        .expect("arpeggiate should succeed");
+    let ClipPayload::Pattern(pattern) = clip.payload else {
+        panic!("arpeggiated clip should stay a pattern");
+    };
+    assert!(pattern.notes.iter().all(|note| note.length_ticks == 120));
+    let rows: Vec<(u32, Option<u8>)> = pattern
+        .rows
+        .iter()
+        .filter(|row| row.note.is_some())
+        .map(|row| (row.row, row.note))
+        .take(3)
+        .collect();
+    assert_eq!(
+        rows,
+        vec![(0, Some(60)), (2, Some(64)), (4, Some(67))],
+        "tracker rows follow the baked notes"
+    );
+    assert!(matches!(
+        engine.arpeggiate_clip(
+            track_id,
+            clip_id,
+            &ArpeggiatorOptions {
+                octaves: 0,
+                ..options
+            }
+        ),
+        Err(EngineError::InvalidArpeggioOctaves(0))
+    ));
+}
+
+#[test]
+fn chord_generator_stacks_diatonic_thirds_in_the_key() {
+    // C, D, B and an out-of-key F#, harmonized in C major.
+    let melody = vec![
+        held(60, 0, 480),
+        held(62, 480, 480),
+        held(71, 960, 480),
+        held(66, 1_440, 480),
+    ];
+    let (mut engine, track_id, clip_id) = engine_with(melody.clone());
+    let clip = engine
+        .generate_clip_chords(
+            track_id,
+            clip_id,
+            Key {
+                root: 0,
+                mode: ScaleMode::Major,
+            },
+            ChordKind::Triad,
+        )
+        .expect("chord generation should succeed");
+    let ClipPayload::Pattern(pattern) = clip.payload else {
+        panic!("chord clip should stay a pattern");
+    };
+    let chords: Vec<(u64, u8)> = pattern
+        .notes
+        .iter()
+        .map(|note| (note.start_tick, note.pitch))
+        .collect();
+    assert_eq!(
+        chords,
+        vec![
+            (0, 60),
+            (0, 64),
+            (0, 67),
+            (480, 62),
+            (480, 65),
+            (480, 69),
+            (960, 71),
+            (960, 74),
+            (960, 77),
+            // F# is lowered onto F before stacking.
+            (1_440, 65),
+            (1_440, 69),
+            (1_440, 72),
+        ]
+    );
+
+    let (mut engine, track_id, clip_id) = engine_with(melody);
+    let clip = engine
+        .generate_clip_chords(
+            track_id,
+            clip_id,
+            Key {
+                root: 9,
+                mode: ScaleMode::Minor,
+            },
+            ChordKind::Seventh,
+        )
+        .expect("seventh chords should succeed");
+    let ClipPayload::Pattern(pattern) = clip.payload else {
+        panic!("chord clip should stay a pattern");
+    };
+    let first: Vec<u8> = pattern
+        .notes
+        .iter()
+        .filter(|note| note.start_tick == 0)
+        .map(|note| note.pitch)
+        .collect();
+    assert_eq!(first, vec![60, 64, 67, 71], "C major seventh in A minor");
+    assert!(matches!(
+        engine.generate_clip_chords(
+            track_id,
+            clip_id,
+            Key {
+                root: 12,
+                mode: ScaleMode::Major,
+            },
+            ChordKind::Triad,
+        ),
+        Err(EngineError::InvalidKeyRoot(12))
+    ));
+}
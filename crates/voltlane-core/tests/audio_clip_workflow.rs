@@ -73,6 +73,7 @@ fn audio_clip_import_and_patch_updates_project_state() {
                 stretch_ratio: Some(1.5),
                 gain_db: Some(-3.0),
                 pan: Some(0.25),
+                ..voltlane_core::AudioClipPatch::default()
             },
         )
         .expect("audio patch should succeed");
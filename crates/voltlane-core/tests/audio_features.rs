@@ -0,0 +1,137 @@
+use std::f32::consts::TAU;
+
+use tempfile::tempdir;
+use voltlane_core::{
+    Key, ScaleMode,
+    assets::{DecodedAudio, analyze_audio_file_with_cache},
+    audio_features::analyze_audio_features,
+};
+
+const SAMPLE_RATE: u32 = 48_000;
+
+fn decoded(samples: Vec<f32>) -> DecodedAudio {
+    DecodedAudio {
+        sample_rate: SAMPLE_RATE,
+        channels: 1,
+        samples,
+    }
+}
+
+/// A C major triad struck on every beat at `bpm`, decaying between hits.
+fn triad_pulse(bpm: f32, seconds: f32) -> Vec<f32> {
+    let beat = 60.0 / bpm;
+    (0..(seconds * SAMPLE_RATE as f32) as usize)
+        .map(|frame| {
+            let time = frame as f32 / SAMPLE_RATE as f32;
+            let decay = (-(time % beat) * 8.0).exp();
+            [261.63_f32, 329.63, 392.0]
+                .iter()
+                .map(|hz| (time * hz * TAU).sin())
+                .sum::<f32>()
+                * decay
+                * 0.25
+        })
+        .collect()
+}
+
+#[test]
+fn levels_loudness_and_silence_trim_follow_a_padded_tone() {
+    // A quarter second of silence either side of one second of -6 dBFS 1 kHz.
+    let tone: Vec<f32> = (0..SAMPLE_RATE as usize)
+        .map(|frame| (frame as f32 / SAMPLE_RATE as f32 * 1_000.0 * TAU).sin() * 0.5)
+        .collect();
+    let lufs = analyze_audio_features(&decoded(tone.clone()))
+        .integrated_lufs
+        .expect("tone should pass the gates");
+    assert!(
+        (lufs + 9.03).abs() < 0.1,
+        "1 kHz reads at its RMS level: {lufs}"
+    );
+
+    let pad = SAMPLE_RATE as usize / 4;
+    let mut samples = vec![0.0_f32; pad];
+    samples.extend(tone);
+    samples.extend(vec![0.0; pad]);
+    let features = analyze_audio_features(&decoded(samples));
+    assert!(
+        (features.peak_dbfs + 6.02).abs() < 0.05,
+        "{}",
+        features.peak_dbfs
+    );
+    // The tone's -9.03 dB RMS, diluted by the silent padding.
+    assert!(
+        (features.rms_dbfs + 10.79).abs() < 0.05,
+        "{}",
+        features.rms_dbfs
+    );
+    // Blocks of pure silence are gated out; those only partly over the tone count.
+    let padded = features
+        .integrated_lufs
+        .expect("tone should pass the gates");
+    assert!(
+        padded < lufs && padded > lufs - 1.0,
+        "padded tone reads {padded}"
+    );
+    assert!((features.trim_start_seconds - 0.25).abs() < 1e-3);
+    assert!((features.trim_end_seconds - 1.25).abs() < 1e-3);
+
+    let silent = analyze_audio_features(&decoded(vec![0.0; SAMPLE_RATE as usize]));
+    assert_eq!(silent.integrated_lufs, None);
+    assert_eq!(silent.peak_dbfs, -120.0);
+    assert_eq!(silent.bpm, None);
+    assert_eq!(
+        (silent.trim_start_seconds, silent.trim_end_seconds),
+        (0.0, 0.0)
+    );
+}
+
+#[test]
+fn tempo_and_key_are_estimated_and_cached_with_the_peaks() {
+    let features = analyze_audio_features(&decoded(triad_pulse(100.0, 8.0)));
+    let bpm = features.bpm.expect("a steady pulse should have a tempo");
+    assert!((bpm - 100.0).abs() < 1.0, "estimated {bpm} bpm");
+    let key = features.key.expect("a triad should have a key");
+    assert_eq!(
+        key.key,
+        Key {
+            root: 0,
+            mode: ScaleMode::Major,
+        }
+    );
+
+    let temp = tempdir().expect("tempdir should be creatable");
+    let audio_path = temp.path().join("pulse.wav");
+    let cache_dir = temp.path().join("cache");
+    let spec = hound::WavSpec {
+        channels: 1,
+        sample_rate: SAMPLE_RATE,
+        bits_per_sample: 16,
+        sample_format: hound::SampleFormat::Int,
+    };
+    let mut writer =
+        hound::WavWriter::create(&audio_path, spec).expect("test wav should be creatable");
+    for sample in triad_pulse(100.0, 4.0) {
+        writer
+            .write_sample((sample * f32::from(i16::MAX)).round() as i16)
+            .expect("test wav sample write should succeed");
+    }
+    writer.finalize().expect("test wav finalize should succeed");
+
+    let analysis = analyze_audio_file_with_cache(&audio_path, &cache_dir, 256)
+        .expect("analysis should succeed");
+    let cache_path = analysis.cache_path.clone().expect("cache path");
+    let mut cached: serde_json::Value =
+        serde_json::from_slice(&std::fs::read(&cache_path).expect("cache should be readable"))
+            .expect("cache should be json");
+    assert!(cached["features"]["integrated_lufs"].is_number());
+
+    // Caches written before feature analysis are regenerated.
+    cached
+        .as_object_mut()
+        .expect("cache object")
+        .remove("features");
+    std::fs::write(&cache_path, cached.to_string()).expect("cache should be writable");
+    let refreshed = analyze_audio_file_with_cache(&audio_path, &cache_dir, 256)
+        .expect("stale cache should regenerate");
+    assert_eq!(refreshed.features, analysis.features);
+}
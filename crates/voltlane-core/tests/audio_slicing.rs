@@ -0,0 +1,133 @@
+mod common;
+
+use common::{SAMPLE_RATE, engine_with_audio};
+use tempfile::TempDir;
+use uuid::Uuid;
+use voltlane_core::{
+    Engine, EngineError, TrackStatePatch,
+    export::render_project_samples,
+    model::{ClipPayload, Track, TrackKind},
+};
+
+/// Four decaying 330 Hz hits half a second apart, a beat each at 120 bpm.
+fn break_samples() -> Vec<f32> {
+    let hit_frames = SAMPLE_RATE as usize / 2;
+    (0..hit_frames * 4)
+        .map(|frame| {
+            let local = (frame % hit_frames) as f32 / SAMPLE_RATE as f32;
+            (local * 330.0 * std::f32::consts::TAU).sin() * (-local * 12.0).exp() * 0.8
+        })
+        .collect()
+}
+
+fn engine_with_break() -> (TempDir, Engine, Uuid, Uuid) {
+    engine_with_audio("Break", 120.0, &break_samples(), 960)
+}
+
+#[test]
+fn transient_slicing_cuts_an_audio_clip_at_each_hit() {
+    let (_temp, mut engine, track_id, clip_id) = engine_with_break();
+    let slices = engine
+        .slice_audio_clip_at_transients(track_id, clip_id, 0.5)
+        .expect("transient slicing should succeed");
+
+    assert_eq!(slices.len(), 4, "one slice per hit");
+    assert_eq!(slices[0].id, clip_id, "the first slice keeps the clip id");
+    for (index, slice) in slices.iter().enumerate() {
+        let expected = 960 + index as u64 * 480;
+        assert!(
+            slice.start_tick.abs_diff(expected) <= 8,
+            "slice {index} starts at {} near {expected}",
+            slice.start_tick
+        );
+        let ClipPayload::Audio(audio) = &slice.payload else {
+            panic!("slices should stay audio");
+        };
+        assert!((audio.trim_start_seconds - index as f64 * 0.5).abs() < 0.01);
+    }
+    assert_eq!(slices.last().expect("slices").end_tick(), 960 + 1_920);
+
+    let single = slices[1].id;
+    assert!(matches!(
+        engine.slice_audio_clip_at_transients(track_id, single, 0.5),
+        Err(EngineError::NoTransients(id)) if id == single
+    ));
+}
+
+#[test]
+fn sampler_slicing_retriggers_the_hits_from_a_midi_clip() {
+    let (_temp, mut engine, audio_track_id, clip_id) = engine_with_break();
+    let midi_track = Track::new("Slices", "#4fb8ff", TrackKind::Midi);
+    let midi_track_id = midi_track.id;
+    let mut project = engine.project().clone();
+    project.tracks.push(midi_track);
+    engine.replace_project(project);
+
+    let original = render_project_samples(engine.project(), 0.0);
+    let midi_clip = engine
+        .slice_audio_clip_to_sampler(audio_track_id, clip_id, midi_track_id, 0.5, 36)
+        .expect("sampler slicing should succeed");
+    assert_eq!((midi_clip.start_tick, midi_clip.length_ticks), (960, 1_920));
+    let ClipPayload::Midi(midi) = &midi_clip.payload else {
+        panic!("slices should be played by a midi clip");
+    };
+    assert_eq!(midi.instrument.as_deref(), Some("Break"));
+    let pitches: Vec<u8> = midi.notes.iter().map(|note| note.pitch).collect();
+    assert_eq!(pitches, vec![36, 37, 38, 39]);
+
+    let track = &engine.project().tracks[1];
+    let sampler = track.sampler(Some("Break")).expect("sampler should exist");
+    assert_eq!(sampler.zones.len(), 4);
+    assert!(sampler.zones.iter().all(|zone| zone.one_shot));
+    assert!((sampler.zones[2].sample_start_seconds - 1.0).abs() < 0.01);
+    assert!((sampler.zones[3].sample_end_seconds - 2.0).abs() < 1e-9);
+
+    engine
+        .patch_track_state(
+            audio_track_id,
+            TrackStatePatch {
+                hidden: None,
+                mute: Some(true),
+                solo: None,
+                enabled: None,
+            },
+        )
+        .expect("mute should apply");
+    let sliced = render_project_samples(engine.project(), 0.0);
+    let error = original
+        .iter()
+        .zip(&sliced)
+        .map(|(left, right)| (left - right).abs())
+        .fold(0.0_f32, f32::max);
+    assert!(error < 0.05, "the slices rebuild the break: {error}");
+
+    let again = engine
+        .slice_audio_clip_to_sampler(audio_track_id, clip_id, midi_track_id, 0.5, 36)
+        .expect("slicing the same clip again should succeed");
+    let ClipPayload::Midi(midi) = &again.payload else {
+        panic!("slices should be played by a midi clip");
+    };
+    assert_eq!(midi.instrument.as_deref(), Some("Break 2"));
+    let samplers: Vec<&str> = engine.project().tracks[1]
+        .samplers
+        .iter()
+        .map(|sampler| sampler.name.as_str())
+        .collect();
+    assert_eq!(
+        samplers,
+        vec!["Break", "Break 2"],
+        "the first sampler is kept"
+    );
+
+    let before = engine.project().clone();
+    assert!(matches!(
+        engine.slice_audio_clip_to_sampler(audio_track_id, clip_id, audio_track_id, 0.5, 36),
+        Err(EngineError::InvalidNoteTrack { track_id, kind: TrackKind::Audio })
+            if track_id == audio_track_id
+    ));
+    assert_eq!(
+        engine.project(),
+        &before,
+        "a rejected slice leaves the project alone"
+    );
+}
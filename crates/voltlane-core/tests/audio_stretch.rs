@@ -0,0 +1,106 @@
+mod common;
+
+use common::{SAMPLE_RATE, engine_with_audio, frequency, sine};
+use voltlane_core::{
+    AudioClipPatch, AudioStretchMode, Engine, EngineError,
+    export::render_project_samples,
+    model::{AudioClip, Project},
+    stretch::time_stretch,
+};
+
+fn rms(samples: &[f32]) -> f32 {
+    (samples.iter().map(|sample| sample * sample).sum::<f32>() / samples.len() as f32).sqrt()
+}
+
+#[test]
+fn wsola_changes_length_and_keeps_pitch() {
+    let source = sine(220.0, 1.0);
+    for output_len in [72_000, 30_000] {
+        let stretched = time_stretch(&source, output_len, SAMPLE_RATE);
+        assert_eq!(stretched.len(), output_len);
+        let hz = frequency(&stretched[output_len / 4..output_len * 3 / 4]);
+        assert!(
+            (hz - 220.0).abs() < 4.0,
+            "stretched to {output_len}: {hz} Hz"
+        );
+        let level = rms(&stretched[output_len / 4..output_len * 3 / 4]);
+        assert!(
+            (level - rms(&source)).abs() < 0.03,
+            "grains add up in phase, rms {level}"
+        );
+    }
+
+    // Too short for a pair of grains: falls back to resampling.
+    let short = time_stretch(&[0.0, 1.0], 3, SAMPLE_RATE);
+    assert_eq!(short, vec![0.0, 0.5, 1.0]);
+}
+
+#[test]
+fn audio_clips_stretch_and_shift_pitch_independently() {
+    let render = |patch: AudioClipPatch| {
+        let (_temp, mut engine, track_id, clip_id) =
+            engine_with_audio("Vocal", 120.0, &sine(220.0, 1.0), 0);
+        let clip = engine
+            .patch_audio_clip(track_id, clip_id, patch)
+            .expect("audio patch should succeed");
+        let samples = render_project_samples(engine.project(), 0.0);
+        let clip_frames = (clip.length_ticks as f32 / 960.0 * SAMPLE_RATE as f32) as usize;
+        frequency(&samples[clip_frames / 4..clip_frames * 3 / 4])
+    };
+    let stretched = |mode: AudioStretchMode, pitch_semitones: f32, pitch_cents: f32| {
+        render(AudioClipPatch {
+            stretch_ratio: Some(2.0),
+            stretch_mode: Some(mode),
+            pitch_semitones: Some(pitch_semitones),
+            pitch_cents: Some(pitch_cents),
+            ..AudioClipPatch::default()
+        })
+    };
+
+    let resampled = stretched(AudioStretchMode::Resample, 0.0, 0.0);
+    assert!(
+        (resampled - 110.0).abs() < 3.0,
+        "resample drops an octave: {resampled}"
+    );
+    let preserved = stretched(AudioStretchMode::PreservePitch, 0.0, 0.0);
+    assert!((preserved - 220.0).abs() < 4.0, "pitch kept: {preserved}");
+    let fifth = stretched(AudioStretchMode::PreservePitch, 7.0, 0.0);
+    assert!((fifth - 329.6).abs() < 5.0, "a fifth up: {fifth}");
+    let octave = stretched(AudioStretchMode::Resample, 11.0, 100.0);
+    assert!(
+        (octave - 220.0).abs() < 4.0,
+        "shift undoes the resample drop: {octave}"
+    );
+
+    let shifted = render(AudioClipPatch {
+        pitch_semitones: Some(-12.0),
+        ..AudioClipPatch::default()
+    });
+    assert!(
+        (shifted - 110.0).abs() < 3.0,
+        "shift without stretch: {shifted}"
+    );
+
+    let mut engine = Engine::new(Project::new("Stretch", 120.0, SAMPLE_RATE));
+    assert!(matches!(
+        engine.patch_audio_clip(
+            uuid::Uuid::new_v4(),
+            uuid::Uuid::new_v4(),
+            AudioClipPatch {
+                pitch_cents: Some(f32::NAN),
+                ..AudioClipPatch::default()
+            }
+        ),
+        Err(EngineError::InvalidAudioPitchShift(_))
+    ));
+
+    let unshifted = serde_json::to_value(AudioClip::default()).expect("clip should serialize");
+    for field in ["stretch_mode", "pitch_semitones", "pitch_cents"] {
+        assert!(
+            unshifted.get(field).is_none(),
+            "{field} left out by default"
+        );
+    }
+    let loaded: AudioClip = serde_json::from_value(unshifted).expect("clip should deserialize");
+    assert_eq!(loaded.stretch_mode, AudioStretchMode::Resample);
+}
@@ -0,0 +1,187 @@
+mod common;
+
+use common::{SAMPLE_RATE, engine_with_audio, write_wav};
+use tempfile::{TempDir, tempdir};
+use uuid::Uuid;
+use voltlane_core::{
+    AddTrackRequest, AudioClipPatch, Engine, EngineError, QuantizeOptions, TranscriptionOptions,
+    model::{ClipPayload, TrackKind},
+};
+
+/// Half-second notes with a few harmonics, each followed by a short breath.
+fn melody(notes: &[(u8, f32)]) -> Vec<f32> {
+    let note_frames = SAMPLE_RATE as usize / 2;
+    let voiced_frames = note_frames * 9 / 10;
+    let mut samples = Vec::with_capacity(note_frames * notes.len());
+    for (pitch, amplitude) in notes {
+        let hz = 440.0 * 2_f32.powf((f32::from(*pitch) - 69.0) / 12.0);
+        samples.extend((0..note_frames).map(|frame| {
+            if frame >= voiced_frames {
+                return 0.0;
+            }
+            let time = frame as f32 / SAMPLE_RATE as f32;
+            [1.0_f32, 0.5, 0.25]
+                .iter()
+                .enumerate()
+                .map(|(harmonic, gain)| {
+                    (time * hz * (harmonic + 1) as f32 * std::f32::consts::TAU).sin() * gain
+                })
+                .sum::<f32>()
+                * amplitude
+                / 1.75
+        }));
+    }
+    samples
+}
+
+fn engine_with_melody(notes: &[(u8, f32)]) -> (TempDir, Engine, Uuid, Uuid, Uuid) {
+    let (temp, mut engine, audio_track_id, clip_id) =
+        engine_with_audio("Hum", 120.0, &melody(notes), 480);
+    let chip_track = engine.add_track(AddTrackRequest {
+        name: "Lead".to_string(),
+        color: "#4fb8ff".to_string(),
+        kind: TrackKind::Chip,
+    });
+    (temp, engine, audio_track_id, clip_id, chip_track.id)
+}
+
+#[test]
+fn hummed_melody_becomes_quantized_midi_notes() {
+    let (_temp, mut engine, track_id, clip_id, target_id) =
+        engine_with_melody(&[(57, 0.6), (60, 0.2), (64, 0.6), (67, 0.6)]);
+    let clip = engine
+        .transcribe_audio_clip(
+            track_id,
+            clip_id,
+            target_id,
+            &TranscriptionOptions {
+                quantize: Some(QuantizeOptions::grid(120)),
+                ..TranscriptionOptions::default()
+            },
+        )
+        .expect("transcription should succeed");
+
+    assert_eq!((clip.start_tick, clip.length_ticks), (480, 1_920));
+    let ClipPayload::Midi(midi) = &clip.payload else {
+        panic!("transcription should write a midi clip");
+    };
+    let notes: Vec<(u8, u64)> = midi
+        .notes
+        .iter()
+        .map(|note| (note.pitch, note.start_tick))
+        .collect();
+    assert_eq!(notes, vec![(57, 0), (60, 480), (64, 960), (67, 1_440)]);
+    assert!(
+        midi.notes
+            .iter()
+            .all(|note| note.length_ticks == 360 || note.length_ticks == 480),
+        "notes last about as long as they were hummed"
+    );
+    assert_eq!(
+        midi.notes[0].velocity, 127,
+        "the loudest note sets full velocity"
+    );
+    // 9.5 dB below the loudest note, a quarter of the way down the velocity range.
+    assert!(
+        (95..=99).contains(&midi.notes[1].velocity),
+        "a quieter note gets a softer velocity: {}",
+        midi.notes[1].velocity
+    );
+}
+
+#[test]
+fn transcription_writes_chip_patterns_and_follows_pitch_shift() {
+    let (_temp, mut engine, track_id, clip_id, target_id) =
+        engine_with_melody(&[(60, 0.5), (62, 0.5)]);
+    engine
+        .patch_audio_clip(
+            track_id,
+            clip_id,
+            AudioClipPatch {
+                pitch_semitones: Some(12.0),
+                ..AudioClipPatch::default()
+            },
+        )
+        .expect("pitch shift should apply");
+    let clip = engine
+        .transcribe_audio_clip(
+            track_id,
+            clip_id,
+            target_id,
+            &TranscriptionOptions {
+                source_chip: Some("nes_apu".to_string()),
+                ..TranscriptionOptions::default()
+            },
+        )
+        .expect("transcription should succeed");
+    let ClipPayload::Pattern(pattern) = &clip.payload else {
+        panic!("a source chip should write a pattern clip");
+    };
+    assert_eq!(pattern.source_chip, "nes_apu");
+    let pitches: Vec<u8> = pattern.rows.iter().filter_map(|row| row.note).collect();
+    assert_eq!(pitches, vec![72, 74], "pitches follow the clip's shift");
+
+    engine
+        .patch_audio_clip(
+            track_id,
+            clip_id,
+            AudioClipPatch {
+                pitch_semitones: Some(-24.0),
+                ..AudioClipPatch::default()
+            },
+        )
+        .expect("pitch shift should apply");
+    let chip_options = |source_chip: &str| TranscriptionOptions {
+        source_chip: Some(source_chip.to_string()),
+        ..TranscriptionOptions::default()
+    };
+    let low = engine
+        .transcribe_audio_clip(track_id, clip_id, target_id, &chip_options(" sn76489 "))
+        .expect("transcription should succeed");
+    let ClipPayload::Pattern(pattern) = &low.payload else {
+        panic!("a source chip should write a pattern clip");
+    };
+    assert_eq!(pattern.source_chip, "sn76489");
+    let pitches: Vec<u8> = pattern.rows.iter().filter_map(|row| row.note).collect();
+    assert_eq!(
+        pitches,
+        vec![48, 50],
+        "notes below the chip move up by octaves"
+    );
+    assert!(matches!(
+        engine.transcribe_audio_clip(track_id, clip_id, target_id, &chip_options("kazoo")),
+        Err(EngineError::UnknownSourceChip(chip)) if chip == "kazoo"
+    ));
+    assert!(matches!(
+        engine.transcribe_audio_clip(track_id, clip_id, track_id, &chip_options("nes_apu")),
+        Err(EngineError::InvalidNoteTrack {
+            kind: TrackKind::Audio,
+            ..
+        })
+    ));
+
+    let temp = tempdir().expect("tempdir should be creatable");
+    let silent_path = temp.path().join("silence.wav");
+    write_wav(&silent_path, &melody(&[(60, 0.0)]));
+    let silent = engine
+        .import_audio_clip(
+            track_id,
+            "Silence".to_string(),
+            &silent_path,
+            4_800,
+            512,
+            None,
+            0.0,
+            0.0,
+        )
+        .expect("audio import should succeed");
+    assert!(matches!(
+        engine.transcribe_audio_clip(
+            track_id,
+            silent.id,
+            target_id,
+            &TranscriptionOptions::default()
+        ),
+        Err(EngineError::NoPitchedNotes(id)) if id == silent.id
+    ));
+}
@@ -0,0 +1,139 @@
+mod common;
+
+use common::{SAMPLE_RATE, engine_with_audio, frequency, sine, window};
+use voltlane_core::{
+    AudioClipPatch, AudioStretchMode, EngineError, WarpMarker, export::render_project_samples,
+    model::ClipPayload,
+};
+
+#[test]
+fn auto_warp_conforms_a_loop_to_project_beats() {
+    // Four beats at 120 bpm, played in a 100 bpm project.
+    let (_temp, mut engine, track_id, clip_id) =
+        engine_with_audio("Loop", 100.0, &sine(220.0, 2.0), 0);
+    engine
+        .patch_audio_clip(
+            track_id,
+            clip_id,
+            AudioClipPatch {
+                stretch_mode: Some(AudioStretchMode::PreservePitch),
+                ..AudioClipPatch::default()
+            },
+        )
+        .expect("stretch mode should apply");
+    let clip = engine
+        .auto_warp_audio_clip(track_id, clip_id, 120.0)
+        .expect("auto-warp should succeed");
+    assert_eq!(
+        clip.length_ticks, 1_920,
+        "four source beats fill four project beats"
+    );
+    let ClipPayload::Audio(audio) = &clip.payload else {
+        panic!("clip should stay audio");
+    };
+    assert_eq!(audio.source_bpm, Some(120.0));
+    assert_eq!(audio.warp_markers.len(), 5);
+    assert_eq!(
+        audio.warp_markers[2],
+        WarpMarker {
+            source_seconds: 1.0,
+            tick: 960,
+        }
+    );
+
+    let samples = render_project_samples(engine.project(), 0.0);
+    let hz = frequency(window(&samples, 0.2, 2.2));
+    assert!((hz - 220.0).abs() < 4.0, "pitch kept while slowed: {hz}");
+    assert_eq!(
+        samples.len(),
+        (2.4 * SAMPLE_RATE as f64).round() as usize,
+        "the loop ends on the fourth project beat, 2.4 s in"
+    );
+
+    let (left, right) = engine
+        .split_clip(track_id, clip_id, 960)
+        .expect("split should succeed");
+    let (ClipPayload::Audio(left), ClipPayload::Audio(right)) = (&left.payload, &right.payload)
+    else {
+        panic!("split halves should stay audio");
+    };
+    assert!(
+        (left.trim_end_seconds - 1.0).abs() < 1e-9,
+        "cut on the warped beat"
+    );
+    assert!((right.trim_start_seconds - 1.0).abs() < 1e-9);
+
+    assert!(matches!(
+        engine.auto_warp_audio_clip(track_id, clip_id, 0.0),
+        Err(EngineError::InvalidSourceBpm(_))
+    ));
+}
+
+#[test]
+fn warp_markers_stretch_each_segment_on_its_own() {
+    let (_temp, mut engine, track_id, clip_id) =
+        engine_with_audio("Loop", 120.0, &sine(220.0, 1.0), 0);
+    // First half at its own speed, second half slowed to twice its length.
+    let clip = engine
+        .set_audio_warp_markers(
+            track_id,
+            clip_id,
+            vec![
+                WarpMarker {
+                    source_seconds: 1.0,
+                    tick: 1_440,
+                },
+                WarpMarker {
+                    source_seconds: 0.0,
+                    tick: 0,
+                },
+                WarpMarker {
+                    source_seconds: 0.5,
+                    tick: 480,
+                },
+            ],
+        )
+        .expect("warp markers should apply");
+    assert_eq!(clip.length_ticks, 1_440);
+    let ClipPayload::Audio(audio) = &clip.payload else {
+        panic!("clip should stay audio");
+    };
+    let ticks: Vec<u64> = audio
+        .warp_markers
+        .iter()
+        .map(|marker| marker.tick)
+        .collect();
+    assert_eq!(ticks, vec![0, 480, 1_440], "markers are kept in tick order");
+    assert!((audio.source_seconds_at(960.0, 120.0, 480) - 0.75).abs() < 1e-9);
+
+    let samples = render_project_samples(engine.project(), 0.0);
+    let first = frequency(window(&samples, 0.05, 0.45));
+    let second = frequency(window(&samples, 0.55, 1.45));
+    assert!((first - 220.0).abs() < 4.0, "unstretched half: {first}");
+    assert!(
+        (second - 110.0).abs() < 3.0,
+        "resampled half drops an octave: {second}"
+    );
+
+    assert!(matches!(
+        engine.set_audio_warp_markers(
+            track_id,
+            clip_id,
+            vec![
+                WarpMarker {
+                    source_seconds: 0.6,
+                    tick: 0,
+                },
+                WarpMarker {
+                    source_seconds: 0.5,
+                    tick: 480,
+                },
+            ],
+        ),
+        Err(EngineError::InvalidWarpMarkers(_))
+    ));
+    let clip = engine
+        .set_audio_warp_markers(track_id, clip_id, Vec::new())
+        .expect("clearing markers should succeed");
+    assert_eq!(clip.length_ticks, 960, "back to the unwarped length");
+}
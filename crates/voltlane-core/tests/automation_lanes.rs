@@ -96,6 +96,7 @@ fn automation_clip_update_rejects_wrong_payload() {
             payload: ClipPayload::Midi(voltlane_core::MidiClip {
                 instrument: Some("Lead".to_string()),
                 notes: vec![],
+                controllers: Vec::new(),
             }),
         })
         .expect("add clip should succeed");
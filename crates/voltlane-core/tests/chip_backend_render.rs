@@ -9,14 +9,13 @@ use voltlane_core::{
 fn chip_project(source_chip: &str, macros: Vec<ChipMacroLane>) -> Project {
     let mut project = Project::new(format!("Chip {source_chip}"), 132.0, DEFAULT_SAMPLE_RATE);
     let mut track = Track::new("Chip", "#f57f20", TrackKind::Chip);
-    track.clips.push(Clip {
-        id: uuid::Uuid::new_v4(),
-        name: "chip-pattern".to_string(),
-        start_tick: 0,
-        length_ticks: 1_920,
-        disabled: false,
-        payload: ClipPayload::Pattern(PatternClip {
+    track.clips.push(Clip::new(
+        "chip-pattern",
+        0,
+        1_920,
+        ClipPayload::Pattern(PatternClip {
             source_chip: source_chip.to_string(),
+            instrument: None,
             notes: vec![
                 MidiNote {
                     pitch: 48,
@@ -24,6 +23,7 @@ fn chip_project(source_chip: &str, macros: Vec<ChipMacroLane>) -> Project {
                     start_tick: 0,
                     length_ticks: 360,
                     channel: 0,
+                    release_velocity: None,
                 },
                 MidiNote {
                     pitch: 55,
@@ -31,6 +31,7 @@ fn chip_project(source_chip: &str, macros: Vec<ChipMacroLane>) -> Project {
                     start_tick: 360,
                     length_ticks: 360,
                     channel: 0,
+                    release_velocity: None,
                 },
                 MidiNote {
                     pitch: 60,
@@ -38,13 +39,14 @@ fn chip_project(source_chip: &str, macros: Vec<ChipMacroLane>) -> Project {
                     start_tick: 720,
                     length_ticks: 360,
                     channel: 0,
+                    release_velocity: None,
                 },
             ],
             rows: Vec::new(),
             macros,
             lines_per_beat: 8,
         }),
-    });
+    ));
     project.tracks.push(track);
     project
 }
@@ -44,6 +44,7 @@ fn add_and_remove_note_roundtrip() {
                 start_tick: 180,
                 length_ticks: 90,
                 channel: 0,
+                release_velocity: None,
             },
         )
         .expect("add note should succeed");
@@ -0,0 +1,218 @@
+use midly::{MidiMessage, Smf, TrackEventKind};
+use uuid::Uuid;
+use voltlane_core::{
+    AddClipRequest, AddTrackRequest, ClipPlaybackPatch, Engine, EngineError,
+    export::{midi_bytes, render_project_samples},
+    model::{
+        ClipPayload, DEFAULT_SAMPLE_RATE, MidiClip, MidiNote, Project, SynthOscillator, SynthPatch,
+        SynthWaveform, TrackKind,
+    },
+};
+
+fn note(pitch: u8, start_tick: u64) -> MidiNote {
+    MidiNote {
+        pitch,
+        velocity: 100,
+        start_tick,
+        length_ticks: 240,
+        channel: 0,
+        release_velocity: None,
+    }
+}
+
+/// One bar of quarter notes on a sine patch, returned with its track and clip ids.
+fn quarter_note_clip(notes: Vec<MidiNote>) -> (Engine, Uuid, Uuid) {
+    let mut engine = Engine::new(Project::new("Playback", 120.0, DEFAULT_SAMPLE_RATE));
+    let track = engine.add_track(AddTrackRequest {
+        name: "Lead".to_string(),
+        color: "#8f6bff".to_string(),
+        kind: TrackKind::Midi,
+    });
+    engine
+        .upsert_synth_patch(
+            track.id,
+            SynthPatch {
+                name: "Sine".to_string(),
+                oscillators: vec![SynthOscillator {
+                    waveform: SynthWaveform::Sine,
+                    ..SynthOscillator::default()
+                }],
+                ..SynthPatch::default()
+            },
+        )
+        .expect("synth patch should be added");
+    let clip = engine
+        .add_clip(AddClipRequest {
+            track_id: track.id,
+            name: "Riff".to_string(),
+            start_tick: 0,
+            length_ticks: 1_920,
+            payload: ClipPayload::Midi(MidiClip {
+                instrument: Some("Sine".to_string()),
+                notes,
+                controllers: Vec::new(),
+            }),
+        })
+        .expect("midi clip add should succeed");
+    (engine, track.id, clip.id)
+}
+
+fn exported_notes(project: &Project) -> Vec<(u64, u8, u8)> {
+    let bytes = midi_bytes(project).expect("midi export should succeed");
+    let smf = Smf::parse(&bytes).expect("exported midi should parse");
+    let mut notes = Vec::new();
+    let mut tick = 0_u64;
+    for event in &smf.tracks[1] {
+        tick += u64::from(event.delta.as_int());
+        if let TrackEventKind::Midi {
+            message: MidiMessage::NoteOn { key, vel },
+            ..
+        } = event.kind
+        {
+            notes.push((tick, key.as_int(), vel.as_int()));
+        }
+    }
+    notes
+}
+
+fn energy(samples: &[f32], from_seconds: f64, to_seconds: f64) -> f32 {
+    let rate = f64::from(DEFAULT_SAMPLE_RATE);
+    let from = ((from_seconds * rate) as usize).min(samples.len());
+    let to = ((to_seconds * rate) as usize).min(samples.len());
+    let window = &samples[from..to];
+    window.iter().map(|sample| sample * sample).sum::<f32>() / window.len().max(1) as f32
+}
+
+#[test]
+fn offset_and_loop_place_clip_content_in_midi_export() {
+    let (mut engine, track_id, clip_id) = quarter_note_clip(vec![
+        note(60, 0),
+        note(62, 480),
+        note(64, 960),
+        note(65, 1_440),
+    ]);
+
+    engine
+        .move_clip(track_id, clip_id, 0, 960)
+        .expect("clip resize should succeed");
+    engine
+        .patch_clip_playback(
+            track_id,
+            clip_id,
+            ClipPlaybackPatch {
+                content_offset_ticks: Some(480),
+                ..ClipPlaybackPatch::default()
+            },
+        )
+        .expect("slip should succeed");
+    assert_eq!(
+        exported_notes(engine.project()),
+        vec![(0, 62, 100), (480, 64, 100)],
+        "the left edge reveals later content and the right edge hides the rest"
+    );
+
+    let clip = engine
+        .patch_clip_playback(
+            track_id,
+            clip_id,
+            ClipPlaybackPatch {
+                content_offset_ticks: Some(0),
+                loop_length_ticks: Some(Some(960)),
+                transpose_semitones: Some(2),
+                velocity_scale: Some(0.5),
+            },
+        )
+        .expect("loop, transpose and velocity scale should be accepted");
+    assert_eq!(clip.loop_length_ticks, Some(960));
+    engine
+        .move_clip(track_id, clip_id, 0, 2_400)
+        .expect("clip resize should succeed");
+    assert_eq!(
+        exported_notes(engine.project()),
+        vec![
+            (0, 62, 50),
+            (480, 64, 50),
+            (960, 62, 50),
+            (1_440, 64, 50),
+            (1_920, 62, 50),
+        ]
+    );
+    let ClipPayload::Midi(midi) = &engine.project().tracks[0].clips[0].payload else {
+        panic!("riff should stay a midi clip");
+    };
+    assert_eq!(
+        midi.notes[0].pitch, 60,
+        "clip settings do not rewrite notes"
+    );
+
+    assert!(
+        engine
+            .patch_clip_playback(
+                track_id,
+                clip_id,
+                ClipPlaybackPatch {
+                    velocity_scale: Some(f32::NAN),
+                    ..ClipPlaybackPatch::default()
+                },
+            )
+            .is_err()
+    );
+    let error = engine
+        .patch_clip_playback(
+            track_id,
+            clip_id,
+            ClipPlaybackPatch {
+                loop_length_ticks: Some(Some(1)),
+                ..ClipPlaybackPatch::default()
+            },
+        )
+        .expect_err("a one-tick loop should be rejected");
+    assert!(matches!(error, EngineError::InvalidClipLoopLength(1)));
+}
+
+#[test]
+fn renderer_cuts_notes_at_the_clip_edge_and_scales_velocity() {
+    // The second note starts past the end of a one-beat clip.
+    let (mut engine, track_id, clip_id) = quarter_note_clip(vec![note(69, 0), note(69, 960)]);
+    engine
+        .move_clip(track_id, clip_id, 0, 480)
+        .expect("clip resize should succeed");
+    let trimmed = render_project_samples(engine.project(), 1.0);
+    assert!(energy(&trimmed, 0.05, 0.2) > 1e-4);
+    assert!(
+        energy(&trimmed, 1.0, 1.4) < 1e-8,
+        "content beyond the clip stays silent"
+    );
+
+    engine
+        .patch_clip_playback(
+            track_id,
+            clip_id,
+            ClipPlaybackPatch {
+                loop_length_ticks: Some(Some(960)),
+                ..ClipPlaybackPatch::default()
+            },
+        )
+        .expect("loop should be accepted");
+    engine
+        .move_clip(track_id, clip_id, 0, 1_920)
+        .expect("clip resize should succeed");
+    let looped = render_project_samples(engine.project(), 1.0);
+    assert!(
+        energy(&looped, 1.05, 1.2) > 1e-4,
+        "the loop repeats the first note"
+    );
+
+    engine
+        .patch_clip_playback(
+            track_id,
+            clip_id,
+            ClipPlaybackPatch {
+                velocity_scale: Some(0.5),
+                ..ClipPlaybackPatch::default()
+            },
+        )
+        .expect("velocity scale should be accepted");
+    let quiet = render_project_samples(engine.project(), 1.0);
+    assert!(energy(&quiet, 0.05, 0.2) < energy(&looped, 0.05, 0.2) * 0.5);
+}
@@ -0,0 +1,152 @@
+use midly::{MidiMessage, Smf, TrackEventKind};
+use voltlane_core::{
+    AddClipRequest, Engine, EngineError,
+    export::{midi_bytes, render_project_samples},
+    model::{
+        ClipPayload, DEFAULT_SAMPLE_RATE, LinkedClip, MidiClip, MidiNote, Project, Track, TrackKind,
+    },
+};
+
+fn note(pitch: u8, start_tick: u64) -> MidiNote {
+    MidiNote {
+        pitch,
+        velocity: 100,
+        start_tick,
+        length_ticks: 240,
+        channel: 0,
+        release_velocity: None,
+    }
+}
+
+fn pooled_engine() -> (Engine, uuid::Uuid, uuid::Uuid, uuid::Uuid) {
+    let mut project = Project::new("Pool", 120.0, DEFAULT_SAMPLE_RATE);
+    let track = Track::new("Lead", "#4fb8ff", TrackKind::Midi);
+    let track_id = track.id;
+    project.tracks.push(track);
+    let mut engine = Engine::new(project);
+
+    let source = engine
+        .add_clip(AddClipRequest {
+            track_id,
+            name: "riff".to_string(),
+            start_tick: 0,
+            length_ticks: 960,
+            payload: ClipPayload::Midi(MidiClip {
+                instrument: None,
+                notes: vec![note(60, 0), note(64, 480)],
+                controllers: Vec::new(),
+            }),
+        })
+        .expect("source clip add should succeed");
+    let copy = engine
+        .duplicate_clip_linked(track_id, source.id, 1_920)
+        .expect("linked duplicate should succeed");
+    (engine, track_id, source.id, copy.id)
+}
+
+fn resolved_notes(engine: &Engine, track_id: uuid::Uuid, clip_id: uuid::Uuid) -> Vec<u8> {
+    let project = engine.project();
+    let clip = project
+        .tracks
+        .iter()
+        .find(|track| track.id == track_id)
+        .and_then(|track| track.clips.iter().find(|clip| clip.id == clip_id))
+        .expect("clip should exist");
+    match project.resolved_payload(clip) {
+        Some(ClipPayload::Midi(midi)) => midi.notes.iter().map(|note| note.pitch).collect(),
+        other => panic!("clip should resolve to midi content, got {other:?}"),
+    }
+}
+
+#[test]
+fn editing_a_linked_clip_updates_every_placement() {
+    let (mut engine, track_id, source_id, copy_id) = pooled_engine();
+    assert_eq!(engine.project().clip_pool.len(), 1);
+
+    engine
+        .add_clip_note(track_id, copy_id, note(67, 720))
+        .expect("note add through linked clip should succeed");
+
+    assert_eq!(
+        resolved_notes(&engine, track_id, source_id),
+        vec![60, 64, 67]
+    );
+    assert_eq!(resolved_notes(&engine, track_id, copy_id), vec![60, 64, 67]);
+    assert_eq!(engine.project().note_count(), 6);
+
+    let bytes = midi_bytes(engine.project()).expect("midi export should succeed");
+    let smf = Smf::parse(&bytes).expect("exported midi should parse");
+    let note_ons = smf
+        .tracks
+        .iter()
+        .flatten()
+        .filter(|event| {
+            matches!(
+                event.kind,
+                TrackEventKind::Midi {
+                    message: MidiMessage::NoteOn { .. },
+                    ..
+                }
+            )
+        })
+        .count();
+    assert_eq!(note_ons, 6, "both placements should export pooled notes");
+
+    let rendered = render_project_samples(engine.project(), 1.5);
+    let second_pass_peak = rendered[96_000..108_000]
+        .iter()
+        .fold(0.0_f32, |peak, sample| peak.max(sample.abs()));
+    assert!(second_pass_peak > 0.01, "linked placement should render");
+}
+
+#[test]
+fn make_unique_forks_content_and_prune_drops_orphans() {
+    let (mut engine, track_id, source_id, copy_id) = pooled_engine();
+
+    engine
+        .make_clip_unique(track_id, copy_id)
+        .expect("make unique should succeed");
+    engine
+        .transpose_clip_notes(track_id, copy_id, 12)
+        .expect("transpose of unique clip should succeed");
+
+    assert_eq!(resolved_notes(&engine, track_id, source_id), vec![60, 64]);
+    assert_eq!(resolved_notes(&engine, track_id, copy_id), vec![72, 76]);
+    assert_eq!(
+        engine.prune_clip_pool(),
+        0,
+        "source still links the content"
+    );
+
+    engine
+        .make_clip_unique(track_id, source_id)
+        .expect("make unique should succeed");
+    assert_eq!(engine.prune_clip_pool(), 1);
+    assert!(engine.project().clip_pool.is_empty());
+}
+
+#[test]
+fn linked_clip_requires_existing_pool_content() {
+    let (mut engine, track_id, _, _) = pooled_engine();
+    let missing = uuid::Uuid::new_v4();
+
+    let error = engine
+        .add_clip(AddClipRequest {
+            track_id,
+            name: "dangling".to_string(),
+            start_tick: 0,
+            length_ticks: 960,
+            payload: ClipPayload::Linked(LinkedClip {
+                content_id: missing,
+            }),
+        })
+        .expect_err("dangling link should fail");
+    assert!(matches!(error, EngineError::PoolContentNotFound(id) if id == missing));
+
+    let content_id = engine.project().clip_pool[0].id;
+    let placed = engine
+        .place_pool_content(track_id, content_id, 3_840, 960)
+        .expect("pool placement should succeed");
+    assert_eq!(placed.name, "riff");
+    assert_eq!(resolved_notes(&engine, track_id, placed.id), vec![60, 64]);
+}
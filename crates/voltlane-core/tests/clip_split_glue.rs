@@ -0,0 +1,186 @@
+mod common;
+
+use common::engine_with_clips;
+use uuid::Uuid;
+use voltlane_core::{
+    EngineError,
+    model::{
+        AudioClip, AutomationClip, AutomationPoint, Clip, ClipPayload, MidiClip, MidiNote,
+        TrackKind,
+    },
+};
+
+fn clip(start_tick: u64, length_ticks: u64, payload: ClipPayload) -> Clip {
+    Clip::new("part", start_tick, length_ticks, payload)
+}
+
+fn midi(notes: &[(u8, u64, u64)]) -> ClipPayload {
+    ClipPayload::Midi(MidiClip {
+        instrument: None,
+        notes: notes
+            .iter()
+            .map(|(pitch, start_tick, length_ticks)| MidiNote {
+                pitch: *pitch,
+                velocity: 100,
+                start_tick: *start_tick,
+                length_ticks: *length_ticks,
+                channel: 0,
+                release_velocity: None,
+            })
+            .collect(),
+        controllers: Vec::new(),
+    })
+}
+
+fn note_spans(clip: &Clip) -> Vec<(u8, u64, u64)> {
+    let ClipPayload::Midi(midi) = &clip.payload else {
+        panic!("clip should be a midi clip");
+    };
+    midi.notes
+        .iter()
+        .map(|note| (note.pitch, note.start_tick, note.length_ticks))
+        .collect()
+}
+
+#[test]
+fn split_partitions_notes_points_and_audio_range() {
+    let keys = clip(
+        0,
+        1_920,
+        midi(&[(60, 0, 480), (62, 720, 480), (64, 1_440, 240)]),
+    );
+    let sweep = clip(
+        0,
+        1_920,
+        ClipPayload::Automation(AutomationClip {
+            target_parameter_id: "track:gain".to_string(),
+            points: vec![
+                AutomationPoint {
+                    tick: 0,
+                    value: 0.0,
+                },
+                AutomationPoint {
+                    tick: 1_920,
+                    value: 1.0,
+                },
+            ],
+        }),
+    );
+    let vocal = clip(
+        0,
+        3_840,
+        ClipPayload::Audio(AudioClip {
+            source_path: "vocal.wav".to_string(),
+            source_duration_seconds: 6.0,
+            trim_start_seconds: 1.0,
+            trim_end_seconds: 5.0,
+            fade_in_seconds: 0.1,
+            fade_out_seconds: 0.2,
+            ..AudioClip::default()
+        }),
+    );
+    let (keys_id, sweep_id, vocal_id) = (keys.id, sweep.id, vocal.id);
+    let (mut engine, track_id) = engine_with_clips(TrackKind::Midi, vec![keys, sweep, vocal]);
+
+    let (left, right) = engine
+        .split_clip(track_id, keys_id, 960)
+        .expect("midi split should succeed");
+    assert_eq!(left.id, keys_id);
+    assert_eq!((left.length_ticks, right.start_tick), (960, 960));
+    assert_eq!(note_spans(&left), vec![(60, 0, 480), (62, 720, 240)]);
+    assert_eq!(note_spans(&right), vec![(62, 0, 240), (64, 480, 240)]);
+
+    let (left, right) = engine
+        .split_clip(track_id, sweep_id, 480)
+        .expect("automation split should succeed");
+    let (ClipPayload::Automation(left), ClipPayload::Automation(right)) =
+        (&left.payload, &right.payload)
+    else {
+        panic!("automation halves should stay automation clips");
+    };
+    assert_eq!(left.points.last().map(|point| point.tick), Some(480));
+    assert_eq!(right.points[0].tick, 0);
+    assert!((right.points[0].value - 0.25).abs() < 1e-6);
+    assert_eq!(right.points[1].tick, 1_440);
+
+    // 960 ticks at 120 bpm is one second of the trimmed source.
+    let (left, right) = engine
+        .split_clip(track_id, vocal_id, 960)
+        .expect("audio split should succeed");
+    let (ClipPayload::Audio(left), ClipPayload::Audio(right)) = (&left.payload, &right.payload)
+    else {
+        panic!("audio halves should stay audio clips");
+    };
+    assert_eq!((left.trim_start_seconds, left.trim_end_seconds), (1.0, 2.0));
+    assert_eq!(
+        (right.trim_start_seconds, right.trim_end_seconds),
+        (2.0, 5.0)
+    );
+    assert_eq!((left.fade_out_seconds, right.fade_in_seconds), (0.0, 0.0));
+
+    assert!(matches!(
+        engine.split_clip(track_id, keys_id, 960),
+        Err(EngineError::InvalidSplitTick { .. })
+    ));
+    assert_eq!(engine.project().tracks[0].clips.len(), 6);
+}
+
+#[test]
+fn slices_glue_back_into_the_original_notes() {
+    let original = clip(
+        480,
+        1_920,
+        midi(&[(60, 0, 480), (62, 600, 600), (64, 1_440, 480)]),
+    );
+    let original_id = original.id;
+    let (mut engine, track_id) = engine_with_clips(TrackKind::Midi, vec![original]);
+
+    let slices = engine
+        .slice_clip(track_id, original_id, 4)
+        .expect("equal slicing should succeed");
+    let starts: Vec<u64> = slices.iter().map(|slice| slice.start_tick).collect();
+    assert_eq!(starts, vec![480, 960, 1_440, 1_920]);
+    assert!(slices.iter().all(|slice| slice.length_ticks == 480));
+    assert!(matches!(
+        engine.slice_clip(track_id, original_id, 1),
+        Err(EngineError::InvalidSliceCount(1))
+    ));
+
+    let ids: Vec<Uuid> = slices.iter().map(|slice| slice.id).collect();
+    let glued = engine
+        .glue_clips(track_id, &ids)
+        .expect("adjacent slices should glue");
+    assert_eq!(glued.id, original_id);
+    assert_eq!((glued.start_tick, glued.length_ticks), (480, 1_920));
+    // Notes cut by the slices come back as adjacent pieces.
+    assert_eq!(
+        note_spans(&glued),
+        vec![
+            (60, 0, 480),
+            (62, 600, 360),
+            (62, 960, 240),
+            (64, 1_440, 480)
+        ]
+    );
+
+    let grid_slices = engine
+        .slice_clip_to_grid(track_id, original_id, 1_000)
+        .expect("grid slicing should succeed");
+    let bounds: Vec<(u64, u64)> = grid_slices
+        .iter()
+        .map(|slice| (slice.start_tick, slice.end_tick()))
+        .collect();
+    assert_eq!(bounds, vec![(480, 1_000), (1_000, 2_000), (2_000, 2_400)]);
+
+    let gap = clip(4_000, 480, midi(&[(67, 0, 240)]));
+    let gap_id = gap.id;
+    engine.replace_project({
+        let mut project = engine.project().clone();
+        project.tracks[0].clips.push(gap);
+        project
+    });
+    assert!(matches!(
+        engine.glue_clips(track_id, &[grid_slices[2].id, gap_id]),
+        Err(EngineError::InvalidGlue(_))
+    ));
+}
@@ -0,0 +1,93 @@
+//! Fixtures shared by the integration tests.
+#![allow(dead_code)]
+
+use std::path::Path;
+
+use tempfile::{TempDir, tempdir};
+use uuid::Uuid;
+use voltlane_core::{
+    Engine,
+    model::{Clip, DEFAULT_SAMPLE_RATE, Project, Track, TrackKind},
+};
+
+pub const SAMPLE_RATE: u32 = 48_000;
+
+pub fn sine(hz: f32, seconds: f32) -> Vec<f32> {
+    let frames = (seconds * SAMPLE_RATE as f32).round() as usize;
+    (0..frames)
+        .map(|frame| (frame as f32 / SAMPLE_RATE as f32 * hz * std::f32::consts::TAU).sin() * 0.5)
+        .collect()
+}
+
+/// An engine with one `kind` track holding `clips`, and that track's id.
+pub fn engine_with_clips(kind: TrackKind, clips: Vec<Clip>) -> (Engine, Uuid) {
+    let mut project = Project::new("Fixture", 120.0, DEFAULT_SAMPLE_RATE);
+    let mut track = Track::new("Track", "#4fb8ff", kind);
+    track.clips = clips;
+    let track_id = track.id;
+    project.tracks.push(track);
+    (Engine::new(project), track_id)
+}
+
+/// Writes `samples` as a mono 16-bit WAV at [`SAMPLE_RATE`].
+pub fn write_wav(path: &Path, samples: &[f32]) {
+    let spec = hound::WavSpec {
+        channels: 1,
+        sample_rate: SAMPLE_RATE,
+        bits_per_sample: 16,
+        sample_format: hound::SampleFormat::Int,
+    };
+    let mut writer = hound::WavWriter::create(path, spec).expect("test wav should be creatable");
+    for sample in samples {
+        writer
+            .write_sample((sample * f32::from(i16::MAX)).round() as i16)
+            .expect("test wav sample write should succeed");
+    }
+    writer.finalize().expect("test wav finalize should succeed");
+}
+
+/// The samples between `from` and `to` seconds.
+pub fn window(samples: &[f32], from: f32, to: f32) -> &[f32] {
+    &samples[(from * SAMPLE_RATE as f32) as usize..(to * SAMPLE_RATE as f32) as usize]
+}
+
+/// Frequency from the upward zero crossings in `samples`.
+pub fn frequency(samples: &[f32]) -> f32 {
+    let crossings = samples
+        .windows(2)
+        .filter(|pair| pair[0] < 0.0 && pair[1] >= 0.0)
+        .count();
+    crossings as f32 * SAMPLE_RATE as f32 / samples.len() as f32
+}
+
+/// An engine at `bpm` with one audio track named `name` holding `samples`
+/// imported at `start_tick`. Returns the temp dir keeping the source alive,
+/// the engine, the track id and the clip id.
+pub fn engine_with_audio(
+    name: &str,
+    bpm: f64,
+    samples: &[f32],
+    start_tick: u64,
+) -> (TempDir, Engine, Uuid, Uuid) {
+    let temp = tempdir().expect("tempdir should be creatable");
+    let audio_path = temp.path().join(format!("{}.wav", name.to_lowercase()));
+    write_wav(&audio_path, samples);
+    let mut project = Project::new(name, bpm, SAMPLE_RATE);
+    let track = Track::new(name, "#ffaa4f", TrackKind::Audio);
+    let track_id = track.id;
+    project.tracks.push(track);
+    let mut engine = Engine::new(project);
+    let clip = engine
+        .import_audio_clip(
+            track_id,
+            name.to_string(),
+            &audio_path,
+            start_tick,
+            512,
+            None,
+            0.0,
+            0.0,
+        )
+        .expect("audio import should succeed");
+    (temp, engine, track_id, clip.id)
+}
@@ -2,8 +2,8 @@ use voltlane_core::{
     RenderMode,
     export::{export_midi, export_stem_wav, export_wav, midi_bytes},
     model::{
-        AudioClip, ChipMacroLane, Clip, ClipPayload, DEFAULT_SAMPLE_RATE, MidiClip, MidiNote,
-        PatternClip, Project, Track, TrackKind,
+        AudioClip, AudioStretchMode, ChipMacroLane, Clip, ClipPayload, DEFAULT_SAMPLE_RATE,
+        MidiClip, MidiNote, PatternClip, Project, Track, TrackKind,
     },
 };
 
@@ -11,13 +11,11 @@ use voltlane_core::{
 fn midi_and_wav_exports_generate_output() {
     let mut project = Project::new("Export Smoke", 120.0, DEFAULT_SAMPLE_RATE);
     let mut track = Track::new("Keys", "#18c0ff", TrackKind::Midi);
-    track.clips.push(Clip {
-        id: uuid::Uuid::new_v4(),
-        name: "intro".to_string(),
-        start_tick: 0,
-        length_ticks: 960,
-        disabled: false,
-        payload: ClipPayload::Midi(MidiClip {
+    track.clips.push(Clip::new(
+        "intro",
+        0,
+        960,
+        ClipPayload::Midi(MidiClip {
             instrument: Some("EP".to_string()),
             notes: vec![MidiNote {
                 pitch: 60,
@@ -25,9 +23,11 @@ fn midi_and_wav_exports_generate_output() {
                 start_tick: 0,
                 length_ticks: 960,
                 channel: 0,
+                release_velocity: None,
             }],
+            controllers: Vec::new(),
         }),
-    });
+    ));
     project.tracks.push(track);
 
     let temp_dir = tempfile::tempdir().expect("tempdir should work");
@@ -59,13 +59,11 @@ fn wav_export_renders_audio_clip_payload() {
 
     let mut project = Project::new("Audio Clip Render", 120.0, DEFAULT_SAMPLE_RATE);
     let mut track = Track::new("Audio", "#ff9757", TrackKind::Audio);
-    track.clips.push(Clip {
-        id: uuid::Uuid::new_v4(),
-        name: "audio".to_string(),
-        start_tick: 0,
-        length_ticks: 1_440,
-        disabled: false,
-        payload: ClipPayload::Audio(AudioClip {
+    track.clips.push(Clip::new(
+        "audio",
+        0,
+        1_440,
+        ClipPayload::Audio(AudioClip {
             source_path: source_wav.display().to_string(),
             gain_db: 0.0,
             pan: 0.0,
@@ -78,11 +76,16 @@ fn wav_export_renders_audio_clip_payload() {
             fade_out_seconds: 0.02,
             reverse: false,
             stretch_ratio: 1.0,
+            stretch_mode: AudioStretchMode::Resample,
+            pitch_semitones: 0.0,
+            pitch_cents: 0.0,
+            warp_markers: Vec::new(),
+            source_bpm: None,
             waveform_bucket_size: 256,
             waveform_peaks: vec![0.3; 64],
             waveform_cache_path: None,
         }),
-    });
+    ));
     project.tracks.push(track);
 
     let output_wav = temp_dir.path().join("audio_clip.wav");
@@ -101,14 +104,13 @@ fn wav_export_renders_audio_clip_payload() {
 fn pattern_arpeggio_macro_changes_midi_pitch_output() {
     let mut project = Project::new("Macro MIDI", 120.0, DEFAULT_SAMPLE_RATE);
     let mut track = Track::new("Chip", "#ffb347", TrackKind::Chip);
-    track.clips.push(Clip {
-        id: uuid::Uuid::new_v4(),
-        name: "chip-pattern".to_string(),
-        start_tick: 0,
-        length_ticks: 960,
-        disabled: false,
-        payload: ClipPayload::Pattern(PatternClip {
+    track.clips.push(Clip::new(
+        "chip-pattern",
+        0,
+        960,
+        ClipPayload::Pattern(PatternClip {
             source_chip: "gameboy_apu".to_string(),
+            instrument: None,
             notes: vec![
                 MidiNote {
                     pitch: 60,
@@ -116,6 +118,7 @@ fn pattern_arpeggio_macro_changes_midi_pitch_output() {
                     start_tick: 0,
                     length_ticks: 240,
                     channel: 0,
+                    release_velocity: None,
                 },
                 MidiNote {
                     pitch: 60,
@@ -123,6 +126,7 @@ fn pattern_arpeggio_macro_changes_midi_pitch_output() {
                     start_tick: 120,
                     length_ticks: 240,
                     channel: 0,
+                    release_velocity: None,
                 },
             ],
             rows: Vec::new(),
@@ -135,7 +139,7 @@ fn pattern_arpeggio_macro_changes_midi_pitch_output() {
             }],
             lines_per_beat: 4,
         }),
-    });
+    ));
     project.tracks.push(track);
 
     let bytes = midi_bytes(&project).expect("midi bytes should render");
@@ -163,13 +167,11 @@ fn pattern_arpeggio_macro_changes_midi_pitch_output() {
 fn stem_export_writes_per_track_wav_files() {
     let mut project = Project::new("Stem Export", 120.0, DEFAULT_SAMPLE_RATE);
     let mut midi_track = Track::new("Lead Synth", "#18c0ff", TrackKind::Midi);
-    midi_track.clips.push(Clip {
-        id: uuid::Uuid::new_v4(),
-        name: "lead".to_string(),
-        start_tick: 0,
-        length_ticks: 960,
-        disabled: false,
-        payload: ClipPayload::Midi(MidiClip {
+    midi_track.clips.push(Clip::new(
+        "lead",
+        0,
+        960,
+        ClipPayload::Midi(MidiClip {
             instrument: Some("Lead".to_string()),
             notes: vec![MidiNote {
                 pitch: 67,
@@ -177,32 +179,34 @@ fn stem_export_writes_per_track_wav_files() {
                 start_tick: 0,
                 length_ticks: 960,
                 channel: 0,
+                release_velocity: None,
             }],
+            controllers: Vec::new(),
         }),
-    });
+    ));
     project.tracks.push(midi_track);
 
     let mut chip_track = Track::new("Chip Bass", "#f97316", TrackKind::Chip);
-    chip_track.clips.push(Clip {
-        id: uuid::Uuid::new_v4(),
-        name: "bass".to_string(),
-        start_tick: 0,
-        length_ticks: 960,
-        disabled: false,
-        payload: ClipPayload::Pattern(PatternClip {
+    chip_track.clips.push(Clip::new(
+        "bass",
+        0,
+        960,
+        ClipPayload::Pattern(PatternClip {
             source_chip: "gameboy_apu".to_string(),
+            instrument: None,
             notes: vec![MidiNote {
                 pitch: 48,
                 velocity: 100,
                 start_tick: 0,
                 length_ticks: 960,
                 channel: 0,
+                release_velocity: None,
             }],
             rows: Vec::new(),
             macros: Vec::new(),
             lines_per_beat: 4,
         }),
-    });
+    ));
     project.tracks.push(chip_track);
 
     let temp_dir = tempfile::tempdir().expect("tempdir should work");
@@ -11,13 +11,11 @@ fn midi_project_with_effects(effects: Vec<EffectSpec>, gain_db: f32) -> Project
     let mut track = Track::new("Lead", "#2ad9b8", TrackKind::Midi);
     track.gain_db = gain_db;
     track.effects = effects;
-    track.clips.push(Clip {
-        id: uuid::Uuid::new_v4(),
-        name: "phrase".to_string(),
-        start_tick: 0,
-        length_ticks: 1_920,
-        disabled: false,
-        payload: ClipPayload::Midi(MidiClip {
+    track.clips.push(Clip::new(
+        "phrase",
+        0,
+        1_920,
+        ClipPayload::Midi(MidiClip {
             instrument: Some("Saw".to_string()),
             notes: vec![
                 MidiNote {
@@ -26,6 +24,7 @@ fn midi_project_with_effects(effects: Vec<EffectSpec>, gain_db: f32) -> Project
                     start_tick: 0,
                     length_ticks: 960,
                     channel: 0,
+                    release_velocity: None,
                 },
                 MidiNote {
                     pitch: 67,
@@ -33,6 +32,7 @@ fn midi_project_with_effects(effects: Vec<EffectSpec>, gain_db: f32) -> Project
                     start_tick: 240,
                     length_ticks: 960,
                     channel: 0,
+                    release_velocity: None,
                 },
                 MidiNote {
                     pitch: 72,
@@ -40,10 +40,12 @@ fn midi_project_with_effects(effects: Vec<EffectSpec>, gain_db: f32) -> Project
                     start_tick: 480,
                     length_ticks: 960,
                     channel: 0,
+                    release_velocity: None,
                 },
             ],
+            controllers: Vec::new(),
         }),
-    });
+    ));
     project.tracks.push(track);
     project
 }
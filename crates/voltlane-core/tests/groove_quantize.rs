@@ -0,0 +1,246 @@
+mod common;
+
+use std::path::Path;
+
+use common::engine_with_clips;
+use tempfile::tempdir;
+use voltlane_core::{
+    EngineError, NoteEndQuantize, QuantizeOptions,
+    model::{AudioClip, Clip, ClipPayload, MidiClip, MidiNote, Track, TrackKind},
+};
+
+fn clip(name: &str, length_ticks: u64, payload: ClipPayload) -> Clip {
+    Clip::new(name, 0, length_ticks, payload)
+}
+
+/// Midi clip from `(start_tick, length_ticks, velocity)` notes.
+fn midi(name: &str, notes: &[(u64, u64, u8)]) -> Clip {
+    clip(
+        name,
+        1_920,
+        ClipPayload::Midi(MidiClip {
+            instrument: None,
+            notes: notes
+                .iter()
+                .map(|(start_tick, length_ticks, velocity)| MidiNote {
+                    pitch: 60,
+                    velocity: *velocity,
+                    start_tick: *start_tick,
+                    length_ticks: *length_ticks,
+                    channel: 0,
+                    release_velocity: None,
+                })
+                .collect(),
+            controllers: Vec::new(),
+        }),
+    )
+}
+
+fn quantized(notes: &[(u64, u64, u8)], options: &QuantizeOptions) -> Vec<(u64, u64, u8)> {
+    let part = midi("part", notes);
+    let clip_id = part.id;
+    let (mut engine, track_id) = engine_with_clips(TrackKind::Midi, vec![part]);
+    let clip = engine
+        .quantize_clip_notes_with(track_id, clip_id, options)
+        .expect("quantize should succeed");
+    let ClipPayload::Midi(midi) = clip.payload else {
+        panic!("quantized clip should stay midi");
+    };
+    midi.notes
+        .iter()
+        .map(|note| (note.start_tick, note.length_ticks, note.velocity))
+        .collect()
+}
+
+/// Short decaying 2 kHz bursts at `hits_seconds`.
+fn write_clicks_wav(path: &Path, seconds: f32, hits_seconds: &[f32]) {
+    let sample_rate = 48_000_u32;
+    let spec = hound::WavSpec {
+        channels: 1,
+        sample_rate,
+        bits_per_sample: 16,
+        sample_format: hound::SampleFormat::Int,
+    };
+    let mut writer = hound::WavWriter::create(path, spec).expect("test wav should be creatable");
+    for frame in 0..(seconds * sample_rate as f32) as usize {
+        let time = frame as f32 / sample_rate as f32;
+        let sample: f32 = hits_seconds
+            .iter()
+            .filter(|hit| time >= **hit && time < **hit + 0.03)
+            .map(|hit| {
+                let age = time - hit;
+                (age * 2_000.0 * std::f32::consts::TAU).sin() * 0.8 * (-age / 0.01).exp()
+            })
+            .sum();
+        writer
+            .write_sample((sample * f32::from(i16::MAX)).round() as i16)
+            .expect("test wav sample write should succeed");
+    }
+    writer.finalize().expect("test wav finalize should succeed");
+}
+
+#[test]
+fn quantize_applies_strength_swing_triplets_and_end_modes() {
+    let loose = [
+        (10, 100, 90),
+        (230, 100, 90),
+        (490, 100, 90),
+        (700, 100, 90),
+    ];
+
+    let swung = QuantizeOptions {
+        swing_percent: 50.0,
+        ..QuantizeOptions::grid(240)
+    };
+    assert_eq!(
+        quantized(&loose, &swung),
+        vec![(0, 240, 90), (300, 240, 90), (480, 240, 90), (780, 240, 90)],
+        "odd grid lines are delayed by a quarter step"
+    );
+
+    let half = QuantizeOptions {
+        strength_percent: 50.0,
+        ends: NoteEndQuantize::Keep,
+        ..QuantizeOptions::grid(240)
+    };
+    assert_eq!(
+        quantized(&loose, &half),
+        vec![(5, 100, 90), (235, 100, 90), (485, 100, 90), (710, 100, 90)]
+    );
+
+    let triplets = QuantizeOptions {
+        triplet: true,
+        ..QuantizeOptions::grid(480)
+    };
+    assert_eq!(
+        quantized(&[(10, 100, 90), (330, 100, 90), (650, 100, 90)], &triplets),
+        vec![(0, 320, 90), (320, 320, 90), (640, 320, 90)]
+    );
+
+    let ends = QuantizeOptions {
+        ends: NoteEndQuantize::End,
+        ..QuantizeOptions::grid(240)
+    };
+    assert_eq!(
+        quantized(&[(10, 500, 90), (230, 20, 90)], &ends),
+        vec![(0, 480, 90), (240, 240, 90)],
+        "ends snap on their own and never collapse onto the start"
+    );
+
+    let part = midi("part", &loose);
+    let clip_id = part.id;
+    let (mut engine, track_id) = engine_with_clips(TrackKind::Midi, vec![part]);
+    assert!(matches!(
+        engine.quantize_clip_notes_with(
+            track_id,
+            clip_id,
+            &QuantizeOptions {
+                strength_percent: 150.0,
+                ..QuantizeOptions::grid(240)
+            }
+        ),
+        Err(EngineError::InvalidQuantizeAmount(_))
+    ));
+    assert!(matches!(
+        engine.quantize_clip_notes_with(
+            track_id,
+            clip_id,
+            &QuantizeOptions {
+                groove: Some("missing".to_string()),
+                ..QuantizeOptions::grid(240)
+            }
+        ),
+        Err(EngineError::GrooveNotFound(_))
+    ));
+}
+
+#[test]
+fn grooves_extracted_from_notes_and_audio_shape_other_clips() {
+    // Late, soft offbeats against loud downbeats.
+    let reference = midi(
+        "reference",
+        &[
+            (0, 120, 120),
+            (280, 120, 60),
+            (480, 120, 120),
+            (760, 120, 60),
+        ],
+    );
+    let straight = midi(
+        "straight",
+        &[(0, 120, 90), (240, 120, 90), (480, 120, 90), (720, 120, 90)],
+    );
+    let (reference_id, straight_id) = (reference.id, straight.id);
+    let (mut engine, track_id) = engine_with_clips(TrackKind::Midi, vec![reference, straight]);
+
+    let groove = engine
+        .extract_clip_groove(track_id, reference_id, "Shuffle", 240, 2)
+        .expect("groove extraction should succeed");
+    assert_eq!(
+        groove
+            .slots
+            .iter()
+            .map(|slot| slot.offset_ticks)
+            .collect::<Vec<_>>(),
+        vec![0, 40]
+    );
+    assert_eq!(engine.project().grooves.len(), 1);
+
+    let grooved = engine
+        .quantize_clip_notes_with(
+            track_id,
+            straight_id,
+            &QuantizeOptions {
+                groove: Some("shuffle".to_string()),
+                ends: NoteEndQuantize::Keep,
+                ..QuantizeOptions::grid(240)
+            },
+        )
+        .expect("grooved quantize should succeed");
+    let ClipPayload::Midi(midi) = grooved.payload else {
+        panic!("grooved clip should stay midi");
+    };
+    assert_eq!(
+        midi.notes
+            .iter()
+            .map(|note| (note.start_tick, note.velocity))
+            .collect::<Vec<_>>(),
+        vec![(0, 120), (280, 60), (480, 120), (760, 60)]
+    );
+
+    // The same feel played as audio: offbeats 30 ms behind the eighth-note grid.
+    let temp = tempdir().expect("tempdir should be created");
+    let wav_path = temp.path().join("loop.wav");
+    write_clicks_wav(&wav_path, 2.0, &[0.5, 0.78, 1.0, 1.28, 1.5, 1.78]);
+    let mut project = engine.project().clone();
+    let mut audio_track = Track::new("Loop", "#ffb84f", TrackKind::Audio);
+    let audio_clip = clip(
+        "loop",
+        1_920,
+        ClipPayload::Audio(AudioClip {
+            source_path: wav_path.to_string_lossy().to_string(),
+            source_duration_seconds: 2.0,
+            trim_end_seconds: 2.0,
+            ..AudioClip::default()
+        }),
+    );
+    let (audio_track_id, audio_clip_id) = (audio_track.id, audio_clip.id);
+    audio_track.clips.push(audio_clip);
+    project.tracks.push(audio_track);
+    engine.replace_project(project);
+
+    let audio_groove = engine
+        .extract_clip_groove(audio_track_id, audio_clip_id, "Shuffle", 240, 2)
+        .expect("audio groove extraction should succeed");
+    assert!(audio_groove.slots[0].offset_ticks.abs() <= 4);
+    assert!(
+        (24..=34).contains(&audio_groove.slots[1].offset_ticks),
+        "30 ms at 120 bpm is about 29 ticks, got {}",
+        audio_groove.slots[1].offset_ticks
+    );
+    assert_eq!(
+        engine.project().grooves.len(),
+        1,
+        "extracting under an existing name replaces it"
+    );
+}
@@ -0,0 +1,206 @@
+mod common;
+
+use common::engine_with_clips;
+use voltlane_core::{
+    EngineError, Key, ScaleMode, detect_key,
+    model::{Clip, ClipPayload, MidiClip, MidiNote, TrackKind},
+};
+
+fn note(pitch: u8, start_tick: u64, length_ticks: u64) -> MidiNote {
+    MidiNote {
+        pitch,
+        velocity: 100,
+        start_tick,
+        length_ticks,
+        channel: 0,
+        release_velocity: None,
+    }
+}
+
+fn midi_clip(notes: Vec<MidiNote>) -> Clip {
+    Clip::new(
+        "melody",
+        0,
+        7_680,
+        ClipPayload::Midi(MidiClip {
+            instrument: None,
+            notes,
+            controllers: Vec::new(),
+        }),
+    )
+}
+
+fn pitches(clip: &Clip) -> Vec<u8> {
+    let ClipPayload::Midi(midi) = &clip.payload else {
+        panic!("clip should stay midi");
+    };
+    midi.notes.iter().map(|note| note.pitch).collect()
+}
+
+fn c_major() -> Key {
+    Key {
+        root: 0,
+        mode: ScaleMode::Major,
+    }
+}
+
+#[test]
+fn scale_snap_and_diatonic_transpose_follow_the_clip_key() {
+    let clip = midi_clip(vec![
+        note(60, 0, 480),
+        note(61, 480, 480),
+        note(67, 960, 480),
+    ]);
+    let clip_id = clip.id;
+    let (mut engine, track_id) = engine_with_clips(TrackKind::Midi, vec![clip]);
+
+    assert!(matches!(
+        engine.transpose_clip_notes_diatonic(track_id, clip_id, 2),
+        Err(EngineError::KeyMissing(_))
+    ));
+    engine
+        .set_project_key(Some(c_major()))
+        .expect("project key should be accepted");
+
+    let clip = engine
+        .transpose_clip_notes_diatonic(track_id, clip_id, 2)
+        .expect("diatonic transpose should succeed");
+    assert_eq!(
+        pitches(&clip),
+        vec![64, 65, 71],
+        "C-E-G move up a third; C# keeps its semitone above the degree"
+    );
+
+    let clip = engine
+        .add_clip_note_in_key(track_id, clip_id, note(66, 1_440, 480))
+        .expect("snapped add should succeed");
+    assert_eq!(pitches(&clip), vec![64, 65, 71, 65], "F# ties down to F");
+
+    let clip = engine
+        .set_clip_key(
+            track_id,
+            clip_id,
+            Some(Key {
+                root: 0,
+                mode: ScaleMode::Custom(vec![9, 0, 4, 2, 7]),
+            }),
+        )
+        .expect("custom clip key should be accepted");
+    assert_eq!(
+        clip.key.as_ref().map(|key| key.mode.intervals().to_vec()),
+        Some(vec![0, 2, 4, 7, 9]),
+        "custom intervals are stored in order"
+    );
+    let clip = engine
+        .upsert_clip_notes_in_key(
+            track_id,
+            clip_id,
+            vec![note(65, 0, 480), note(66, 480, 480), note(71, 960, 480)],
+        )
+        .expect("snapped upsert should succeed");
+    assert_eq!(
+        pitches(&clip),
+        vec![64, 67, 72],
+        "the clip's pentatonic key overrides the project key"
+    );
+    assert_eq!(
+        clip.key.as_ref().map(Key::pitch_classes).map(|classes| {
+            classes
+                .iter()
+                .enumerate()
+                .filter(|(_, in_key)| **in_key)
+                .map(|(class, _)| class)
+                .collect::<Vec<_>>()
+        }),
+        Some(vec![0, 2, 4, 7, 9])
+    );
+
+    assert!(matches!(
+        engine.set_clip_key(
+            track_id,
+            clip_id,
+            Some(Key {
+                root: 0,
+                mode: ScaleMode::Custom(vec![2, 4]),
+            }),
+        ),
+        Err(EngineError::InvalidScale(_))
+    ));
+}
+
+#[test]
+fn keys_loaded_without_validation_still_snap_and_highlight() {
+    // Project files can carry keys the engine would have rejected.
+    let rootless = Key {
+        root: 12,
+        mode: ScaleMode::Custom(vec![4, 7]),
+    };
+    let mut clip = midi_clip(Vec::new());
+    clip.key = Some(rootless.clone());
+    let clip_id = clip.id;
+    let (mut engine, track_id) = engine_with_clips(TrackKind::Midi, vec![clip]);
+
+    let clip = engine
+        .add_clip_note_in_key(track_id, clip_id, note(60, 0, 480))
+        .expect("snapped add should succeed");
+    assert_eq!(pitches(&clip), vec![64]);
+    assert_eq!(rootless.snap(57), 55);
+    assert_eq!(rootless.transpose(64, -1), 55);
+
+    let wide = Key {
+        root: 0,
+        mode: ScaleMode::Custom(vec![0, 250]),
+    };
+    let classes = wide.pitch_classes();
+    assert!(classes[0] && classes[10]);
+    assert_eq!(classes.iter().filter(|class| **class).count(), 2);
+}
+
+#[test]
+fn key_detection_reads_tonal_centre_from_notes() {
+    // A G major melody leaning on G, B and D.
+    let melody = vec![
+        note(67, 0, 960),
+        note(69, 960, 480),
+        note(71, 1_440, 960),
+        note(72, 2_400, 480),
+        note(74, 2_880, 960),
+        note(76, 3_840, 480),
+        note(78, 4_320, 480),
+        note(79, 4_800, 1_920),
+    ];
+    let estimate = detect_key(&melody).expect("melody should have a key");
+    assert_eq!(
+        estimate.key,
+        Key {
+            root: 7,
+            mode: ScaleMode::Major,
+        }
+    );
+    assert!(estimate.confidence > 0.5);
+
+    // A long C# on the drum channel would drag a naive estimate off G.
+    let mut drums = note(61, 0, 7_680);
+    drums.channel = 9;
+    let (engine, track_id) = engine_with_clips(
+        TrackKind::Midi,
+        vec![midi_clip(melody), midi_clip(vec![drums])],
+    );
+    let clip_id = engine.project().tracks[0].clips[0].id;
+    assert_eq!(
+        engine
+            .detect_clip_key(track_id, clip_id)
+            .expect("clip detection should succeed")
+            .map(|estimate| estimate.key.root),
+        Some(7)
+    );
+    assert_eq!(
+        engine.detect_project_key().map(|estimate| estimate.key),
+        Some(Key {
+            root: 7,
+            mode: ScaleMode::Major,
+        }),
+        "drum-channel notes do not pull the estimate"
+    );
+    assert!(detect_key(&[]).is_none());
+}
@@ -0,0 +1,220 @@
+use midly::{MetaMessage, MidiMessage, Smf, TrackEventKind};
+use voltlane_core::{
+    AddClipRequest, AddTrackRequest, AutomationPoint, Engine, TrackMixPatch,
+    export::midi_bytes,
+    midi_import::{MidiImportOptions, parse_midi},
+    model::{ClipPayload, DEFAULT_SAMPLE_RATE, MidiClip, MidiNote, Project, TrackKind},
+};
+
+fn note(pitch: u8, start_tick: u64) -> MidiNote {
+    MidiNote {
+        pitch,
+        velocity: 100,
+        start_tick,
+        length_ticks: 240,
+        channel: 0,
+        release_velocity: None,
+    }
+}
+
+fn add_midi_track(engine: &mut Engine, name: &str, instrument: Option<&str>) -> uuid::Uuid {
+    let track = engine.add_track(AddTrackRequest {
+        name: name.to_string(),
+        color: "#4fb8ff".to_string(),
+        kind: TrackKind::Midi,
+    });
+    engine
+        .add_clip(AddClipRequest {
+            track_id: track.id,
+            name: name.to_string(),
+            start_tick: 0,
+            length_ticks: 1_920,
+            payload: ClipPayload::Midi(MidiClip {
+                instrument: instrument.map(str::to_string),
+                notes: vec![note(40, 0), note(43, 480)],
+                controllers: Vec::new(),
+            }),
+        })
+        .expect("midi clip add should succeed");
+    track.id
+}
+
+#[derive(Debug, Default)]
+struct ExportedTrack {
+    name: String,
+    channels: Vec<u8>,
+    programs: Vec<u8>,
+    controllers: Vec<(u64, u8, u8)>,
+}
+
+fn exported_tracks(bytes: &[u8]) -> (String, Vec<ExportedTrack>) {
+    let smf = Smf::parse(bytes).expect("exported midi should parse");
+    let mut sequence_name = String::new();
+    let mut tracks = Vec::new();
+    for (index, events) in smf.tracks.iter().enumerate() {
+        let mut track = ExportedTrack::default();
+        let mut tick = 0_u64;
+        for event in events {
+            tick += u64::from(event.delta.as_int());
+            match event.kind {
+                TrackEventKind::Meta(MetaMessage::TrackName(name)) => {
+                    let name = String::from_utf8_lossy(name).to_string();
+                    if index == 0 {
+                        sequence_name = name;
+                    } else {
+                        track.name = name;
+                    }
+                }
+                TrackEventKind::Midi { channel, message } => {
+                    if !track.channels.contains(&channel.as_int()) {
+                        track.channels.push(channel.as_int());
+                    }
+                    match message {
+                        MidiMessage::ProgramChange { program } => {
+                            track.programs.push(program.as_int());
+                        }
+                        MidiMessage::Controller { controller, value } => {
+                            track
+                                .controllers
+                                .push((tick, controller.as_int(), value.as_int()));
+                        }
+                        _ => {}
+                    }
+                }
+                _ => {}
+            }
+        }
+        if index > 0 {
+            tracks.push(track);
+        }
+    }
+    (sequence_name, tracks)
+}
+
+#[test]
+fn midi_export_writes_names_channels_programs_and_mix_controllers() {
+    let mut engine = Engine::new(Project::new("Export Names", 120.0, DEFAULT_SAMPLE_RATE));
+    let bass = add_midi_track(&mut engine, "Bass", Some("Electric Bass (finger)"));
+    add_midi_track(&mut engine, "Drums", Some("Drum Kit"));
+    add_midi_track(&mut engine, "Keys", None);
+    engine
+        .patch_track_mix(
+            bass,
+            TrackMixPatch {
+                gain_db: Some(-6.0),
+                pan: Some(-1.0),
+                ..TrackMixPatch::default()
+            },
+        )
+        .expect("mix patch should succeed");
+
+    let lanes = engine.add_track(AddTrackRequest {
+        name: "Automation".to_string(),
+        color: "#a07cff".to_string(),
+        kind: TrackKind::Automation,
+    });
+    engine
+        .add_automation_clip(
+            lanes.id,
+            "Bass pan".to_string(),
+            960,
+            960,
+            format!("track:{bass}:pan"),
+            vec![
+                AutomationPoint {
+                    tick: 0,
+                    value: 0.0,
+                },
+                AutomationPoint {
+                    tick: 480,
+                    value: 1.0,
+                },
+            ],
+        )
+        .expect("automation clip add should succeed");
+
+    let bytes = midi_bytes(engine.project()).expect("midi export should succeed");
+    let (sequence_name, tracks) = exported_tracks(&bytes);
+    assert_eq!(sequence_name, "Export Names");
+    let names: Vec<&str> = tracks.iter().map(|track| track.name.as_str()).collect();
+    assert_eq!(names, vec!["Bass", "Drums", "Keys"]);
+
+    assert_eq!(tracks[0].channels, vec![0]);
+    assert_eq!(tracks[0].programs, vec![33]);
+    assert_eq!(
+        tracks[0].controllers,
+        vec![(0, 7, 90), (0, 10, 0), (960, 10, 64), (1_440, 10, 127)]
+    );
+    assert_eq!(
+        tracks[1].channels,
+        vec![9],
+        "drum kit uses the GM drum channel"
+    );
+    assert_eq!(tracks[2].channels, vec![1]);
+    assert_eq!(tracks[2].programs, vec![0]);
+    assert_eq!(tracks[2].controllers, vec![(0, 7, 127), (0, 10, 64)]);
+
+    let reimported =
+        parse_midi(&bytes, &MidiImportOptions::default()).expect("reimport should succeed");
+    assert_eq!(reimported.title, "Export Names");
+    let ClipPayload::Midi(midi) = &reimported.tracks[0].clips[0].payload else {
+        panic!("reimported bass should be a midi clip");
+    };
+    assert_eq!(midi.instrument.as_deref(), Some("Electric Bass (finger)"));
+    let ClipPayload::Midi(drums) = &reimported.tracks[1].clips[0].payload else {
+        panic!("reimported drums should be a midi clip");
+    };
+    assert_eq!(drums.instrument.as_deref(), Some("Drum Kit"));
+}
+
+#[test]
+fn linked_automation_clips_export_mix_controllers_per_placement() {
+    let mut engine = Engine::new(Project::new("Linked Lanes", 120.0, DEFAULT_SAMPLE_RATE));
+    let bass = add_midi_track(&mut engine, "Bass", None);
+    let lanes = engine.add_track(AddTrackRequest {
+        name: "Automation".to_string(),
+        color: "#a07cff".to_string(),
+        kind: TrackKind::Automation,
+    });
+    let sweep = engine
+        .add_automation_clip(
+            lanes.id,
+            "Bass pan".to_string(),
+            480,
+            480,
+            format!("track:{bass}:pan"),
+            vec![
+                AutomationPoint {
+                    tick: 0,
+                    value: 0.0,
+                },
+                AutomationPoint {
+                    tick: 240,
+                    value: 1.0,
+                },
+            ],
+        )
+        .expect("automation clip add should succeed");
+    engine
+        .duplicate_clip_linked(lanes.id, sweep.id, 1_440)
+        .expect("linked duplicate should succeed");
+
+    let bytes = midi_bytes(engine.project()).expect("midi export should succeed");
+    let (_, tracks) = exported_tracks(&bytes);
+    let automated: Vec<(u64, u8, u8)> = tracks[0]
+        .controllers
+        .iter()
+        .copied()
+        .filter(|(tick, _, _)| *tick > 0)
+        .collect();
+    assert_eq!(
+        automated,
+        vec![
+            (480, 10, 64),
+            (720, 10, 127),
+            (1_440, 10, 64),
+            (1_680, 10, 127)
+        ],
+        "both linked placements should write their pan sweep"
+    );
+}
@@ -0,0 +1,232 @@
+use midly::{MidiMessage, Smf, TrackEventKind};
+use voltlane_core::{
+    AddClipRequest, AddTrackRequest, AutomationPoint, Engine,
+    export::{midi_bytes, render_project_samples},
+    midi_import::{MidiImportOptions, parse_midi},
+    model::{
+        Clip, ClipPayload, ControllerLane, ControllerTarget, DEFAULT_SAMPLE_RATE, MidiClip,
+        MidiNote, Project, SynthOscillator, SynthPatch, SynthWaveform, Track, TrackKind,
+    },
+};
+
+fn points(values: &[(u64, f32)]) -> Vec<AutomationPoint> {
+    values
+        .iter()
+        .map(|(tick, value)| AutomationPoint {
+            tick: *tick,
+            value: *value,
+        })
+        .collect()
+}
+
+fn lane(target: ControllerTarget, values: &[(u64, f32)]) -> ControllerLane {
+    ControllerLane {
+        target,
+        points: points(values),
+    }
+}
+
+/// One A4 quarter note through a plain sine patch at 120 bpm.
+fn sine_project(controllers: Vec<ControllerLane>) -> Project {
+    let mut project = Project::new("Expression", 120.0, DEFAULT_SAMPLE_RATE);
+    let mut track = Track::new("Lead", "#8f6bff", TrackKind::Midi);
+    track.synth_patches = vec![SynthPatch {
+        name: "Sine".to_string(),
+        oscillators: vec![SynthOscillator {
+            waveform: SynthWaveform::Sine,
+            ..SynthOscillator::default()
+        }],
+        ..SynthPatch::default()
+    }];
+    track.clips.push(Clip::new(
+        "phrase",
+        0,
+        1_920,
+        ClipPayload::Midi(MidiClip {
+            instrument: Some("Sine".to_string()),
+            notes: vec![MidiNote {
+                pitch: 69,
+                velocity: 120,
+                start_tick: 0,
+                length_ticks: 480,
+                channel: 0,
+                release_velocity: None,
+            }],
+            controllers,
+        }),
+    ));
+    project.tracks.push(track);
+    project
+}
+
+fn window(samples: &[f32], from_seconds: f64, to_seconds: f64) -> &[f32] {
+    let rate = f64::from(DEFAULT_SAMPLE_RATE);
+    let from = ((from_seconds * rate) as usize).min(samples.len());
+    let to = ((to_seconds * rate) as usize).min(samples.len());
+    &samples[from..to]
+}
+
+fn zero_crossings(samples: &[f32]) -> usize {
+    samples
+        .windows(2)
+        .filter(|pair| (pair[0] < 0.0) != (pair[1] < 0.0))
+        .count()
+}
+
+fn energy(samples: &[f32]) -> f32 {
+    samples.iter().map(|sample| sample * sample).sum::<f32>() / samples.len().max(1) as f32
+}
+
+#[test]
+fn controller_lanes_bend_scale_and_sustain_synth_voices() {
+    let plain = render_project_samples(&sine_project(Vec::new()), 1.0);
+    let bent = render_project_samples(
+        &sine_project(vec![lane(ControllerTarget::PitchBend, &[(0, 1.0)])]),
+        1.0,
+    );
+    let quiet = render_project_samples(
+        &sine_project(vec![lane(ControllerTarget::Expression, &[(0, 0.25)])]),
+        1.0,
+    );
+    let sustained = render_project_samples(
+        &sine_project(vec![lane(
+            ControllerTarget::Sustain,
+            &[(0, 1.0), (1_440, 0.0)],
+        )]),
+        1.0,
+    );
+
+    // A full-scale bend raises the note by the two-semitone bend range.
+    let ratio = zero_crossings(window(&bent, 0.05, 0.45)) as f32
+        / zero_crossings(window(&plain, 0.05, 0.45)) as f32;
+    assert!(
+        (ratio - 2_f32.powf(2.0 / 12.0)).abs() < 0.02,
+        "bend ratio was {ratio}"
+    );
+
+    let level = energy(window(&quiet, 0.05, 0.45)) / energy(window(&plain, 0.05, 0.45));
+    assert!(
+        (level - 0.0625).abs() < 0.01,
+        "expression energy ratio was {level}"
+    );
+
+    // The pedal holds the gate from the note-off at 0.5s until it lifts at 1.5s.
+    assert!(energy(window(&plain, 1.0, 1.4)) < 1e-8);
+    assert!(energy(window(&sustained, 1.0, 1.4)) > energy(window(&plain, 0.05, 0.45)) * 0.5);
+}
+
+#[test]
+fn midi_export_writes_controller_lanes_and_release_velocity() {
+    let mut engine = Engine::new(Project::new("Lanes", 120.0, DEFAULT_SAMPLE_RATE));
+    let track = engine.add_track(AddTrackRequest {
+        name: "Keys".to_string(),
+        color: "#4fb8ff".to_string(),
+        kind: TrackKind::Midi,
+    });
+    let clip = engine
+        .add_clip(AddClipRequest {
+            track_id: track.id,
+            name: "Keys".to_string(),
+            start_tick: 0,
+            length_ticks: 1_920,
+            payload: ClipPayload::Midi(MidiClip {
+                instrument: None,
+                notes: vec![MidiNote {
+                    pitch: 60,
+                    velocity: 100,
+                    start_tick: 0,
+                    length_ticks: 480,
+                    channel: 0,
+                    release_velocity: Some(90),
+                }],
+                controllers: vec![lane(ControllerTarget::Sustain, &[(0, 1.0), (960, 0.0)])],
+            }),
+        })
+        .expect("midi clip add should succeed");
+    engine
+        .upsert_controller_lane(
+            track.id,
+            clip.id,
+            ControllerTarget::ModWheel,
+            points(&[(0, 0.0), (480, 1.0)]),
+        )
+        .expect("mod wheel lane should be added");
+    engine
+        .upsert_controller_lane(
+            track.id,
+            clip.id,
+            ControllerTarget::PitchBend,
+            points(&[(240, 3.0)]),
+        )
+        .expect("pitch bend lane should be added");
+
+    let bytes = midi_bytes(engine.project()).expect("midi export should succeed");
+    let smf = Smf::parse(&bytes).expect("exported midi should parse");
+    let mut controllers = Vec::new();
+    let mut bends = Vec::new();
+    let mut release_velocities = Vec::new();
+    let mut tick = 0_u64;
+    for event in &smf.tracks[1] {
+        tick += u64::from(event.delta.as_int());
+        let TrackEventKind::Midi { message, .. } = event.kind else {
+            continue;
+        };
+        match message {
+            MidiMessage::Controller { controller, value }
+                if matches!(controller.as_int(), 1 | 64) =>
+            {
+                controllers.push((tick, controller.as_int(), value.as_int()));
+            }
+            MidiMessage::PitchBend { bend } => bends.push((tick, bend.as_int())),
+            MidiMessage::NoteOff { vel, .. } => release_velocities.push(vel.as_int()),
+            _ => {}
+        }
+    }
+
+    // The mod wheel ramp is written in eighth-beat steps.
+    let mod_wheel: Vec<(u64, u8)> = controllers
+        .iter()
+        .filter(|(_, controller, _)| *controller == 1)
+        .map(|(tick, _, value)| (*tick, *value))
+        .collect();
+    assert_eq!(mod_wheel.len(), 9);
+    assert_eq!(mod_wheel.first(), Some(&(0, 0)));
+    assert_eq!(mod_wheel.get(4), Some(&(240, 64)));
+    assert_eq!(mod_wheel.last(), Some(&(480, 127)));
+    let sustain: Vec<(u64, u8)> = controllers
+        .iter()
+        .filter(|(_, controller, _)| *controller == 64)
+        .map(|(tick, _, value)| (*tick, *value))
+        .collect();
+    assert_eq!(sustain, vec![(0, 127), (960, 0)]);
+    assert_eq!(
+        bends,
+        vec![(240, 8_191)],
+        "bend clamps to the top of its range"
+    );
+    assert_eq!(release_velocities, vec![90]);
+
+    let reimported =
+        parse_midi(&bytes, &MidiImportOptions::default()).expect("reimport should succeed");
+    let ClipPayload::Midi(midi) = &reimported.tracks[0].clips[0].payload else {
+        panic!("reimported keys should be a midi clip");
+    };
+    assert_eq!(midi.notes[0].release_velocity, Some(90));
+    let targets: Vec<ControllerTarget> = midi.controllers.iter().map(|lane| lane.target).collect();
+    assert_eq!(
+        targets,
+        vec![
+            ControllerTarget::ModWheel,
+            ControllerTarget::Sustain,
+            ControllerTarget::PitchBend,
+        ]
+    );
+    let sustain = midi
+        .controller(ControllerTarget::Sustain)
+        .expect("sustain lane should be imported");
+    assert_eq!(sustain.points, points(&[(0, 1.0), (960, 0.0)]));
+    let bend = midi
+        .controller(ControllerTarget::PitchBend)
+        .expect("pitch bend lane should be imported");
+    assert!((bend.value_at(240) - 1.0).abs() < 1e-3);
+}
@@ -0,0 +1,246 @@
+use midly::{
+    Format, Header, MetaMessage, MidiMessage, Smf, Timing, TrackEvent, TrackEventKind,
+    num::{u4, u7, u15, u24, u28},
+};
+use tempfile::tempdir;
+use voltlane_core::{
+    Engine,
+    midi_import::{MidiImportOptions, parse_midi},
+    model::{ClipPayload, DEFAULT_SAMPLE_RATE, Project, TimeSignature, TrackKind},
+};
+
+type ClipSummary<'a> = (u64, u64, Option<&'a str>, Vec<(u8, u64, u64)>);
+
+fn event(delta: u32, kind: TrackEventKind<'static>) -> TrackEvent<'static> {
+    TrackEvent {
+        delta: u28::from(delta),
+        kind,
+    }
+}
+
+fn message(channel: u8, message: MidiMessage) -> TrackEventKind<'static> {
+    TrackEventKind::Midi {
+        channel: u4::from(channel),
+        message,
+    }
+}
+
+fn note_on(channel: u8, key: u8, vel: u8) -> TrackEventKind<'static> {
+    message(
+        channel,
+        MidiMessage::NoteOn {
+            key: u7::from(key),
+            vel: u7::from(vel),
+        },
+    )
+}
+
+fn note_off(channel: u8, key: u8) -> TrackEventKind<'static> {
+    message(
+        channel,
+        MidiMessage::NoteOff {
+            key: u7::from(key),
+            vel: u7::from(0),
+        },
+    )
+}
+
+fn program(channel: u8, program: u8) -> TrackEventKind<'static> {
+    message(
+        channel,
+        MidiMessage::ProgramChange {
+            program: u7::from(program),
+        },
+    )
+}
+
+fn smf_bytes(format: Format, tracks: Vec<Vec<TrackEvent<'static>>>) -> Vec<u8> {
+    let tracks = tracks
+        .into_iter()
+        .map(|mut events| {
+            events.push(event(0, TrackEventKind::Meta(MetaMessage::EndOfTrack)));
+            events
+        })
+        .collect();
+    let smf = Smf {
+        header: Header {
+            format,
+            timing: Timing::Metrical(u15::from(96)),
+        },
+        tracks,
+    };
+    let mut bytes = Vec::new();
+    smf.write_std(&mut bytes)
+        .expect("test midi file should encode");
+    bytes
+}
+
+#[test]
+fn format_one_file_maps_tempo_meter_names_programs_and_channels() {
+    let conductor = vec![
+        event(0, TrackEventKind::Meta(MetaMessage::TrackName(b"Sketch"))),
+        event(
+            0,
+            TrackEventKind::Meta(MetaMessage::Tempo(u24::from(600_000))),
+        ),
+        event(
+            0,
+            TrackEventKind::Meta(MetaMessage::TimeSignature(3, 2, 24, 8)),
+        ),
+    ];
+    let lead = vec![
+        event(0, TrackEventKind::Meta(MetaMessage::TrackName(b"Lead"))),
+        event(0, program(0, 80)),
+        event(0, note_on(0, 60, 100)),
+        event(96, note_off(0, 60)),
+        event(0, note_on(0, 64, 90)),
+        event(96, note_on(0, 64, 0)),
+        event(192, program(0, 81)),
+        event(16, note_on(0, 67, 80)),
+        event(48, note_off(0, 67)),
+    ];
+    let mixed = vec![
+        event(0, TrackEventKind::Meta(MetaMessage::TrackName(b"Mixed"))),
+        event(0, note_on(1, 40, 70)),
+        event(0, note_on(9, 36, 127)),
+        event(48, note_off(9, 36)),
+        event(48, note_off(1, 40)),
+    ];
+    let bytes = smf_bytes(Format::Parallel, vec![conductor, lead, mixed]);
+    let project =
+        parse_midi(&bytes, &MidiImportOptions::default()).expect("midi import should succeed");
+
+    assert_eq!(project.title, "Sketch");
+    assert!((project.bpm - 100.0).abs() < 1e-9);
+    assert_eq!(
+        project.time_signature,
+        TimeSignature {
+            numerator: 3,
+            denominator: 4,
+        }
+    );
+    let names: Vec<&str> = project
+        .tracks
+        .iter()
+        .map(|track| track.name.as_str())
+        .collect();
+    assert_eq!(names, vec!["Lead", "Mixed Ch 02", "Mixed Ch 10"]);
+
+    // File ppq 96 scales to 480, and 3/4 bars are 1440 ticks long.
+    let lead = &project.tracks[0];
+    assert_eq!(lead.kind, TrackKind::Midi);
+    let clips: Vec<ClipSummary<'_>> = lead
+        .clips
+        .iter()
+        .map(|clip| {
+            let ClipPayload::Midi(midi) = &clip.payload else {
+                panic!("lead clips should be midi clips");
+            };
+            (
+                clip.start_tick,
+                clip.length_ticks,
+                midi.instrument.as_deref(),
+                midi.notes
+                    .iter()
+                    .map(|note| (note.pitch, note.start_tick, note.length_ticks))
+                    .collect(),
+            )
+        })
+        .collect();
+    assert_eq!(
+        clips,
+        vec![
+            (
+                0,
+                1_440,
+                Some("Lead 1 (square)"),
+                vec![(60, 0, 480), (64, 480, 480)]
+            ),
+            (1_920, 960, Some("Lead 2 (sawtooth)"), vec![(67, 80, 240)]),
+        ]
+    );
+
+    let ClipPayload::Midi(drums) = &project.tracks[2].clips[0].payload else {
+        panic!("drum clip should be a midi clip");
+    };
+    assert_eq!(drums.instrument.as_deref(), Some("Drum Kit"));
+    assert_eq!(drums.notes[0].channel, 9);
+    let ClipPayload::Midi(bass) = &project.tracks[1].clips[0].payload else {
+        panic!("bass clip should be a midi clip");
+    };
+    assert_eq!(bass.instrument, None, "no program change was sent");
+    assert_eq!(bass.notes[0].length_ticks, 480);
+}
+
+#[test]
+fn engine_merges_format_zero_file_and_converts_pattern_channels() {
+    let song = vec![
+        event(
+            0,
+            TrackEventKind::Meta(MetaMessage::Tempo(u24::from(400_000))),
+        ),
+        event(0, note_on(0, 48, 100)),
+        event(0, note_on(2, 72, 110)),
+        event(24, note_off(2, 72)),
+        event(24, note_on(2, 76, 90)),
+        event(24, note_off(2, 76)),
+        event(24, note_off(0, 48)),
+    ];
+    let temp = tempdir().expect("tempdir should be created");
+    let path = temp.path().join("sketch.mid");
+    std::fs::write(&path, smf_bytes(Format::SingleTrack, vec![song]))
+        .expect("midi file should be written");
+
+    let mut engine = Engine::new(Project::new("Existing", 140.0, DEFAULT_SAMPLE_RATE));
+    engine.add_track(voltlane_core::AddTrackRequest {
+        name: "Keep".to_string(),
+        color: "#ffffff".to_string(),
+        kind: TrackKind::Midi,
+    });
+    let options = MidiImportOptions {
+        pattern_channels: vec![2],
+        pattern_chip: "nes_2a03_pulse".to_string(),
+    };
+    let merged = engine
+        .import_midi(&path, &options)
+        .expect("midi merge should succeed");
+
+    let project = engine.project();
+    assert!(
+        (project.bpm - 140.0).abs() < f64::EPSILON,
+        "merging keeps the current tempo"
+    );
+    assert_eq!(project.tracks.len(), 3);
+    assert_eq!(project.tracks[0].name, "Keep");
+    let names: Vec<&str> = merged.iter().map(|track| track.name.as_str()).collect();
+    assert_eq!(names, vec!["Track 01 Ch 01", "Track 01 Ch 03"]);
+
+    let chip = &project.tracks[2];
+    assert_eq!(chip.kind, TrackKind::Chip);
+    let ClipPayload::Pattern(pattern) = &chip.clips[0].payload else {
+        panic!("converted channel should hold a pattern clip");
+    };
+    assert_eq!(pattern.source_chip, "nes_2a03_pulse");
+    let rows: Vec<(u32, Option<u8>, u8)> = pattern
+        .rows
+        .iter()
+        .map(|row| (row.row, row.note, row.velocity))
+        .collect();
+    assert_eq!(rows, vec![(0, Some(72), 110), (2, Some(76), 90)]);
+
+    let mut empty = Engine::new(Project::new("Empty", 120.0, DEFAULT_SAMPLE_RATE));
+    empty
+        .import_midi(&path, &MidiImportOptions::default())
+        .expect("midi merge into an empty project should succeed");
+    assert!((empty.project().bpm - 150.0).abs() < 1e-9);
+    assert!(
+        parse_midi(
+            &std::fs::read(&path).expect("midi file should be readable"),
+            &MidiImportOptions {
+                pattern_channels: vec![2],
+                pattern_chip: String::new(),
+            },
+        )
+        .is_err()
+    );
+}
@@ -0,0 +1,344 @@
+use std::path::Path;
+
+use tempfile::tempdir;
+use voltlane_core::{
+    Engine,
+    export::render_project_samples,
+    model::{ClipPayload, PatternClip, Project},
+};
+
+fn pooled_pattern<'a>(project: &'a Project, name: &str) -> &'a PatternClip {
+    let entry = project
+        .clip_pool
+        .iter()
+        .find(|entry| entry.name == name)
+        .unwrap_or_else(|| panic!("pool should contain pattern {name}"));
+    match &entry.payload {
+        ClipPayload::Pattern(pattern) => pattern,
+        other => panic!("pool entry {name} should be a pattern, got {other:?}"),
+    }
+}
+
+type Cell<'a> = (u32, Option<u8>, u8, Option<&'a str>, Option<u16>);
+
+fn cells(pattern: &PatternClip) -> Vec<Cell<'_>> {
+    pattern
+        .rows
+        .iter()
+        .map(|row| {
+            (
+                row.row,
+                row.note,
+                row.velocity,
+                row.effect.as_deref(),
+                row.effect_value,
+            )
+        })
+        .collect()
+}
+
+fn put_text(bytes: &mut [u8], offset: usize, text: &str) {
+    bytes[offset..offset + text.len()].copy_from_slice(text.as_bytes());
+}
+
+fn put_u16_le(bytes: &mut [u8], offset: usize, value: u16) {
+    bytes[offset..offset + 2].copy_from_slice(&value.to_le_bytes());
+}
+
+fn put_u32_le(bytes: &mut [u8], offset: usize, value: u32) {
+    bytes[offset..offset + 4].copy_from_slice(&value.to_le_bytes());
+}
+
+/// Four-channel M.K. module: a looped bass and a one-shot hat over two patterns,
+/// with a `B01` jump at the end of the second.
+fn protracker_module() -> Vec<u8> {
+    let mut bytes = vec![0_u8; 1_084];
+    put_text(&mut bytes, 0, "Mod Fixture");
+    let samples: [(&str, u16, u8, u16, u16); 2] = [("Bass", 32, 48, 0, 32), ("Hat", 16, 64, 0, 1)];
+    for (index, (name, words, volume, loop_start, loop_words)) in samples.iter().enumerate() {
+        let offset = 20 + index * 30;
+        put_text(&mut bytes, offset, name);
+        bytes[offset + 22..offset + 24].copy_from_slice(&words.to_be_bytes());
+        bytes[offset + 25] = *volume;
+        bytes[offset + 26..offset + 28].copy_from_slice(&loop_start.to_be_bytes());
+        bytes[offset + 28..offset + 30].copy_from_slice(&loop_words.to_be_bytes());
+    }
+    bytes[950] = 2;
+    bytes[952] = 0;
+    bytes[953] = 1;
+    put_text(&mut bytes, 1_080, "M.K.");
+
+    let cell = |period: u16, instrument: u8, effect: u8, param: u8| -> [u8; 4] {
+        [
+            (instrument & 0xF0) | (period >> 8) as u8,
+            period as u8,
+            ((instrument & 0x0F) << 4) | effect,
+            param,
+        ]
+    };
+    let mut patterns = vec![0_u8; 2 * 64 * 4 * 4];
+    let mut set = |pattern: usize, row: usize, channel: usize, value: [u8; 4]| {
+        let offset = ((pattern * 64 + row) * 4 + channel) * 4;
+        patterns[offset..offset + 4].copy_from_slice(&value);
+    };
+    set(0, 0, 0, cell(428, 1, 0x0F, 0x03));
+    set(0, 0, 1, cell(214, 2, 0x0C, 0x20));
+    set(0, 8, 0, cell(453, 0, 0, 0));
+    set(1, 0, 0, cell(428, 1, 0, 0));
+    set(1, 63, 0, cell(0, 0, 0x0B, 0x01));
+    bytes.extend(patterns);
+
+    bytes.extend((0..64).map(|frame| if frame % 16 < 8 { 0x60_u8 } else { 0xA0 }));
+    bytes.extend((0..32).map(|frame| (frame * 37 % 255) as u8));
+    bytes
+}
+
+/// Two-channel XM with one 16-bit sample pitched up an octave via relative note.
+fn fasttracker_module() -> Vec<u8> {
+    let mut bytes = vec![0_u8; 60 + 276];
+    put_text(&mut bytes, 0, "Extended Module: ");
+    put_text(&mut bytes, 17, "Xm Fixture");
+    bytes[37] = 0x1A;
+    put_u16_le(&mut bytes, 58, 0x0104);
+    put_u32_le(&mut bytes, 60, 276);
+    put_u16_le(&mut bytes, 64, 1);
+    put_u16_le(&mut bytes, 68, 2);
+    put_u16_le(&mut bytes, 70, 1);
+    put_u16_le(&mut bytes, 72, 1);
+    put_u16_le(&mut bytes, 74, 1);
+    put_u16_le(&mut bytes, 76, 6);
+    put_u16_le(&mut bytes, 78, 120);
+
+    let mut packed = vec![49, 1, 0x30, 0, 0, 0x80, 0x81, 97, 0x80];
+    packed.extend(std::iter::repeat_n(0x80, 14 * 2));
+    let mut pattern_header = vec![0_u8; 9];
+    put_u32_le(&mut pattern_header, 0, 9);
+    put_u16_le(&mut pattern_header, 5, 16);
+    put_u16_le(&mut pattern_header, 7, packed.len() as u16);
+    bytes.extend(pattern_header);
+    bytes.extend(packed);
+
+    let mut instrument = vec![0_u8; 263];
+    put_u32_le(&mut instrument, 0, 263);
+    put_text(&mut instrument, 4, "Lead");
+    put_u16_le(&mut instrument, 27, 1);
+    put_u32_le(&mut instrument, 29, 40);
+    bytes.extend(instrument);
+
+    let mut sample_header = vec![0_u8; 40];
+    put_u32_le(&mut sample_header, 0, 32);
+    sample_header[12] = 64;
+    sample_header[14] = 0x10;
+    sample_header[15] = 128;
+    sample_header[16] = 12;
+    put_text(&mut sample_header, 18, "Sine");
+    bytes.extend(sample_header);
+
+    let mut previous = 0_i16;
+    for frame in 0..16 {
+        let value = (f64::from(frame) / 16.0 * std::f64::consts::TAU).sin() * 20_000.0;
+        let value = value as i16;
+        bytes.extend(value.wrapping_sub(previous).to_le_bytes());
+        previous = value;
+    }
+    bytes
+}
+
+/// Sample-mode IT with one looped signed 8-bit sample and a 32-row pattern that
+/// reuses the previous mask for its second note.
+fn impulse_tracker_module() -> Vec<u8> {
+    let mut bytes = vec![0_u8; 0xD0];
+    put_text(&mut bytes, 0, "IMPM");
+    put_text(&mut bytes, 4, "It Fixture");
+    put_u16_le(&mut bytes, 0x20, 2);
+    put_u16_le(&mut bytes, 0x24, 1);
+    put_u16_le(&mut bytes, 0x26, 1);
+    bytes[0x32] = 6;
+    bytes[0x33] = 150;
+    bytes[0xC0] = 0;
+    bytes[0xC1] = 255;
+
+    let sample_header = 0xD0;
+    let sample_data = sample_header + 0x50;
+    let pattern_header = sample_data + 32;
+    put_u32_le(&mut bytes, 0xC2, sample_header as u32);
+    put_u32_le(&mut bytes, 0xC6, pattern_header as u32);
+
+    let mut sample = vec![0_u8; 0x50];
+    put_text(&mut sample, 0, "IMPS");
+    sample[0x12] = 0x01 | 0x10;
+    sample[0x13] = 64;
+    put_text(&mut sample, 0x14, "Pad");
+    sample[0x2E] = 0x01;
+    put_u32_le(&mut sample, 0x30, 32);
+    put_u32_le(&mut sample, 0x38, 32);
+    put_u32_le(&mut sample, 0x3C, 22_050);
+    put_u32_le(&mut sample, 0x48, sample_data as u32);
+    bytes.extend(sample);
+    bytes.extend((0..32).map(|frame| if frame < 16 { 0x50_u8 } else { 0xB0 }));
+
+    let mut rows = vec![0x81, 0x0B, 60, 1, 1, 4, 0];
+    rows.push(0);
+    rows.extend([0x01, 64, 1, 20, 0x80, 0]);
+    rows.push(0);
+    rows.extend([0x81, 0x01, 255, 0]);
+    rows.extend(std::iter::repeat_n(0, 27));
+    let mut header = vec![0_u8; 8];
+    put_u16_le(&mut header, 0, rows.len() as u16);
+    put_u16_le(&mut header, 2, 32);
+    bytes.extend(header);
+    bytes.extend(rows);
+    bytes
+}
+
+#[test]
+fn protracker_module_builds_sampler_tracks_patterns_and_order_list() {
+    let temp = tempdir().expect("tempdir should be created");
+    let module_path = temp.path().join("fixture.mod");
+    std::fs::write(&module_path, protracker_module()).expect("module should be written");
+    let sample_dir = temp.path().join("samples");
+
+    let mut engine = Engine::default();
+    let project = engine
+        .import_module(&module_path, &sample_dir)
+        .expect("mod import should succeed");
+
+    assert_eq!(project.title, "Mod Fixture");
+    assert!(
+        (project.bpm - 250.0).abs() < f64::EPSILON,
+        "F03 at tempo 125"
+    );
+    let names: Vec<&str> = project
+        .tracks
+        .iter()
+        .map(|track| track.name.as_str())
+        .collect();
+    assert_eq!(names, vec!["Ch 01 01 Bass", "Ch 02 02 Hat"]);
+
+    let bass = &project.tracks[0].samplers[0];
+    assert_eq!(bass.name, "01 Bass");
+    assert!(bass.zones[0].loop_enabled);
+    assert!(!bass.zones[0].one_shot);
+    assert!(Path::new(&bass.zones[0].source_path).ends_with("samples/01_Bass.wav"));
+    assert!(Path::new(&bass.zones[0].source_path).is_file());
+    let hat = &project.tracks[1].samplers[0];
+    assert!(hat.zones[0].one_shot);
+    assert!(sample_dir.join("02_Hat.wav").is_file());
+
+    let order_list = project
+        .order_list
+        .as_ref()
+        .expect("order list should be set");
+    assert_eq!(order_list.rows.len(), 2);
+    assert_eq!(order_list.row_length_ticks, 64 * 120);
+    assert_eq!(order_list.loop_row, Some(1), "B01 in the last order");
+    assert_eq!(order_list.rows[1].patterns[1], None, "hat is silent in 01");
+
+    let first = pooled_pattern(&project, "Ch 01 01 Bass 00");
+    assert_eq!(first.source_chip, "mod");
+    assert_eq!(first.instrument.as_deref(), Some("01 Bass"));
+    assert_eq!(
+        cells(first),
+        vec![
+            (0, Some(60), 95, Some("F"), Some(3)),
+            (8, Some(59), 95, None, None)
+        ]
+    );
+    let lengths: Vec<u64> = first.notes.iter().map(|note| note.length_ticks).collect();
+    assert_eq!(
+        lengths,
+        vec![8 * 120, 56 * 120],
+        "notes ring until the next event"
+    );
+
+    let hat_pattern = pooled_pattern(&project, "Ch 02 02 Hat 00");
+    assert_eq!(
+        cells(hat_pattern),
+        vec![(0, Some(72), 64, Some("C"), Some(0x20))]
+    );
+    let second = pooled_pattern(&project, "Ch 01 01 Bass 01");
+    assert_eq!(second.rows[1].effect.as_deref(), Some("B"));
+
+    let rendered = render_project_samples(&project, 0.0);
+    assert!(rendered.iter().any(|sample| sample.abs() > 0.01));
+}
+
+#[test]
+fn fasttracker_module_maps_keymap_relative_note_and_key_off() {
+    let temp = tempdir().expect("tempdir should be created");
+    let module_path = temp.path().join("fixture.xm");
+    std::fs::write(&module_path, fasttracker_module()).expect("module should be written");
+
+    let project = voltlane_core::module_import::import_module(&module_path, temp.path())
+        .expect("xm import should succeed");
+
+    assert_eq!(project.title, "Xm Fixture");
+    assert!((project.bpm - 120.0).abs() < f64::EPSILON);
+    assert_eq!(project.tracks.len(), 1);
+    assert_eq!(project.tracks[0].name, "Ch 01 01 Lead");
+
+    let zone = &project.tracks[0].samplers[0].zones[0];
+    assert_eq!(zone.root_note, 48, "relative note +12 lowers the root");
+    assert_eq!((zone.low_note, zone.high_note), (12, 107));
+    let mut reader =
+        hound::WavReader::open(&zone.source_path).expect("sample asset should be a wav");
+    assert_eq!(reader.spec().sample_rate, 8_363);
+    let frames: Vec<i16> = reader
+        .samples::<i16>()
+        .collect::<Result<_, _>>()
+        .expect("sample frames should decode");
+    assert_eq!(frames.len(), 16);
+    assert!(frames[4] > 19_000, "delta-decoded sine peak");
+
+    let pattern = pooled_pattern(&project, "Ch 01 01 Lead 00");
+    assert_eq!(
+        cells(pattern),
+        vec![
+            (0, Some(60), 64, None, None),
+            (1, None, 0, Some("OFF"), None)
+        ]
+    );
+    assert_eq!(pattern.notes[0].length_ticks, 120);
+    let order_list = project
+        .order_list
+        .as_ref()
+        .expect("order list should be set");
+    assert_eq!(order_list.row_length_ticks, 16 * 120);
+    assert_eq!(order_list.loop_row, None);
+}
+
+#[test]
+fn impulse_tracker_module_decodes_packed_masks_and_letter_effects() {
+    let temp = tempdir().expect("tempdir should be created");
+    let module_path = temp.path().join("fixture.it");
+    std::fs::write(&module_path, impulse_tracker_module()).expect("module should be written");
+
+    let project = voltlane_core::module_import::import_module(&module_path, temp.path())
+        .expect("it import should succeed");
+
+    assert_eq!(project.title, "It Fixture");
+    assert!((project.bpm - 150.0).abs() < f64::EPSILON);
+    let zone = &project.tracks[0].samplers[0].zones[0];
+    assert!(zone.loop_enabled);
+    assert!((zone.loop_end_seconds - 32.0 / 22_050.0).abs() < 1e-9);
+
+    let pattern = pooled_pattern(&project, "Ch 01 01 Pad 00");
+    assert_eq!(
+        cells(pattern),
+        vec![
+            (0, Some(60), 127, Some("A"), Some(4)),
+            (2, Some(64), 127, Some("T"), Some(0x80)),
+            (4, None, 0, Some("OFF"), None),
+        ]
+    );
+    let order_list = project
+        .order_list
+        .as_ref()
+        .expect("order list should be set");
+    assert_eq!(order_list.rows.len(), 1, "orders stop at the 255 marker");
+    assert_eq!(order_list.row_length_ticks, 32 * 120);
+
+    let unknown_path = temp.path().join("fixture.bin");
+    std::fs::write(&unknown_path, b"RIFF not a module").expect("file should be written");
+    assert!(voltlane_core::module_import::import_module(&unknown_path, temp.path()).is_err());
+}
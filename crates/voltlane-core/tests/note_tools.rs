@@ -0,0 +1,197 @@
+mod common;
+
+use common::engine_with_clips;
+use uuid::Uuid;
+use voltlane_core::{
+    Engine, EngineError, HumanizeOptions, StrumDirection, VelocityShape,
+    model::{Clip, ClipPayload, MidiClip, MidiNote, TrackKind},
+};
+
+/// Midi clip from `(pitch, start_tick, length_ticks)` notes at velocity 100.
+fn engine_with(notes: &[(u8, u64, u64)]) -> (Engine, Uuid, Uuid) {
+    let clip = Clip::new(
+        "phrase",
+        0,
+        3_840,
+        ClipPayload::Midi(MidiClip {
+            instrument: None,
+            notes: notes
+                .iter()
+                .map(|(pitch, start_tick, length_ticks)| MidiNote {
+                    pitch: *pitch,
+                    velocity: 100,
+                    start_tick: *start_tick,
+                    length_ticks: *length_ticks,
+                    channel: 0,
+                    release_velocity: None,
+                })
+                .collect(),
+            controllers: Vec::new(),
+        }),
+    );
+    let clip_id = clip.id;
+    let (engine, track_id) = engine_with_clips(TrackKind::Midi, vec![clip]);
+    (engine, track_id, clip_id)
+}
+
+fn notes(clip: &Clip) -> Vec<MidiNote> {
+    let ClipPayload::Midi(midi) = &clip.payload else {
+        panic!("clip should stay midi");
+    };
+    midi.notes.clone()
+}
+
+#[test]
+fn humanize_is_seeded_and_length_tools_reshape_selections() {
+    let phrase = [
+        (60, 480, 240),
+        (62, 960, 240),
+        (64, 1_440, 240),
+        (65, 1_920, 240),
+    ];
+    let options = HumanizeOptions {
+        seed: 7,
+        timing_ticks: 20,
+        velocity: 10,
+        length_percent: 25.0,
+    };
+    let humanize = |seed: u64| {
+        let (mut engine, track_id, clip_id) = engine_with(&phrase);
+        let clip = engine
+            .humanize_clip_notes(
+                track_id,
+                clip_id,
+                &[],
+                &HumanizeOptions {
+                    seed,
+                    ..options.clone()
+                },
+            )
+            .expect("humanize should succeed");
+        notes(&clip)
+    };
+    let first = humanize(7);
+    assert_eq!(first, humanize(7), "the same seed gives the same feel");
+    assert_ne!(first, humanize(8));
+    for (note, (_, start_tick, _)) in first.iter().zip(phrase) {
+        assert!(note.start_tick.abs_diff(start_tick) <= 20);
+        assert!((90..=110).contains(&note.velocity));
+        assert!((180..=300).contains(&note.length_ticks));
+    }
+
+    let (mut engine, track_id, clip_id) = engine_with(&phrase);
+    let clip = engine
+        .legato_clip_notes(track_id, clip_id, &[0, 1, 3])
+        .expect("legato should succeed");
+    let lengths: Vec<u64> = notes(&clip).iter().map(|note| note.length_ticks).collect();
+    assert_eq!(
+        lengths,
+        vec![480, 960, 240, 240],
+        "selected notes reach the next selected note; the last keeps its length"
+    );
+
+    let clip = engine
+        .set_clip_note_lengths(track_id, clip_id, &[], 120)
+        .expect("fixed length should succeed");
+    assert!(notes(&clip).iter().all(|note| note.length_ticks == 120));
+    assert!(matches!(
+        engine.set_clip_note_lengths(track_id, clip_id, &[], 0),
+        Err(EngineError::InvalidNoteLength(0))
+    ));
+    assert!(matches!(
+        engine.humanize_clip_notes(
+            track_id,
+            clip_id,
+            &[],
+            &HumanizeOptions {
+                length_percent: f32::NAN,
+                ..options
+            }
+        ),
+        Err(EngineError::InvalidHumanizeAmount(_))
+    ));
+}
+
+#[test]
+fn strum_and_velocity_ramps_follow_time_order() {
+    // A C major triad followed by a lone note.
+    let chord = [(64, 0, 960), (60, 0, 960), (67, 0, 960), (72, 960, 480)];
+
+    let (mut engine, track_id, clip_id) = engine_with(&chord);
+    let clip = engine
+        .strum_clip_notes(track_id, clip_id, &[], StrumDirection::Up, 60)
+        .expect("strum up should succeed");
+    let spans: Vec<(u8, u64, u64)> = notes(&clip)
+        .iter()
+        .map(|note| (note.pitch, note.start_tick, note.length_ticks))
+        .collect();
+    assert_eq!(
+        spans,
+        vec![(60, 0, 960), (64, 30, 930), (67, 60, 900), (72, 960, 480)]
+    );
+
+    let (mut engine, track_id, clip_id) = engine_with(&chord);
+    let clip = engine
+        .strum_clip_notes(track_id, clip_id, &[0, 1, 2], StrumDirection::Down, 60)
+        .expect("strum down should succeed");
+    let starts: Vec<(u8, u64)> = notes(&clip)
+        .iter()
+        .map(|note| (note.pitch, note.start_tick))
+        .collect();
+    assert_eq!(starts, vec![(67, 0), (64, 30), (60, 60), (72, 960)]);
+
+    let (mut engine, track_id, clip_id) = engine_with(&[
+        (60, 0, 240),
+        (60, 480, 240),
+        (60, 960, 240),
+        (60, 1_440, 240),
+        (60, 1_920, 240),
+    ]);
+    let clip = engine
+        .shape_clip_note_velocities(
+            track_id,
+            clip_id,
+            &[1, 2, 3, 4],
+            &VelocityShape {
+                from: 40,
+                to: 120,
+                curve: 1.0,
+            },
+        )
+        .expect("velocity ramp should succeed");
+    let velocities: Vec<u8> = notes(&clip).iter().map(|note| note.velocity).collect();
+    assert_eq!(velocities, vec![100, 40, 67, 93, 120]);
+
+    let clip = engine
+        .shape_clip_note_velocities(
+            track_id,
+            clip_id,
+            &[],
+            &VelocityShape {
+                from: 20,
+                to: 100,
+                curve: 2.0,
+            },
+        )
+        .expect("velocity curve should succeed");
+    let velocities: Vec<u8> = notes(&clip).iter().map(|note| note.velocity).collect();
+    assert_eq!(velocities, vec![20, 25, 40, 65, 100]);
+
+    assert!(matches!(
+        engine.strum_clip_notes(track_id, clip_id, &[9], StrumDirection::Up, 60),
+        Err(EngineError::InvalidNoteIndex(9))
+    ));
+    assert!(matches!(
+        engine.shape_clip_note_velocities(
+            track_id,
+            clip_id,
+            &[],
+            &VelocityShape {
+                from: 20,
+                to: 100,
+                curve: 0.0,
+            }
+        ),
+        Err(EngineError::InvalidVelocityCurve(_))
+    ));
+}
@@ -0,0 +1,234 @@
+use midly::{MetaMessage, Smf, TrackEventKind};
+use tempfile::tempdir;
+use voltlane_core::{
+    AddClipRequest, Engine, EngineError, RenderMode,
+    export::{export_wav, midi_bytes, song_loop_frames},
+    model::{
+        ClipPayload, DEFAULT_SAMPLE_RATE, MidiNote, OrderList, OrderRow, PatternClip, Project,
+        Track, TrackKind,
+    },
+    time::ticks_to_samples,
+};
+
+const ROW_TICKS: u64 = 1_920;
+
+fn pattern(pitch: u8) -> ClipPayload {
+    ClipPayload::Pattern(PatternClip {
+        source_chip: "nes_2a03_pulse".to_string(),
+        notes: vec![MidiNote {
+            pitch,
+            velocity: 100,
+            start_tick: 0,
+            length_ticks: 480,
+            channel: 0,
+            release_velocity: None,
+        }],
+        ..PatternClip::default()
+    })
+}
+
+fn song_engine() -> (Engine, uuid::Uuid) {
+    let mut project = Project::new("Song", 120.0, DEFAULT_SAMPLE_RATE);
+    let track = Track::new("Pulse 1", "#ffd166", TrackKind::Chip);
+    let track_id = track.id;
+    project.tracks.push(track);
+    let mut engine = Engine::new(project);
+
+    for (index, pitch) in [60_u8, 67].into_iter().enumerate() {
+        engine
+            .add_clip(AddClipRequest {
+                track_id,
+                name: format!("pattern {index:02}"),
+                start_tick: ROW_TICKS * index as u64,
+                length_ticks: ROW_TICKS,
+                payload: pattern(pitch),
+            })
+            .expect("pattern clip add should succeed");
+    }
+    (engine, track_id)
+}
+
+#[test]
+fn order_list_round_trips_between_timeline_and_rows() {
+    let (mut engine, track_id) = song_engine();
+
+    let order_list = engine
+        .order_list_from_timeline(vec![track_id], ROW_TICKS)
+        .expect("order list build should succeed");
+    assert_eq!(order_list.rows.len(), 2);
+    let first = order_list.rows[0].patterns[0].expect("row 0 should hold a pattern");
+    let second = order_list.rows[1].patterns[0].expect("row 1 should hold a pattern");
+    assert_eq!(engine.project().clip_pool.len(), 2);
+
+    let rows = [first, second, first, second, second]
+        .into_iter()
+        .map(|content_id| OrderRow {
+            patterns: vec![Some(content_id)],
+        })
+        .collect();
+    engine
+        .set_order_list(OrderList {
+            rows,
+            loop_row: Some(2),
+            ..order_list
+        })
+        .expect("order list apply should succeed");
+
+    let project = engine.project();
+    let starts: Vec<u64> = project.tracks[0]
+        .clips
+        .iter()
+        .map(|clip| clip.start_tick)
+        .collect();
+    assert_eq!(starts, vec![0, 1_920, 3_840, 5_760, 7_680]);
+    assert_eq!(project.max_tick(), ROW_TICKS * 5);
+    assert_eq!(project.song_loop_ticks(), Some((3_840, 9_600)));
+    assert!(project.transport.loop_enabled);
+    assert_eq!(project.transport.loop_start_tick, 3_840);
+
+    let error = engine
+        .set_order_loop_row(Some(5))
+        .expect_err("loop row past the end should fail");
+    assert!(matches!(error, EngineError::InvalidOrderLoopRow(5)));
+}
+
+#[test]
+fn reapplying_order_list_keeps_hand_placed_clips_and_clears_its_loop() {
+    let (mut engine, track_id) = song_engine();
+    let order_list = engine
+        .order_list_from_timeline(vec![track_id], ROW_TICKS)
+        .expect("order list build should succeed");
+    engine
+        .set_order_loop_row(Some(1))
+        .expect("loop row update should succeed");
+    assert!(engine.project().transport.loop_enabled);
+
+    let sketch = engine
+        .add_clip(AddClipRequest {
+            track_id,
+            name: "sketch".to_string(),
+            start_tick: ROW_TICKS * 4,
+            length_ticks: ROW_TICKS,
+            payload: pattern(72),
+        })
+        .expect("hand-placed clip add should succeed");
+    let first = order_list.rows[0].patterns[0];
+    engine
+        .set_order_list(OrderList {
+            rows: vec![OrderRow {
+                patterns: vec![first],
+            }],
+            loop_row: None,
+            ..order_list
+        })
+        .expect("order list apply should succeed");
+
+    let project = engine.project();
+    let clips: Vec<(u64, bool)> = project.tracks[0]
+        .clips
+        .iter()
+        .map(|clip| (clip.start_tick, clip.id == sketch.id))
+        .collect();
+    assert_eq!(clips, vec![(0, false), (ROW_TICKS * 4, true)]);
+    assert!(
+        !project.transport.loop_enabled,
+        "dropping the loop row should turn the song loop off"
+    );
+}
+
+#[test]
+fn timeline_order_list_shares_repeated_patterns_and_rejects_stacked_cells() {
+    let (mut engine, track_id) = song_engine();
+    engine
+        .add_clip(AddClipRequest {
+            track_id,
+            name: "pattern 00 again".to_string(),
+            start_tick: ROW_TICKS * 2,
+            length_ticks: ROW_TICKS,
+            payload: pattern(60),
+        })
+        .expect("repeat clip add should succeed");
+    let order_list = engine
+        .order_list_from_timeline(vec![track_id], ROW_TICKS)
+        .expect("order list build should succeed");
+    assert_eq!(order_list.rows[0].patterns, order_list.rows[2].patterns);
+    assert_eq!(engine.project().clip_pool.len(), 2);
+
+    engine
+        .add_clip(AddClipRequest {
+            track_id,
+            name: "stacked".to_string(),
+            start_tick: ROW_TICKS,
+            length_ticks: ROW_TICKS,
+            payload: pattern(64),
+        })
+        .expect("stacked clip add should succeed");
+    let error = engine
+        .order_list_from_timeline(vec![track_id], ROW_TICKS)
+        .expect_err("two clips in one cell should fail");
+    assert!(matches!(
+        error,
+        EngineError::OrderCellOverlap { row: 1, .. }
+    ));
+    assert_eq!(
+        engine.project().clip_pool.len(),
+        2,
+        "pool is left untouched"
+    );
+}
+
+#[test]
+fn song_loop_point_reaches_midi_markers_and_wav_smpl_chunk() {
+    let (mut engine, track_id) = song_engine();
+    engine
+        .order_list_from_timeline(vec![track_id], ROW_TICKS)
+        .expect("order list build should succeed");
+    engine
+        .set_order_loop_row(Some(1))
+        .expect("loop row update should succeed");
+    let project = engine.project();
+
+    let bytes = midi_bytes(project).expect("midi export should succeed");
+    let smf = Smf::parse(&bytes).expect("exported midi should parse");
+    let mut tick = 0_u64;
+    let mut markers = Vec::new();
+    for event in &smf.tracks[0] {
+        tick += u64::from(event.delta.as_int());
+        if let TrackEventKind::Meta(MetaMessage::Marker(name)) = event.kind {
+            markers.push((String::from_utf8_lossy(name).to_string(), tick));
+        }
+    }
+    assert_eq!(
+        markers,
+        vec![
+            ("loopStart".to_string(), 1_920),
+            ("loopEnd".to_string(), 3_840)
+        ]
+    );
+
+    let temp = tempdir().expect("tempdir should be creatable");
+    let path = temp.path().join("song.wav");
+    export_wav(project, &path, RenderMode::Offline).expect("wav export should succeed");
+    let reader = hound::WavReader::open(&path).expect("looped wav should stay readable");
+    assert!(reader.duration() > 0);
+
+    let wav = std::fs::read(&path).expect("wav should be readable");
+    let smpl = wav
+        .windows(4)
+        .position(|window| window == b"smpl")
+        .expect("wav should carry a smpl chunk");
+    let field = |index: usize| {
+        let offset = smpl + 8 + index * 4;
+        u32::from_le_bytes(
+            wav[offset..offset + 4]
+                .try_into()
+                .expect("smpl field should be four bytes"),
+        )
+    };
+    let expected_start = ticks_to_samples(1_920, 120.0, project.ppq, DEFAULT_SAMPLE_RATE);
+    assert_eq!(u64::from(field(11)), expected_start);
+    assert_eq!(
+        song_loop_frames(project).map(|(start, _)| start),
+        Some(expected_start)
+    );
+}
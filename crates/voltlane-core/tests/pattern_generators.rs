@@ -0,0 +1,188 @@
+mod common;
+
+use common::engine_with_clips;
+use uuid::Uuid;
+use voltlane_core::{
+    Engine, EngineError, EuclideanRhythm, ProbabilityFill,
+    model::{Clip, ClipPayload, PatternClip, TrackKind, TrackerRow},
+};
+
+/// A one-bar pattern clip on a chip track, 16 rows at the given lines per beat.
+fn engine_with(rows: Vec<TrackerRow>, lines_per_beat: u16) -> (Engine, Uuid, Uuid) {
+    let clip = Clip::new(
+        "beat",
+        0,
+        1_920,
+        ClipPayload::Pattern(PatternClip {
+            source_chip: "2a03".to_string(),
+            instrument: None,
+            notes: Vec::new(),
+            rows,
+            macros: Vec::new(),
+            lines_per_beat,
+        }),
+    );
+    let clip_id = clip.id;
+    let (engine, track_id) = engine_with_clips(TrackKind::Chip, vec![clip]);
+    (engine, track_id, clip_id)
+}
+
+fn pattern(clip: &Clip) -> &PatternClip {
+    let ClipPayload::Pattern(pattern) = &clip.payload else {
+        panic!("clip should stay a pattern");
+    };
+    pattern
+}
+
+fn played(clip: &Clip) -> Vec<(u32, u8)> {
+    pattern(clip)
+        .rows
+        .iter()
+        .filter_map(|row| row.note.map(|note| (row.row, note)))
+        .collect()
+}
+
+fn row(row: u32, note: u8) -> TrackerRow {
+    TrackerRow {
+        row,
+        note: Some(note),
+        gate: true,
+        ..TrackerRow::default()
+    }
+}
+
+#[test]
+fn euclidean_and_probability_rows_fill_the_clip_deterministically() {
+    let (mut engine, track_id, clip_id) = engine_with(vec![row(3, 72)], 4);
+    let tresillo = EuclideanRhythm {
+        hits: 3,
+        steps: 8,
+        rotation: 0,
+        pitch: 36,
+        velocity: 110,
+    };
+    let clip = engine
+        .generate_euclidean_rows(track_id, clip_id, &tresillo)
+        .expect("euclidean rhythm should generate");
+    let rows: Vec<u32> = played(&clip).iter().map(|(row, _)| *row).collect();
+    assert_eq!(rows, vec![0, 3, 6, 8, 11, 14], "E(3,8) twice over 16 rows");
+    assert_eq!(pattern(&clip).notes.len(), 6);
+    assert_eq!(pattern(&clip).notes[1].start_tick, 360);
+
+    let clip = engine
+        .generate_euclidean_rows(
+            track_id,
+            clip_id,
+            &EuclideanRhythm {
+                rotation: 1,
+                ..tresillo.clone()
+            },
+        )
+        .expect("rotated rhythm should generate");
+    let rows: Vec<u32> = played(&clip).iter().map(|(row, _)| *row).take(3).collect();
+    assert_eq!(rows, vec![1, 4, 7]);
+    assert!(matches!(
+        engine.generate_euclidean_rows(
+            track_id,
+            clip_id,
+            &EuclideanRhythm {
+                hits: 9,
+                ..tresillo
+            }
+        ),
+        Err(EngineError::InvalidEuclideanRhythm { hits: 9, steps: 8 })
+    ));
+    engine
+        .move_clip(track_id, clip_id, 0, 1_920 * 100)
+        .expect("clip resize should succeed");
+    assert!(matches!(
+        engine.generate_euclidean_rows(track_id, clip_id, &tresillo),
+        Err(EngineError::TooManyPatternRows {
+            rows: 1_600,
+            max: 256
+        })
+    ));
+
+    // Eight lines per beat fit 32 rows into the same bar.
+    let fill = ProbabilityFill {
+        seed: 11,
+        chances: vec![100.0, 0.0, 50.0, 25.0],
+        pitches: vec![38, 42],
+        velocity: 90,
+    };
+    let generate = |seed: u64| {
+        let (mut engine, track_id, clip_id) = engine_with(Vec::new(), 8);
+        let clip = engine
+            .generate_probability_rows(
+                track_id,
+                clip_id,
+                &ProbabilityFill {
+                    seed,
+                    ..fill.clone()
+                },
+            )
+            .expect("probability fill should generate");
+        played(&clip)
+    };
+    let first = generate(11);
+    assert_eq!(first, generate(11), "the same seed gives the same fill");
+    assert_ne!(first, generate(12));
+    assert!(
+        first
+            .iter()
+            .all(|(row, pitch)| *row < 32 && [38, 42].contains(pitch))
+    );
+    assert!((0..8).all(|bar| first.iter().any(|(row, _)| *row == bar * 4)));
+    assert!(first.iter().all(|(row, _)| row % 4 != 1));
+    assert!(matches!(
+        engine.generate_probability_rows(
+            track_id,
+            clip_id,
+            &ProbabilityFill {
+                chances: vec![120.0],
+                ..fill
+            }
+        ),
+        Err(EngineError::InvalidFillChance(_))
+    ));
+}
+
+#[test]
+fn markov_continuation_extends_the_melody_from_its_own_transitions() {
+    // C-E-G-E up and down, two rows apart.
+    let motif = vec![row(0, 60), row(2, 64), row(4, 67), row(6, 64), row(8, 60)];
+    let continue_with = |seed: u64| {
+        let (mut engine, track_id, clip_id) = engine_with(motif.clone(), 4);
+        let clip = engine
+            .continue_pattern_markov(track_id, clip_id, seed)
+            .expect("markov continuation should generate");
+        played(&clip)
+    };
+
+    let continued = continue_with(5);
+    assert_eq!(continued, continue_with(5));
+    assert_eq!(
+        &continued[..5],
+        &[(0, 60), (2, 64), (4, 67), (6, 64), (8, 60)]
+    );
+    assert_eq!(
+        continued.iter().map(|(row, _)| *row).collect::<Vec<_>>(),
+        vec![0, 2, 4, 6, 8, 10, 12, 14],
+        "the learned two-row gap carries on to the end of the clip"
+    );
+    assert_eq!(continued[5].1, 64, "C only ever led to E");
+    for pair in continued[5..].windows(2) {
+        let allowed: &[u8] = match pair[0].1 {
+            60 | 67 => &[64],
+            64 => &[67, 60],
+            other => panic!("unexpected pitch {other}"),
+        };
+        assert!(allowed.contains(&pair[1].1));
+    }
+
+    let (mut engine, track_id, clip_id) = engine_with(vec![row(0, 60)], 4);
+    assert!(matches!(
+        engine.continue_pattern_markov(track_id, clip_id, 5),
+        Err(EngineError::MarkovSourceTooShort(id)) if id == clip_id
+    ));
+}
@@ -43,12 +43,14 @@ fn perf_project() -> Project {
                     start_tick: note_index * 40,
                     length_ticks: 60,
                     channel: 0,
+                    release_velocity: None,
                 });
             }
 
             let payload = if is_chip {
                 ClipPayload::Pattern(PatternClip {
                     source_chip: "gameboy_apu".to_string(),
+                    instrument: None,
                     notes,
                     rows: Vec::new(),
                     macros: Vec::new(),
@@ -58,17 +60,16 @@ fn perf_project() -> Project {
                 ClipPayload::Midi(MidiClip {
                     instrument: Some("Perf Synth".to_string()),
                     notes,
+                    controllers: Vec::new(),
                 })
             };
 
-            track.clips.push(Clip {
-                id: uuid::Uuid::new_v4(),
-                name: format!("clip-{}-{}", track_index + 1, clip_index + 1),
+            track.clips.push(Clip::new(
+                format!("clip-{}-{}", track_index + 1, clip_index + 1),
                 start_tick,
-                length_ticks: 1_920,
-                disabled: false,
+                1_920,
                 payload,
-            });
+            ));
         }
 
         project.tracks.push(track);
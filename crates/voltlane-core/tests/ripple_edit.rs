@@ -0,0 +1,251 @@
+use midly::{MetaMessage, Smf, TrackEventKind};
+use voltlane_core::{
+    Engine, EngineError,
+    export::midi_bytes,
+    model::{
+        AutomationClip, AutomationPoint, Clip, ClipPayload, DEFAULT_SAMPLE_RATE, MidiClip,
+        MidiNote, Project, Track, TrackKind,
+    },
+};
+
+const BAR: u64 = 1_920;
+
+fn clip(name: &str, start_tick: u64, length_ticks: u64, payload: ClipPayload) -> Clip {
+    Clip::new(name, start_tick, length_ticks, payload)
+}
+
+/// A note on every beat of the clip.
+fn beats(length_ticks: u64) -> ClipPayload {
+    ClipPayload::Midi(MidiClip {
+        instrument: None,
+        notes: (0..length_ticks / 480)
+            .map(|beat| MidiNote {
+                pitch: 60 + beat as u8,
+                velocity: 100,
+                start_tick: beat * 480,
+                length_ticks: 240,
+                channel: 0,
+                release_velocity: None,
+            })
+            .collect(),
+        controllers: Vec::new(),
+    })
+}
+
+/// Verse (bars 1-2), chorus (bars 3-4) and a fade spanning bars 2-3.
+fn song() -> Engine {
+    let mut project = Project::new("Song", 120.0, DEFAULT_SAMPLE_RATE);
+    let mut keys = Track::new("Keys", "#4fb8ff", TrackKind::Midi);
+    keys.clips = vec![
+        clip("verse", 0, 2 * BAR, beats(2 * BAR)),
+        clip("chorus", 2 * BAR, 2 * BAR, beats(2 * BAR)),
+    ];
+    let mut automation = Track::new("Fade", "#ffb84f", TrackKind::Automation);
+    automation.clips = vec![clip(
+        "fade",
+        BAR,
+        2 * BAR,
+        ClipPayload::Automation(AutomationClip {
+            target_parameter_id: format!("track:{}:gain_db", keys.id),
+            points: vec![
+                AutomationPoint {
+                    tick: 0,
+                    value: 0.0,
+                },
+                AutomationPoint {
+                    tick: 2 * BAR,
+                    value: -12.0,
+                },
+            ],
+        }),
+    )];
+    project.tracks = vec![keys, automation];
+    project.transport.playhead_tick = 3 * BAR;
+    project.transport.loop_start_tick = 2 * BAR;
+    project.transport.loop_end_tick = 4 * BAR;
+    Engine::new(project)
+}
+
+fn spans(engine: &Engine, track_index: usize) -> Vec<(String, u64, u64)> {
+    engine.project().tracks[track_index]
+        .clips
+        .iter()
+        .map(|clip| (clip.name.clone(), clip.start_tick, clip.end_tick()))
+        .collect()
+}
+
+fn owned(spans: &[(&str, u64, u64)]) -> Vec<(String, u64, u64)> {
+    spans
+        .iter()
+        .map(|(name, start, end)| ((*name).to_string(), *start, *end))
+        .collect()
+}
+
+#[test]
+fn inserting_time_splits_straddling_clips_and_moves_everything_after() {
+    let mut engine = song();
+    engine
+        .insert_time(BAR, 4 * BAR)
+        .expect("insert time should succeed");
+
+    assert_eq!(
+        spans(&engine, 0),
+        owned(&[
+            ("verse", 0, BAR),
+            ("verse", 5 * BAR, 6 * BAR),
+            ("chorus", 6 * BAR, 8 * BAR),
+        ])
+    );
+    assert_eq!(spans(&engine, 1), owned(&[("fade", 5 * BAR, 7 * BAR)]));
+    let verse_tail = &engine.project().tracks[0].clips[1];
+    let ClipPayload::Midi(midi) = &verse_tail.payload else {
+        panic!("split verse should stay midi");
+    };
+    assert_eq!(midi.notes[0].pitch, 64, "the tail starts on the fifth beat");
+    assert_eq!(midi.notes[0].start_tick, 0);
+
+    let transport = &engine.project().transport;
+    assert_eq!(transport.playhead_tick, 7 * BAR);
+    assert_eq!(
+        (transport.loop_start_tick, transport.loop_end_tick),
+        (6 * BAR, 8 * BAR)
+    );
+
+    assert!(matches!(
+        engine.insert_time(BAR, 0),
+        Err(EngineError::InvalidTimeRange { .. })
+    ));
+}
+
+#[test]
+fn deleting_time_removes_the_range_and_closes_the_gap() {
+    let mut engine = song();
+    let before = engine.project().clone();
+    assert!(engine.delete_time(2 * BAR, BAR).is_err());
+    assert_eq!(
+        engine.project(),
+        &before,
+        "a rejected ripple changes nothing"
+    );
+
+    engine
+        .delete_time(BAR + 960, 2 * BAR + 960)
+        .expect("delete time should succeed");
+
+    assert_eq!(
+        spans(&engine, 0),
+        owned(&[("verse", 0, BAR + 960), ("chorus", BAR + 960, 3 * BAR)])
+    );
+    let ClipPayload::Midi(chorus) = &engine.project().tracks[0].clips[1].payload else {
+        panic!("chorus should stay midi");
+    };
+    assert_eq!(chorus.notes.len(), 6, "the first two chorus beats were cut");
+
+    assert_eq!(
+        spans(&engine, 1),
+        owned(&[("fade", BAR, BAR + 960), ("fade", BAR + 960, 2 * BAR)])
+    );
+    let ClipPayload::Automation(tail) = &engine.project().tracks[1].clips[1].payload else {
+        panic!("fade tail should stay automation");
+    };
+    // The tail resumes where the deleted bar left the ramp.
+    assert_eq!(tail.points[0].tick, 0);
+    assert!((tail.points[0].value + 9.0).abs() < 1e-4);
+
+    let transport = &engine.project().transport;
+    assert_eq!(transport.playhead_tick, 2 * BAR);
+    assert_eq!(
+        (transport.loop_start_tick, transport.loop_end_tick),
+        (BAR + 960, 3 * BAR)
+    );
+}
+
+fn loop_markers(engine: &Engine) -> Vec<u64> {
+    let bytes = midi_bytes(engine.project()).expect("midi export should succeed");
+    let smf = Smf::parse(&bytes).expect("exported midi should parse");
+    let mut tick = 0_u64;
+    let mut markers = Vec::new();
+    for event in &smf.tracks[0] {
+        tick += u64::from(event.delta.as_int());
+        if let TrackEventKind::Meta(MetaMessage::Marker(_)) = event.kind {
+            markers.push(tick);
+        }
+    }
+    markers
+}
+
+#[test]
+fn ripple_edits_on_order_rows_move_the_song_loop_in_export() {
+    let mut engine = song();
+    let keys_id = engine.project().tracks[0].id;
+    engine
+        .order_list_from_timeline(vec![keys_id], 2 * BAR)
+        .expect("order list build should succeed");
+    engine
+        .set_order_loop_row(Some(1))
+        .expect("loop row update should succeed");
+    assert_eq!(loop_markers(&engine), vec![2 * BAR, 4 * BAR]);
+
+    engine
+        .insert_time(5 * BAR, BAR)
+        .expect("insert after the song should succeed");
+    assert_eq!(
+        loop_markers(&engine),
+        vec![2 * BAR, 4 * BAR],
+        "time after the song leaves the order list alone"
+    );
+
+    let before = engine.project().clone();
+    assert!(matches!(
+        engine.insert_time(BAR, BAR),
+        Err(EngineError::MisalignedOrderListEdit {
+            tick: BAR,
+            length_ticks: BAR,
+            row_length_ticks,
+        }) if row_length_ticks == 2 * BAR
+    ));
+    assert_eq!(
+        engine.project(),
+        &before,
+        "a rejected ripple changes nothing"
+    );
+
+    engine
+        .insert_time(2 * BAR, 2 * BAR)
+        .expect("insert on a row boundary should succeed");
+    let order_list = engine
+        .project()
+        .order_list
+        .as_ref()
+        .expect("order list kept");
+    assert_eq!(order_list.rows.len(), 3);
+    assert_eq!(
+        order_list.rows[1].patterns,
+        vec![None],
+        "an empty row opens"
+    );
+    assert_eq!(order_list.loop_row, Some(2));
+    assert_eq!(loop_markers(&engine), vec![4 * BAR, 6 * BAR]);
+    let placements: Vec<(u64, u64)> = spans(&engine, 0)
+        .into_iter()
+        .map(|(_, start, end)| (start, end))
+        .collect();
+    assert_eq!(placements, vec![(0, 2 * BAR), (4 * BAR, 6 * BAR)]);
+
+    engine
+        .delete_time(0, 2 * BAR)
+        .expect("delete on a row boundary should succeed");
+    let order_list = engine
+        .project()
+        .order_list
+        .as_ref()
+        .expect("order list kept");
+    assert_eq!(order_list.rows.len(), 2);
+    assert_eq!(order_list.loop_row, Some(1));
+    assert_eq!(
+        order_list.placement_ids.len(),
+        1,
+        "the deleted placement is forgotten"
+    );
+    assert_eq!(loop_markers(&engine), vec![2 * BAR, 4 * BAR]);
+}
@@ -0,0 +1,176 @@
+use std::path::Path;
+
+use tempfile::tempdir;
+use voltlane_core::{
+    Engine, EngineError,
+    export::render_project_samples,
+    model::{
+        ClipPayload, DEFAULT_SAMPLE_RATE, MidiClip, MidiNote, PatternClip, Project, Track,
+        TrackKind,
+    },
+};
+
+fn write_sine_wav(path: &Path, seconds: f32, frequency_hz: f32) {
+    let sample_rate = 48_000_u32;
+    let frame_count = (seconds * sample_rate as f32).round() as usize;
+    let spec = hound::WavSpec {
+        channels: 1,
+        sample_rate,
+        bits_per_sample: 16,
+        sample_format: hound::SampleFormat::Int,
+    };
+
+    let mut writer = hound::WavWriter::create(path, spec).expect("test wav should be creatable");
+    for frame in 0..frame_count {
+        let phase = frame as f32 / sample_rate as f32 * frequency_hz * std::f32::consts::TAU;
+        let sample = (phase.sin() * 0.5 * f32::from(i16::MAX)).round() as i16;
+        writer
+            .write_sample(sample)
+            .expect("test wav sample write should succeed");
+    }
+    writer.finalize().expect("test wav finalize should succeed");
+}
+
+fn note(pitch: u8, start_tick: u64) -> MidiNote {
+    MidiNote {
+        pitch,
+        velocity: 110,
+        start_tick,
+        length_ticks: 480,
+        channel: 0,
+        release_velocity: None,
+    }
+}
+
+fn sampler_engine() -> (Engine, uuid::Uuid) {
+    let mut project = Project::new("Sampler", 120.0, DEFAULT_SAMPLE_RATE);
+    let track = Track::new("Sampler", "#ffb347", TrackKind::Midi);
+    let track_id = track.id;
+    project.tracks.push(track);
+    (Engine::new(project), track_id)
+}
+
+fn add_midi_clip(engine: &mut Engine, track_id: uuid::Uuid, notes: Vec<MidiNote>) {
+    engine
+        .add_clip(voltlane_core::AddClipRequest {
+            track_id,
+            name: "hits".to_string(),
+            start_tick: 0,
+            length_ticks: 1_920,
+            payload: ClipPayload::Midi(MidiClip {
+                instrument: Some("Kit".to_string()),
+                notes,
+                controllers: Vec::new(),
+            }),
+        })
+        .expect("clip add should succeed");
+}
+
+fn zero_crossings(samples: &[f32]) -> usize {
+    samples
+        .windows(2)
+        .filter(|pair| (pair[0] < 0.0) != (pair[1] < 0.0))
+        .count()
+}
+
+#[test]
+fn kit_sampler_maps_assets_to_consecutive_one_shot_keys() {
+    let temp = tempdir().expect("tempdir should be creatable");
+    let kick = temp.path().join("kick.wav");
+    let snare = temp.path().join("snare.wav");
+    write_sine_wav(&kick, 0.2, 60.0);
+    write_sine_wav(&snare, 0.2, 220.0);
+
+    let (mut engine, track_id) = sampler_engine();
+    let assets = engine
+        .scan_audio_assets(temp.path())
+        .expect("asset scan should succeed");
+    let track = engine
+        .create_sampler_from_assets(track_id, "Kit".to_string(), &assets, 36)
+        .expect("sampler creation should succeed");
+
+    let zones = &track.samplers[0].zones;
+    assert_eq!(zones.len(), 2);
+    assert_eq!((zones[0].low_note, zones[0].high_note), (36, 36));
+    assert_eq!((zones[1].low_note, zones[1].high_note), (37, 37));
+    assert!(zones.iter().all(|zone| zone.one_shot));
+
+    add_midi_clip(&mut engine, track_id, vec![note(36, 0), note(40, 960)]);
+    let rendered = render_project_samples(engine.project(), 0.5);
+
+    let hit_peak = rendered[..4_800]
+        .iter()
+        .fold(0.0_f32, |peak, sample| peak.max(sample.abs()));
+    let unmapped_peak = rendered[48_000..52_800]
+        .iter()
+        .fold(0.0_f32, |peak, sample| peak.max(sample.abs()));
+    assert!(hit_peak > 0.1, "mapped key should trigger its sample");
+    assert!(unmapped_peak < 1e-4, "unmapped key should stay silent");
+}
+
+#[test]
+fn chromatic_sampler_repitches_from_root_note() {
+    let temp = tempdir().expect("tempdir should be creatable");
+    let tone = temp.path().join("tone.wav");
+    write_sine_wav(&tone, 1.0, 220.0);
+
+    let (mut engine, track_id) = sampler_engine();
+    let assets = engine
+        .scan_audio_assets(temp.path())
+        .expect("asset scan should succeed");
+    engine
+        .create_sampler_from_assets(track_id, "Kit".to_string(), &assets, 60)
+        .expect("sampler creation should succeed");
+    add_midi_clip(&mut engine, track_id, vec![note(60, 0), note(72, 960)]);
+
+    let rendered = render_project_samples(engine.project(), 0.5);
+    let root = zero_crossings(&rendered[0..12_000]);
+    let octave = zero_crossings(&rendered[48_000..60_000]);
+    let ratio = octave as f32 / root as f32;
+
+    assert!(
+        (1.9..2.1).contains(&ratio),
+        "an octave above root should double the frequency, got ratio {ratio}"
+    );
+}
+
+#[test]
+fn pattern_clip_instrument_plays_sampler_and_missing_sampler_errors() {
+    let temp = tempdir().expect("tempdir should be creatable");
+    let tone = temp.path().join("tone.wav");
+    write_sine_wav(&tone, 0.5, 330.0);
+
+    let (mut engine, track_id) = sampler_engine();
+    let assets = engine
+        .scan_audio_assets(temp.path())
+        .expect("asset scan should succeed");
+    engine
+        .create_sampler_from_assets(track_id, "Chops".to_string(), &assets, 60)
+        .expect("sampler creation should succeed");
+    engine
+        .add_clip(voltlane_core::AddClipRequest {
+            track_id,
+            name: "pattern".to_string(),
+            start_tick: 0,
+            length_ticks: 960,
+            payload: ClipPayload::Pattern(PatternClip {
+                source_chip: "gameboy_apu".to_string(),
+                instrument: Some("chops".to_string()),
+                notes: vec![note(60, 0)],
+                ..PatternClip::default()
+            }),
+        })
+        .expect("pattern clip add should succeed");
+
+    let rendered = render_project_samples(engine.project(), 0.5);
+    let crossings = zero_crossings(&rendered[0..4_800]);
+    assert!(
+        (60..72).contains(&crossings),
+        "pattern note at the root key should play the 330 Hz sample unchanged, got {crossings}"
+    );
+
+    let error = engine
+        .remove_sampler(track_id, "Drums")
+        .expect_err("unknown sampler removal should fail");
+    assert!(matches!(error, EngineError::SamplerNotFound(_)));
+}
@@ -0,0 +1,166 @@
+use midly::{MidiMessage, Smf, TrackEventKind};
+use voltlane_core::{
+    AddClipRequest, Engine, EngineError,
+    export::{midi_bytes, render_project_samples},
+    model::{
+        ClipPayload, DEFAULT_SAMPLE_RATE, Project, Step, StepRow, StepSequencerClip, Track,
+        TrackKind,
+    },
+    sequencer::{STEP_DRUM_CHANNEL, step_notes},
+};
+
+fn hit() -> Step {
+    Step {
+        active: true,
+        ..Step::default()
+    }
+}
+
+fn drum_clip() -> StepSequencerClip {
+    let mut kick = vec![Step::default(); 16];
+    kick[0] = hit();
+    kick[8] = Step {
+        ratchet: 2,
+        ..hit()
+    };
+    let mut snare = vec![Step::default(); 16];
+    snare[4] = Step {
+        flam: true,
+        ..hit()
+    };
+    snare[12] = Step {
+        probability: 0.0,
+        ..hit()
+    };
+
+    StepSequencerClip {
+        source_chip: "nes_2a03_noise".to_string(),
+        rows: vec![
+            StepRow {
+                name: "Kick".to_string(),
+                pitch: 36,
+                steps: kick,
+                ..StepRow::default()
+            },
+            StepRow {
+                name: "Snare".to_string(),
+                pitch: 38,
+                steps: snare,
+                ..StepRow::default()
+            },
+        ],
+        ..StepSequencerClip::default()
+    }
+}
+
+fn drum_engine() -> (Engine, uuid::Uuid, uuid::Uuid) {
+    let mut project = Project::new("Steps", 120.0, DEFAULT_SAMPLE_RATE);
+    let track = Track::new("Drums", "#ff5f7e", TrackKind::Chip);
+    let track_id = track.id;
+    project.tracks.push(track);
+    let mut engine = Engine::new(project);
+    let clip = engine
+        .add_clip(AddClipRequest {
+            track_id,
+            name: "beat".to_string(),
+            start_tick: 0,
+            length_ticks: 3_840,
+            payload: ClipPayload::Steps(drum_clip()),
+        })
+        .expect("step clip add should succeed");
+    (engine, track_id, clip.id)
+}
+
+#[test]
+fn step_notes_expand_ratchet_flam_probability_and_loop() {
+    let notes = step_notes(&drum_clip(), 3_840, 480);
+
+    // Per pass: kick 1 + ratchet 2, snare flam grace + main, probability 0 dropped.
+    assert_eq!(notes.len(), 10, "two passes of five hits each");
+    let starts: Vec<u64> = notes
+        .iter()
+        .filter(|note| note.row_index == 0)
+        .map(|note| note.note.start_tick)
+        .collect();
+    assert_eq!(starts, vec![0, 960, 1_020, 1_920, 2_880, 2_940]);
+
+    let flam: Vec<_> = notes
+        .iter()
+        .filter(|note| note.row_index == 1)
+        .take(2)
+        .collect();
+    assert_eq!(
+        flam[0].note.start_tick, 465,
+        "grace hit lands ahead of the grid"
+    );
+    assert_eq!(flam[1].note.start_tick, 480, "main hit stays on the grid");
+    assert!(flam[0].note.velocity < flam[1].note.velocity);
+    assert!(
+        notes
+            .iter()
+            .all(|note| note.note.channel == STEP_DRUM_CHANNEL)
+    );
+}
+
+#[test]
+fn step_clip_exports_drum_channel_notes_and_renders_noise() {
+    let (engine, _, _) = drum_engine();
+    let bytes = midi_bytes(engine.project()).expect("midi export should succeed");
+    let smf = Smf::parse(&bytes).expect("exported midi should parse");
+    let note_ons = smf
+        .tracks
+        .iter()
+        .flatten()
+        .filter(|event| {
+            matches!(
+                event.kind,
+                TrackEventKind::Midi {
+                    channel,
+                    message: MidiMessage::NoteOn { .. },
+                } if channel.as_int() == STEP_DRUM_CHANNEL
+            )
+        })
+        .count();
+    assert_eq!(note_ons, 10);
+
+    let rendered = render_project_samples(engine.project(), 0.5);
+    let peak = rendered
+        .iter()
+        .fold(0.0_f32, |peak, sample| peak.max(sample.abs()));
+    assert!(peak > 0.01, "noise voice rows should be audible");
+}
+
+#[test]
+fn set_step_validates_position_and_payload() {
+    let (mut engine, track_id, clip_id) = drum_engine();
+
+    let updated = engine
+        .set_step(
+            track_id,
+            clip_id,
+            1,
+            2,
+            Step {
+                ratchet: 40,
+                ..hit()
+            },
+        )
+        .expect("setting an existing step should succeed");
+    let ClipPayload::Steps(steps) = &updated.payload else {
+        panic!("clip payload should stay a step clip");
+    };
+    assert_eq!(steps.rows[1].steps[2].ratchet, 8);
+
+    let error = engine
+        .set_step(track_id, clip_id, 5, 0, hit())
+        .expect_err("unknown row should fail");
+    assert!(matches!(
+        error,
+        EngineError::InvalidStepIndex { row: 5, step: 0 }
+    ));
+
+    let error = engine
+        .upsert_step_rows(track_id, clip_id, Vec::new(), Some(0), None)
+        .expect_err("zero steps_per_beat should fail");
+    assert!(matches!(error, EngineError::InvalidStepsPerBeat(0)));
+}
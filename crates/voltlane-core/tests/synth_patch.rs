@@ -0,0 +1,139 @@
+use voltlane_core::{
+    Engine, EngineError,
+    export::render_project_samples,
+    model::{
+        Clip, ClipPayload, DEFAULT_SAMPLE_RATE, MidiClip, MidiNote, Project, SynthFilterMode,
+        SynthLfoTarget, SynthOscillator, SynthPatch, SynthWaveform, Track, TrackKind,
+    },
+};
+
+fn synth_project(instrument: &str, patches: Vec<SynthPatch>) -> Project {
+    let mut project = Project::new("Synth", 120.0, DEFAULT_SAMPLE_RATE);
+    let mut track = Track::new("Synth", "#8f6bff", TrackKind::Midi);
+    track.synth_patches = patches;
+    track.clips.push(Clip::new(
+        "phrase",
+        0,
+        1_920,
+        ClipPayload::Midi(MidiClip {
+            instrument: Some(instrument.to_string()),
+            notes: vec![
+                MidiNote {
+                    pitch: 48,
+                    velocity: 120,
+                    start_tick: 0,
+                    length_ticks: 480,
+                    channel: 0,
+                    release_velocity: None,
+                },
+                MidiNote {
+                    pitch: 55,
+                    velocity: 120,
+                    start_tick: 480,
+                    length_ticks: 480,
+                    channel: 0,
+                    release_velocity: None,
+                },
+            ],
+            controllers: Vec::new(),
+        }),
+    ));
+    project.tracks.push(track);
+    project
+}
+
+fn bright_patch() -> SynthPatch {
+    SynthPatch {
+        name: "Bright".to_string(),
+        oscillators: vec![
+            SynthOscillator::default(),
+            SynthOscillator {
+                waveform: SynthWaveform::Square,
+                level: 0.6,
+                octave: -1,
+                detune_cents: 7.0,
+                pulse_width: 0.3,
+            },
+        ],
+        unison_voices: 3,
+        unison_detune_cents: 12.0,
+        glide_seconds: 0.05,
+        ..SynthPatch::default()
+    }
+}
+
+fn energy(samples: &[f32]) -> f32 {
+    samples.iter().map(|sample| sample * sample).sum::<f32>() / samples.len().max(1) as f32
+}
+
+fn high_frequency_energy(samples: &[f32]) -> f32 {
+    let diffs: Vec<f32> = samples.windows(2).map(|pair| pair[1] - pair[0]).collect();
+    energy(&diffs)
+}
+
+#[test]
+fn midi_clip_instrument_selects_track_synth_patch() {
+    let legacy = render_project_samples(&synth_project("Missing", vec![bright_patch()]), 0.5);
+    let patched = render_project_samples(&synth_project("bright", vec![bright_patch()]), 0.5);
+
+    assert!(
+        energy(&patched) > 0.0001,
+        "patched render should be audible"
+    );
+    let difference = legacy
+        .iter()
+        .zip(patched.iter())
+        .map(|(left, right)| (left - right).abs())
+        .sum::<f32>()
+        / legacy.len() as f32;
+    assert!(
+        difference > 0.005,
+        "matching instrument name should switch away from the fallback voice"
+    );
+}
+
+#[test]
+fn low_pass_filter_darkens_patch_output() {
+    let open = bright_patch();
+    let mut closed = bright_patch();
+    closed.filter.mode = SynthFilterMode::LowPass;
+    closed.filter.cutoff_hz = 300.0;
+    closed.filter.resonance = 0.3;
+    closed.lfo.target = SynthLfoTarget::FilterCutoff;
+    closed.lfo.depth = 0.5;
+
+    let open_render = render_project_samples(&synth_project("Bright", vec![open]), 0.5);
+    let closed_render = render_project_samples(&synth_project("Bright", vec![closed]), 0.5);
+
+    assert!(
+        high_frequency_energy(&closed_render) < high_frequency_energy(&open_render) * 0.5,
+        "closing the filter should remove high frequency content"
+    );
+}
+
+#[test]
+fn synth_patches_are_upserted_by_name_and_removable() {
+    let mut engine = Engine::new(synth_project("Bright", Vec::new()));
+    let track_id = engine.project().tracks[0].id;
+
+    engine
+        .upsert_synth_patch(track_id, bright_patch())
+        .expect("patch insert should succeed");
+    let mut replacement = bright_patch();
+    replacement.name = " bright ".to_string();
+    replacement.unison_voices = 40;
+    let track = engine
+        .upsert_synth_patch(track_id, replacement)
+        .expect("patch replace should succeed");
+
+    assert_eq!(track.synth_patches.len(), 1);
+    assert_eq!(track.synth_patches[0].unison_voices, 8);
+
+    engine
+        .remove_synth_patch(track_id, "BRIGHT")
+        .expect("patch removal should succeed");
+    let error = engine
+        .remove_synth_patch(track_id, "Bright")
+        .expect_err("second removal should fail");
+    assert!(matches!(error, EngineError::SynthPatchNotFound(_)));
+}
@@ -0,0 +1,160 @@
+use std::path::Path;
+
+use voltlane_core::{
+    Engine,
+    export::render_project_samples,
+    model::{ClipPayload, PatternClip, Project, TrackKind},
+    tracker_import::parse_tracker_text,
+};
+
+fn pooled_pattern<'a>(project: &'a Project, name: &str) -> &'a PatternClip {
+    let entry = project
+        .clip_pool
+        .iter()
+        .find(|entry| entry.name == name)
+        .unwrap_or_else(|| panic!("pool should contain pattern {name}"));
+    match &entry.payload {
+        ClipPayload::Pattern(pattern) => pattern,
+        other => panic!("pool entry {name} should be a pattern, got {other:?}"),
+    }
+}
+
+#[test]
+fn famitracker_text_export_builds_tracks_patterns_macros_and_order() {
+    let mut engine = Engine::default();
+    let project = engine
+        .import_tracker_text(Path::new("tests/fixtures/tracker/famitracker_song.txt"))
+        .expect("famitracker import should succeed");
+
+    assert_eq!(project.title, "Fixture Song");
+    assert!((project.bpm - 150.0).abs() < f64::EPSILON);
+    let names: Vec<&str> = project
+        .tracks
+        .iter()
+        .map(|track| track.name.as_str())
+        .collect();
+    assert_eq!(names, vec!["Pulse 1", "Pulse 2", "Triangle", "Noise"]);
+    assert!(
+        project
+            .tracks
+            .iter()
+            .all(|track| track.kind == TrackKind::Chip)
+    );
+
+    let order_list = project
+        .order_list
+        .as_ref()
+        .expect("order list should be set");
+    assert_eq!(order_list.rows.len(), 3);
+    assert_eq!(order_list.row_length_ticks, 960);
+    assert_eq!(order_list.loop_row, Some(1), "B01 in the last frame");
+    assert_eq!(order_list.rows[0].patterns[3], None, "empty noise pattern");
+    let pulse_starts: Vec<u64> = project.tracks[0]
+        .clips
+        .iter()
+        .map(|clip| clip.start_tick)
+        .collect();
+    assert_eq!(pulse_starts, vec![0, 960, 1_920]);
+
+    let lead = pooled_pattern(&project, "Pulse 1 00");
+    assert_eq!(lead.source_chip, "nes_2a03_pulse");
+    assert_eq!(lead.instrument.as_deref(), Some("Lead"));
+    let cells: Vec<(u32, Option<u8>, u8, Option<&str>)> = lead
+        .rows
+        .iter()
+        .map(|row| (row.row, row.note, row.velocity, row.effect.as_deref()))
+        .collect();
+    assert_eq!(
+        cells,
+        vec![
+            (0, Some(60), 127, None),
+            (2, Some(64), 102, None),
+            (4, Some(67), 102, Some("F")),
+            (6, None, 102, Some("OFF")),
+        ]
+    );
+    assert_eq!(lead.rows[2].effect_value, Some(0x06));
+    assert_eq!(lead.notes.len(), 3);
+
+    let env = lead
+        .macros
+        .iter()
+        .find(|lane| lane.target == "env")
+        .expect("volume macro should map to env lane");
+    assert_eq!(env.values, vec![0, -25, -42, -59]);
+    assert_eq!((env.loop_start, env.loop_end), (Some(2), Some(3)));
+    let arpeggio = lead
+        .macros
+        .iter()
+        .find(|lane| lane.target == "arpeggio")
+        .expect("arpeggio macro should be mapped");
+    assert_eq!(arpeggio.values, vec![0, 4, 7]);
+
+    let noise = pooled_pattern(&project, "Noise 01");
+    let noise_notes: Vec<Option<u8>> = noise.rows.iter().map(|row| row.note).collect();
+    assert_eq!(noise_notes, vec![Some(90), Some(66), None]);
+
+    let rendered = render_project_samples(&project, 0.0);
+    assert!(rendered.iter().any(|sample| sample.abs() > 0.01));
+}
+
+#[test]
+fn furnace_text_export_maps_system_channels_and_loop() {
+    let text = std::fs::read_to_string("tests/fixtures/tracker/furnace_song.txt")
+        .expect("furnace fixture should be readable");
+    let project = parse_tracker_text(&text).expect("furnace import should succeed");
+
+    assert_eq!(project.title, "Furnace Fixture");
+    assert!((project.bpm - 150.0).abs() < f64::EPSILON);
+    let names: Vec<&str> = project
+        .tracks
+        .iter()
+        .map(|track| track.name.as_str())
+        .collect();
+    assert_eq!(names, vec!["Pulse 1", "Pulse 2", "Wave", "Noise"]);
+
+    let order_list = project
+        .order_list
+        .as_ref()
+        .expect("order list should be set");
+    assert_eq!(order_list.rows.len(), 2);
+    assert_eq!(order_list.row_length_ticks, 480);
+    assert_eq!(order_list.loop_row, Some(0), "0B00 in the last order");
+    assert_eq!(
+        order_list.rows[0].patterns[1], order_list.rows[1].patterns[1],
+        "reused patterns should share pooled content"
+    );
+
+    let first = pooled_pattern(&project, "Pulse 1 00");
+    assert_eq!(first.source_chip, "gameboy_apu_pulse");
+    let cells: Vec<(Option<u8>, u8)> = first
+        .rows
+        .iter()
+        .map(|row| (row.note, row.velocity))
+        .collect();
+    assert_eq!(cells, vec![(Some(72), 127), (Some(76), 68), (None, 68)]);
+    let env = first
+        .macros
+        .iter()
+        .find(|lane| lane.target == "env")
+        .expect("vol macro should map to env lane");
+    assert_eq!(env.values, vec![0, -17, -34, -51]);
+    assert_eq!(env.loop_start, Some(3));
+
+    let second = pooled_pattern(&project, "Pulse 1 01");
+    assert_eq!(second.rows[0].note, Some(79));
+    assert_eq!(second.rows[1].effect.as_deref(), Some("0B"));
+
+    let garbled = text.replace(
+        "02 |... .. .. ....|... .. .. ....|",
+        "02 |... .. .. éA|... .. .. ....|",
+    );
+    assert_ne!(garbled, text);
+    let project = parse_tracker_text(&garbled).expect("a garbled effect should be skipped");
+    assert_eq!(pooled_pattern(&project, "Pulse 1 01"), second);
+}
+
+#[test]
+fn unknown_tracker_text_is_rejected() {
+    assert!(parse_tracker_text("just some notes\nC-4 E-4 G-4").is_err());
+}
@@ -0,0 +1,194 @@
+use midly::{Smf, TrackEventKind};
+use tempfile::tempdir;
+use voltlane_core::{
+    Engine,
+    export::{midi_bytes, render_project_samples},
+    model::{
+        Clip, ClipPayload, DEFAULT_SAMPLE_RATE, MidiClip, MidiNote, Project, SynthOscillator,
+        SynthPatch, SynthWaveform, Track, TrackKind, Tuning,
+    },
+    tuning::parse_scala_tuning,
+};
+
+const JUST_MAJOR_SCL: &str = "! just.scl
+!
+Just major
+ 7
+!
+ 9/8
+ 5/4
+ 4/3
+ 701.955 cents
+ 5/3
+ 15/8
+ 2/1
+";
+
+/// White keys only, with A4 (the major sixth above middle C) at 440 Hz.
+const WHITE_KEYS_KBM: &str = "! white.kbm
+12
+0
+127
+60
+69
+440.0
+7
+! mapping
+0
+x
+1
+x
+2
+3
+x
+4
+x
+5
+x
+6
+";
+
+fn sine_project(pitch: u8) -> Project {
+    let mut project = Project::new("Tuning", 120.0, DEFAULT_SAMPLE_RATE);
+    let mut track = Track::new("Lead", "#8f6bff", TrackKind::Midi);
+    track.synth_patches = vec![SynthPatch {
+        name: "Sine".to_string(),
+        oscillators: vec![SynthOscillator {
+            waveform: SynthWaveform::Sine,
+            ..SynthOscillator::default()
+        }],
+        ..SynthPatch::default()
+    }];
+    track.clips.push(Clip::new(
+        "drone",
+        0,
+        1_920,
+        ClipPayload::Midi(MidiClip {
+            instrument: Some("Sine".to_string()),
+            notes: vec![MidiNote {
+                pitch,
+                velocity: 120,
+                start_tick: 0,
+                length_ticks: 1_920,
+                channel: 0,
+                release_velocity: None,
+            }],
+            controllers: Vec::new(),
+        }),
+    ));
+    project.tracks.push(track);
+    project
+}
+
+fn zero_crossings(samples: &[f32]) -> usize {
+    let rate = DEFAULT_SAMPLE_RATE as usize;
+    samples[rate / 20..rate * 19 / 20]
+        .windows(2)
+        .filter(|pair| (pair[0] < 0.0) != (pair[1] < 0.0))
+        .count()
+}
+
+#[test]
+fn scala_scale_and_keyboard_mapping_set_key_frequencies() {
+    let tuning =
+        parse_scala_tuning(JUST_MAJOR_SCL, Some(WHITE_KEYS_KBM)).expect("scala should parse");
+    assert_eq!(tuning.name, "Just major");
+    assert_eq!(tuning.scale_cents.len(), 7);
+
+    let frequency = |key: u8| tuning.frequency_hz(key);
+    let expected = [
+        (60, 264.0),
+        (62, 297.0),
+        (64, 330.0),
+        (67, 396.0),
+        (69, 440.0),
+    ];
+    for (key, hz) in expected {
+        let actual = frequency(key).expect("white keys should be mapped");
+        assert!((actual - hz).abs() < 0.01, "key {key} was {actual} Hz");
+    }
+    assert!((frequency(72).expect("c5 should be mapped") - 528.0).abs() < 0.01);
+    assert!((frequency(48).expect("c3 should be mapped") - 132.0).abs() < 0.01);
+    assert_eq!(frequency(61), None, "black keys are unmapped");
+
+    let plain = parse_scala_tuning(JUST_MAJOR_SCL, None).expect("scala without kbm should parse");
+    assert_eq!(plain.root_note, 60);
+    assert!((plain.frequency_hz(60).expect("middle c should sound") - 176.0).abs() < 0.01);
+    assert!(parse_scala_tuning("Broken\n 3\n 3/2\n 2/1\n", None).is_err());
+    assert!(parse_scala_tuning("Huge\n 18446744073709551615\n 2/1\n", None).is_err());
+    let huge_map = WHITE_KEYS_KBM.replacen("12", "18446744073709551615", 1);
+    assert!(parse_scala_tuning(JUST_MAJOR_SCL, Some(&huge_map)).is_err());
+
+    let temp = tempdir().expect("tempdir should be created");
+    let scl_path = temp.path().join("just.scl");
+    let kbm_path = temp.path().join("white.kbm");
+    std::fs::write(&scl_path, JUST_MAJOR_SCL).expect("scl should be written");
+    std::fs::write(&kbm_path, WHITE_KEYS_KBM).expect("kbm should be written");
+    let mut engine = Engine::new(Project::new("Scala", 120.0, DEFAULT_SAMPLE_RATE));
+    engine
+        .import_scala_tuning(&scl_path, Some(&kbm_path))
+        .expect("scala import should succeed");
+    assert_eq!(engine.project().tuning, tuning);
+
+    let error = engine
+        .set_tuning(Tuning {
+            first_note: 96,
+            last_note: 36,
+            ..tuning.clone()
+        })
+        .expect_err("an inverted keyboard range should fail");
+    assert!(error.to_string().contains("inverted"));
+    assert_eq!(engine.project().tuning, tuning);
+}
+
+#[test]
+fn reference_pitch_retunes_voices_and_midi_export_sends_mts() {
+    let mut engine = Engine::new(sine_project(69));
+    let standard = render_project_samples(engine.project(), 0.2);
+    assert!(
+        midi_bytes(engine.project())
+            .map(|bytes| !bytes
+                .windows(4)
+                .any(|window| window == [0x7F, 0x7F, 0x08, 0x02]))
+            .expect("midi export should succeed"),
+        "standard tuning sends no tuning messages"
+    );
+
+    engine
+        .set_tuning(Tuning {
+            reference_hz: 432.0,
+            ..Tuning::default()
+        })
+        .expect("tuning should be accepted");
+    assert!(
+        engine
+            .set_tuning(Tuning {
+                reference_hz: f64::NAN,
+                ..Tuning::default()
+            })
+            .is_err()
+    );
+    let tuned = render_project_samples(engine.project(), 0.2);
+    let ratio = zero_crossings(&tuned) as f64 / zero_crossings(&standard) as f64;
+    assert!(
+        (ratio - 432.0 / 440.0).abs() < 0.004,
+        "pitch ratio was {ratio}"
+    );
+
+    let bytes = midi_bytes(engine.project()).expect("midi export should succeed");
+    let smf = Smf::parse(&bytes).expect("exported midi should parse");
+    let messages: Vec<&[u8]> = smf.tracks[0]
+        .iter()
+        .filter_map(|event| match event.kind {
+            TrackEventKind::SysEx(data) => Some(data),
+            _ => None,
+        })
+        .collect();
+    assert_eq!(messages.len(), 2, "128 keys are split over two messages");
+    let upper = messages[1];
+    assert_eq!(&upper[..6], &[0x7F, 0x7F, 0x08, 0x02, 0x00, 64]);
+    assert_eq!(upper.last(), Some(&0xF7));
+    // A4 at 432 Hz sits 0.682 semitones above G#4.
+    let a4 = &upper[6 + 5 * 4..6 + 6 * 4];
+    assert_eq!(a4, &[69, 68, 87, 43]);
+}
@@ -0,0 +1,161 @@
+use std::collections::BTreeMap;
+
+use voltlane_core::{
+    AddClipRequest, Engine,
+    model::{ClipPayload, DEFAULT_SAMPLE_RATE, MidiNote, PatternClip, Project, Track, TrackKind},
+    vgm::{VGM_SAMPLE_RATE, vgm_bytes},
+};
+
+const ROW_TICKS: u64 = 1_920;
+
+fn read_u32(bytes: &[u8], offset: usize) -> u32 {
+    u32::from_le_bytes(
+        bytes[offset..offset + 4]
+            .try_into()
+            .expect("header field should be four bytes"),
+    )
+}
+
+/// Walks the command stream, returning write counts per command byte and the
+/// total number of waited samples. Panics on unknown commands.
+fn parse_commands(bytes: &[u8], data_offset: usize) -> (BTreeMap<u8, usize>, u64) {
+    let mut writes = BTreeMap::new();
+    let mut waited = 0_u64;
+    let mut cursor = data_offset;
+    loop {
+        let command = bytes[cursor];
+        match command {
+            0x50 => {
+                *writes.entry(command).or_default() += 1;
+                cursor += 2;
+            }
+            0x52 | 0x53 | 0xA0 | 0xB3 | 0xB4 => {
+                *writes.entry(command).or_default() += 1;
+                cursor += 3;
+            }
+            0x61 => {
+                waited += u64::from(u16::from_le_bytes([bytes[cursor + 1], bytes[cursor + 2]]));
+                cursor += 3;
+            }
+            0x62 => {
+                waited += 735;
+                cursor += 1;
+            }
+            0x63 => {
+                waited += 882;
+                cursor += 1;
+            }
+            0x70..=0x7F => {
+                waited += u64::from(command - 0x6F);
+                cursor += 1;
+            }
+            0x66 => return (writes, waited),
+            other => panic!("unexpected vgm command {other:#04x} at {cursor:#x}"),
+        }
+    }
+}
+
+fn chip_song() -> Engine {
+    let mut project = Project::new("Chip Song", 150.0, DEFAULT_SAMPLE_RATE);
+    let sources = [
+        "nes_2a03_pulse",
+        "nes_2a03_triangle",
+        "nes_2a03_noise",
+        "sn76489",
+        "gameboy_apu",
+        "ay_3_8910",
+        "ym2612",
+    ];
+    let mut track_ids = Vec::new();
+    for source in sources {
+        let track = Track::new(source, "#9be564", TrackKind::Chip);
+        track_ids.push(track.id);
+        project.tracks.push(track);
+    }
+
+    let mut engine = Engine::new(project);
+    for (track_id, source) in track_ids.iter().zip(sources) {
+        for row in 0..2_u64 {
+            engine
+                .add_clip(AddClipRequest {
+                    track_id: *track_id,
+                    name: format!("{source} {row}"),
+                    start_tick: row * ROW_TICKS,
+                    length_ticks: ROW_TICKS,
+                    payload: ClipPayload::Pattern(PatternClip {
+                        source_chip: source.to_string(),
+                        notes: (0..4_u64)
+                            .map(|beat| MidiNote {
+                                pitch: 48 + (beat as u8) * 4,
+                                velocity: 100,
+                                start_tick: beat * 480,
+                                length_ticks: 240,
+                                channel: 0,
+                                release_velocity: None,
+                            })
+                            .collect(),
+                        ..PatternClip::default()
+                    }),
+                })
+                .expect("pattern clip add should succeed");
+        }
+    }
+
+    engine
+        .order_list_from_timeline(track_ids, ROW_TICKS)
+        .expect("order list build should succeed");
+    engine
+        .set_order_loop_row(Some(1))
+        .expect("loop row update should succeed");
+    engine
+}
+
+#[test]
+fn vgm_export_writes_header_register_stream_loop_and_gd3() {
+    let engine = chip_song();
+    let bytes = vgm_bytes(engine.project()).expect("vgm export should succeed");
+
+    assert_eq!(&bytes[0..4], b"Vgm ");
+    assert_eq!(read_u32(&bytes, 0x04) as usize, bytes.len() - 4);
+    assert_eq!(read_u32(&bytes, 0x08), 0x171);
+    for clock_offset in [0x0C, 0x2C, 0x74, 0x80, 0x84] {
+        assert!(
+            read_u32(&bytes, clock_offset) > 0,
+            "chip clock at {clock_offset:#x}"
+        );
+    }
+
+    // Two rows of 1920 ticks at 150 bpm is 3.2 seconds; the loop covers the second.
+    let total_samples = read_u32(&bytes, 0x18);
+    assert_eq!(total_samples, (3.2 * f64::from(VGM_SAMPLE_RATE)) as u32);
+    assert_eq!(read_u32(&bytes, 0x20), total_samples / 2);
+
+    let data_offset = 0x34 + read_u32(&bytes, 0x34) as usize;
+    let (writes, waited) = parse_commands(&bytes, data_offset);
+    assert_eq!(waited, u64::from(total_samples));
+    for command in [0x50, 0x52, 0x53, 0xA0, 0xB3, 0xB4] {
+        assert!(
+            writes.get(&command).copied().unwrap_or_default() > 0,
+            "expected register writes for command {command:#04x}"
+        );
+    }
+
+    let loop_offset = 0x1C + read_u32(&bytes, 0x1C) as usize;
+    let (_, waited_after_loop) = parse_commands(&bytes, loop_offset);
+    assert_eq!(waited_after_loop, u64::from(total_samples / 2));
+
+    let gd3_offset = 0x14 + read_u32(&bytes, 0x14) as usize;
+    assert_eq!(&bytes[gd3_offset..gd3_offset + 4], b"Gd3 ");
+    let title: Vec<u16> = bytes[gd3_offset + 12..]
+        .chunks_exact(2)
+        .map(|pair| u16::from_le_bytes([pair[0], pair[1]]))
+        .take_while(|unit| *unit != 0)
+        .collect();
+    assert_eq!(String::from_utf16_lossy(&title), "Chip Song");
+}
+
+#[test]
+fn vgm_export_requires_chip_pattern_clips() {
+    let project = Project::new("Empty", 120.0, DEFAULT_SAMPLE_RATE);
+    assert!(vgm_bytes(&project).is_err());
+}
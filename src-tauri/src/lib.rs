@@ -13,10 +13,13 @@ use tauri_plugin_log::{Target, TargetKind, log::LevelFilter};
 use tracing::{error, info, instrument};
 use uuid::Uuid;
 use voltlane_core::{
-    AddClipRequest, AddTrackRequest, AudioAnalysis, AudioAssetEntry, AudioClipPatch,
-    AutomationPoint, ChipMacroLane, ClipPayload, DEFAULT_TRACKER_LINES_PER_BEAT, Engine,
-    ExportKind, MidiClip, MidiNote, ParityReport, PatternClip, Project, RenderMode, TrackMixPatch,
-    TrackSend, TrackStatePatch, TrackerRow, init_tracing_with_options,
+    AddClipRequest, AddTrackRequest, ArpeggiatorOptions, ArpeggioMode, AudioAnalysis,
+    AudioAssetEntry, AudioClipPatch, AudioStretchMode, AutomationPoint, ChipMacroLane, ChordKind,
+    ClipPayload, ClipPlaybackPatch, ControllerTarget, DEFAULT_TRACKER_LINES_PER_BEAT, Engine,
+    EuclideanRhythm, ExportKind, HumanizeOptions, Key, KeyEstimate, MidiClip, MidiNote,
+    NoteEndQuantize, ParityReport, PatternClip, ProbabilityFill, Project, QuantizeOptions,
+    RenderMode, ScaleMode, StrumDirection, TrackMixPatch, TrackSend, TrackStatePatch, TrackerRow,
+    TranscriptionOptions, Tuning, VelocityShape, WarpMarker, init_tracing_with_options,
 };
 
 use crate::config::{AppConfig, AppMode};
@@ -78,6 +81,35 @@ struct UpdateAudioClipInput {
     fade_out_seconds: Option<f64>,
     reverse: Option<bool>,
     stretch_ratio: Option<f32>,
+    stretch_mode: Option<AudioStretchMode>,
+    pitch_semitones: Option<f32>,
+    pitch_cents: Option<f32>,
+}
+
+/// An empty `markers` list unwarps the clip.
+#[derive(Debug, Deserialize)]
+struct SetAudioWarpMarkersInput {
+    track_id: String,
+    clip_id: String,
+    markers: Vec<WarpMarker>,
+}
+
+#[derive(Debug, Deserialize)]
+struct AutoWarpAudioClipInput {
+    track_id: String,
+    clip_id: String,
+    source_bpm: f64,
+}
+
+/// `loop_length_ticks` of zero stops the clip looping.
+#[derive(Debug, Deserialize)]
+struct UpdateClipPlaybackInput {
+    track_id: String,
+    clip_id: String,
+    content_offset_ticks: Option<u64>,
+    loop_length_ticks: Option<u64>,
+    transpose_semitones: Option<i8>,
+    velocity_scale: Option<f32>,
 }
 
 #[derive(Debug, Deserialize)]
@@ -140,11 +172,92 @@ struct MoveClipInput {
     length_ticks: u64,
 }
 
+#[derive(Debug, Deserialize)]
+struct SplitClipInput {
+    track_id: String,
+    clip_id: String,
+    tick: u64,
+}
+
+/// Slices at `grid_ticks` when given, otherwise into `parts` equal pieces.
+#[derive(Debug, Deserialize)]
+struct SliceClipInput {
+    track_id: String,
+    clip_id: String,
+    parts: Option<u32>,
+    grid_ticks: Option<u64>,
+}
+
+/// `sensitivity` runs from `0.0` (only the strongest hits) to `1.0`.
+#[derive(Debug, Deserialize)]
+struct SliceAudioClipAtTransientsInput {
+    track_id: String,
+    clip_id: String,
+    sensitivity: f32,
+}
+
+#[derive(Debug, Deserialize)]
+struct SliceAudioClipToSamplerInput {
+    track_id: String,
+    clip_id: String,
+    target_track_id: String,
+    sensitivity: f32,
+    first_note: u8,
+}
+
+/// A `source_chip` writes a pattern clip instead of a MIDI clip; notes are
+/// quantized only when `quantize_grid_ticks` is set.
+#[derive(Debug, Deserialize)]
+struct TranscribeAudioClipInput {
+    track_id: String,
+    clip_id: String,
+    target_track_id: String,
+    source_chip: Option<String>,
+    threshold: Option<f32>,
+    min_note_seconds: Option<f64>,
+    quantize_grid_ticks: Option<u64>,
+    quantize_strength_percent: Option<f32>,
+}
+
+#[derive(Debug, Deserialize)]
+struct GlueClipsInput {
+    track_id: String,
+    clip_ids: Vec<String>,
+}
+
+#[derive(Debug, Deserialize)]
+struct DuplicateClipLinkedInput {
+    track_id: String,
+    clip_id: String,
+    start_tick: u64,
+}
+
+#[derive(Debug, Deserialize)]
+struct PlacePoolContentInput {
+    track_id: String,
+    content_id: String,
+    start_tick: u64,
+    length_ticks: u64,
+}
+
+#[derive(Debug, Deserialize)]
+struct InsertTimeInput {
+    at_tick: u64,
+    length_ticks: u64,
+}
+
+#[derive(Debug, Deserialize)]
+struct DeleteTimeInput {
+    start_tick: u64,
+    end_tick: u64,
+}
+
 #[derive(Debug, Deserialize)]
 struct UpdateClipNotesInput {
     track_id: String,
     clip_id: String,
     notes: Vec<MidiNote>,
+    snap_to_scale: Option<bool>,
 }
 
 #[derive(Debug, Deserialize)]
@@ -155,6 +268,34 @@ struct UpdatePatternRowsInput {
     lines_per_beat: Option<u16>,
 }
 
+#[derive(Debug, Deserialize)]
+struct GenerateEuclideanRowsInput {
+    track_id: String,
+    clip_id: String,
+    hits: u32,
+    steps: u32,
+    rotation: Option<u32>,
+    pitch: u8,
+    velocity: Option<u8>,
+}
+
+#[derive(Debug, Deserialize)]
+struct GenerateProbabilityRowsInput {
+    track_id: String,
+    clip_id: String,
+    seed: Option<u64>,
+    chances: Vec<f32>,
+    pitches: Vec<u8>,
+    velocity: Option<u8>,
+}
+
+#[derive(Debug, Deserialize)]
+struct ContinuePatternMarkovInput {
+    track_id: String,
+    clip_id: String,
+    seed: Option<u64>,
+}
+
 #[derive(Debug, Deserialize)]
 struct UpdatePatternMacrosInput {
     track_id: String,
@@ -167,6 +308,7 @@ struct AddClipNoteInput {
     track_id: String,
     clip_id: String,
     note: MidiNote,
+    snap_to_scale: Option<bool>,
 }
 
 #[derive(Debug, Deserialize)]
@@ -181,6 +323,21 @@ struct TransposeClipNotesInput {
     track_id: String,
     clip_id: String,
     semitones: i16,
+    /// Reads `semitones` as scale degrees of the clip's key.
+    diatonic: Option<bool>,
+}
+
+#[derive(Debug, Deserialize)]
+struct SetClipKeyInput {
+    track_id: String,
+    clip_id: String,
+    key: Option<Key>,
+}
+
+#[derive(Debug, Deserialize)]
+struct ClipRefInput {
+    track_id: String,
+    clip_id: String,
 }
 
 #[derive(Debug, Deserialize)]
@@ -188,6 +345,85 @@ struct QuantizeClipNotesInput {
     track_id: String,
     clip_id: String,
     grid_ticks: u64,
+    strength_percent: Option<f32>,
+    swing_percent: Option<f32>,
+    triplet: Option<bool>,
+    ends: Option<NoteEndQuantize>,
+    groove: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+struct HumanizeClipNotesInput {
+    track_id: String,
+    clip_id: String,
+    note_indices: Option<Vec<usize>>,
+    seed: u64,
+    timing_ticks: u64,
+    velocity: u8,
+    length_percent: f32,
+}
+
+#[derive(Debug, Deserialize)]
+struct StrumClipNotesInput {
+    track_id: String,
+    clip_id: String,
+    note_indices: Option<Vec<usize>>,
+    direction: StrumDirection,
+    spread_ticks: u64,
+}
+
+#[derive(Debug, Deserialize)]
+struct ShapeClipNoteVelocitiesInput {
+    track_id: String,
+    clip_id: String,
+    note_indices: Option<Vec<usize>>,
+    from: u8,
+    to: u8,
+    curve: Option<f32>,
+}
+
+#[derive(Debug, Deserialize)]
+struct LegatoClipNotesInput {
+    track_id: String,
+    clip_id: String,
+    note_indices: Option<Vec<usize>>,
+}
+
+#[derive(Debug, Deserialize)]
+struct SetClipNoteLengthsInput {
+    track_id: String,
+    clip_id: String,
+    note_indices: Option<Vec<usize>>,
+    length_ticks: u64,
+}
+
+#[derive(Debug, Deserialize)]
+struct ArpeggiateClipInput {
+    track_id: String,
+    clip_id: String,
+    mode: ArpeggioMode,
+    rate_ticks: u64,
+    octaves: Option<u8>,
+    gate_percent: Option<f32>,
+    seed: Option<u64>,
+}
+
+#[derive(Debug, Deserialize)]
+struct GenerateClipChordsInput {
+    track_id: String,
+    clip_id: String,
+    key_root: u8,
+    scale: ScaleMode,
+    kind: ChordKind,
+}
+
+#[derive(Debug, Deserialize)]
+struct ExtractGrooveInput {
+    track_id: String,
+    clip_id: String,
+    name: String,
+    grid_ticks: u64,
+    steps: usize,
 }
 
 #[derive(Debug, Deserialize)]
@@ -208,6 +444,20 @@ struct UpdateAutomationClipInput {
     points: Vec<AutomationPoint>,
 }
 
+#[derive(Debug, Deserialize)]
+struct ImportScalaTuningInput {
+    scl_path: String,
+    kbm_path: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+struct UpdateControllerLaneInput {
+    track_id: String,
+    clip_id: String,
+    target: ControllerTarget,
+    points: Vec<AutomationPoint>,
+}
+
 #[derive(Debug, Serialize)]
 struct AutosaveStatusOutput {
     exists: bool,
@@ -372,6 +622,7 @@ fn add_midi_clip(state: State<'_, AppState>, input: AddMidiClipInput) -> Result<
     let payload = if let Some(source_chip) = input.source_chip {
         ClipPayload::Pattern(PatternClip {
             source_chip,
+            instrument: input.instrument,
             notes: input.notes,
             rows: Vec::new(),
             macros: Vec::new(),
@@ -381,6 +632,7 @@ fn add_midi_clip(state: State<'_, AppState>, input: AddMidiClipInput) -> Result<
         ClipPayload::Midi(MidiClip {
             instrument: input.instrument,
             notes: input.notes,
+            controllers: Vec::new(),
         })
     };
 
@@ -527,6 +779,9 @@ fn update_audio_clip(
         fade_out_seconds: input.fade_out_seconds,
         reverse: input.reverse,
         stretch_ratio: input.stretch_ratio,
+        stretch_mode: input.stretch_mode,
+        pitch_semitones: input.pitch_semitones,
+        pitch_cents: input.pitch_cents,
     };
 
     let mut engine = state.engine.lock();
@@ -538,60 +793,64 @@ fn update_audio_clip(
 
 #[instrument(skip(state, input))]
 #[tauri::command]
-fn move_clip(state: State<'_, AppState>, input: MoveClipInput) -> Result<Project, String> {
+fn set_audio_warp_markers(
+    state: State<'_, AppState>,
+    input: SetAudioWarpMarkersInput,
+) -> Result<Project, String> {
     let track_id = parse_uuid(&input.track_id)?;
     let clip_id = parse_uuid(&input.clip_id)?;
     let mut engine = state.engine.lock();
     engine
-        .move_clip(track_id, clip_id, input.start_tick, input.length_ticks)
+        .set_audio_warp_markers(track_id, clip_id, input.markers)
         .map_err(|error| error.to_string())?;
-
     Ok(engine.project().clone())
 }
 
 #[instrument(skip(state, input))]
 #[tauri::command]
-fn update_clip_notes(
+fn auto_warp_audio_clip(
     state: State<'_, AppState>,
-    input: UpdateClipNotesInput,
+    input: AutoWarpAudioClipInput,
 ) -> Result<Project, String> {
     let track_id = parse_uuid(&input.track_id)?;
     let clip_id = parse_uuid(&input.clip_id)?;
     let mut engine = state.engine.lock();
     engine
-        .upsert_clip_notes(track_id, clip_id, input.notes)
+        .auto_warp_audio_clip(track_id, clip_id, input.source_bpm)
         .map_err(|error| error.to_string())?;
-
     Ok(engine.project().clone())
 }
 
 #[instrument(skip(state, input))]
 #[tauri::command]
-fn update_automation_clip(
+fn update_clip_playback(
     state: State<'_, AppState>,
-    input: UpdateAutomationClipInput,
+    input: UpdateClipPlaybackInput,
 ) -> Result<Project, String> {
     let track_id = parse_uuid(&input.track_id)?;
     let clip_id = parse_uuid(&input.clip_id)?;
+    let patch = ClipPlaybackPatch {
+        content_offset_ticks: input.content_offset_ticks,
+        loop_length_ticks: input.loop_length_ticks.map(Some),
+        transpose_semitones: input.transpose_semitones,
+        velocity_scale: input.velocity_scale,
+    };
+
     let mut engine = state.engine.lock();
     engine
-        .upsert_automation_clip(track_id, clip_id, input.target_parameter_id, input.points)
+        .patch_clip_playback(track_id, clip_id, patch)
         .map_err(|error| error.to_string())?;
-
     Ok(engine.project().clone())
 }
 
 #[instrument(skip(state, input))]
 #[tauri::command]
-fn update_pattern_rows(
-    state: State<'_, AppState>,
-    input: UpdatePatternRowsInput,
-) -> Result<Project, String> {
+fn move_clip(state: State<'_, AppState>, input: MoveClipInput) -> Result<Project, String> {
     let track_id = parse_uuid(&input.track_id)?;
     let clip_id = parse_uuid(&input.clip_id)?;
     let mut engine = state.engine.lock();
     engine
-        .upsert_pattern_rows(track_id, clip_id, input.rows, input.lines_per_beat)
+        .move_clip(track_id, clip_id, input.start_tick, input.length_ticks)
         .map_err(|error| error.to_string())?;
 
     Ok(engine.project().clone())
@@ -599,137 +858,734 @@ fn update_pattern_rows(
 
 #[instrument(skip(state, input))]
 #[tauri::command]
-fn update_pattern_macros(
-    state: State<'_, AppState>,
-    input: UpdatePatternMacrosInput,
-) -> Result<Project, String> {
+fn split_clip(state: State<'_, AppState>, input: SplitClipInput) -> Result<Project, String> {
     let track_id = parse_uuid(&input.track_id)?;
     let clip_id = parse_uuid(&input.clip_id)?;
     let mut engine = state.engine.lock();
     engine
-        .upsert_pattern_macros(track_id, clip_id, input.macros)
+        .split_clip(track_id, clip_id, input.tick)
         .map_err(|error| error.to_string())?;
+    Ok(engine.project().clone())
+}
 
+#[instrument(skip(state, input))]
+#[tauri::command]
+fn slice_clip(state: State<'_, AppState>, input: SliceClipInput) -> Result<Project, String> {
+    let track_id = parse_uuid(&input.track_id)?;
+    let clip_id = parse_uuid(&input.clip_id)?;
+    let mut engine = state.engine.lock();
+    match (input.grid_ticks, input.parts) {
+        (Some(grid_ticks), _) => engine.slice_clip_to_grid(track_id, clip_id, grid_ticks),
+        (None, Some(parts)) => engine.slice_clip(track_id, clip_id, parts),
+        (None, None) => return Err("slice requires parts or grid_ticks".to_string()),
+    }
+    .map_err(|error| error.to_string())?;
     Ok(engine.project().clone())
 }
 
 #[instrument(skip(state, input))]
 #[tauri::command]
-fn add_clip_note(state: State<'_, AppState>, input: AddClipNoteInput) -> Result<Project, String> {
+fn slice_audio_clip_at_transients(
+    state: State<'_, AppState>,
+    input: SliceAudioClipAtTransientsInput,
+) -> Result<Project, String> {
     let track_id = parse_uuid(&input.track_id)?;
     let clip_id = parse_uuid(&input.clip_id)?;
     let mut engine = state.engine.lock();
     engine
-        .add_clip_note(track_id, clip_id, input.note)
+        .slice_audio_clip_at_transients(track_id, clip_id, input.sensitivity)
         .map_err(|error| error.to_string())?;
-
     Ok(engine.project().clone())
 }
 
 #[instrument(skip(state, input))]
 #[tauri::command]
-fn remove_clip_note(
+fn slice_audio_clip_to_sampler(
     state: State<'_, AppState>,
-    input: RemoveClipNoteInput,
+    input: SliceAudioClipToSamplerInput,
 ) -> Result<Project, String> {
     let track_id = parse_uuid(&input.track_id)?;
     let clip_id = parse_uuid(&input.clip_id)?;
+    let target_track_id = parse_uuid(&input.target_track_id)?;
     let mut engine = state.engine.lock();
     engine
-        .remove_clip_note(track_id, clip_id, input.note_index)
+        .slice_audio_clip_to_sampler(
+            track_id,
+            clip_id,
+            target_track_id,
+            input.sensitivity,
+            input.first_note,
+        )
         .map_err(|error| error.to_string())?;
-
     Ok(engine.project().clone())
 }
 
 #[instrument(skip(state, input))]
 #[tauri::command]
-fn transpose_clip_notes(
+fn transcribe_audio_clip(
     state: State<'_, AppState>,
-    input: TransposeClipNotesInput,
+    input: TranscribeAudioClipInput,
 ) -> Result<Project, String> {
     let track_id = parse_uuid(&input.track_id)?;
     let clip_id = parse_uuid(&input.clip_id)?;
+    let target_track_id = parse_uuid(&input.target_track_id)?;
+    let defaults = TranscriptionOptions::default();
+    let options = TranscriptionOptions {
+        source_chip: input.source_chip.filter(|chip| !chip.trim().is_empty()),
+        threshold: input.threshold.unwrap_or(defaults.threshold),
+        min_note_seconds: input.min_note_seconds.unwrap_or(defaults.min_note_seconds),
+        quantize: input.quantize_grid_ticks.map(|grid_ticks| {
+            let grid = QuantizeOptions::grid(grid_ticks);
+            QuantizeOptions {
+                strength_percent: input
+                    .quantize_strength_percent
+                    .unwrap_or(grid.strength_percent),
+                ..grid
+            }
+        }),
+    };
+    let mut engine = state.engine.lock();
+    engine
+        .transcribe_audio_clip(track_id, clip_id, target_track_id, &options)
+        .map_err(|error| error.to_string())?;
+    Ok(engine.project().clone())
+}
+
+#[instrument(skip(state, input))]
+#[tauri::command]
+fn glue_clips(state: State<'_, AppState>, input: GlueClipsInput) -> Result<Project, String> {
+    let track_id = parse_uuid(&input.track_id)?;
+    let clip_ids = input
+        .clip_ids
+        .iter()
+        .map(|clip_id| parse_uuid(clip_id))
+        .collect::<Result<Vec<_>, _>>()?;
     let mut engine = state.engine.lock();
     engine
-        .transpose_clip_notes(track_id, clip_id, input.semitones)
+        .glue_clips(track_id, &clip_ids)
         .map_err(|error| error.to_string())?;
+    Ok(engine.project().clone())
+}
 
+#[instrument(skip(state, input))]
+#[tauri::command]
+fn pool_clip(state: State<'_, AppState>, input: ClipRefInput) -> Result<Project, String> {
+    let track_id = parse_uuid(&input.track_id)?;
+    let clip_id = parse_uuid(&input.clip_id)?;
+    let mut engine = state.engine.lock();
+    engine
+        .pool_clip(track_id, clip_id)
+        .map_err(|error| error.to_string())?;
     Ok(engine.project().clone())
 }
 
 #[instrument(skip(state, input))]
 #[tauri::command]
-fn quantize_clip_notes(
+fn duplicate_clip_linked(
     state: State<'_, AppState>,
-    input: QuantizeClipNotesInput,
+    input: DuplicateClipLinkedInput,
 ) -> Result<Project, String> {
     let track_id = parse_uuid(&input.track_id)?;
     let clip_id = parse_uuid(&input.clip_id)?;
     let mut engine = state.engine.lock();
     engine
-        .quantize_clip_notes(track_id, clip_id, input.grid_ticks)
+        .duplicate_clip_linked(track_id, clip_id, input.start_tick)
         .map_err(|error| error.to_string())?;
-
     Ok(engine.project().clone())
 }
 
 #[instrument(skip(state, input))]
 #[tauri::command]
-fn add_effect(state: State<'_, AppState>, input: AddEffectInput) -> Result<Project, String> {
+fn place_pool_content(
+    state: State<'_, AppState>,
+    input: PlacePoolContentInput,
+) -> Result<Project, String> {
     let track_id = parse_uuid(&input.track_id)?;
+    let content_id = parse_uuid(&input.content_id)?;
     let mut engine = state.engine.lock();
     engine
-        .add_effect(track_id, voltlane_core::EffectSpec::new(input.effect_name))
+        .place_pool_content(track_id, content_id, input.start_tick, input.length_ticks)
         .map_err(|error| error.to_string())?;
     Ok(engine.project().clone())
 }
 
-#[instrument(skip(state))]
+#[instrument(skip(state, input))]
 #[tauri::command]
-fn set_playback(state: State<'_, AppState>, is_playing: bool) -> Project {
+fn make_clip_unique(state: State<'_, AppState>, input: ClipRefInput) -> Result<Project, String> {
+    let track_id = parse_uuid(&input.track_id)?;
+    let clip_id = parse_uuid(&input.clip_id)?;
     let mut engine = state.engine.lock();
-    engine.toggle_playback(is_playing);
-    engine.project().clone()
+    engine
+        .make_clip_unique(track_id, clip_id)
+        .map_err(|error| error.to_string())?;
+    Ok(engine.project().clone())
 }
 
 #[instrument(skip(state))]
 #[tauri::command]
-fn set_loop_region(
-    state: State<'_, AppState>,
-    loop_start_tick: u64,
-    loop_end_tick: u64,
-    loop_enabled: bool,
-) -> Project {
+fn prune_clip_pool(state: State<'_, AppState>) -> Project {
     let mut engine = state.engine.lock();
-    engine.set_loop_region(loop_start_tick, loop_end_tick, loop_enabled);
+    engine.prune_clip_pool();
     engine.project().clone()
 }
 
 #[instrument(skip(state, input))]
 #[tauri::command]
-fn export_project(state: State<'_, AppState>, input: ExportProjectInput) -> Result<String, String> {
-    let engine = state.engine.lock();
-    let ffmpeg_path = input
-        .ffmpeg_binary
-        .as_deref()
-        .unwrap_or(state.config.export.ffmpeg_binary.as_str());
-    let ffmpeg_binary = Some(Path::new(ffmpeg_path));
-    let render_mode = input
-        .render_mode
-        .unwrap_or(state.config.export.default_render_mode);
-    let output_path =
-        if matches!(input.kind, ExportKind::StemWav) && input.output_path.trim().is_empty() {
-            resolve_dev_path(&state.config.paths.dev_export_dir)
-                .join(&state.config.export.stems_output_dir_name)
-        } else {
-            PathBuf::from(&input.output_path)
-        };
-
+fn insert_time(state: State<'_, AppState>, input: InsertTimeInput) -> Result<Project, String> {
+    let mut engine = state.engine.lock();
     engine
-        .export(input.kind, &output_path, ffmpeg_binary, render_mode)
+        .insert_time(input.at_tick, input.length_ticks)
         .map_err(|error| error.to_string())?;
-    Ok(output_path.display().to_string())
+    Ok(engine.project().clone())
+}
+
+#[instrument(skip(state, input))]
+#[tauri::command]
+fn delete_time(state: State<'_, AppState>, input: DeleteTimeInput) -> Result<Project, String> {
+    let mut engine = state.engine.lock();
+    engine
+        .delete_time(input.start_tick, input.end_tick)
+        .map_err(|error| error.to_string())?;
+    Ok(engine.project().clone())
+}
+
+#[instrument(skip(state, input))]
+#[tauri::command]
+fn update_clip_notes(
+    state: State<'_, AppState>,
+    input: UpdateClipNotesInput,
+) -> Result<Project, String> {
+    let track_id = parse_uuid(&input.track_id)?;
+    let clip_id = parse_uuid(&input.clip_id)?;
+    let mut engine = state.engine.lock();
+    if input.snap_to_scale.unwrap_or(false) {
+        engine.upsert_clip_notes_in_key(track_id, clip_id, input.notes)
+    } else {
+        engine.upsert_clip_notes(track_id, clip_id, input.notes)
+    }
+    .map_err(|error| error.to_string())?;
+
+    Ok(engine.project().clone())
+}
+
+#[instrument(skip(state, input))]
+#[tauri::command]
+fn update_automation_clip(
+    state: State<'_, AppState>,
+    input: UpdateAutomationClipInput,
+) -> Result<Project, String> {
+    let track_id = parse_uuid(&input.track_id)?;
+    let clip_id = parse_uuid(&input.clip_id)?;
+    let mut engine = state.engine.lock();
+    engine
+        .upsert_automation_clip(track_id, clip_id, input.target_parameter_id, input.points)
+        .map_err(|error| error.to_string())?;
+
+    Ok(engine.project().clone())
+}
+
+#[instrument(skip(state, input))]
+#[tauri::command]
+fn update_controller_lane(
+    state: State<'_, AppState>,
+    input: UpdateControllerLaneInput,
+) -> Result<Project, String> {
+    let track_id = parse_uuid(&input.track_id)?;
+    let clip_id = parse_uuid(&input.clip_id)?;
+    let mut engine = state.engine.lock();
+    engine
+        .upsert_controller_lane(track_id, clip_id, input.target, input.points)
+        .map_err(|error| error.to_string())?;
+
+    Ok(engine.project().clone())
+}
+
+#[instrument(skip(state, input))]
+#[tauri::command]
+fn update_pattern_rows(
+    state: State<'_, AppState>,
+    input: UpdatePatternRowsInput,
+) -> Result<Project, String> {
+    let track_id = parse_uuid(&input.track_id)?;
+    let clip_id = parse_uuid(&input.clip_id)?;
+    let mut engine = state.engine.lock();
+    engine
+        .upsert_pattern_rows(track_id, clip_id, input.rows, input.lines_per_beat)
+        .map_err(|error| error.to_string())?;
+
+    Ok(engine.project().clone())
+}
+
+#[instrument(skip(state, input))]
+#[tauri::command]
+fn generate_euclidean_rows(
+    state: State<'_, AppState>,
+    input: GenerateEuclideanRowsInput,
+) -> Result<Project, String> {
+    let track_id = parse_uuid(&input.track_id)?;
+    let clip_id = parse_uuid(&input.clip_id)?;
+    let rhythm = EuclideanRhythm {
+        hits: input.hits,
+        steps: input.steps,
+        rotation: input.rotation.unwrap_or_default(),
+        pitch: input.pitch,
+        velocity: input.velocity.unwrap_or(100),
+    };
+    let mut engine = state.engine.lock();
+    engine
+        .generate_euclidean_rows(track_id, clip_id, &rhythm)
+        .map_err(|error| error.to_string())?;
+
+    Ok(engine.project().clone())
+}
+
+#[instrument(skip(state, input))]
+#[tauri::command]
+fn generate_probability_rows(
+    state: State<'_, AppState>,
+    input: GenerateProbabilityRowsInput,
+) -> Result<Project, String> {
+    let track_id = parse_uuid(&input.track_id)?;
+    let clip_id = parse_uuid(&input.clip_id)?;
+    let fill = ProbabilityFill {
+        seed: input.seed.unwrap_or_default(),
+        chances: input.chances,
+        pitches: input.pitches,
+        velocity: input.velocity.unwrap_or(100),
+    };
+    let mut engine = state.engine.lock();
+    engine
+        .generate_probability_rows(track_id, clip_id, &fill)
+        .map_err(|error| error.to_string())?;
+
+    Ok(engine.project().clone())
+}
+
+#[instrument(skip(state, input))]
+#[tauri::command]
+fn continue_pattern_markov(
+    state: State<'_, AppState>,
+    input: ContinuePatternMarkovInput,
+) -> Result<Project, String> {
+    let track_id = parse_uuid(&input.track_id)?;
+    let clip_id = parse_uuid(&input.clip_id)?;
+    let mut engine = state.engine.lock();
+    engine
+        .continue_pattern_markov(track_id, clip_id, input.seed.unwrap_or_default())
+        .map_err(|error| error.to_string())?;
+
+    Ok(engine.project().clone())
+}
+
+#[instrument(skip(state, input))]
+#[tauri::command]
+fn update_pattern_macros(
+    state: State<'_, AppState>,
+    input: UpdatePatternMacrosInput,
+) -> Result<Project, String> {
+    let track_id = parse_uuid(&input.track_id)?;
+    let clip_id = parse_uuid(&input.clip_id)?;
+    let mut engine = state.engine.lock();
+    engine
+        .upsert_pattern_macros(track_id, clip_id, input.macros)
+        .map_err(|error| error.to_string())?;
+
+    Ok(engine.project().clone())
+}
+
+#[instrument(skip(state, input))]
+#[tauri::command]
+fn add_clip_note(state: State<'_, AppState>, input: AddClipNoteInput) -> Result<Project, String> {
+    let track_id = parse_uuid(&input.track_id)?;
+    let clip_id = parse_uuid(&input.clip_id)?;
+    let mut engine = state.engine.lock();
+    if input.snap_to_scale.unwrap_or(false) {
+        engine.add_clip_note_in_key(track_id, clip_id, input.note)
+    } else {
+        engine.add_clip_note(track_id, clip_id, input.note)
+    }
+    .map_err(|error| error.to_string())?;
+
+    Ok(engine.project().clone())
+}
+
+#[instrument(skip(state, input))]
+#[tauri::command]
+fn remove_clip_note(
+    state: State<'_, AppState>,
+    input: RemoveClipNoteInput,
+) -> Result<Project, String> {
+    let track_id = parse_uuid(&input.track_id)?;
+    let clip_id = parse_uuid(&input.clip_id)?;
+    let mut engine = state.engine.lock();
+    engine
+        .remove_clip_note(track_id, clip_id, input.note_index)
+        .map_err(|error| error.to_string())?;
+
+    Ok(engine.project().clone())
+}
+
+#[instrument(skip(state, input))]
+#[tauri::command]
+fn transpose_clip_notes(
+    state: State<'_, AppState>,
+    input: TransposeClipNotesInput,
+) -> Result<Project, String> {
+    let track_id = parse_uuid(&input.track_id)?;
+    let clip_id = parse_uuid(&input.clip_id)?;
+    let mut engine = state.engine.lock();
+    if input.diatonic.unwrap_or(false) {
+        engine.transpose_clip_notes_diatonic(track_id, clip_id, input.semitones)
+    } else {
+        engine.transpose_clip_notes(track_id, clip_id, input.semitones)
+    }
+    .map_err(|error| error.to_string())?;
+
+    Ok(engine.project().clone())
+}
+
+#[instrument(skip(state, input))]
+#[tauri::command]
+fn quantize_clip_notes(
+    state: State<'_, AppState>,
+    input: QuantizeClipNotesInput,
+) -> Result<Project, String> {
+    let track_id = parse_uuid(&input.track_id)?;
+    let clip_id = parse_uuid(&input.clip_id)?;
+    let mut engine = state.engine.lock();
+    let defaults = QuantizeOptions::grid(input.grid_ticks);
+    let options = QuantizeOptions {
+        strength_percent: input.strength_percent.unwrap_or(defaults.strength_percent),
+        swing_percent: input.swing_percent.unwrap_or(defaults.swing_percent),
+        triplet: input.triplet.unwrap_or(defaults.triplet),
+        ends: input.ends.unwrap_or(defaults.ends),
+        groove: input.groove.filter(|name| !name.trim().is_empty()),
+        ..defaults
+    };
+    engine
+        .quantize_clip_notes_with(track_id, clip_id, &options)
+        .map_err(|error| error.to_string())?;
+
+    Ok(engine.project().clone())
+}
+
+#[instrument(skip(state, input))]
+#[tauri::command]
+fn humanize_clip_notes(
+    state: State<'_, AppState>,
+    input: HumanizeClipNotesInput,
+) -> Result<Project, String> {
+    let track_id = parse_uuid(&input.track_id)?;
+    let clip_id = parse_uuid(&input.clip_id)?;
+    let options = HumanizeOptions {
+        seed: input.seed,
+        timing_ticks: input.timing_ticks,
+        velocity: input.velocity,
+        length_percent: input.length_percent,
+    };
+    let mut engine = state.engine.lock();
+    engine
+        .humanize_clip_notes(
+            track_id,
+            clip_id,
+            &input.note_indices.unwrap_or_default(),
+            &options,
+        )
+        .map_err(|error| error.to_string())?;
+
+    Ok(engine.project().clone())
+}
+
+#[instrument(skip(state, input))]
+#[tauri::command]
+fn strum_clip_notes(
+    state: State<'_, AppState>,
+    input: StrumClipNotesInput,
+) -> Result<Project, String> {
+    let track_id = parse_uuid(&input.track_id)?;
+    let clip_id = parse_uuid(&input.clip_id)?;
+    let mut engine = state.engine.lock();
+    engine
+        .strum_clip_notes(
+            track_id,
+            clip_id,
+            &input.note_indices.unwrap_or_default(),
+            input.direction,
+            input.spread_ticks,
+        )
+        .map_err(|error| error.to_string())?;
+
+    Ok(engine.project().clone())
+}
+
+#[instrument(skip(state, input))]
+#[tauri::command]
+fn shape_clip_note_velocities(
+    state: State<'_, AppState>,
+    input: ShapeClipNoteVelocitiesInput,
+) -> Result<Project, String> {
+    let track_id = parse_uuid(&input.track_id)?;
+    let clip_id = parse_uuid(&input.clip_id)?;
+    let shape = VelocityShape {
+        from: input.from,
+        to: input.to,
+        curve: input.curve.unwrap_or(1.0),
+    };
+    let mut engine = state.engine.lock();
+    engine
+        .shape_clip_note_velocities(
+            track_id,
+            clip_id,
+            &input.note_indices.unwrap_or_default(),
+            &shape,
+        )
+        .map_err(|error| error.to_string())?;
+
+    Ok(engine.project().clone())
+}
+
+#[instrument(skip(state, input))]
+#[tauri::command]
+fn legato_clip_notes(
+    state: State<'_, AppState>,
+    input: LegatoClipNotesInput,
+) -> Result<Project, String> {
+    let track_id = parse_uuid(&input.track_id)?;
+    let clip_id = parse_uuid(&input.clip_id)?;
+    let mut engine = state.engine.lock();
+    engine
+        .legato_clip_notes(track_id, clip_id, &input.note_indices.unwrap_or_default())
+        .map_err(|error| error.to_string())?;
+
+    Ok(engine.project().clone())
+}
+
+#[instrument(skip(state, input))]
+#[tauri::command]
+fn set_clip_note_lengths(
+    state: State<'_, AppState>,
+    input: SetClipNoteLengthsInput,
+) -> Result<Project, String> {
+    let track_id = parse_uuid(&input.track_id)?;
+    let clip_id = parse_uuid(&input.clip_id)?;
+    let mut engine = state.engine.lock();
+    engine
+        .set_clip_note_lengths(
+            track_id,
+            clip_id,
+            &input.note_indices.unwrap_or_default(),
+            input.length_ticks,
+        )
+        .map_err(|error| error.to_string())?;
+
+    Ok(engine.project().clone())
+}
+
+#[instrument(skip(state, input))]
+#[tauri::command]
+fn arpeggiate_clip(
+    state: State<'_, AppState>,
+    input: ArpeggiateClipInput,
+) -> Result<Project, String> {
+    let track_id = parse_uuid(&input.track_id)?;
+    let clip_id = parse_uuid(&input.clip_id)?;
+    let options = ArpeggiatorOptions {
+        mode: input.mode,
+        rate_ticks: input.rate_ticks,
+        octaves: input.octaves.unwrap_or(1),
+        gate_percent: input.gate_percent.unwrap_or(100.0),
+        seed: input.seed.unwrap_or_default(),
+    };
+    let mut engine = state.engine.lock();
+    engine
+        .arpeggiate_clip(track_id, clip_id, &options)
+        .map_err(|error| error.to_string())?;
+
+    Ok(engine.project().clone())
+}
+
+#[instrument(skip(state, input))]
+#[tauri::command]
+fn generate_clip_chords(
+    state: State<'_, AppState>,
+    input: GenerateClipChordsInput,
+) -> Result<Project, String> {
+    let track_id = parse_uuid(&input.track_id)?;
+    let clip_id = parse_uuid(&input.clip_id)?;
+    let key = Key {
+        root: input.key_root,
+        mode: input.scale,
+    };
+    let mut engine = state.engine.lock();
+    engine
+        .generate_clip_chords(track_id, clip_id, key, input.kind)
+        .map_err(|error| error.to_string())?;
+
+    Ok(engine.project().clone())
+}
+
+#[instrument(skip(state, input))]
+#[tauri::command]
+fn extract_groove(
+    state: State<'_, AppState>,
+    input: ExtractGrooveInput,
+) -> Result<Project, String> {
+    let track_id = parse_uuid(&input.track_id)?;
+    let clip_id = parse_uuid(&input.clip_id)?;
+    let mut engine = state.engine.lock();
+    engine
+        .extract_clip_groove(
+            track_id,
+            clip_id,
+            &input.name,
+            input.grid_ticks,
+            input.steps,
+        )
+        .map_err(|error| error.to_string())?;
+
+    Ok(engine.project().clone())
+}
+
+#[instrument(skip(state, input))]
+#[tauri::command]
+fn add_effect(state: State<'_, AppState>, input: AddEffectInput) -> Result<Project, String> {
+    let track_id = parse_uuid(&input.track_id)?;
+    let mut engine = state.engine.lock();
+    engine
+        .add_effect(track_id, voltlane_core::EffectSpec::new(input.effect_name))
+        .map_err(|error| error.to_string())?;
+    Ok(engine.project().clone())
+}
+
+#[instrument(skip(state))]
+#[tauri::command]
+fn set_playback(state: State<'_, AppState>, is_playing: bool) -> Project {
+    let mut engine = state.engine.lock();
+    engine.toggle_playback(is_playing);
+    engine.project().clone()
+}
+
+#[instrument(skip(state))]
+#[tauri::command]
+fn set_loop_region(
+    state: State<'_, AppState>,
+    loop_start_tick: u64,
+    loop_end_tick: u64,
+    loop_enabled: bool,
+) -> Project {
+    let mut engine = state.engine.lock();
+    engine.set_loop_region(loop_start_tick, loop_end_tick, loop_enabled);
+    engine.project().clone()
+}
+
+#[instrument(skip(state, input))]
+#[tauri::command]
+fn export_project(state: State<'_, AppState>, input: ExportProjectInput) -> Result<String, String> {
+    let engine = state.engine.lock();
+    let ffmpeg_path = input
+        .ffmpeg_binary
+        .as_deref()
+        .unwrap_or(state.config.export.ffmpeg_binary.as_str());
+    let ffmpeg_binary = Some(Path::new(ffmpeg_path));
+    let render_mode = input
+        .render_mode
+        .unwrap_or(state.config.export.default_render_mode);
+    let output_path =
+        if matches!(input.kind, ExportKind::StemWav) && input.output_path.trim().is_empty() {
+            resolve_dev_path(&state.config.paths.dev_export_dir)
+                .join(&state.config.export.stems_output_dir_name)
+        } else {
+            PathBuf::from(&input.output_path)
+        };
+
+    engine
+        .export(input.kind, &output_path, ffmpeg_binary, render_mode)
+        .map_err(|error| error.to_string())?;
+    Ok(output_path.display().to_string())
+}
+
+#[instrument(skip(state, tuning))]
+#[tauri::command]
+fn set_project_tuning(state: State<'_, AppState>, tuning: Tuning) -> Result<Project, String> {
+    let mut engine = state.engine.lock();
+    engine
+        .set_tuning(tuning)
+        .map_err(|error| error.to_string())?;
+    Ok(engine.project().clone())
+}
+
+#[instrument(skip(state))]
+#[tauri::command]
+fn set_project_key(state: State<'_, AppState>, key: Option<Key>) -> Result<Project, String> {
+    let mut engine = state.engine.lock();
+    engine
+        .set_project_key(key)
+        .map_err(|error| error.to_string())?;
+    Ok(engine.project().clone())
+}
+
+#[instrument(skip(state, input))]
+#[tauri::command]
+fn set_clip_key(state: State<'_, AppState>, input: SetClipKeyInput) -> Result<Project, String> {
+    let track_id = parse_uuid(&input.track_id)?;
+    let clip_id = parse_uuid(&input.clip_id)?;
+    let mut engine = state.engine.lock();
+    engine
+        .set_clip_key(track_id, clip_id, input.key)
+        .map_err(|error| error.to_string())?;
+    Ok(engine.project().clone())
+}
+
+/// Pitch classes, from C, in the key a clip uses; `None` when no key is set.
+#[instrument(skip(state, input))]
+#[tauri::command]
+fn clip_scale_pitch_classes(
+    state: State<'_, AppState>,
+    input: ClipRefInput,
+) -> Result<Option<[bool; 12]>, String> {
+    let track_id = parse_uuid(&input.track_id)?;
+    let clip_id = parse_uuid(&input.clip_id)?;
+    let engine = state.engine.lock();
+    let key = engine
+        .clip_key(track_id, clip_id)
+        .map_err(|error| error.to_string())?;
+    Ok(key.map(|key| key.pitch_classes()))
+}
+
+#[instrument(skip(state, input))]
+#[tauri::command]
+fn detect_clip_key(
+    state: State<'_, AppState>,
+    input: ClipRefInput,
+) -> Result<Option<KeyEstimate>, String> {
+    let track_id = parse_uuid(&input.track_id)?;
+    let clip_id = parse_uuid(&input.clip_id)?;
+    let engine = state.engine.lock();
+    engine
+        .detect_clip_key(track_id, clip_id)
+        .map_err(|error| error.to_string())
+}
+
+#[instrument(skip(state))]
+#[tauri::command]
+fn detect_project_key(state: State<'_, AppState>) -> Option<KeyEstimate> {
+    state.engine.lock().detect_project_key()
+}
+
+#[instrument(skip(state, input), fields(scl_path = %input.scl_path))]
+#[tauri::command]
+fn import_scala_tuning(
+    state: State<'_, AppState>,
+    input: ImportScalaTuningInput,
+) -> Result<Project, String> {
+    let mut engine = state.engine.lock();
+    engine
+        .import_scala_tuning(
+            Path::new(&input.scl_path),
+            input.kbm_path.as_deref().map(Path::new),
+        )
+        .map_err(|error| error.to_string())?;
+    Ok(engine.project().clone())
 }
 
 #[instrument(skip(state), fields(path = %path))]
@@ -973,15 +1829,50 @@ pub fn run() {
             analyze_audio_asset,
             import_audio_clip,
             update_audio_clip,
+            set_audio_warp_markers,
+            auto_warp_audio_clip,
+            update_clip_playback,
             move_clip,
+            split_clip,
+            slice_clip,
+            slice_audio_clip_at_transients,
+            slice_audio_clip_to_sampler,
+            transcribe_audio_clip,
+            glue_clips,
+            pool_clip,
+            duplicate_clip_linked,
+            place_pool_content,
+            make_clip_unique,
+            prune_clip_pool,
+            insert_time,
+            delete_time,
             update_clip_notes,
             update_automation_clip,
+            update_controller_lane,
+            set_project_tuning,
+            import_scala_tuning,
+            set_project_key,
+            set_clip_key,
+            clip_scale_pitch_classes,
+            detect_clip_key,
+            detect_project_key,
             update_pattern_rows,
+            generate_euclidean_rows,
+            generate_probability_rows,
+            continue_pattern_markov,
             update_pattern_macros,
             add_clip_note,
             remove_clip_note,
             transpose_clip_notes,
             quantize_clip_notes,
+            extract_groove,
+            humanize_clip_notes,
+            strum_clip_notes,
+            shape_clip_note_velocities,
+            legato_clip_notes,
+            set_clip_note_lengths,
+            arpeggiate_clip,
+            generate_clip_chords,
             add_effect,
             set_playback,
             set_loop_region,
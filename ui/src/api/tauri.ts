@@ -10,12 +10,30 @@ import type {
   AutosaveStatus,
   AudioAnalysis,
   AudioAssetEntry,
+  AudioClip,
+  AutoWarpAudioClipInput,
   Clip,
   ExportProjectInput,
   ImportAudioClipInput,
   MoveClipInput,
   RemoveTrackSendInput,
   QuantizeClipNotesInput,
+  ExtractGrooveInput,
+  ArpeggiateClipInput,
+  GenerateClipChordsInput,
+  ScaleMode,
+  Key,
+  KeyEstimate,
+  MidiNote,
+  SetClipKeyInput,
+  ClipRefInput,
+  HumanizeClipNotesInput,
+  LegatoClipNotesInput,
+  NoteSelectionInput,
+  SetClipNoteLengthsInput,
+  ShapeClipNoteVelocitiesInput,
+  StrumClipNotesInput,
+  GrooveTemplate,
   ParityReport,
   PatchTrackMixInput,
   PatchTrackInput,
@@ -27,9 +45,27 @@ import type {
   TrackKind,
   TransposeClipNotesInput,
   UpdateAudioClipInput,
+  SetAudioWarpMarkersInput,
+  UpdateClipPlaybackInput,
+  SplitClipInput,
+  SliceClipInput,
+  SliceAudioClipAtTransientsInput,
+  SliceAudioClipToSamplerInput,
+  TranscribeAudioClipInput,
+  GlueClipsInput,
+  DuplicateClipLinkedInput,
+  PlacePoolContentInput,
+  InsertTimeInput,
+  DeleteTimeInput,
+  ImportScalaTuningInput,
+  Tuning,
   UpdateAutomationClipInput,
+  UpdateControllerLaneInput,
   UpdatePatternMacrosInput,
   UpdatePatternRowsInput,
+  GenerateEuclideanRowsInput,
+  GenerateProbabilityRowsInput,
+  ContinuePatternMarkovInput,
   UpsertTrackSendInput,
   UpdateClipNotesInput
 } from "../types";
@@ -196,10 +232,233 @@ function getClipRefs(project: Project, trackId: string, clipId: string): { track
   return { track, clip };
 }
 
+/** Mock split: note clips partition their notes, everything else just slips the right part. */
+/** Stand-in for transient detection: the mock cuts audio on every beat. */
+function mockTransientTicks(clip: Clip): number[] {
+  const ticks: number[] = [];
+  for (let tick = 0; tick < clip.length_ticks; tick += mockProject.ppq) {
+    ticks.push(tick);
+  }
+  return ticks;
+}
+
+function splitMockClip(track: Track, clip: Clip, tick: number): Clip {
+  const at = tick - clip.start_tick;
+  if (at <= 0 || at >= clip.length_ticks) {
+    throw new Error(`split tick ${tick} is outside clip ${clip.id}`);
+  }
+
+  const right: Clip = structuredClone(clip);
+  right.id = crypto.randomUUID();
+  right.start_tick = tick;
+  right.length_ticks = clip.length_ticks - at;
+  right.content_offset_ticks = (clip.content_offset_ticks ?? 0) + at;
+  clip.length_ticks = at;
+  if (isNoteClip(clip) && isNoteClip(right) && !clip.loop_length_ticks) {
+    const split = right.content_offset_ticks;
+    const notes = noteListFromClip(clip);
+    const leftNotes = notes
+      .filter((note) => note.start_tick < split)
+      .map((note) => ({ ...note, length_ticks: Math.min(note.length_ticks, split - note.start_tick) }));
+    const rightNotes = notes
+      .filter((note) => note.start_tick + note.length_ticks > split)
+      .map((note) => ({
+        ...note,
+        start_tick: Math.max(0, note.start_tick - split),
+        length_ticks: note.start_tick + note.length_ticks - Math.max(split, note.start_tick)
+      }));
+    noteListFromClip(clip).splice(0, notes.length, ...leftNotes);
+    noteListFromClip(right).splice(0, noteListFromClip(right).length, ...rightNotes);
+    right.content_offset_ticks = 0;
+  }
+  track.clips.splice(track.clips.indexOf(clip) + 1, 0, right);
+  return right;
+}
+
+/** Mock ripple: cuts every clip at the boundaries, then moves or drops each piece. */
+function rippleMockProject(boundaries: number[], place: (clip: Clip) => boolean, shift: (tick: number) => number): void {
+  for (const track of mockProject.tracks) {
+    for (const boundary of boundaries) {
+      for (const clip of [...track.clips]) {
+        if (clip.start_tick < boundary && clip.start_tick + clip.length_ticks > boundary) {
+          splitMockClip(track, clip, boundary);
+        }
+      }
+    }
+    track.clips = track.clips.filter(place);
+  }
+
+  const transport = mockProject.transport;
+  const loopLength = Math.max(1, transport.loop_end_tick - transport.loop_start_tick);
+  transport.playhead_tick = shift(transport.playhead_tick);
+  transport.loop_start_tick = shift(transport.loop_start_tick);
+  transport.loop_end_tick = shift(transport.loop_end_tick);
+  if (transport.loop_end_tick <= transport.loop_start_tick) {
+    transport.loop_end_tick = transport.loop_start_tick + loopLength;
+  }
+}
+
 function isNoteClip(clip: Clip): clip is Clip & ({ payload: { midi: { notes: UpdateClipNotesInput["notes"] } } } | { payload: { pattern: { notes: UpdateClipNotesInput["notes"] } } }) {
   return "midi" in clip.payload || "pattern" in clip.payload;
 }
 
+/** Applies a note tool to the selected notes (all when empty), then re-sorts the clip. */
+function editMockNoteSelection(
+  input: NoteSelectionInput,
+  edit: (notes: UpdateClipNotesInput["notes"]) => void
+): void {
+  const { clip } = getClipRefs(mockProject, input.track_id, input.clip_id);
+  if (!isNoteClip(clip)) {
+    throw new Error(`clip payload is not midi/pattern: ${input.clip_id}`);
+  }
+  const notes = noteListFromClip(clip);
+  const indices = [...new Set(input.note_indices ?? [])].sort((left, right) => left - right);
+  const invalid = indices.find((index) => index < 0 || index >= notes.length);
+  if (invalid !== undefined) {
+    throw new Error(`invalid note index: ${invalid}`);
+  }
+  edit(indices.length > 0 ? indices.map((index) => notes[index]) : notes);
+  notes.sort((left, right) => left.start_tick - right.start_tick);
+  syncPatternRowsFromNotes(clip);
+  touchProject();
+}
+
+const MOCK_SCALE_INTERVALS: Record<Exclude<ScaleMode, { custom: number[] }>, number[]> = {
+  major: [0, 2, 4, 5, 7, 9, 11],
+  minor: [0, 2, 3, 5, 7, 8, 10],
+  harmonic_minor: [0, 2, 3, 5, 7, 8, 11],
+  melodic_minor: [0, 2, 3, 5, 7, 9, 11],
+  dorian: [0, 2, 3, 5, 7, 9, 10],
+  phrygian: [0, 1, 3, 5, 7, 8, 10],
+  lydian: [0, 2, 4, 6, 7, 9, 11],
+  mixolydian: [0, 2, 4, 5, 7, 9, 10],
+  locrian: [0, 1, 3, 5, 6, 8, 10]
+};
+
+function mockScaleIntervals(mode: ScaleMode): number[] {
+  if (typeof mode === "object") {
+    return mode.custom.length > 0 ? mode.custom : Array.from({ length: 12 }, (_, index) => index);
+  }
+  return MOCK_SCALE_INTERVALS[mode];
+}
+
+function sanitizeMockKey(key: Key): Key {
+  if (!Number.isInteger(key.root) || key.root < 0 || key.root > 11) {
+    throw new Error(`invalid key root pitch class: ${key.root}`);
+  }
+  if (typeof key.mode !== "object") {
+    return { ...key };
+  }
+  const custom = [...new Set(key.mode.custom)].sort((left, right) => left - right);
+  if (custom[0] !== 0 || custom.some((interval) => !Number.isInteger(interval) || interval > 11)) {
+    throw new Error(`invalid scale: custom intervals must start at 0 and stay below 12`);
+  }
+  return { root: key.root, mode: { custom } };
+}
+
+function mockStepBelow(key: Key, pitch: number): number {
+  const intervals = mockScaleIntervals(key.mode);
+  const relative = pitch - key.root;
+  const pitchClass = ((relative % 12) + 12) % 12;
+  const degree = Math.max(0, intervals.filter((interval) => interval <= pitchClass).length - 1);
+  return Math.floor(relative / 12) * intervals.length + degree;
+}
+
+function mockStepPitch(key: Key, step: number): number {
+  const intervals = mockScaleIntervals(key.mode);
+  const degree = ((step % intervals.length) + intervals.length) % intervals.length;
+  return key.root + Math.floor(step / intervals.length) * 12 + intervals[degree];
+}
+
+/** Nearest in-key pitch, preferring the lower one on a tie. */
+function mockSnapToKey(key: Key, pitch: number): number {
+  const below = mockStepBelow(key, pitch);
+  const lower = mockStepPitch(key, below);
+  const upper = mockStepPitch(key, below + 1);
+  if (lower < 0) {
+    return upper;
+  }
+  return upper <= 127 && upper - pitch < pitch - lower ? upper : lower;
+}
+
+function mockClipKey(trackId: string, clipId: string): Key | null {
+  const { clip } = getClipRefs(mockProject, trackId, clipId);
+  return clip.key ?? mockProject.key ?? null;
+}
+
+function requireMockClipKey(trackId: string, clipId: string): Key {
+  const key = mockClipKey(trackId, clipId);
+  if (!key) {
+    throw new Error(`clip has no key and the project has no key: ${clipId}`);
+  }
+  return key;
+}
+
+/** Moves a clip's content into the mock pool and links the clip to it. */
+function poolMockClip(trackId: string, clipId: string): Clip {
+  const { clip } = getClipRefs(mockProject, trackId, clipId);
+  if (!("linked" in clip.payload)) {
+    const contentId = crypto.randomUUID();
+    mockProject.clip_pool = [...(mockProject.clip_pool ?? []), { id: contentId, name: clip.name, payload: clip.payload }];
+    clip.payload = { linked: { content_id: contentId } };
+  }
+  return clip;
+}
+
+/** Swaps a note clip's notes for the ones `generate` derives from them. */
+function replaceMockClipNotes(
+  trackId: string,
+  clipId: string,
+  generate: (notes: UpdateClipNotesInput["notes"]) => UpdateClipNotesInput["notes"]
+): void {
+  const { clip } = getClipRefs(mockProject, trackId, clipId);
+  if (!isNoteClip(clip)) {
+    throw new Error(`clip payload is not midi/pattern: ${clipId}`);
+  }
+  const notes = generate(noteListFromClip(clip));
+  notes.sort((left, right) => left.start_tick - right.start_tick);
+  if ("midi" in clip.payload) {
+    clip.payload.midi.notes = notes;
+  } else {
+    clip.payload.pattern.notes = notes;
+  }
+  syncPatternRowsFromNotes(clip);
+  touchProject();
+}
+
+/** Deterministic value in [-1, 1) per note and property. */
+function mockRoll(seed: number, index: number, salt: number): number {
+  const value = Math.sin(seed * 12.9898 + index * 78.233 + salt * 37.719) * 43758.5453;
+  return (value - Math.floor(value)) * 2 - 1;
+}
+
+/** Rebuilds a pattern clip's rows from `generate`, given the rows that fit in the clip. */
+function replaceMockPatternRows(
+  trackId: string,
+  clipId: string,
+  generate: (rows: TrackerRow[], rowCount: number) => TrackerRow[]
+): void {
+  const { clip } = getClipRefs(mockProject, trackId, clipId);
+  if (!("pattern" in clip.payload)) {
+    throw new Error(`clip payload is not pattern: ${clipId}`);
+  }
+  const pattern = clip.payload.pattern;
+  const ticksPerRow = Math.max(1, Math.round(mockProject.ppq / pattern.lines_per_beat));
+  const rowCount = Math.max(1, Math.floor(clip.length_ticks / ticksPerRow));
+  pattern.rows = generate(pattern.rows, rowCount).sort((left, right) => left.row - right.row);
+  pattern.notes = trackerRowsToNotes(pattern.rows, pattern.lines_per_beat, mockProject.ppq);
+  touchProject();
+}
+
+function mockGatedRow(row: number, note: number, velocity: number): TrackerRow {
+  return { row, note, velocity, gate: true, effect: null, effect_value: null };
+}
+
+function findMockGroove(name: string): GrooveTemplate | undefined {
+  const wanted = name.trim().toLowerCase();
+  return mockProject.grooves?.find((groove) => groove.name.toLowerCase() === wanted);
+}
+
 function noteListFromClip(clip: Clip): UpdateClipNotesInput["notes"] {
   if ("midi" in clip.payload) {
     return clip.payload.midi.notes;
@@ -258,6 +517,26 @@ function syncPatternRowsFromNotes(clip: Clip): void {
   );
 }
 
+function mockAudioLengthTicks(audio: AudioClip): number {
+  const ticksPerSecond = (mockProject.bpm * mockProject.ppq) / 60;
+  const markers = audio.warp_markers ?? [];
+  if (markers.length < 2) {
+    const trimmedDuration = Math.max(0, audio.trim_end_seconds - audio.trim_start_seconds);
+    const effectiveDuration = trimmedDuration * Math.max(0.01, audio.stretch_ratio);
+    return Math.max(1, Math.round(effectiveDuration * ticksPerSecond));
+  }
+
+  const tickAt = (seconds: number): number => {
+    const next = markers.findIndex((marker) => marker.source_seconds > seconds);
+    const index = next < 0 ? markers.length - 2 : Math.min(markers.length - 2, Math.max(0, next - 1));
+    const from = markers[index];
+    const to = markers[index + 1];
+    const span = to.source_seconds - from.source_seconds;
+    return from.tick + ((seconds - from.source_seconds) / span) * (to.tick - from.tick);
+  };
+  return Math.max(1, Math.round(tickAt(audio.trim_end_seconds) - tickAt(audio.trim_start_seconds)));
+}
+
 function mockWaveformPeaks(bucketSize: number, count = 128): number[] {
   const density = Math.max(1, bucketSize);
   return Array.from({ length: count }, (_, index) => {
@@ -281,7 +560,16 @@ function mockAudioAnalysisFromPath(path: string, bucketSize: number): AudioAnaly
       bucket_size: bucketSize,
       peaks: mockWaveformPeaks(bucketSize)
     },
-    cache_path: `localStorage://waveform-cache/${encodeURIComponent(path)}.json`
+    cache_path: `localStorage://waveform-cache/${encodeURIComponent(path)}.json`,
+    features: {
+      bpm: 120,
+      key: { key: { root: 9, mode: "minor" }, confidence: 0.72 },
+      integrated_lufs: -14.2,
+      rms_dbfs: -16.8,
+      peak_dbfs: -1.3,
+      trim_start_seconds: 0.02,
+      trim_end_seconds: durationSeconds - 0.15
+    }
   };
 }
 
@@ -523,13 +811,85 @@ async function invokeMock<T>(command: string, args?: Record<string, unknown>): P
       if (typeof input.stretch_ratio === "number") {
         audio.stretch_ratio = Math.max(0.01, input.stretch_ratio);
       }
+      if (input.stretch_mode) audio.stretch_mode = input.stretch_mode;
+      if (typeof input.pitch_semitones === "number") {
+        audio.pitch_semitones = Math.max(-24, Math.min(24, input.pitch_semitones));
+      }
+      if (typeof input.pitch_cents === "number") {
+        audio.pitch_cents = Math.max(-100, Math.min(100, input.pitch_cents));
+      }
 
-      const trimmedDuration = Math.max(0, audio.trim_end_seconds - audio.trim_start_seconds);
-      const effectiveDuration = trimmedDuration * Math.max(0.01, audio.stretch_ratio);
-      clip.length_ticks = Math.max(
-        1,
-        Math.round((effectiveDuration * mockProject.bpm * mockProject.ppq) / 60)
-      );
+      clip.length_ticks = mockAudioLengthTicks(audio);
+      touchProject();
+      return mockProject as T;
+    }
+
+    case "set_audio_warp_markers": {
+      const input = args?.input as SetAudioWarpMarkersInput;
+      const { clip } = getClipRefs(mockProject, input.track_id, input.clip_id);
+      if (!("audio" in clip.payload)) {
+        throw new Error(`clip is not audio: ${input.clip_id}`);
+      }
+      if (input.markers.length === 1) {
+        throw new Error("invalid warp markers: a single marker cannot warp a clip");
+      }
+      const markers = [...input.markers].sort((left, right) => left.tick - right.tick);
+      markers.forEach((marker, index) => {
+        const previous = markers[index - 1];
+        if (previous && (marker.tick <= previous.tick || marker.source_seconds <= previous.source_seconds)) {
+          throw new Error("invalid warp markers: markers must increase in both tick and source time");
+        }
+      });
+
+      const audio = clip.payload.audio;
+      audio.warp_markers = markers;
+      delete audio.source_bpm;
+      clip.length_ticks = mockAudioLengthTicks(audio);
+      touchProject();
+      return mockProject as T;
+    }
+
+    case "auto_warp_audio_clip": {
+      const input = args?.input as AutoWarpAudioClipInput;
+      const { clip } = getClipRefs(mockProject, input.track_id, input.clip_id);
+      if (!("audio" in clip.payload)) {
+        throw new Error(`clip is not audio: ${input.clip_id}`);
+      }
+      if (!Number.isFinite(input.source_bpm) || input.source_bpm < 20 || input.source_bpm > 999) {
+        throw new Error(`invalid source bpm: ${input.source_bpm}`);
+      }
+
+      const audio = clip.payload.audio;
+      const beatSeconds = 60 / input.source_bpm;
+      const beats = Math.max(1, Math.ceil(audio.source_duration_seconds / beatSeconds));
+      audio.warp_markers = Array.from({ length: beats + 1 }, (_, beat) => ({
+        source_seconds: beat * beatSeconds,
+        tick: beat * mockProject.ppq
+      }));
+      audio.source_bpm = input.source_bpm;
+      clip.length_ticks = mockAudioLengthTicks(audio);
+      touchProject();
+      return mockProject as T;
+    }
+
+    case "update_clip_playback": {
+      const input = args?.input as UpdateClipPlaybackInput;
+      const { clip } = getClipRefs(mockProject, input.track_id, input.clip_id);
+      if (typeof input.content_offset_ticks === "number") {
+        clip.content_offset_ticks = Math.max(0, Math.round(input.content_offset_ticks));
+      }
+      if (typeof input.loop_length_ticks === "number") {
+        clip.loop_length_ticks = input.loop_length_ticks > 0 ? Math.round(input.loop_length_ticks) : null;
+      }
+      if (typeof input.transpose_semitones === "number") {
+        clip.transpose_semitones = Math.max(-48, Math.min(48, Math.round(input.transpose_semitones)));
+      }
+      if (typeof input.velocity_scale === "number") {
+        if (!Number.isFinite(input.velocity_scale) || input.velocity_scale <= 0) {
+          throw new Error(`invalid clip velocity scale: ${input.velocity_scale}`);
+        }
+        clip.velocity_scale = Math.min(4, input.velocity_scale);
+      }
       touchProject();
       return mockProject as T;
     }
@@ -544,6 +904,277 @@ async function invokeMock<T>(command: string, args?: Record<string, unknown>): P
       return mockProject as T;
     }
 
+    case "split_clip": {
+      const input = args?.input as SplitClipInput;
+      const { track, clip } = getClipRefs(mockProject, input.track_id, input.clip_id);
+      splitMockClip(track, clip, input.tick);
+      touchProject();
+      return mockProject as T;
+    }
+
+    case "slice_clip": {
+      const input = args?.input as SliceClipInput;
+      const { track, clip } = getClipRefs(mockProject, input.track_id, input.clip_id);
+      const end = clip.start_tick + clip.length_ticks;
+      const ticks: number[] = [];
+      if (input.grid_ticks && input.grid_ticks > 0) {
+        for (let tick = (Math.floor(clip.start_tick / input.grid_ticks) + 1) * input.grid_ticks; tick < end; tick += input.grid_ticks) {
+          ticks.push(tick);
+        }
+      } else if (input.parts && input.parts >= 2) {
+        const partTicks = Math.floor(clip.length_ticks / input.parts);
+        for (let part = 1; part < input.parts; part += 1) {
+          ticks.push(clip.start_tick + part * partTicks);
+        }
+      } else {
+        throw new Error("slice requires parts or grid_ticks");
+      }
+
+      let rest = clip;
+      for (const tick of ticks) {
+        rest = splitMockClip(track, rest, tick);
+      }
+      touchProject();
+      return mockProject as T;
+    }
+
+    case "slice_audio_clip_at_transients": {
+      const input = args?.input as SliceAudioClipAtTransientsInput;
+      const { track, clip } = getClipRefs(mockProject, input.track_id, input.clip_id);
+      if (!("audio" in clip.payload)) {
+        throw new Error(`clip is not an audio clip: ${input.clip_id}`);
+      }
+      const ticks = mockTransientTicks(clip).slice(1);
+      if (ticks.length === 0) {
+        throw new Error(`no transients found in audio clip: ${input.clip_id}`);
+      }
+
+      const start = clip.start_tick;
+      let rest = clip;
+      for (const tick of ticks) {
+        rest = splitMockClip(track, rest, start + tick);
+      }
+      touchProject();
+      return mockProject as T;
+    }
+
+    case "transcribe_audio_clip": {
+      const input = args?.input as TranscribeAudioClipInput;
+      const { clip } = getClipRefs(mockProject, input.track_id, input.clip_id);
+      if (!("audio" in clip.payload)) {
+        throw new Error(`clip is not an audio clip: ${input.clip_id}`);
+      }
+      const target = mockProject.tracks.find((candidate) => candidate.id === input.target_track_id);
+      if (!target) {
+        throw new Error(`track not found: ${input.target_track_id}`);
+      }
+
+      // Stand-in for pitch tracking: the mock hears a rising C major line, a note per beat.
+      const steps = [0, 2, 4, 5, 7, 9, 11, 12];
+      const grid = input.quantize_grid_ticks && input.quantize_grid_ticks > 0 ? input.quantize_grid_ticks : 1;
+      const notes: MidiNote[] = mockTransientTicks(clip).map((tick, index) => ({
+        pitch: 60 + steps[index % steps.length],
+        velocity: 100,
+        start_tick: Math.round(tick / grid) * grid,
+        length_ticks: Math.max(1, Math.min(mockProject.ppq, clip.length_ticks - tick)),
+        channel: 0
+      }));
+      const sourceChip = input.source_chip?.trim();
+      target.clips.push({
+        id: crypto.randomUUID(),
+        name: clip.name,
+        start_tick: clip.start_tick,
+        length_ticks: clip.length_ticks,
+        disabled: false,
+        payload: sourceChip
+          ? {
+              pattern: {
+                source_chip: sourceChip,
+                notes,
+                rows: notesToTrackerRows(notes),
+                macros: [],
+                lines_per_beat: 4
+              }
+            }
+          : { midi: { instrument: null, notes } }
+      });
+      touchProject();
+      return mockProject as T;
+    }
+
+    case "slice_audio_clip_to_sampler": {
+      const input = args?.input as SliceAudioClipToSamplerInput;
+      const { clip } = getClipRefs(mockProject, input.track_id, input.clip_id);
+      if (!("audio" in clip.payload)) {
+        throw new Error(`clip is not an audio clip: ${input.clip_id}`);
+      }
+      const target = mockProject.tracks.find((candidate) => candidate.id === input.target_track_id);
+      if (!target) {
+        throw new Error(`track not found: ${input.target_track_id}`);
+      }
+      const bounds = mockTransientTicks(clip);
+      if (bounds.length < 2) {
+        throw new Error(`no transients found in audio clip: ${input.clip_id}`);
+      }
+      const firstNote = Math.max(0, Math.min(127, Math.round(input.first_note)));
+      if (bounds.length > 128 - firstNote) {
+        throw new Error(`invalid slice count: ${bounds.length}`);
+      }
+
+      bounds.push(clip.length_ticks);
+      const notes: MidiNote[] = bounds.slice(0, -1).map((tick, index) => ({
+        pitch: firstNote + index,
+        velocity: 127,
+        start_tick: tick,
+        length_ticks: Math.max(1, bounds[index + 1] - tick),
+        channel: 0
+      }));
+      target.clips.push({
+        id: crypto.randomUUID(),
+        name: clip.name,
+        start_tick: clip.start_tick,
+        length_ticks: clip.length_ticks,
+        disabled: false,
+        payload: { midi: { instrument: clip.name, notes } }
+      });
+      touchProject();
+      return mockProject as T;
+    }
+
+    case "glue_clips": {
+      const input = args?.input as GlueClipsInput;
+      const clips = input.clip_ids
+        .map((clipId) => getClipRefs(mockProject, input.track_id, clipId).clip)
+        .sort((left, right) => left.start_tick - right.start_tick);
+      const [first] = clips;
+      if (clips.length < 2 || !clips.every(isNoteClip)) {
+        throw new Error("mock glue supports two or more midi/pattern clips");
+      }
+      for (let index = 1; index < clips.length; index += 1) {
+        const previous = clips[index - 1];
+        if (previous.start_tick + previous.length_ticks !== clips[index].start_tick) {
+          throw new Error(`clip ${previous.id} does not end where clip ${clips[index].id} starts`);
+        }
+      }
+
+      const glued = clips.flatMap((clip) =>
+        noteListFromClip(clip)
+          .filter((note) => note.start_tick < clip.length_ticks)
+          .map((note) => ({ ...note, start_tick: note.start_tick + clip.start_tick - first.start_tick }))
+      );
+      const last = clips[clips.length - 1];
+      first.length_ticks = last.start_tick + last.length_ticks - first.start_tick;
+      noteListFromClip(first).splice(0, noteListFromClip(first).length, ...glued);
+      const { track } = getClipRefs(mockProject, input.track_id, first.id);
+      track.clips = track.clips.filter((clip) => clip === first || !input.clip_ids.includes(clip.id));
+      touchProject();
+      return mockProject as T;
+    }
+
+    case "pool_clip": {
+      const input = args?.input as ClipRefInput;
+      poolMockClip(input.track_id, input.clip_id);
+      touchProject();
+      return mockProject as T;
+    }
+
+    case "duplicate_clip_linked": {
+      const input = args?.input as DuplicateClipLinkedInput;
+      const source = poolMockClip(input.track_id, input.clip_id);
+      const { track } = getClipRefs(mockProject, input.track_id, input.clip_id);
+      track.clips.push({ ...structuredClone(source), id: crypto.randomUUID(), start_tick: input.start_tick });
+      touchProject();
+      return mockProject as T;
+    }
+
+    case "place_pool_content": {
+      const input = args?.input as PlacePoolContentInput;
+      const entry = (mockProject.clip_pool ?? []).find((candidate) => candidate.id === input.content_id);
+      if (!entry) {
+        throw new Error(`clip pool content not found: ${input.content_id}`);
+      }
+      const track = mockProject.tracks.find((candidate) => candidate.id === input.track_id);
+      if (!track) {
+        throw new Error(`track not found: ${input.track_id}`);
+      }
+      track.clips.push({
+        id: crypto.randomUUID(),
+        name: entry.name,
+        start_tick: input.start_tick,
+        length_ticks: Math.max(1, input.length_ticks),
+        disabled: false,
+        payload: { linked: { content_id: entry.id } }
+      });
+      touchProject();
+      return mockProject as T;
+    }
+
+    case "make_clip_unique": {
+      const input = args?.input as ClipRefInput;
+      const { clip } = getClipRefs(mockProject, input.track_id, input.clip_id);
+      if ("linked" in clip.payload) {
+        const contentId = clip.payload.linked.content_id;
+        const entry = (mockProject.clip_pool ?? []).find((candidate) => candidate.id === contentId);
+        if (!entry) {
+          throw new Error(`clip pool content not found: ${contentId}`);
+        }
+        clip.payload = structuredClone(entry.payload);
+        touchProject();
+      }
+      return mockProject as T;
+    }
+
+    case "prune_clip_pool": {
+      const linked = new Set(
+        mockProject.tracks.flatMap((track) =>
+          track.clips.flatMap((clip) => ("linked" in clip.payload ? [clip.payload.linked.content_id] : []))
+        )
+      );
+      mockProject.clip_pool = (mockProject.clip_pool ?? []).filter((entry) => linked.has(entry.id));
+      touchProject();
+      return mockProject as T;
+    }
+
+    case "insert_time": {
+      const input = args?.input as InsertTimeInput;
+      if (input.length_ticks <= 0) {
+        throw new Error(`invalid time range: start=${input.at_tick} end=${input.at_tick}`);
+      }
+      const shift = (tick: number) => (tick >= input.at_tick ? tick + input.length_ticks : tick);
+      rippleMockProject(
+        [input.at_tick],
+        (clip) => {
+          clip.start_tick = shift(clip.start_tick);
+          return true;
+        },
+        shift
+      );
+      touchProject();
+      return mockProject as T;
+    }
+
+    case "delete_time": {
+      const input = args?.input as DeleteTimeInput;
+      if (input.end_tick <= input.start_tick) {
+        throw new Error(`invalid time range: start=${input.start_tick} end=${input.end_tick}`);
+      }
+      const removed = input.end_tick - input.start_tick;
+      const shift = (tick: number) => (tick >= input.end_tick ? tick - removed : Math.min(tick, input.start_tick));
+      rippleMockProject(
+        [input.start_tick, input.end_tick],
+        (clip) => {
+          if (clip.start_tick >= input.start_tick && clip.start_tick < input.end_tick) {
+            return false;
+          }
+          clip.start_tick = shift(clip.start_tick);
+          return true;
+        },
+        shift
+      );
+      touchProject();
+      return mockProject as T;
+    }
+
     case "update_clip_notes": {
       const input = args?.input as UpdateClipNotesInput;
       const { clip } = getClipRefs(mockProject, input.track_id, input.clip_id);
@@ -551,7 +1182,10 @@ async function invokeMock<T>(command: string, args?: Record<string, unknown>): P
         throw new Error(`clip payload is not midi/pattern: ${input.clip_id}`);
       }
 
-      const notes = input.notes.map(clampNote);
+      const key = input.snap_to_scale ? requireMockClipKey(input.track_id, input.clip_id) : null;
+      const notes = input.notes
+        .map(clampNote)
+        .map((note) => (key ? { ...note, pitch: mockSnapToKey(key, note.pitch) } : note));
       if ("midi" in clip.payload) {
         clip.payload.midi.notes = notes;
       } else {
@@ -580,6 +1214,26 @@ async function invokeMock<T>(command: string, args?: Record<string, unknown>): P
       return mockProject as T;
     }
 
+    case "update_controller_lane": {
+      const input = args?.input as UpdateControllerLaneInput;
+      const { clip } = getClipRefs(mockProject, input.track_id, input.clip_id);
+      if (!("midi" in clip.payload)) {
+        throw new Error(`clip payload is not midi: ${input.clip_id}`);
+      }
+      const [min, max] = input.target === "pitch_bend" ? [-1, 1] : [0, 1];
+      const points = [...input.points]
+        .filter((point) => Number.isFinite(point.value))
+        .map((point) => ({ ...point, value: Math.max(min, Math.min(max, point.value)) }))
+        .sort((left, right) => left.tick - right.tick);
+      const lanes = (clip.payload.midi.controllers ?? []).filter((lane) => lane.target !== input.target);
+      if (points.length > 0) {
+        lanes.push({ target: input.target, points });
+      }
+      clip.payload.midi.controllers = lanes;
+      touchProject();
+      return mockProject as T;
+    }
+
     case "update_pattern_rows": {
       const input = args?.input as UpdatePatternRowsInput;
       const { clip } = getClipRefs(mockProject, input.track_id, input.clip_id);
@@ -606,6 +1260,79 @@ async function invokeMock<T>(command: string, args?: Record<string, unknown>): P
       return mockProject as T;
     }
 
+    case "generate_euclidean_rows": {
+      const input = args?.input as GenerateEuclideanRowsInput;
+      if (input.steps < 1 || input.steps > 64 || input.hits < 0 || input.hits > input.steps) {
+        throw new Error(`invalid euclidean rhythm: hits=${input.hits} steps=${input.steps}`);
+      }
+      const rotation = (input.rotation ?? 0) % input.steps;
+      replaceMockPatternRows(input.track_id, input.clip_id, (_, rowCount) =>
+        Array.from({ length: rowCount }, (_, row) => row)
+          .filter((row) => {
+            const source = ((row % input.steps) - rotation + input.steps) % input.steps;
+            return (source * input.hits) % input.steps < input.hits;
+          })
+          .map((row) => mockGatedRow(row, input.pitch, input.velocity ?? 100))
+      );
+      return mockProject as T;
+    }
+
+    case "generate_probability_rows": {
+      const input = args?.input as GenerateProbabilityRowsInput;
+      if (input.chances.length === 0 || input.pitches.length === 0) {
+        throw new Error("probability fill needs at least one chance and one pitch");
+      }
+      const invalid = input.chances.find((chance) => !Number.isFinite(chance) || chance < 0 || chance > 100);
+      if (invalid !== undefined) {
+        throw new Error(`invalid fill chance percent: ${invalid}`);
+      }
+      const seed = input.seed ?? 0;
+      replaceMockPatternRows(input.track_id, input.clip_id, (_, rowCount) =>
+        Array.from({ length: rowCount }, (_, row) => row)
+          .filter((row) => ((mockRoll(seed, row, 0) + 1) / 2) * 100 < input.chances[row % input.chances.length])
+          .map((row) => {
+            const pick = Math.floor(((mockRoll(seed, row, 1) + 1) / 2) * input.pitches.length);
+            return mockGatedRow(row, input.pitches[Math.min(pick, input.pitches.length - 1)], input.velocity ?? 100);
+          })
+      );
+      return mockProject as T;
+    }
+
+    case "continue_pattern_markov": {
+      const input = args?.input as ContinuePatternMarkovInput;
+      const seed = input.seed ?? 0;
+      replaceMockPatternRows(input.track_id, input.clip_id, (rows, rowCount) => {
+        const played = rows.filter((row) => row.gate && row.note !== null).sort((left, right) => left.row - right.row);
+        const transitions = new Map<number, { note: number; gap: number; velocity: number }[]>();
+        const all: { note: number; gap: number; velocity: number }[] = [];
+        played.slice(1).forEach((next, index) => {
+          const from = played[index].note as number;
+          const step = { note: next.note as number, gap: Math.max(1, next.row - played[index].row), velocity: next.velocity };
+          transitions.set(from, [...(transitions.get(from) ?? []), step]);
+          all.push(step);
+        });
+        if (all.length === 0) {
+          throw new Error(`pattern needs at least two notes to continue: ${input.clip_id}`);
+        }
+        const continued = [...rows];
+        let pitch = played[played.length - 1].note as number;
+        let row = played[played.length - 1].row;
+        for (let index = 0; ; index += 1) {
+          const choices = transitions.get(pitch) ?? all;
+          const pick = Math.floor(((mockRoll(seed, index, 0) + 1) / 2) * choices.length);
+          const step = choices[Math.min(pick, choices.length - 1)];
+          row += step.gap;
+          if (row >= rowCount) {
+            break;
+          }
+          continued.push(mockGatedRow(row, step.note, step.velocity));
+          pitch = step.note;
+        }
+        return continued;
+      });
+      return mockProject as T;
+    }
+
     case "update_pattern_macros": {
       const input = args?.input as UpdatePatternMacrosInput;
       const { clip } = getClipRefs(mockProject, input.track_id, input.clip_id);
@@ -633,6 +1360,9 @@ async function invokeMock<T>(command: string, args?: Record<string, unknown>): P
         throw new Error(`clip payload is not midi/pattern: ${input.clip_id}`);
       }
       const note = clampNote(input.note);
+      if (input.snap_to_scale) {
+        note.pitch = mockSnapToKey(requireMockClipKey(input.track_id, input.clip_id), note.pitch);
+      }
       noteListFromClip(clip).push(note);
       noteListFromClip(clip).sort((left, right) => left.start_tick - right.start_tick);
       syncPatternRowsFromNotes(clip);
@@ -663,8 +1393,16 @@ async function invokeMock<T>(command: string, args?: Record<string, unknown>): P
         throw new Error(`clip payload is not midi/pattern: ${input.clip_id}`);
       }
       const notes = noteListFromClip(clip);
+      const key = input.diatonic ? requireMockClipKey(input.track_id, input.clip_id) : null;
       for (const note of notes) {
-        note.pitch = Math.max(0, Math.min(127, Math.round(note.pitch + input.semitones)));
+        if (key) {
+          const below = mockStepBelow(key, note.pitch);
+          const chromatic = note.pitch - mockStepPitch(key, below);
+          note.pitch = mockStepPitch(key, below + Math.round(input.semitones)) + chromatic;
+        } else {
+          note.pitch = note.pitch + Math.round(input.semitones);
+        }
+        note.pitch = Math.max(0, Math.min(127, note.pitch));
       }
       syncPatternRowsFromNotes(clip);
       touchProject();
@@ -680,11 +1418,47 @@ async function invokeMock<T>(command: string, args?: Record<string, unknown>): P
       if (input.grid_ticks <= 0) {
         throw new Error(`invalid grid_ticks: ${input.grid_ticks}`);
       }
-      const grid = Math.round(input.grid_ticks);
+      const strength = (input.strength_percent ?? 100) / 100;
+      const swing = (input.swing_percent ?? 0) / 100;
+      if (strength < 0 || strength > 1 || swing < 0 || swing > 1) {
+        throw new Error(`invalid quantize amount: ${input.strength_percent ?? input.swing_percent}`);
+      }
+      const groove = input.groove ? findMockGroove(input.groove) : undefined;
+      if (input.groove && !groove) {
+        throw new Error(`groove template not found: ${input.groove}`);
+      }
+      const baseGrid = Math.round(input.grid_ticks);
+      const grid = input.triplet ? Math.max(1, Math.floor((baseGrid * 2) / 3)) : baseGrid;
+      const target = (tick: number) => {
+        const line = Math.round(tick / grid);
+        let snapped = line * grid;
+        if (line % 2 === 1) {
+          snapped += Math.round((grid * swing) / 2);
+        }
+        const slot = groove && groove.slots.length > 0
+          ? groove.slots[Math.round((line * grid) / groove.grid_ticks) % groove.slots.length]
+          : undefined;
+        return { tick: Math.max(0, snapped + (slot?.offset_ticks ?? 0)), slot };
+      };
+      const toward = (from: number, to: number) => Math.max(0, Math.round(from + (to - from) * strength));
       const notes = noteListFromClip(clip);
       for (const note of notes) {
-        note.start_tick = Math.max(0, Math.round(note.start_tick / grid) * grid);
-        note.length_ticks = Math.max(grid, Math.round(note.length_ticks / grid) * grid);
+        const start = target(note.start_tick);
+        const startTick = toward(note.start_tick, start.tick);
+        const ends = input.ends ?? "length";
+        if (ends === "length") {
+          const rounded = Math.max(grid, Math.round(Math.max(1, note.length_ticks) / grid) * grid);
+          note.length_ticks = Math.max(1, toward(note.length_ticks, rounded));
+        } else if (ends === "end") {
+          const end = note.start_tick + Math.max(1, note.length_ticks);
+          const endTarget = Math.max(target(end).tick, start.tick + grid);
+          note.length_ticks = Math.max(1, toward(end, endTarget) - startTick);
+        }
+        note.start_tick = startTick;
+        if (start.slot) {
+          const scale = 1 + (start.slot.velocity_scale - 1) * strength;
+          note.velocity = Math.min(127, Math.max(1, Math.round(note.velocity * scale)));
+        }
       }
       notes.sort((left, right) => left.start_tick - right.start_tick);
       syncPatternRowsFromNotes(clip);
@@ -692,6 +1466,232 @@ async function invokeMock<T>(command: string, args?: Record<string, unknown>): P
       return mockProject as T;
     }
 
+    case "humanize_clip_notes": {
+      const input = args?.input as HumanizeClipNotesInput;
+      if (!Number.isFinite(input.length_percent) || input.length_percent < 0 || input.length_percent > 100) {
+        throw new Error(`invalid humanize length percent: ${input.length_percent}`);
+      }
+      editMockNoteSelection(input, (notes) => {
+        notes.forEach((note, index) => {
+          note.start_tick = Math.max(0, Math.round(note.start_tick + mockRoll(input.seed, index, 0) * input.timing_ticks));
+          note.velocity = Math.min(127, Math.max(1, Math.round(note.velocity + mockRoll(input.seed, index, 1) * input.velocity)));
+          note.length_ticks = Math.max(
+            1,
+            Math.round(note.length_ticks * (1 + (mockRoll(input.seed, index, 2) * input.length_percent) / 100))
+          );
+        });
+      });
+      return mockProject as T;
+    }
+
+    case "strum_clip_notes": {
+      const input = args?.input as StrumClipNotesInput;
+      editMockNoteSelection(input, (notes) => {
+        const chords = new Map<number, typeof notes>();
+        for (const note of notes) {
+          chords.set(note.start_tick, [...(chords.get(note.start_tick) ?? []), note]);
+        }
+        for (const chord of chords.values()) {
+          if (chord.length < 2) {
+            continue;
+          }
+          chord.sort((left, right) => left.pitch - right.pitch);
+          const last = chord.length - 1;
+          chord.forEach((note, position) => {
+            const step = input.direction === "down" ? last - position : position;
+            const offset = Math.min(Math.floor((input.spread_ticks * step) / last), Math.max(0, note.length_ticks - 1));
+            note.start_tick += offset;
+            note.length_ticks -= offset;
+          });
+        }
+      });
+      return mockProject as T;
+    }
+
+    case "shape_clip_note_velocities": {
+      const input = args?.input as ShapeClipNoteVelocitiesInput;
+      const curve = input.curve ?? 1;
+      if (!Number.isFinite(curve) || curve <= 0) {
+        throw new Error(`invalid velocity curve: ${curve}`);
+      }
+      editMockNoteSelection(input, (notes) => {
+        const ordered = [...notes].sort((left, right) => left.start_tick - right.start_tick || left.pitch - right.pitch);
+        const first = ordered[0]?.start_tick ?? 0;
+        const span = (ordered[ordered.length - 1]?.start_tick ?? 0) - first;
+        ordered.forEach((note, position) => {
+          const progress = span > 0
+            ? (note.start_tick - first) / span
+            : ordered.length > 1 ? position / (ordered.length - 1) : 0;
+          const velocity = input.from + (input.to - input.from) * progress ** curve;
+          note.velocity = Math.min(127, Math.max(1, Math.round(velocity)));
+        });
+      });
+      return mockProject as T;
+    }
+
+    case "legato_clip_notes": {
+      const input = args?.input as LegatoClipNotesInput;
+      editMockNoteSelection(input, (notes) => {
+        const starts = [...new Set(notes.map((note) => note.start_tick))].sort((left, right) => left - right);
+        for (const note of notes) {
+          const next = starts.find((start) => start > note.start_tick);
+          if (next !== undefined) {
+            note.length_ticks = next - note.start_tick;
+          }
+        }
+      });
+      return mockProject as T;
+    }
+
+    case "set_clip_note_lengths": {
+      const input = args?.input as SetClipNoteLengthsInput;
+      if (input.length_ticks <= 0) {
+        throw new Error(`invalid note length ticks: ${input.length_ticks}`);
+      }
+      editMockNoteSelection(input, (notes) => {
+        for (const note of notes) {
+          note.length_ticks = Math.round(input.length_ticks);
+        }
+      });
+      return mockProject as T;
+    }
+
+    case "arpeggiate_clip": {
+      const input = args?.input as ArpeggiateClipInput;
+      const octaves = input.octaves ?? 1;
+      const gate = input.gate_percent ?? 100;
+      if (input.rate_ticks <= 0) {
+        throw new Error(`invalid arpeggio rate ticks: ${input.rate_ticks}`);
+      }
+      if (octaves < 1 || octaves > 4) {
+        throw new Error(`invalid arpeggio octave count: ${octaves}`);
+      }
+      if (!Number.isFinite(gate) || gate <= 0 || gate > 200) {
+        throw new Error(`invalid arpeggio gate percent: ${gate}`);
+      }
+      replaceMockClipNotes(input.track_id, input.clip_id, (notes) => {
+        if (notes.length === 0) {
+          return [];
+        }
+        const first = Math.min(...notes.map((note) => note.start_tick));
+        const last = Math.max(...notes.map((note) => note.start_tick + note.length_ticks));
+        const length = Math.max(1, Math.round((input.rate_ticks * gate) / 100));
+        const arpeggio: typeof notes = [];
+        let previous = "";
+        let counter = 0;
+        for (let tick = first, step = 0; tick < last; tick += input.rate_ticks, step += 1) {
+          const held = notes.filter((note) => note.start_tick <= tick && tick < note.start_tick + note.length_ticks);
+          const key = held.map((note) => notes.indexOf(note)).join(",");
+          if (key !== previous) {
+            counter = 0;
+            previous = key;
+          }
+          const played = [...held].sort((left, right) =>
+            input.mode === "as_played"
+              ? left.start_tick - right.start_tick || left.pitch - right.pitch
+              : left.pitch - right.pitch
+          ).filter((note, index, list) => list.findIndex((other) => other.pitch === note.pitch) === index);
+          const pool = Array.from({ length: octaves }, (_, octave) =>
+            played.map((note) => ({ pitch: note.pitch + octave * 12, note }))
+          ).flat().filter((entry) => entry.pitch <= 127);
+          if (pool.length === 0) {
+            continue;
+          }
+          const size = pool.length;
+          let index = counter % size;
+          if (input.mode === "down") {
+            index = size - 1 - (counter % size);
+          } else if (input.mode === "up_down") {
+            const position = size === 1 ? 0 : counter % (2 * size - 2);
+            index = position < size ? position : 2 * size - 2 - position;
+          } else if (input.mode === "random") {
+            index = Math.min(size - 1, Math.floor(((mockRoll(input.seed ?? 0, step, 3) + 1) / 2) * size));
+          }
+          counter += 1;
+          arpeggio.push({ ...pool[index].note, pitch: pool[index].pitch, start_tick: tick, length_ticks: length });
+        }
+        return arpeggio;
+      });
+      return mockProject as T;
+    }
+
+    case "generate_clip_chords": {
+      const input = args?.input as GenerateClipChordsInput;
+      if (input.key_root < 0 || input.key_root > 11) {
+        throw new Error(`invalid key root pitch class: ${input.key_root}`);
+      }
+      const intervals = mockScaleIntervals(input.scale);
+      const pitchAtStep = (step: number) =>
+        input.key_root + Math.floor(step / intervals.length) * 12 + intervals[((step % intervals.length) + intervals.length) % intervals.length];
+      const stacked = input.kind === "seventh" ? [0, 2, 4, 6] : [0, 2, 4];
+      replaceMockClipNotes(input.track_id, input.clip_id, (notes) => {
+        const chords = notes.flatMap((note) => {
+          const relative = note.pitch - input.key_root;
+          const octave = Math.floor(relative / 12);
+          const pitchClass = ((relative % 12) + 12) % 12;
+          const degree = Math.max(0, intervals.filter((interval) => interval <= pitchClass).length - 1);
+          const rootStep = octave * intervals.length + degree;
+          return stacked
+            .map((step) => pitchAtStep(rootStep + step))
+            .filter((pitch) => pitch >= 0 && pitch <= 127)
+            .map((pitch) => ({ ...note, pitch }));
+        });
+        chords.sort((left, right) => left.start_tick - right.start_tick || left.pitch - right.pitch);
+        return chords.filter(
+          (note, index) =>
+            index === 0 || note.start_tick !== chords[index - 1].start_tick || note.pitch !== chords[index - 1].pitch
+        );
+      });
+      return mockProject as T;
+    }
+
+    case "extract_groove": {
+      const input = args?.input as ExtractGrooveInput;
+      const { clip } = getClipRefs(mockProject, input.track_id, input.clip_id);
+      if (!isNoteClip(clip)) {
+        throw new Error(`audio grooves need the desktop runtime: ${input.clip_id}`);
+      }
+      if (input.grid_ticks <= 0) {
+        throw new Error(`invalid grid_ticks: ${input.grid_ticks}`);
+      }
+      if (input.steps <= 0 || input.steps > 64) {
+        throw new Error(`invalid groove step count: ${input.steps}`);
+      }
+      const onsets = noteListFromClip(clip).filter((note) => note.start_tick < clip.length_ticks);
+      if (onsets.length === 0) {
+        throw new Error(`clip has no onsets to take a groove from: ${input.clip_id}`);
+      }
+      const sums = Array.from({ length: input.steps }, () => ({ deviation: 0, level: 0, count: 0 }));
+      for (const note of onsets) {
+        const line = Math.round(note.start_tick / input.grid_ticks);
+        const sum = sums[line % input.steps];
+        sum.deviation += note.start_tick - line * input.grid_ticks;
+        sum.level += note.velocity;
+        sum.count += 1;
+      }
+      const averageLevel = onsets.reduce((total, note) => total + note.velocity, 0) / onsets.length;
+      const groove: GrooveTemplate = {
+        name: input.name.trim() || clip.name,
+        grid_ticks: input.grid_ticks,
+        slots: sums.map((sum) =>
+          sum.count === 0
+            ? { offset_ticks: 0, velocity_scale: 1 }
+            : {
+                offset_ticks: Math.round(sum.deviation / sum.count),
+                velocity_scale: averageLevel > 0
+                  ? Math.min(4, Math.max(0.25, sum.level / sum.count / averageLevel))
+                  : 1
+              }
+        )
+      };
+      const existing = findMockGroove(groove.name);
+      mockProject.grooves = existing
+        ? (mockProject.grooves ?? []).map((candidate) => (candidate === existing ? groove : candidate))
+        : [...(mockProject.grooves ?? []), groove];
+      touchProject();
+      return mockProject as T;
+    }
+
     case "add_effect": {
       const input = args?.input as AddEffectInput;
       const track = mockProject.tracks.find((candidate) => candidate.id === input.track_id);
@@ -806,6 +1806,63 @@ async function invokeMock<T>(command: string, args?: Record<string, unknown>): P
       return input.output_path as T;
     }
 
+    case "set_project_tuning": {
+      const tuning = args?.tuning as Tuning;
+      if (!Number.isFinite(tuning.reference_hz) || tuning.reference_hz < 1 || tuning.reference_hz > 20000) {
+        throw new Error(`invalid tuning: reference pitch out of range: ${tuning.reference_hz}`);
+      }
+      mockProject.tuning = { ...tuning };
+      touchProject();
+      return mockProject as T;
+    }
+
+    case "set_project_key": {
+      const key = args?.key as Key | null;
+      if (key) {
+        mockProject.key = sanitizeMockKey(key);
+      } else {
+        delete mockProject.key;
+      }
+      touchProject();
+      return mockProject as T;
+    }
+
+    case "set_clip_key": {
+      const input = args?.input as SetClipKeyInput;
+      const { clip } = getClipRefs(mockProject, input.track_id, input.clip_id);
+      if (input.key) {
+        clip.key = sanitizeMockKey(input.key);
+      } else {
+        delete clip.key;
+      }
+      touchProject();
+      return mockProject as T;
+    }
+
+    case "clip_scale_pitch_classes": {
+      const input = args?.input as ClipRefInput;
+      const key = mockClipKey(input.track_id, input.clip_id);
+      if (!key) {
+        return null as T;
+      }
+      const classes = new Array<boolean>(12).fill(false);
+      for (const interval of mockScaleIntervals(key.mode)) {
+        classes[(interval + key.root) % 12] = true;
+      }
+      return classes as T;
+    }
+
+    case "detect_clip_key":
+    case "detect_project_key": {
+      // Key profiles are only evaluated in the desktop runtime.
+      return null as T;
+    }
+
+    case "import_scala_tuning": {
+      const input = args?.input as ImportScalaTuningInput;
+      throw new Error(`scala import needs the desktop runtime: ${input.scl_path}`);
+    }
+
     case "save_project": {
       localStorage.setItem("voltlane.mock.project", JSON.stringify(mockProject));
       return mockProject as T;
@@ -911,6 +1968,70 @@ export async function updateAudioClip(input: UpdateAudioClipInput): Promise<Proj
   return normalizeProjectShape(await invokeCommand<Project>("update_audio_clip", { input }));
 }
 
+export async function setAudioWarpMarkers(input: SetAudioWarpMarkersInput): Promise<Project> {
+  return normalizeProjectShape(await invokeCommand<Project>("set_audio_warp_markers", { input }));
+}
+
+export async function autoWarpAudioClip(input: AutoWarpAudioClipInput): Promise<Project> {
+  return normalizeProjectShape(await invokeCommand<Project>("auto_warp_audio_clip", { input }));
+}
+
+export async function splitClip(input: SplitClipInput): Promise<Project> {
+  return normalizeProjectShape(await invokeCommand<Project>("split_clip", { input }));
+}
+
+export async function sliceClip(input: SliceClipInput): Promise<Project> {
+  return normalizeProjectShape(await invokeCommand<Project>("slice_clip", { input }));
+}
+
+export async function sliceAudioClipAtTransients(input: SliceAudioClipAtTransientsInput): Promise<Project> {
+  return normalizeProjectShape(await invokeCommand<Project>("slice_audio_clip_at_transients", { input }));
+}
+
+export async function transcribeAudioClip(input: TranscribeAudioClipInput): Promise<Project> {
+  return normalizeProjectShape(await invokeCommand<Project>("transcribe_audio_clip", { input }));
+}
+
+export async function sliceAudioClipToSampler(input: SliceAudioClipToSamplerInput): Promise<Project> {
+  return normalizeProjectShape(await invokeCommand<Project>("slice_audio_clip_to_sampler", { input }));
+}
+
+export async function glueClips(input: GlueClipsInput): Promise<Project> {
+  return normalizeProjectShape(await invokeCommand<Project>("glue_clips", { input }));
+}
+
+export async function poolClip(input: ClipRefInput): Promise<Project> {
+  return normalizeProjectShape(await invokeCommand<Project>("pool_clip", { input }));
+}
+
+export async function duplicateClipLinked(input: DuplicateClipLinkedInput): Promise<Project> {
+  return normalizeProjectShape(await invokeCommand<Project>("duplicate_clip_linked", { input }));
+}
+
+export async function placePoolContent(input: PlacePoolContentInput): Promise<Project> {
+  return normalizeProjectShape(await invokeCommand<Project>("place_pool_content", { input }));
+}
+
+export async function makeClipUnique(input: ClipRefInput): Promise<Project> {
+  return normalizeProjectShape(await invokeCommand<Project>("make_clip_unique", { input }));
+}
+
+export async function pruneClipPool(): Promise<Project> {
+  return normalizeProjectShape(await invokeCommand<Project>("prune_clip_pool"));
+}
+
+export async function insertTime(input: InsertTimeInput): Promise<Project> {
+  return normalizeProjectShape(await invokeCommand<Project>("insert_time", { input }));
+}
+
+export async function deleteTime(input: DeleteTimeInput): Promise<Project> {
+  return normalizeProjectShape(await invokeCommand<Project>("delete_time", { input }));
+}
+
+export async function updateClipPlayback(input: UpdateClipPlaybackInput): Promise<Project> {
+  return normalizeProjectShape(await invokeCommand<Project>("update_clip_playback", { input }));
+}
+
 export async function moveClip(input: MoveClipInput): Promise<Project> {
   return normalizeProjectShape(await invokeCommand<Project>("move_clip", { input }));
 }
@@ -923,10 +2044,54 @@ export async function updateAutomationClip(input: UpdateAutomationClipInput): Pr
   return normalizeProjectShape(await invokeCommand<Project>("update_automation_clip", { input }));
 }
 
+export async function updateControllerLane(input: UpdateControllerLaneInput): Promise<Project> {
+  return normalizeProjectShape(await invokeCommand<Project>("update_controller_lane", { input }));
+}
+
+export async function setProjectTuning(tuning: Tuning): Promise<Project> {
+  return normalizeProjectShape(await invokeCommand<Project>("set_project_tuning", { tuning }));
+}
+
+export async function setProjectKey(key: Key | null): Promise<Project> {
+  return normalizeProjectShape(await invokeCommand<Project>("set_project_key", { key }));
+}
+
+export async function setClipKey(input: SetClipKeyInput): Promise<Project> {
+  return normalizeProjectShape(await invokeCommand<Project>("set_clip_key", { input }));
+}
+
+export async function clipScalePitchClasses(input: ClipRefInput): Promise<boolean[] | null> {
+  return invokeCommand<boolean[] | null>("clip_scale_pitch_classes", { input });
+}
+
+export async function detectClipKey(input: ClipRefInput): Promise<KeyEstimate | null> {
+  return invokeCommand<KeyEstimate | null>("detect_clip_key", { input });
+}
+
+export async function detectProjectKey(): Promise<KeyEstimate | null> {
+  return invokeCommand<KeyEstimate | null>("detect_project_key");
+}
+
+export async function importScalaTuning(input: ImportScalaTuningInput): Promise<Project> {
+  return normalizeProjectShape(await invokeCommand<Project>("import_scala_tuning", { input }));
+}
+
 export async function updatePatternRows(input: UpdatePatternRowsInput): Promise<Project> {
   return normalizeProjectShape(await invokeCommand<Project>("update_pattern_rows", { input }));
 }
 
+export async function generateEuclideanRows(input: GenerateEuclideanRowsInput): Promise<Project> {
+  return normalizeProjectShape(await invokeCommand<Project>("generate_euclidean_rows", { input }));
+}
+
+export async function generateProbabilityRows(input: GenerateProbabilityRowsInput): Promise<Project> {
+  return normalizeProjectShape(await invokeCommand<Project>("generate_probability_rows", { input }));
+}
+
+export async function continuePatternMarkov(input: ContinuePatternMarkovInput): Promise<Project> {
+  return normalizeProjectShape(await invokeCommand<Project>("continue_pattern_markov", { input }));
+}
+
 export async function updatePatternMacros(input: UpdatePatternMacrosInput): Promise<Project> {
   return normalizeProjectShape(await invokeCommand<Project>("update_pattern_macros", { input }));
 }
@@ -947,6 +2112,38 @@ export async function quantizeClipNotes(input: QuantizeClipNotesInput): Promise<
   return normalizeProjectShape(await invokeCommand<Project>("quantize_clip_notes", { input }));
 }
 
+export async function humanizeClipNotes(input: HumanizeClipNotesInput): Promise<Project> {
+  return normalizeProjectShape(await invokeCommand<Project>("humanize_clip_notes", { input }));
+}
+
+export async function strumClipNotes(input: StrumClipNotesInput): Promise<Project> {
+  return normalizeProjectShape(await invokeCommand<Project>("strum_clip_notes", { input }));
+}
+
+export async function shapeClipNoteVelocities(input: ShapeClipNoteVelocitiesInput): Promise<Project> {
+  return normalizeProjectShape(await invokeCommand<Project>("shape_clip_note_velocities", { input }));
+}
+
+export async function legatoClipNotes(input: LegatoClipNotesInput): Promise<Project> {
+  return normalizeProjectShape(await invokeCommand<Project>("legato_clip_notes", { input }));
+}
+
+export async function setClipNoteLengths(input: SetClipNoteLengthsInput): Promise<Project> {
+  return normalizeProjectShape(await invokeCommand<Project>("set_clip_note_lengths", { input }));
+}
+
+export async function arpeggiateClip(input: ArpeggiateClipInput): Promise<Project> {
+  return normalizeProjectShape(await invokeCommand<Project>("arpeggiate_clip", { input }));
+}
+
+export async function generateClipChords(input: GenerateClipChordsInput): Promise<Project> {
+  return normalizeProjectShape(await invokeCommand<Project>("generate_clip_chords", { input }));
+}
+
+export async function extractGroove(input: ExtractGrooveInput): Promise<Project> {
+  return normalizeProjectShape(await invokeCommand<Project>("extract_groove", { input }));
+}
+
 export async function addEffect(input: AddEffectInput): Promise<Project> {
   return normalizeProjectShape(await invokeCommand<Project>("add_effect", { input }));
 }
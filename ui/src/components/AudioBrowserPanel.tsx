@@ -1,6 +1,6 @@
 import { useEffect, useMemo, useState } from "react";
 
-import type { AudioAnalysis, AudioAssetEntry } from "../types";
+import type { AudioAnalysis, AudioAssetEntry, KeyEstimate } from "../types";
 
 interface AudioBrowserPanelProps {
   directory: string;
@@ -13,6 +13,13 @@ interface AudioBrowserPanelProps {
   onImportAsset: (assetPath: string) => void;
 }
 
+const PITCH_CLASS_NAMES = ["C", "C#", "D", "D#", "E", "F", "F#", "G", "G#", "A", "A#", "B"];
+
+function keyLabel(estimate: KeyEstimate): string {
+  const mode = typeof estimate.key.mode === "string" ? estimate.key.mode.replace("_", " ") : "custom";
+  return `${PITCH_CLASS_NAMES[estimate.key.root % 12]} ${mode}`;
+}
+
 function prettyBytes(sizeBytes: number): string {
   if (sizeBytes < 1024) {
     return `${sizeBytes} B`;
@@ -106,6 +113,32 @@ export function AudioBrowserPanel({
             <span className="label">Frames</span>
             <strong>{preview.total_frames.toLocaleString()}</strong>
           </p>
+          {preview.features ? (
+            <>
+              <p>
+                <span className="label">Tempo / Key</span>
+                <strong>
+                  {preview.features.bpm !== null ? `${preview.features.bpm.toFixed(1)} BPM` : "No pulse"} •{" "}
+                  {preview.features.key ? keyLabel(preview.features.key) : "No key"}
+                </strong>
+              </p>
+              <p>
+                <span className="label">Loudness</span>
+                <strong>
+                  {preview.features.integrated_lufs !== null
+                    ? `${preview.features.integrated_lufs.toFixed(1)} LUFS`
+                    : "Silent"}{" "}
+                  • {preview.features.rms_dbfs.toFixed(1)} dB RMS • {preview.features.peak_dbfs.toFixed(1)} dB peak
+                </strong>
+              </p>
+              <p>
+                <span className="label">Audible</span>
+                <strong>
+                  {preview.features.trim_start_seconds.toFixed(2)}s – {preview.features.trim_end_seconds.toFixed(2)}s
+                </strong>
+              </p>
+            </>
+          ) : null}
           <div className="audio-browser__waveform">
             {peakBars.map((peak, index) => (
               <span
@@ -1,6 +1,6 @@
 import { useEffect, useMemo, useRef, useState } from "react";
 
-import type { AutomationPoint, ChipMacroLane, Clip, MidiNote, TrackerRow } from "../types";
+import type { AudioStretchMode, AutomationPoint, ChipMacroLane, Clip, MidiNote, TrackerRow } from "../types";
 
 interface ClipEditorProps {
   clip: Clip | null;
@@ -39,6 +39,9 @@ interface ClipEditorProps {
       fade_out_seconds?: number;
       reverse?: boolean;
       stretch_ratio?: number;
+      stretch_mode?: AudioStretchMode;
+      pitch_semitones?: number;
+      pitch_cents?: number;
     }
   ) => void;
 }
@@ -132,6 +135,9 @@ export function ClipEditor({
   const [fadeOutSeconds, setFadeOutSeconds] = useState(0);
   const [stretchRatio, setStretchRatio] = useState(1);
   const [reverse, setReverse] = useState(false);
+  const [stretchMode, setStretchMode] = useState<AudioStretchMode>("resample");
+  const [pitchSemitones, setPitchSemitones] = useState(0);
+  const [pitchCents, setPitchCents] = useState(0);
 
   useEffect(() => {
     setClipStart(clip?.start_tick ?? 0);
@@ -158,6 +164,9 @@ export function ClipEditor({
       setFadeOutSeconds(audio.fade_out_seconds);
       setStretchRatio(audio.stretch_ratio);
       setReverse(audio.reverse);
+      setStretchMode(audio.stretch_mode ?? "resample");
+      setPitchSemitones(audio.pitch_semitones ?? 0);
+      setPitchCents(audio.pitch_cents ?? 0);
     }
   }, [clip]);
 
@@ -1055,6 +1064,38 @@ export function ClipEditor({
                 onChange={(event) => setStretchRatio(Number(event.target.value))}
               />
             </label>
+            <label className="field">
+              <span>Stretch Mode</span>
+              <select
+                value={stretchMode}
+                onChange={(event) => setStretchMode(event.target.value as AudioStretchMode)}
+              >
+                <option value="resample">Resample</option>
+                <option value="preserve_pitch">Preserve Pitch</option>
+              </select>
+            </label>
+            <label className="field">
+              <span>Pitch (st)</span>
+              <input
+                type="number"
+                min={-24}
+                max={24}
+                step={1}
+                value={pitchSemitones}
+                onChange={(event) => setPitchSemitones(Number(event.target.value))}
+              />
+            </label>
+            <label className="field">
+              <span>Pitch (cents)</span>
+              <input
+                type="number"
+                min={-100}
+                max={100}
+                step={1}
+                value={pitchCents}
+                onChange={(event) => setPitchCents(Number(event.target.value))}
+              />
+            </label>
             <label className="field field--checkbox">
               <span>Reverse</span>
               <input
@@ -1078,6 +1119,9 @@ export function ClipEditor({
                   fade_in_seconds: fadeInSeconds,
                   fade_out_seconds: fadeOutSeconds,
                   stretch_ratio: stretchRatio,
+                  stretch_mode: stretchMode,
+                  pitch_semitones: pitchSemitones,
+                  pitch_cents: pitchCents,
                   reverse
                 })
               }
@@ -7,7 +7,7 @@ interface TransportBarProps {
   loading: boolean;
   onPlay: (isPlaying: boolean) => void;
   onLoopToggle: (enabled: boolean, loopStartTick?: number, loopEndTick?: number) => void;
-  onExport: (kind: "midi" | "wav" | "mp3" | "stem_wav") => void;
+  onExport: (kind: "midi" | "wav" | "mp3" | "stem_wav" | "vgm") => void;
   exportRenderMode: RenderMode;
   onExportRenderModeChange: (mode: RenderMode) => void;
   onAutosave: () => void;
@@ -117,6 +117,9 @@ export function TransportBar({
         <button type="button" className="pill" onClick={() => onExport("stem_wav")} disabled={loading}>
           Export Stems
         </button>
+        <button type="button" className="pill" onClick={() => onExport("vgm")} disabled={loading}>
+          Export VGM
+        </button>
         <button type="button" className="pill" onClick={onAutosave} disabled={loading}>
           Autosave
         </button>
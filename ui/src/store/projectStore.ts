@@ -39,6 +39,7 @@ import { logger } from "../lib/logger";
 import type {
   AudioAnalysis,
   AudioAssetEntry,
+  AudioStretchMode,
   AutomationPoint,
   ChipMacroLane,
   ExportKind,
@@ -142,6 +143,9 @@ interface ProjectStore {
       fade_out_seconds?: number;
       reverse?: boolean;
       stretch_ratio?: number;
+      stretch_mode?: AudioStretchMode;
+      pitch_semitones?: number;
+      pitch_cents?: number;
     }
   ) => Promise<void>;
   runExport: (kind: ExportKind) => Promise<void>;
@@ -179,6 +183,8 @@ function extensionFor(kind: ExportKind): string {
       return "mp3";
     case "stem_wav":
       return "wav";
+    case "vgm":
+      return "vgm";
   }
 }
 
@@ -15,11 +15,20 @@ export interface MidiNote {
   start_tick: number;
   length_ticks: number;
   channel: number;
+  release_velocity?: number;
+}
+
+export type ControllerTarget = "mod_wheel" | "expression" | "sustain" | "pitch_bend";
+
+export interface ControllerLane {
+  target: ControllerTarget;
+  points: AutomationPoint[];
 }
 
 export interface MidiClip {
   instrument: string | null;
   notes: MidiNote[];
+  controllers?: ControllerLane[];
 }
 
 export interface TrackerRow {
@@ -60,11 +69,25 @@ export interface AudioClip {
   fade_out_seconds: number;
   reverse: boolean;
   stretch_ratio: number;
+  stretch_mode?: AudioStretchMode;
+  /** Pitch shift applied on top of the stretch, independent of it. */
+  pitch_semitones?: number;
+  pitch_cents?: number;
+  /** Two or more markers warp the clip piecewise; `tick` counts from where the source start plays. */
+  warp_markers?: WarpMarker[];
+  source_bpm?: number;
   waveform_bucket_size: number;
   waveform_peaks: number[];
   waveform_cache_path: string | null;
 }
 
+export type AudioStretchMode = "resample" | "preserve_pitch";
+
+export interface WarpMarker {
+  source_seconds: number;
+  tick: number;
+}
+
 export interface AutomationPoint {
   tick: number;
   value: number;
@@ -79,7 +102,19 @@ export type ClipPayload =
   | { midi: MidiClip }
   | { pattern: PatternClip }
   | { audio: AudioClip }
-  | { automation: AutomationClip };
+  | { automation: AutomationClip }
+  | { linked: LinkedClip };
+
+/** Placement of shared content from `Project.clip_pool`. */
+export interface LinkedClip {
+  content_id: string;
+}
+
+export interface ClipPoolEntry {
+  id: string;
+  name: string;
+  payload: ClipPayload;
+}
 
 export interface Clip {
   id: string;
@@ -87,6 +122,12 @@ export interface Clip {
   start_tick: number;
   length_ticks: number;
   disabled: boolean;
+  content_offset_ticks?: number;
+  loop_length_ticks?: number | null;
+  transpose_semitones?: number;
+  velocity_scale?: number;
+  /** Overrides the project key for scale snapping and highlighting. */
+  key?: Key;
   payload: ClipPayload;
 }
 
@@ -132,10 +173,42 @@ export interface Project {
   sample_rate: number;
   transport: Transport;
   tracks: Track[];
+  clip_pool?: ClipPoolEntry[];
+  tuning?: Tuning;
+  key?: Key;
+  grooves?: GrooveTemplate[];
   created_at: string;
   updated_at: string;
 }
 
+export interface GrooveSlot {
+  offset_ticks: number;
+  velocity_scale: number;
+}
+
+export interface GrooveTemplate {
+  name: string;
+  grid_ticks: number;
+  slots: GrooveSlot[];
+}
+
+export interface Tuning {
+  name: string;
+  reference_note: number;
+  reference_hz: number;
+  scale_cents: number[];
+  root_note: number;
+  key_map: (number | null)[];
+  map_octave_degree: number;
+  first_note: number;
+  last_note: number;
+}
+
+export interface ImportScalaTuningInput {
+  scl_path: string;
+  kbm_path?: string;
+}
+
 export interface AddTrackRequest {
   name: string;
   color: string;
@@ -181,6 +254,18 @@ export interface AudioAnalysis {
   duration_seconds: number;
   peaks: AudioWaveformPeaks;
   cache_path: string | null;
+  features?: AudioFeatures | null;
+}
+
+/** Tempo, key and levels of the mono mixdown of an asset. */
+export interface AudioFeatures {
+  bpm: number | null;
+  key: KeyEstimate | null;
+  integrated_lufs: number | null;
+  rms_dbfs: number;
+  peak_dbfs: number;
+  trim_start_seconds: number;
+  trim_end_seconds: number;
 }
 
 export interface AnalyzeAudioAssetInput {
@@ -209,6 +294,98 @@ export interface UpdateAudioClipInput {
   fade_out_seconds?: number;
   reverse?: boolean;
   stretch_ratio?: number;
+  stretch_mode?: AudioStretchMode;
+  pitch_semitones?: number;
+  pitch_cents?: number;
+}
+
+export interface SetAudioWarpMarkersInput {
+  track_id: string;
+  clip_id: string;
+  markers: WarpMarker[];
+}
+
+export interface AutoWarpAudioClipInput {
+  track_id: string;
+  clip_id: string;
+  source_bpm: number;
+}
+
+export interface UpdateClipPlaybackInput {
+  track_id: string;
+  clip_id: string;
+  content_offset_ticks?: number;
+  loop_length_ticks?: number;
+  transpose_semitones?: number;
+  velocity_scale?: number;
+}
+
+export interface SplitClipInput {
+  track_id: string;
+  clip_id: string;
+  tick: number;
+}
+
+export interface SliceClipInput {
+  track_id: string;
+  clip_id: string;
+  parts?: number;
+  grid_ticks?: number;
+}
+
+/** `sensitivity` runs from 0 (only the strongest hits) to 1. */
+export interface SliceAudioClipAtTransientsInput {
+  track_id: string;
+  clip_id: string;
+  sensitivity: number;
+}
+
+/** A `source_chip` writes a pattern clip; notes are quantized only with `quantize_grid_ticks`. */
+export interface TranscribeAudioClipInput {
+  track_id: string;
+  clip_id: string;
+  target_track_id: string;
+  source_chip?: string;
+  threshold?: number;
+  min_note_seconds?: number;
+  quantize_grid_ticks?: number;
+  quantize_strength_percent?: number;
+}
+
+export interface SliceAudioClipToSamplerInput {
+  track_id: string;
+  clip_id: string;
+  target_track_id: string;
+  sensitivity: number;
+  first_note: number;
+}
+
+export interface GlueClipsInput {
+  track_id: string;
+  clip_ids: string[];
+}
+
+export interface DuplicateClipLinkedInput {
+  track_id: string;
+  clip_id: string;
+  start_tick: number;
+}
+
+export interface PlacePoolContentInput {
+  track_id: string;
+  content_id: string;
+  start_tick: number;
+  length_ticks: number;
+}
+
+export interface InsertTimeInput {
+  at_tick: number;
+  length_ticks: number;
+}
+
+export interface DeleteTimeInput {
+  start_tick: number;
+  end_tick: number;
 }
 
 export interface MoveClipInput {
@@ -222,6 +399,7 @@ export interface UpdateClipNotesInput {
   track_id: string;
   clip_id: string;
   notes: MidiNote[];
+  snap_to_scale?: boolean;
 }
 
 export interface UpdatePatternRowsInput {
@@ -231,6 +409,33 @@ export interface UpdatePatternRowsInput {
   lines_per_beat?: number;
 }
 
+export interface GenerateEuclideanRowsInput {
+  track_id: string;
+  clip_id: string;
+  hits: number;
+  steps: number;
+  /** Steps the pattern is shifted later by. */
+  rotation?: number;
+  pitch: number;
+  velocity?: number;
+}
+
+export interface GenerateProbabilityRowsInput {
+  track_id: string;
+  clip_id: string;
+  seed?: number;
+  /** Hit chance per step in percent, cycled over the rows. */
+  chances: number[];
+  pitches: number[];
+  velocity?: number;
+}
+
+export interface ContinuePatternMarkovInput {
+  track_id: string;
+  clip_id: string;
+  seed?: number;
+}
+
 export interface UpdatePatternMacrosInput {
   track_id: string;
   clip_id: string;
@@ -241,6 +446,7 @@ export interface AddClipNoteInput {
   track_id: string;
   clip_id: string;
   note: MidiNote;
+  snap_to_scale?: boolean;
 }
 
 export interface RemoveClipNoteInput {
@@ -253,15 +459,124 @@ export interface TransposeClipNotesInput {
   track_id: string;
   clip_id: string;
   semitones: number;
+  /** Reads `semitones` as scale degrees of the clip's key. */
+  diatonic?: boolean;
 }
 
+export type NoteEndQuantize = "keep" | "length" | "end";
+
 export interface QuantizeClipNotesInput {
   track_id: string;
   clip_id: string;
   grid_ticks: number;
+  strength_percent?: number;
+  swing_percent?: number;
+  triplet?: boolean;
+  ends?: NoteEndQuantize;
+  groove?: string;
+}
+
+export interface NoteSelectionInput {
+  track_id: string;
+  clip_id: string;
+  /** Indices into the clip's notes; empty or omitted means every note. */
+  note_indices?: number[];
+}
+
+export interface HumanizeClipNotesInput extends NoteSelectionInput {
+  seed: number;
+  timing_ticks: number;
+  velocity: number;
+  length_percent: number;
+}
+
+export type StrumDirection = "up" | "down";
+
+export interface StrumClipNotesInput extends NoteSelectionInput {
+  direction: StrumDirection;
+  spread_ticks: number;
+}
+
+export interface ShapeClipNoteVelocitiesInput extends NoteSelectionInput {
+  from: number;
+  to: number;
+  curve?: number;
+}
+
+export type LegatoClipNotesInput = NoteSelectionInput;
+
+export interface SetClipNoteLengthsInput extends NoteSelectionInput {
+  length_ticks: number;
+}
+
+export type ArpeggioMode = "up" | "down" | "up_down" | "random" | "as_played";
+
+export interface ArpeggiateClipInput {
+  track_id: string;
+  clip_id: string;
+  mode: ArpeggioMode;
+  rate_ticks: number;
+  octaves?: number;
+  gate_percent?: number;
+  seed?: number;
+}
+
+export type ScaleMode =
+  | "major"
+  | "minor"
+  | "harmonic_minor"
+  | "melodic_minor"
+  | "dorian"
+  | "phrygian"
+  | "lydian"
+  | "mixolydian"
+  | "locrian"
+  /** Semitones above the root, ascending from 0 and below 12. */
+  | { custom: number[] };
+
+export interface Key {
+  /** Pitch class of the tonic, 0 = C. */
+  root: number;
+  mode: ScaleMode;
+}
+
+export interface KeyEstimate {
+  key: Key;
+  /** Correlation with the key profile, from -1 to 1. */
+  confidence: number;
+}
+
+export interface SetClipKeyInput {
+  track_id: string;
+  clip_id: string;
+  key: Key | null;
+}
+
+export interface ClipRefInput {
+  track_id: string;
+  clip_id: string;
+}
+
+export type ChordKind = "triad" | "seventh";
+
+export interface GenerateClipChordsInput {
+  track_id: string;
+  clip_id: string;
+  /** Pitch class of the key's tonic, 0 = C. */
+  key_root: number;
+  scale: ScaleMode;
+  kind: ChordKind;
+}
+
+export interface ExtractGrooveInput {
+  track_id: string;
+  clip_id: string;
+  name: string;
+  grid_ticks: number;
+  steps: number;
 }
 
-export type ExportKind = "midi" | "wav" | "mp3" | "stem_wav";
+export type ExportKind = "midi" | "wav" | "mp3" | "stem_wav" | "vgm";
 export type RenderMode = "offline" | "realtime";
 
 export interface ExportProjectInput {
@@ -322,6 +637,13 @@ export interface UpdateAutomationClipInput {
   points: AutomationPoint[];
 }
 
+export interface UpdateControllerLaneInput {
+  track_id: string;
+  clip_id: string;
+  target: ControllerTarget;
+  points: AutomationPoint[];
+}
+
 export interface AutosaveStatus {
   exists: boolean;
   path: string | null;